@@ -0,0 +1,69 @@
+//! Measures the overhead of `AsyncClient::paginate()` per page by paginating
+//! over a large number of small pages served by an in-memory backend.
+//!
+//! Run with `cargo bench --features tokio --bench paginate`.
+use futures_util::TryStreamExt;
+use ghreq::{
+    Endpoint,
+    client::{ClientConfig, tokio::AsyncFnBackend},
+    pagination::PaginationRequest,
+};
+use std::time::Instant;
+
+const PAGES: u64 = 20_000;
+const ROUNDS: u32 = 5;
+
+struct ListNumbers;
+
+impl PaginationRequest for ListNumbers {
+    type Item = u64;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter(["numbers"])
+    }
+}
+
+fn main() {
+    let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(|parts, _| {
+        let page = parts
+            .url
+            .as_url()
+            .query_pairs()
+            .find_map(|(k, v)| (k == "page").then(|| v.parse::<u64>().ok()))
+            .flatten()
+            .unwrap_or(1);
+        let mut resp = http::Response::builder();
+        if page < PAGES {
+            resp = resp.header(
+                http::header::LINK,
+                format!(
+                    "<https://api.github.com/numbers?page={}>; rel=\"next\"",
+                    page + 1
+                ),
+            );
+        }
+        resp.body(format!("[{page}]").into_bytes())
+            .expect("response should be valid")
+    }));
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime should build");
+    let mut best = None;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let numbers = rt
+            .block_on(client.paginate(ListNumbers).try_collect::<Vec<_>>())
+            .expect("pagination should succeed");
+        let elapsed = start.elapsed();
+        assert_eq!(
+            numbers.len(),
+            usize::try_from(PAGES).unwrap_or(usize::MAX),
+            "all pages should be fetched"
+        );
+        best = Some(best.map_or(elapsed, |b| elapsed.min(b)));
+    }
+    if let Some(best) = best {
+        let per_page = best / u32::try_from(PAGES).unwrap_or(u32::MAX);
+        println!("paginate: {PAGES} pages in {best:?} ({per_page:?} per page, best of {ROUNDS})");
+    }
+}
@@ -1,7 +1,10 @@
 use clap::Parser;
 use futures_util::StreamExt;
 use ghreq::{
-    Endpoint, HttpUrl, client::ClientConfig, pagination::PaginationRequest, reqwest::ReqwestError,
+    Endpoint, HttpUrl,
+    client::{ClientConfig, GhTokenError},
+    pagination::PaginationRequest,
+    reqwest::ReqwestError,
 };
 use serde::{Deserialize, Serialize};
 use std::process::ExitCode;
@@ -42,16 +45,15 @@ struct Arguments {
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> ExitCode {
     let args = Arguments::parse();
-    let mut cfg = ClientConfig::new();
-    if let Ok(token) = gh_token::get() {
-        cfg = match cfg.with_auth_token(&token) {
-            Ok(cfg2) => cfg2,
-            Err(cfg2) => {
-                eprintln!("Warning: invalid GitHub API auth token");
-                cfg2
-            }
+    let cfg = ClientConfig::new();
+    let cfg = match cfg.clone().with_gh_cli_token() {
+        Ok(cfg2) => cfg2,
+        Err(GhTokenError::NotFound(_)) => cfg,
+        Err(e) => {
+            eprintln!("Warning: {e}");
+            cfg
         }
-    }
+    };
     let client = cfg.with_reqwest();
     let req = ListRepositories { owner: args.owner };
     let mut first = true;
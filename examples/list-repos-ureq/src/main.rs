@@ -1,6 +1,9 @@
 use clap::Parser;
 use ghreq::{
-    Endpoint, HttpUrl, client::ClientConfig, pagination::PaginationRequest, ureq::UreqError,
+    Endpoint, HttpUrl,
+    client::{ClientConfig, GhTokenError},
+    pagination::PaginationRequest,
+    ureq::UreqError,
 };
 use serde::{Deserialize, Serialize};
 use std::process::ExitCode;
@@ -40,16 +43,15 @@ struct Arguments {
 
 fn main() -> ExitCode {
     let args = Arguments::parse();
-    let mut cfg = ClientConfig::new();
-    if let Ok(token) = gh_token::get() {
-        cfg = match cfg.with_auth_token(&token) {
-            Ok(cfg2) => cfg2,
-            Err(cfg2) => {
-                eprintln!("Warning: invalid GitHub API auth token");
-                cfg2
-            }
+    let cfg = ClientConfig::new();
+    let cfg = match cfg.clone().with_gh_cli_token() {
+        Ok(cfg2) => cfg2,
+        Err(GhTokenError::NotFound(_)) => cfg,
+        Err(e) => {
+            eprintln!("Warning: {e}");
+            cfg
         }
-    }
+    };
     let client = cfg.with_ureq();
     let req = ListRepositories { owner: args.owner };
     let mut first = true;
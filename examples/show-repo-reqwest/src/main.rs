@@ -2,7 +2,7 @@ use clap::Parser;
 use ghrepo::GHRepo;
 use ghreq::{
     Endpoint, HttpUrl, Method,
-    client::ClientConfig,
+    client::{ClientConfig, GhTokenError},
     errors::CommonError,
     parser::{JsonResponse, ResponseParser},
     request::Request,
@@ -62,16 +62,15 @@ struct Arguments {
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> ExitCode {
     let args = Arguments::parse();
-    let mut cfg = ClientConfig::new();
-    if let Ok(token) = gh_token::get() {
-        cfg = match cfg.with_auth_token(&token) {
-            Ok(cfg2) => cfg2,
-            Err(cfg2) => {
-                eprintln!("Warning: invalid GitHub API auth token");
-                cfg2
-            }
+    let cfg = ClientConfig::new();
+    let cfg = match cfg.clone().with_gh_cli_token() {
+        Ok(cfg2) => cfg2,
+        Err(GhTokenError::NotFound(_)) => cfg,
+        Err(e) => {
+            eprintln!("Warning: {e}");
+            cfg
         }
-    }
+    };
     let client = cfg.with_reqwest();
     let req = ShowRepository { spec: args.spec };
     match client.request(req).await {
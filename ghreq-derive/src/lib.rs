@@ -0,0 +1,259 @@
+//! Derive macro for the `Request` trait of [`ghreq`](https://docs.rs/ghreq)
+//!
+//! This crate is an implementation detail of `ghreq`; use it via `ghreq`'s
+//! `derive` feature rather than depending on it directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, Ident, LitStr, Type, parse_macro_input};
+
+/// Derive an implementation of `ghreq::request::Request` for a struct
+///
+/// The request is configured with a `#[ghreq(...)]` attribute on the struct
+/// containing the following keys:
+///
+/// - `method = "..."` (required) — the HTTP method of the request, e.g.,
+///   `"GET"`.  Methods other than GET, HEAD, POST, PUT, PATCH, DELETE, and
+///   OPTIONS are converted to `ghreq::Method::Other`.
+///
+/// - `endpoint = "..."` (required) — the path of the endpoint relative to the
+///   client's base API URL, with `/`-separated path segments.  Segments may
+///   contain placeholders of the form `{field}`, which are replaced by the
+///   [`Display`](std::fmt::Display) representation of the struct's field
+///   with the given name.
+///
+/// - `output = Type` (required) — the type of the request's output, which
+///   must implement `serde::de::DeserializeOwned`.  The response body is
+///   parsed as JSON into this type.
+///
+/// - `error = Type` — the request's error type; defaults to
+///   `ghreq::errors::CommonError`
+///
+/// - `body = field` — the name of a field whose value (which must implement
+///   `Clone` and `serde::Serialize`) is sent as a JSON request body.  By
+///   default, the request has no body.
+///
+/// - `accept = "..."` — a media type to merge into the request's `Accept`
+///   header via `Request::extra_accept()`, e.g., a preview media type needed
+///   for the response to include certain fields.  This key may be given
+///   multiple times.
+///
+/// # Example
+///
+/// ```ignore
+/// use ghreq::request::Request;
+///
+/// #[derive(Request)]
+/// #[ghreq(method = "GET", endpoint = "repos/{owner}/{name}", output = Repository)]
+/// struct GetRepository {
+///     owner: String,
+///     name: String,
+/// }
+/// ```
+#[proc_macro_derive(Request, attributes(ghreq))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The contents of the `#[ghreq(...)]` attributes on a struct
+#[derive(Default)]
+struct Attributes {
+    method: Option<TokenStream2>,
+    endpoint: Option<LitStr>,
+    output: Option<Type>,
+    error: Option<Type>,
+    body: Option<Ident>,
+    accept: Vec<LitStr>,
+}
+
+impl Attributes {
+    fn parse(input: &DeriveInput) -> syn::Result<Attributes> {
+        let mut attrs = Attributes::default();
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("ghreq")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("method") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    attrs.method = Some(expand_method(&lit)?);
+                } else if meta.path.is_ident("endpoint") {
+                    attrs.endpoint = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("output") {
+                    attrs.output = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("error") {
+                    attrs.error = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("body") {
+                    attrs.body = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("accept") {
+                    attrs.accept.push(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown ghreq attribute key"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named.named.iter().collect::<Vec<_>>(),
+            Fields::Unnamed(_) | Fields::Unit => Vec::new(),
+        },
+        Data::Enum(_) | Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(Request)] is only supported on structs",
+            ));
+        }
+    };
+    let attrs = Attributes::parse(input)?;
+    let missing = |key: &str| {
+        syn::Error::new_spanned(
+            &input.ident,
+            format!("missing required #[ghreq({key} = ...)] attribute"),
+        )
+    };
+    let method = attrs.method.ok_or_else(|| missing("method"))?;
+    let endpoint = attrs.endpoint.ok_or_else(|| missing("endpoint"))?;
+    let endpoint = expand_endpoint(&endpoint, &fields)?;
+    let output = attrs.output.ok_or_else(|| missing("output"))?;
+    let error = attrs
+        .error
+        .map_or_else(|| quote!(::ghreq::errors::CommonError), |ty| quote!(#ty));
+    let (body_type, body_expr) = match attrs.body {
+        Some(name) => {
+            let field = find_field(&fields, &name.to_string())
+                .ok_or_else(|| syn::Error::new(name.span(), format!("no field named `{name}`")))?;
+            let ty = &field.ty;
+            (
+                quote!(::ghreq::request::JsonBody<#ty>),
+                quote!(::ghreq::request::JsonBody::new(::std::clone::Clone::clone(&self.#name))),
+            )
+        }
+        None => (quote!(()), TokenStream2::new()),
+    };
+    let extra_accept = if attrs.accept.is_empty() {
+        TokenStream2::new()
+    } else {
+        let accept = &attrs.accept;
+        quote! {
+            fn extra_accept(&self) -> ::ghreq::AcceptList {
+                ::ghreq::AcceptList::from_iter([#(#accept),*])
+            }
+        }
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ghreq::request::Request for #name #ty_generics #where_clause {
+            type Output = #output;
+            type Error = #error;
+            type Body = #body_type;
+
+            fn endpoint(&self) -> ::ghreq::Endpoint {
+                #endpoint
+            }
+
+            fn method(&self) -> ::ghreq::Method {
+                #method
+            }
+
+            #extra_accept
+
+            fn body(&self) -> Self::Body {
+                #body_expr
+            }
+
+            fn parser(
+                &self,
+            ) -> impl ::ghreq::parser::ResponseParser<
+                Output = Self::Output,
+                Error: ::std::convert::Into<Self::Error>,
+            > + ::std::marker::Send {
+                ::ghreq::parser::JsonResponse::new()
+            }
+        }
+    })
+}
+
+/// Convert a method name into an expression constructing a `Method`
+fn expand_method(lit: &LitStr) -> syn::Result<TokenStream2> {
+    let name = lit.value();
+    let variant = match name.to_ascii_uppercase().as_str() {
+        "GET" => "Get",
+        "HEAD" => "Head",
+        "POST" => "Post",
+        "PUT" => "Put",
+        "PATCH" => "Patch",
+        "DELETE" => "Delete",
+        "OPTIONS" => "Options",
+        _ => {
+            // Only allow the characters permitted in an RFC 9110 token so that
+            // parsing at runtime cannot fail
+            if name.is_empty()
+                || !name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+            {
+                return Err(syn::Error::new(lit.span(), "invalid HTTP method"));
+            }
+            return Ok(quote! {
+                match <::ghreq::Method as ::std::str::FromStr>::from_str(#lit) {
+                    ::std::result::Result::Ok(m) => m,
+                    ::std::result::Result::Err(_) => ::std::unreachable!(),
+                }
+            });
+        }
+    };
+    let variant = Ident::new(variant, lit.span());
+    Ok(quote!(::ghreq::Method::#variant))
+}
+
+/// Convert an endpoint template into an expression constructing an
+/// `Endpoint::Path`
+fn expand_endpoint(template: &LitStr, fields: &[&Field]) -> syn::Result<TokenStream2> {
+    let err = |msg: String| syn::Error::new(template.span(), msg);
+    let mut segments = Vec::new();
+    for seg in template.value().split('/').filter(|s| !s.is_empty()) {
+        let mut fmt = String::new();
+        let mut args = Vec::new();
+        let mut rest = seg;
+        while let Some(start) = rest.find('{') {
+            fmt.push_str(&rest[..start]);
+            let after = &rest[(start + 1)..];
+            let end = after
+                .find('}')
+                .ok_or_else(|| err(String::from("unclosed `{` in endpoint")))?;
+            let field_name = &after[..end];
+            let field = find_field(fields, field_name)
+                .ok_or_else(|| err(format!("no field named `{field_name}`")))?;
+            let ident = &field.ident;
+            fmt.push_str("{}");
+            args.push(quote!(self.#ident));
+            rest = &after[(end + 1)..];
+        }
+        if rest.contains('}') {
+            return Err(err(String::from("unmatched `}` in endpoint")));
+        }
+        fmt.push_str(rest);
+        if args.is_empty() {
+            segments.push(quote!(::std::string::String::from(#fmt)));
+        } else {
+            segments.push(quote!(::std::format!(#fmt, #(#args),*)));
+        }
+    }
+    Ok(quote!(::ghreq::Endpoint::Path(::std::vec![#(#segments),*])))
+}
+
+/// Find the named field with the given name
+fn find_field<'a>(fields: &[&'a Field], name: &str) -> Option<&'a Field> {
+    fields
+        .iter()
+        .copied()
+        .find(|f| f.ident.as_ref().is_some_and(|i| i == name))
+}
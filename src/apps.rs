@@ -0,0 +1,228 @@
+//! Requests for discovering a GitHub App and its installations
+//!
+//! All of the requests in this module must be made while authenticated as the
+//! app itself, i.e., with a JSON Web Token signed with the app's private key
+//! sent as a bearer token.  As such tokens expire after at most ten minutes,
+//! long-lived clients should supply them via an
+//! [`AuthProvider`][crate::client::AuthProvider] that regenerates them as
+//! needed.
+use crate::{
+    Endpoint, HttpUrl, Method, Timestamp,
+    errors::CommonError,
+    pagination::PaginationRequest,
+    parser::{JsonResponse, ResponseParser},
+    request::Request,
+};
+use serde::{Deserialize, Serialize};
+
+/// A request for the authenticated app via `GET /app`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GetApp;
+
+impl GetApp {
+    pub fn new() -> GetApp {
+        GetApp
+    }
+}
+
+impl Request for GetApp {
+    type Output = App;
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter(["app"])
+    }
+
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        JsonResponse::new()
+    }
+}
+
+/// A paginated request for the installations of the authenticated app via
+/// `GET /app/installations`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ListInstallations;
+
+impl ListInstallations {
+    pub fn new() -> ListInstallations {
+        ListInstallations
+    }
+}
+
+impl PaginationRequest for ListInstallations {
+    type Item = Installation;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter(["app", "installations"])
+    }
+}
+
+/// A request for the authenticated app's installation on a repository via
+/// `GET /repos/{owner}/{repo}/installation`
+///
+/// If the app is not installed on the repository, the request fails with a
+/// 404 error.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GetRepoInstallation {
+    owner: String,
+    repo: String,
+}
+
+impl GetRepoInstallation {
+    pub fn new<S, T>(owner: S, repo: T) -> GetRepoInstallation
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        GetRepoInstallation {
+            owner: owner.into(),
+            repo: repo.into(),
+        }
+    }
+}
+
+impl Request for GetRepoInstallation {
+    type Output = Installation;
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter(["repos", &self.owner, &self.repo, "installation"])
+    }
+
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        JsonResponse::new()
+    }
+}
+
+/// A GitHub App, as returned by [`GetApp`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct App {
+    pub id: u64,
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner: Option<Account>,
+    pub html_url: HttpUrl,
+    pub installations_count: Option<u64>,
+}
+
+/// An installation of a GitHub App on a user or organization account
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Installation {
+    pub id: u64,
+    pub app_id: u64,
+    pub account: Option<Account>,
+
+    /// The type of account the app is installed on, `"User"` or
+    /// `"Organization"`
+    pub target_type: String,
+
+    /// Whether the installation has access to `"all"` of the account's
+    /// repositories or only `"selected"` ones
+    pub repository_selection: String,
+
+    /// The URL at which the installation's access tokens can be created
+    pub access_tokens_url: HttpUrl,
+
+    pub suspended_at: Option<Timestamp>,
+}
+
+/// A user or organization account
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Account {
+    pub login: String,
+    pub id: u64,
+
+    /// `"User"`, `"Organization"`, or `"Bot"`
+    #[serde(rename = "type")]
+    pub account_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend};
+    use serde_json::json;
+
+    fn installation(id: u64, login: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "app_id": 42,
+            "account": {"login": login, "id": id * 10, "type": "Organization"},
+            "target_type": "Organization",
+            "repository_selection": "all",
+            "access_tokens_url": format!("https://api.github.com/app/installations/{id}/access_tokens"),
+            "suspended_at": null,
+            "permissions": {"contents": "read"},
+        })
+    }
+
+    #[test]
+    fn get_repo_installation() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/installation"
+            );
+            http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&installation(7, "octocat")).unwrap())
+                .unwrap()
+        }));
+        let inst = client
+            .request(GetRepoInstallation::new("octocat", "hello-world"))
+            .unwrap();
+        assert_eq!(inst.id, 7);
+        assert_eq!(inst.account.unwrap().login, "octocat");
+    }
+
+    #[test]
+    fn list_installations() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            let (body, link) = if parts.url.as_str().contains("page=2") {
+                (json!([installation(2, "b")]), None)
+            } else {
+                assert_eq!(
+                    parts.url.as_str(),
+                    "https://api.github.com/app/installations?per_page=100"
+                );
+                (
+                    json!([installation(1, "a")]),
+                    Some(
+                        r#"<https://api.github.com/app/installations?per_page=100&page=2>; rel="next""#,
+                    ),
+                )
+            };
+            let mut resp = http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/json");
+            if let Some(link) = link {
+                resp = resp.header(http::header::LINK, link);
+            }
+            resp.body(serde_json::to_vec(&body).unwrap()).unwrap()
+        }));
+        let ids = client
+            .paginate(ListInstallations::new())
+            .map(|r| r.map(|inst| inst.id))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(ids, [1, 2]);
+    }
+}
@@ -0,0 +1,332 @@
+//! Listing and downloading GitHub Actions artifacts
+//!
+//! [`Client::artifacts()`] paginates over a repository's artifacts and pairs
+//! each one with an [`ArtifactDownload`] handle, which fetches the artifact's
+//! ZIP archive only when asked to.  Archive downloads are served by a
+//! redirect from the API to a storage service, so the client must follow
+//! redirects, either in its backend (the default for the `ureq` and `reqwest`
+//! backends) or via
+//! [`RedirectPolicy::Follow`][crate::client::RedirectPolicy::Follow].
+use crate::{
+    Endpoint, HttpUrl, Method, Timestamp,
+    client::{Backend, Client, ClientResult},
+    download::{DownloadError, DownloadState, ResumableDownload},
+    errors::{CommonError, Error},
+    pagination::{PaginationIter, PaginationRequest},
+    parser::ResponseParser,
+    request::Request,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[cfg(feature = "tokio")]
+use crate::{
+    client::tokio::{AsyncBackend, AsyncClient, AsyncClientResult},
+    pagination::PaginationStream,
+};
+#[cfg(feature = "tokio")]
+use futures_util::Stream;
+#[cfg(feature = "tokio")]
+use pin_project_lite::pin_project;
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::task::{Context, Poll, ready};
+
+/// A paginated request for the artifacts of a repository via
+/// `GET /repos/{owner}/{repo}/actions/artifacts`
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ListArtifacts {
+    owner: String,
+    repo: String,
+    name: Option<String>,
+}
+
+impl ListArtifacts {
+    pub fn new<S, T>(owner: S, repo: T) -> ListArtifacts
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        ListArtifacts {
+            owner: owner.into(),
+            repo: repo.into(),
+            name: None,
+        }
+    }
+
+    /// Only list artifacts with the given name
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+impl PaginationRequest for ListArtifacts {
+    type Item = Artifact;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter(["repos", &self.owner, &self.repo, "actions", "artifacts"])
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.name
+            .iter()
+            .map(|name| ("name".into(), name.clone()))
+            .collect()
+    }
+}
+
+/// A GitHub Actions artifact, as returned by [`ListArtifacts`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Artifact {
+    pub id: u64,
+    pub name: String,
+
+    /// The size of the artifact's ZIP archive in bytes
+    pub size_in_bytes: u64,
+
+    /// The URL from which the artifact's ZIP archive can be downloaded
+    pub archive_download_url: HttpUrl,
+
+    /// Whether the artifact has expired, in which case it can no longer be
+    /// downloaded
+    pub expired: bool,
+
+    pub created_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
+    pub updated_at: Option<Timestamp>,
+}
+
+impl Artifact {
+    /// Returns a handle for downloading the artifact's ZIP archive
+    pub fn download_handle(&self) -> ArtifactDownload {
+        ArtifactDownload {
+            url: self.archive_download_url.clone(),
+        }
+    }
+}
+
+/// A handle for downloading the ZIP archive of an [`Artifact`]
+///
+/// Creating a handle does not make any requests; the archive is only fetched
+/// when one of the handle's download methods is called.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ArtifactDownload {
+    url: HttpUrl,
+}
+
+impl ArtifactDownload {
+    /// Returns the URL from which the archive is downloaded
+    pub fn url(&self) -> &HttpUrl {
+        &self.url
+    }
+
+    /// Returns a [`ResumableDownload`] of the archive, which can be
+    /// customized before being started
+    pub fn resumable(&self) -> ResumableDownload {
+        ResumableDownload::new(Endpoint::Url(self.url.clone()))
+    }
+
+    /// Download the archive into memory
+    pub fn bytes<B: Backend>(&self, client: &Client<B>) -> ClientResult<Vec<u8>, B> {
+        client.request(GetArchive(&self.url))
+    }
+
+    /// Download the archive to `path`, overwriting any existing file, and
+    /// resuming the download after network failures; see
+    /// [`ResumableDownload`]
+    pub fn download<B: Backend>(
+        &self,
+        client: &Client<B>,
+        path: &Path,
+    ) -> Result<DownloadState, DownloadError<B::Error>> {
+        self.resumable().download(client, path)
+    }
+
+    /// Asynchronously download the archive into memory
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn bytes_async<B: AsyncBackend + Send + Sync>(
+        &self,
+        client: &AsyncClient<B>,
+    ) -> AsyncClientResult<Vec<u8>, B> {
+        client.request(GetArchive(&self.url)).await
+    }
+
+    /// Asynchronously download the archive to `path`, overwriting any
+    /// existing file; see [`ResumableDownload::download_async()`]
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn download_async<B: AsyncBackend + Send + Sync>(
+        &self,
+        client: &AsyncClient<B>,
+        path: &Path,
+    ) -> Result<DownloadState, DownloadError<B::Error>> {
+        self.resumable().download_async(client, path).await
+    }
+}
+
+/// [Private] A request for the complete contents of an artifact archive
+#[derive(Clone, Copy, Debug)]
+struct GetArchive<'a>(&'a HttpUrl);
+
+impl Request for GetArchive<'_> {
+    type Output = Vec<u8>;
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Url(self.0.clone())
+    }
+
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        Vec::new()
+    }
+}
+
+impl<B: Backend> Client<B> {
+    /// Paginate over the artifacts listed by `req`, pairing each one with a
+    /// handle for downloading its archive
+    pub fn artifacts(&self, req: ListArtifacts) -> ArtifactIter<'_, B> {
+        ArtifactIter {
+            inner: self.paginate(req),
+        }
+    }
+}
+
+/// An iterator over artifacts and their download handles, returned by
+/// [`Client::artifacts()`]
+#[derive(Debug)]
+pub struct ArtifactIter<'a, B> {
+    inner: PaginationIter<'a, B, ListArtifacts>,
+}
+
+impl<B: Backend> Iterator for ArtifactIter<'_, B> {
+    type Item = Result<(Artifact, ArtifactDownload), Error<B::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| {
+            r.map(|artifact| {
+                let handle = artifact.download_handle();
+                (artifact, handle)
+            })
+        })
+    }
+}
+
+impl<B: Backend> std::iter::FusedIterator for ArtifactIter<'_, B> {}
+
+#[cfg(feature = "tokio")]
+impl<B: AsyncBackend + Send + Sync> AsyncClient<B> {
+    /// Paginate over the artifacts listed by `req`, pairing each one with a
+    /// handle for downloading its archive
+    pub fn artifacts(&self, req: ListArtifacts) -> ArtifactStream<B> {
+        ArtifactStream {
+            inner: self.paginate(req),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+pin_project! {
+    /// A stream of artifacts and their download handles, returned by
+    /// [`AsyncClient::artifacts()`]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub struct ArtifactStream<B: AsyncBackend> {
+        #[pin]
+        inner: PaginationStream<B, ListArtifacts>,
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B: AsyncBackend> std::fmt::Debug for ArtifactStream<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArtifactStream").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B> Stream for ArtifactStream<B>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+{
+    type Item = Result<(Artifact, ArtifactDownload), Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let r = ready!(self.project().inner.poll_next(cx));
+        Poll::Ready(r.map(|r| {
+            r.map(|artifact| {
+                let handle = artifact.download_handle();
+                (artifact, handle)
+            })
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend};
+    use serde_json::json;
+
+    fn artifact(id: u64, name: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "node_id": format!("MDg6QXJ0aWZhY3Qx{id}"),
+            "name": name,
+            "size_in_bytes": 3,
+            "url": format!("https://api.github.com/repos/octocat/hello-world/actions/artifacts/{id}"),
+            "archive_download_url": format!("https://api.github.com/repos/octocat/hello-world/actions/artifacts/{id}/zip"),
+            "expired": false,
+            "created_at": "2024-01-02T03:04:05Z",
+            "expires_at": "2024-04-01T03:04:05Z",
+            "updated_at": "2024-01-02T03:04:05Z",
+        })
+    }
+
+    #[test]
+    fn list_and_download() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            match parts.url.as_str() {
+                "https://api.github.com/repos/octocat/hello-world/actions/artifacts?name=logs&per_page=100" => {
+                    http::Response::builder()
+                        .header(http::header::CONTENT_TYPE, "application/json")
+                        .body(
+                            serde_json::to_vec(&json!({
+                                "total_count": 2,
+                                "artifacts": [artifact(1, "logs"), artifact(2, "logs")],
+                            }))
+                            .unwrap(),
+                        )
+                        .unwrap()
+                }
+                "https://api.github.com/repos/octocat/hello-world/actions/artifacts/2/zip" => {
+                    http::Response::new(b"PK\x03".to_vec())
+                }
+                url => panic!("Unexpected request to {url}"),
+            }
+        }));
+        let mut artifacts = client
+            .artifacts(ListArtifacts::new("octocat", "hello-world").with_name("logs"))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(artifacts.len(), 2);
+        let (artifact, handle) = artifacts.pop().unwrap();
+        assert_eq!(artifact.id, 2);
+        assert_eq!(handle, artifact.download_handle());
+        assert_eq!(handle.bytes(&client).unwrap(), b"PK\x03");
+    }
+}
@@ -0,0 +1,181 @@
+use http::header::{ACCEPT, HeaderMap, HeaderValue};
+use std::fmt;
+
+/// An ordered list of media types for use in an `Accept` header
+///
+/// Some GitHub endpoints only include certain fields in their responses when
+/// a specific media type is requested — historically, for example, a
+/// repository's `topics` required the `mercy-preview` media type (see
+/// [`TOPICS_PREVIEW_ACCEPT`][crate::consts::TOPICS_PREVIEW_ACCEPT]).  Request
+/// types can declare such media types via
+/// [`Request::extra_accept()`][crate::request::Request::extra_accept], and
+/// the client merges them with the `Accept` header that would otherwise be
+/// sent instead of replacing it.
+///
+/// Media types are compared case-insensitively, and adding a media type that
+/// is already present has no effect.
+///
+/// # Example
+///
+/// ```
+/// # use ghreq::AcceptList;
+/// let accept = AcceptList::new()
+///     .with_media_type("application/vnd.github+json")
+///     .with_media_type("application/vnd.github.mercy-preview+json")
+///     .with_media_type("application/vnd.github+json");
+/// assert_eq!(
+///     accept.to_string(),
+///     "application/vnd.github+json, application/vnd.github.mercy-preview+json"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AcceptList(Vec<String>);
+
+impl AcceptList {
+    /// Create an empty list
+    pub fn new() -> AcceptList {
+        AcceptList(Vec::new())
+    }
+
+    /// Create a list from the values of the `Accept` headers in `headers`,
+    /// splitting each one on commas.  Values that are not valid UTF-8 are
+    /// ignored.
+    pub fn from_headers(headers: &HeaderMap) -> AcceptList {
+        let mut accept = AcceptList::new();
+        for value in headers.get_all(ACCEPT) {
+            if let Ok(s) = value.to_str() {
+                for media_type in s.split(',') {
+                    accept.push(media_type);
+                }
+            }
+        }
+        accept
+    }
+
+    /// Add a media type to the end of the list unless it is already present
+    pub fn with_media_type<S: AsRef<str>>(mut self, media_type: S) -> Self {
+        self.push(media_type);
+        self
+    }
+
+    /// Add a media type to the end of the list unless it is already present.
+    /// Leading & trailing whitespace is ignored, as are empty media types.
+    ///
+    /// Returns `true` if the media type was added.
+    pub fn push<S: AsRef<str>>(&mut self, media_type: S) -> bool {
+        let media_type = media_type.as_ref().trim();
+        if media_type.is_empty() || self.contains(media_type) {
+            false
+        } else {
+            self.0.push(media_type.to_owned());
+            true
+        }
+    }
+
+    /// Add each media type in `other` that is not already present to the end
+    /// of the list
+    pub fn merge(&mut self, other: &AcceptList) {
+        for media_type in &other.0 {
+            self.push(media_type);
+        }
+    }
+
+    /// Returns `true` if the list contains the given media type
+    pub fn contains(&self, media_type: &str) -> bool {
+        let media_type = media_type.trim();
+        self.0.iter().any(|m| m.eq_ignore_ascii_case(media_type))
+    }
+
+    /// Returns `true` if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the media types in the list
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Convert the list into a value for an `Accept` header.  Returns `None`
+    /// if the list is empty or contains characters not permitted in a header.
+    pub fn to_header_value(&self) -> Option<HeaderValue> {
+        if self.is_empty() {
+            None
+        } else {
+            HeaderValue::from_str(&self.to_string()).ok()
+        }
+    }
+
+    /// [Private] Merge the list into the `Accept` header of `headers`,
+    /// leaving the headers unchanged if the list is empty
+    pub(crate) fn apply_to(&self, headers: &mut HeaderMap) {
+        if self.is_empty() {
+            return;
+        }
+        let mut accept = AcceptList::from_headers(headers);
+        accept.merge(self);
+        if let Some(value) = accept.to_header_value() {
+            headers.insert(ACCEPT, value);
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for AcceptList {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> AcceptList {
+        let mut accept = AcceptList::new();
+        accept.extend(iter);
+        accept
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for AcceptList {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for media_type in iter {
+            self.push(media_type);
+        }
+    }
+}
+
+impl fmt::Display for AcceptList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, media_type) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{media_type}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_to() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json, application/json"),
+        );
+        let extra = AcceptList::from_iter([
+            "application/JSON",
+            "application/vnd.github.mercy-preview+json",
+        ]);
+        extra.apply_to(&mut headers);
+        assert_eq!(
+            headers[ACCEPT],
+            "application/vnd.github+json, application/json, application/vnd.github.mercy-preview+json"
+        );
+    }
+
+    #[test]
+    fn apply_to_no_accept() {
+        let mut headers = HeaderMap::new();
+        AcceptList::new().apply_to(&mut headers);
+        assert!(!headers.contains_key(ACCEPT));
+        AcceptList::from_iter(["application/vnd.github.raw+json"]).apply_to(&mut headers);
+        assert_eq!(headers[ACCEPT], "application/vnd.github.raw+json");
+    }
+}
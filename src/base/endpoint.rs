@@ -1,23 +1,194 @@
-use crate::HttpUrl;
+use crate::{HttpUrl, ParseHttpUrlError};
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+use std::fmt;
+use thiserror::Error;
+use url::form_urlencoded;
+
+/// The characters percent-encoded in path segments when displaying an
+/// [`Endpoint::Path`]; this matches the set used by the `url` crate, plus `%`
+/// and `/`.
+pub(super) const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`')
+    .add(b'?')
+    .add(b'{')
+    .add(b'}')
+    .add(b'/')
+    .add(b'%');
 
 /// A description of an API endpoint to make a request to.
 ///
 /// This can be either a complete URL or a sequence of path components to
-/// append to the client's base API URL.
+/// append to one of the client's base URLs (the base API URL, unless the
+/// request's [`Request::base_url()`][crate::request::Request::base_url]
+/// method says otherwise).
+///
+/// How each kind of endpoint relates to the base URL is as follows:
+///
+/// - [`Endpoint::Path`] and [`Endpoint::PathWithQuery`] are relative to the
+///   base URL, including any path it has.
+///
+/// - [`Endpoint::Absolute`] is used as-is, whatever the base URL.
+///
+/// - [`Endpoint::Url`] is used as-is as long as this is unambiguous: a URL on
+///   the same host as the base URL but outside of the base URL's path is
+///   rejected when the request is prepared, as it most likely lacks the base
+///   path by mistake.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Endpoint {
-    /// A complete HTTP(S) URL.
+    /// A complete, absolute HTTP(S) URL that is expected to lie under the base
+    /// URL if it is on the same host.
     ///
     /// When an `Endpoint` of this type is used to make a request, the given
-    /// URL is used as-is for the request.
+    /// URL is used as-is for the request; the base URL's path is *not*
+    /// prepended to the URL's path.  If the base URL has a path (as on GitHub
+    /// Enterprise Server, e.g., `https://ghe.example.com/api/v3`) and the URL
+    /// has the same scheme, host, and port as the base URL but a path outside
+    /// of the base URL's path (e.g., `https://ghe.example.com/repos/...`),
+    /// clients refuse to make the request, returning an
+    /// [`ErrorPayload::InvalidEndpoint`][crate::errors::ErrorPayload::InvalidEndpoint]
+    /// error with [`EndpointError::OutsideBaseUrl`].  Use
+    /// [`Endpoint::Absolute`] for URLs that are meant to be outside of the
+    /// base URL's path.
+    ///
+    /// URLs on other hosts, such as `https://uploads.github.com`, are always
+    /// used as-is.
     Url(HttpUrl),
 
-    /// A sequence of path components.
+    /// A complete, absolute HTTP(S) URL that is used as-is, without regard to
+    /// the base URL.
+    ///
+    /// Unlike [`Endpoint::Url`], this is never checked against the base URL,
+    /// and so it can be used for, e.g., the `https://ghe.example.com/api/graphql`
+    /// endpoint of a GitHub Enterprise Server instance whose base API URL is
+    /// `https://ghe.example.com/api/v3`.
+    Absolute(HttpUrl),
+
+    /// A sequence of path components, relative to the client's base API URL
+    /// (or to whichever base URL is selected by the request's
+    /// [`Request::base_url()`][crate::request::Request::base_url] method).
     ///
     /// When an `Endpoint` of this type is used to make a request, the path
     /// components are appended to the client object's base API URL with
-    /// [`url::PathSegmentsMut::extend`].
+    /// [`url::PathSegmentsMut::extend`], after any path the base URL already
+    /// has.  For example, `["repos", "octocat", "hello-world"]` on a base URL
+    /// of `https://ghe.example.com/api/v3` results in a request to
+    /// `https://ghe.example.com/api/v3/repos/octocat/hello-world`.
+    ///
+    /// Each component is a single path segment: a forward slash in a
+    /// component is percent-encoded as `%2F` rather than treated as a
+    /// separator, so that, e.g., a branch name like `feature/x` can be used
+    /// as a component.  Components may not be empty or be `.` or `..` (as
+    /// they would be silently discarded).  Clients refuse to make requests to
+    /// endpoints that violate these rules, returning an
+    /// [`ErrorPayload::InvalidEndpoint`][crate::errors::ErrorPayload::InvalidEndpoint]
+    /// error instead.
     Path(Vec<String>),
+
+    /// A sequence of path components plus a list of fixed query parameters.
+    ///
+    /// The path is resolved exactly as for [`Endpoint::Path`] and is subject
+    /// to the same rules.  The query parameters are then appended to the
+    /// resulting URL, before any parameters returned by the request's
+    /// [`Request::params()`][crate::request::Request::params] and
+    /// [`Request::query()`][crate::request::Request::query] methods.
+    PathWithQuery {
+        /// The path components
+        path: Vec<String>,
+
+        /// The query parameters, as name-value pairs
+        query: Vec<(String, String)>,
+    },
+}
+
+impl Endpoint {
+    /// Check that the endpoint can be resolved against a base URL
+    /// unambiguously.  See the documentation of [`Endpoint::Path`] for the
+    /// rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the endpoint is a `Path` or `PathWithQuery` with an
+    /// invalid component.
+    pub fn validate(&self) -> Result<(), EndpointError> {
+        let path = match self {
+            Endpoint::Url(_) | Endpoint::Absolute(_) => return Ok(()),
+            Endpoint::Path(path) | Endpoint::PathWithQuery { path, .. } => path,
+        };
+        for seg in path {
+            if seg.is_empty() {
+                return Err(EndpointError::EmptySegment);
+            } else if seg == "." || seg == ".." {
+                return Err(EndpointError::DotSegment(seg.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the endpoint can be resolved against the base URL `base`
+    /// unambiguously.  In addition to the checks performed by
+    /// [`Endpoint::validate()`], this checks that an [`Endpoint::Url`] on the
+    /// same host as `base` lies under `base`'s path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if [`Endpoint::validate()`] fails or if the endpoint is
+    /// a `Url` outside of `base`'s path.
+    pub fn validate_against(&self, base: &HttpUrl) -> Result<(), EndpointError> {
+        match self {
+            Endpoint::Url(url) if is_outside_base(url, base) => {
+                Err(EndpointError::OutsideBaseUrl {
+                    url: Box::new(url.clone()),
+                    base: Box::new(base.clone()),
+                })
+            }
+            _ => self.validate(),
+        }
+    }
+
+    /// Add a fixed query parameter to the endpoint.  A `Path` is converted
+    /// into a `PathWithQuery`, while a `Url` has the parameter appended to
+    /// its query string.
+    pub fn with_query_param<K: Into<String>, V: Into<String>>(self, key: K, value: V) -> Endpoint {
+        let (key, value) = (key.into(), value.into());
+        match self {
+            Endpoint::Url(mut url) => {
+                url.append_query_param(&key, &value);
+                Endpoint::Url(url)
+            }
+            Endpoint::Absolute(mut url) => {
+                url.append_query_param(&key, &value);
+                Endpoint::Absolute(url)
+            }
+            Endpoint::Path(path) => Endpoint::PathWithQuery {
+                path,
+                query: vec![(key, value)],
+            },
+            Endpoint::PathWithQuery { path, mut query } => {
+                query.push((key, value));
+                Endpoint::PathWithQuery { path, query }
+            }
+        }
+    }
+}
+
+/// [Private] Returns true if `url` has the same origin as `base` but is not
+/// `base` or a URL under `base`'s path
+fn is_outside_base(url: &HttpUrl, base: &HttpUrl) -> bool {
+    let (url, base) = (url.as_url(), base.as_url());
+    if url.origin() != base.origin() {
+        return false;
+    }
+    let base_path = base.path().trim_end_matches('/');
+    let path = url.path();
+    !(path == base_path
+        || path
+            .strip_prefix(base_path)
+            .is_some_and(|rest| rest.starts_with('/')))
 }
 
 impl From<HttpUrl> for Endpoint {
@@ -32,3 +203,288 @@ impl<S: Into<String>> FromIterator<S> for Endpoint {
         Endpoint::Path(iter.into_iter().map(Into::into).collect())
     }
 }
+
+/// An `Endpoint` is displayed as either its URL or its path components
+/// joined with `/` (followed by `?` and the form-encoded query, if any), with
+/// each component percent-encoded so that the output can be parsed back into
+/// an equal `Endpoint`
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (path, query) = match self {
+            Endpoint::Url(url) | Endpoint::Absolute(url) => return write!(f, "{url}"),
+            Endpoint::Path(path) => (path, None),
+            Endpoint::PathWithQuery { path, query } => (path, Some(query)),
+        };
+        for (i, seg) in path.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", utf8_percent_encode(seg, PATH_SEGMENT))?;
+        }
+        if let Some(query) = query {
+            let query = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(query)
+                .finish();
+            write!(f, "?{query}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Endpoint {
+    type Err = ParseEndpointError;
+
+    /// Parse a string into an `Endpoint`.  Strings containing `://` are
+    /// parsed as [`Endpoint::Url`]s; all other strings are parsed as
+    /// [`Endpoint::Path`]s by splitting on `/` (ignoring a single leading &
+    /// trailing slash) and percent-decoding each component.  If the path is
+    /// followed by a `?` and a form-encoded query string, the result is an
+    /// [`Endpoint::PathWithQuery`] instead.  The resulting path must pass
+    /// [`Endpoint::validate()`].
+    fn from_str(s: &str) -> Result<Endpoint, ParseEndpointError> {
+        if s.contains("://") {
+            return Ok(Endpoint::Url(s.parse::<HttpUrl>()?));
+        }
+        let (s, query) = match s.split_once('?') {
+            Some((path, query)) => (
+                path,
+                Some(
+                    form_urlencoded::parse(query.as_bytes())
+                        .into_owned()
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+            None => (s, None),
+        };
+        let s = s.strip_prefix('/').unwrap_or(s);
+        let s = s.strip_suffix('/').unwrap_or(s);
+        let path = if s.is_empty() {
+            Vec::new()
+        } else {
+            s.split('/')
+                .map(|seg| {
+                    percent_decode_str(seg)
+                        .decode_utf8()
+                        .map(Into::into)
+                        .map_err(|_| ParseEndpointError::Utf8(seg.to_owned()))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let endpoint = match query {
+            Some(query) => Endpoint::PathWithQuery { path, query },
+            None => Endpoint::Path(path),
+        };
+        endpoint.validate()?;
+        Ok(endpoint)
+    }
+}
+
+/// Error returned by [`Endpoint`]'s `FromStr` implementation
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ParseEndpointError {
+    /// The string was not a valid HTTP(S) URL
+    #[error("invalid endpoint URL")]
+    Url(#[from] ParseHttpUrlError),
+
+    /// A path component did not decode to valid UTF-8
+    #[error("endpoint path component {0:?} does not percent-decode to UTF-8")]
+    Utf8(String),
+
+    /// The path was not a valid endpoint path
+    #[error(transparent)]
+    Path(#[from] EndpointError),
+}
+
+/// Error returned by [`Endpoint::validate()`]
+///
+/// New variants may be added in future versions.
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum EndpointError {
+    /// A path component was empty
+    #[error("endpoint path contains an empty component")]
+    EmptySegment,
+
+    /// A path component was `.` or `..`
+    #[error("endpoint path component {0:?} is not allowed")]
+    DotSegment(String),
+
+    /// An [`Endpoint::Url`] had the same scheme, host, and port as the base
+    /// URL it was resolved against but was outside of the base URL's path
+    #[error(
+        "endpoint URL {url} is on the same host as base URL {base} but outside of its path; use Endpoint::Absolute if this is intended"
+    )]
+    OutsideBaseUrl {
+        url: Box<HttpUrl>,
+        base: Box<HttpUrl>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&["repos", "octocat", "hello-world"])]
+    #[case(&["search", "code"])]
+    #[case(&["repos", "octocat", "hello-world", "contents", "foo%bar"])]
+    #[case(&["repos", "octocat", "hello-world", "branches", "feature/x"])]
+    fn validate_ok(#[case] path: &[&str]) {
+        assert_eq!(Endpoint::from_iter(path.iter().copied()).validate(), Ok(()));
+    }
+
+    #[rstest]
+    #[case(&["repos", "", "hello-world"], EndpointError::EmptySegment)]
+    #[case(&["repos", "octocat", ".."], EndpointError::DotSegment("..".into()))]
+    #[case(&[".", "repos"], EndpointError::DotSegment(".".into()))]
+    fn validate_err(#[case] path: &[&str], #[case] err: EndpointError) {
+        assert_eq!(
+            Endpoint::from_iter(path.iter().copied()).validate(),
+            Err(err)
+        );
+    }
+
+    #[rstest]
+    #[case("repos/octocat/hello-world", &["repos", "octocat", "hello-world"])]
+    #[case("/repos/octocat/hello-world/", &["repos", "octocat", "hello-world"])]
+    #[case("repos/octocat/hello-world/contents/foo%20bar%25", &["repos", "octocat", "hello-world", "contents", "foo bar%"])]
+    #[case("repos/octocat/hello-world/branches/feature%2Fx", &["repos", "octocat", "hello-world", "branches", "feature/x"])]
+    #[case("", &[])]
+    fn parse_path(#[case] s: &str, #[case] path: &[&str]) {
+        let endpoint = s.parse::<Endpoint>().unwrap();
+        assert_eq!(endpoint, Endpoint::from_iter(path.iter().copied()));
+        assert_eq!(endpoint.to_string(), s.trim_matches('/'));
+    }
+
+    #[test]
+    fn parse_path_with_query() {
+        let endpoint = "repos/octocat/hello-world/issues?state=all&labels=bug%2Cgood+first+issue"
+            .parse::<Endpoint>()
+            .unwrap();
+        assert_eq!(
+            endpoint,
+            Endpoint::PathWithQuery {
+                path: vec![
+                    "repos".into(),
+                    "octocat".into(),
+                    "hello-world".into(),
+                    "issues".into()
+                ],
+                query: vec![
+                    ("state".into(), "all".into()),
+                    ("labels".into(), "bug,good first issue".into()),
+                ],
+            }
+        );
+        assert_eq!(
+            endpoint.to_string(),
+            "repos/octocat/hello-world/issues?state=all&labels=bug%2Cgood+first+issue"
+        );
+        assert_eq!(
+            endpoint,
+            Endpoint::from_iter(["repos", "octocat", "hello-world", "issues"])
+                .with_query_param("state", "all")
+                .with_query_param("labels", "bug,good first issue")
+        );
+    }
+
+    #[test]
+    fn parse_url() {
+        let endpoint = "https://api.github.com/repos/octocat/hello-world?per_page=100"
+            .parse::<Endpoint>()
+            .unwrap();
+        assert_eq!(
+            endpoint,
+            Endpoint::Url(
+                "https://api.github.com/repos/octocat/hello-world?per_page=100"
+                    .parse()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            endpoint.to_string(),
+            "https://api.github.com/repos/octocat/hello-world?per_page=100"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "repos//hello-world",
+        ParseEndpointError::Path(EndpointError::EmptySegment)
+    )]
+    #[case("repos/../hello-world", ParseEndpointError::Path(EndpointError::DotSegment("..".into())))]
+    #[case("repos/%FF", ParseEndpointError::Utf8("%FF".into()))]
+    #[case("repos/../hello-world?q=1", ParseEndpointError::Path(EndpointError::DotSegment("..".into())))]
+    #[case(
+        "ftp://example.com/foo",
+        ParseEndpointError::Url(ParseHttpUrlError::NotHttp)
+    )]
+    fn parse_err(#[case] s: &str, #[case] err: ParseEndpointError) {
+        assert_eq!(s.parse::<Endpoint>(), Err(err));
+    }
+
+    #[rstest]
+    #[case("https://ghe.example.com/api/v3", "https://ghe.example.com/api/v3")]
+    #[case(
+        "https://ghe.example.com/api/v3/",
+        "https://ghe.example.com/api/v3/repos/octocat/hello-world"
+    )]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "https://ghe.example.com/api/v3/repos/octocat/hello-world?page=2"
+    )]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "https://uploads.ghe.example.com/repos/octocat/hello-world"
+    )]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "http://ghe.example.com/repos/octocat/hello-world"
+    )]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "https://ghe.example.com:8443/repos/octocat/hello-world"
+    )]
+    #[case(
+        "https://api.github.com",
+        "https://api.github.com/repos/octocat/hello-world"
+    )]
+    fn validate_against_ok(#[case] base: HttpUrl, #[case] url: HttpUrl) {
+        assert_eq!(Endpoint::Url(url).validate_against(&base), Ok(()));
+    }
+
+    #[rstest]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "https://ghe.example.com/repos/octocat/hello-world"
+    )]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "https://ghe.example.com/api/graphql"
+    )]
+    #[case(
+        "https://ghe.example.com/api/v3",
+        "https://ghe.example.com/api/v30/repos"
+    )]
+    #[case("https://ghe.example.com:443/api/v3", "https://ghe.example.com/api")]
+    fn validate_against_outside_base(#[case] base: HttpUrl, #[case] url: HttpUrl) {
+        assert_eq!(
+            Endpoint::Url(url.clone()).validate_against(&base),
+            Err(EndpointError::OutsideBaseUrl {
+                url: Box::new(url.clone()),
+                base: Box::new(base.clone())
+            })
+        );
+        assert_eq!(Endpoint::Absolute(url).validate_against(&base), Ok(()));
+    }
+
+    #[test]
+    fn validate_against_invalid_path() {
+        let base = "https://ghe.example.com/api/v3".parse::<HttpUrl>().unwrap();
+        assert_eq!(
+            Endpoint::from_iter(["repos", ".."]).validate_against(&base),
+            Err(EndpointError::DotSegment("..".into()))
+        );
+    }
+}
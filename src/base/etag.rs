@@ -0,0 +1,236 @@
+use serde::{
+    Deserialize, Serialize,
+    de::{Deserializer, Error},
+    ser::Serializer,
+};
+use std::fmt;
+use thiserror::Error;
+
+/// An HTTP entity tag, as used in `ETag`, `If-Match`, and `If-None-Match`
+/// headers
+///
+/// An entity tag is either strong or weak (indicated by a `W/` prefix in its
+/// serialized form).  Two entity tags can be compared in one of two ways:
+///
+/// - [`EntityTag::strong_eq()`] — both tags must be strong and have the same
+///   opaque tag.  This is the comparison used for `If-Match` and `If-Range`.
+///
+/// - [`EntityTag::weak_eq()`] — the tags must have the same opaque tag,
+///   regardless of whether either is weak.  This is the comparison used for
+///   `If-None-Match`.
+///
+/// Note that the derived `PartialEq` implementation compares both the
+/// weakness and the opaque tag and thus does not correspond to either of the
+/// above.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EntityTag {
+    weak: bool,
+    tag: String,
+}
+
+impl EntityTag {
+    /// Construct a strong entity tag from an opaque tag (without surrounding
+    /// double quotes)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `tag` contains a double quote or any characters that
+    /// are not allowed in an entity tag.
+    pub fn strong<S: Into<String>>(tag: S) -> Result<EntityTag, ParseEntityTagError> {
+        EntityTag::new(false, tag.into())
+    }
+
+    /// Construct a weak entity tag from an opaque tag (without surrounding
+    /// double quotes)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `tag` contains a double quote or any characters that
+    /// are not allowed in an entity tag.
+    pub fn weak<S: Into<String>>(tag: S) -> Result<EntityTag, ParseEntityTagError> {
+        EntityTag::new(true, tag.into())
+    }
+
+    fn new(weak: bool, tag: String) -> Result<EntityTag, ParseEntityTagError> {
+        if tag.bytes().all(is_etagc) {
+            Ok(EntityTag { weak, tag })
+        } else {
+            Err(ParseEntityTagError)
+        }
+    }
+
+    /// Returns true if the entity tag is weak
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Returns true if the entity tag is strong
+    pub fn is_strong(&self) -> bool {
+        !self.weak
+    }
+
+    /// Returns the opaque tag, without the weakness indicator or surrounding
+    /// double quotes
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns true if both entity tags are strong and their opaque tags are
+    /// equal
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        self.is_strong() && other.is_strong() && self.tag == other.tag
+    }
+
+    /// Returns true if the opaque tags of both entity tags are equal,
+    /// regardless of weakness
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+
+    /// Format a list of entity tags as a value for an `If-Match` or
+    /// `If-None-Match` header.  An empty list is formatted as `*`, which
+    /// matches any current representation of the resource.
+    pub fn list_header_value(tags: &[EntityTag]) -> http::header::HeaderValue {
+        let s = if tags.is_empty() {
+            String::from("*")
+        } else {
+            tags.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        to_header_value(s)
+    }
+}
+
+/// [Private] Convert a string of formatted entity tags to a `HeaderValue`
+fn to_header_value(s: String) -> http::header::HeaderValue {
+    s.parse()
+        .expect("formatted entity tags should be a valid HeaderValue")
+}
+
+/// Returns true if `b` is allowed in the opaque tag of an entity tag (`etagc`
+/// in RFC 9110)
+fn is_etagc(b: u8) -> bool {
+    b == 0x21 || (0x23..=0x7E).contains(&b) || b >= 0x80
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+impl std::str::FromStr for EntityTag {
+    type Err = ParseEntityTagError;
+
+    /// Parse an entity tag in the form `"tag"` or `W/"tag"`.  Leading &
+    /// trailing whitespace is ignored.
+    fn from_str(s: &str) -> Result<EntityTag, ParseEntityTagError> {
+        let s = s.trim();
+        let (weak, s) = match s.strip_prefix("W/") {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let tag = s
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .ok_or(ParseEntityTagError)?;
+        EntityTag::new(weak, tag.to_owned())
+    }
+}
+
+impl From<EntityTag> for http::header::HeaderValue {
+    fn from(value: EntityTag) -> http::header::HeaderValue {
+        to_header_value(value.to_string())
+    }
+}
+
+impl Serialize for EntityTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<EntityTag>().map_err(D::Error::custom)
+    }
+}
+
+/// Error returned when parsing or constructing an invalid [`EntityTag`]
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, PartialEq)]
+#[error("invalid entity tag")]
+pub struct ParseEntityTagError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(r#""abc123""#, false, "abc123")]
+    #[case(r#"W/"abc123""#, true, "abc123")]
+    #[case(r#""""#, false, "")]
+    #[case(r#"  "abc"  "#, false, "abc")]
+    fn parse(#[case] s: &str, #[case] weak: bool, #[case] tag: &str) {
+        let etag = s.parse::<EntityTag>().unwrap();
+        assert_eq!(etag.is_weak(), weak);
+        assert_eq!(etag.tag(), tag);
+    }
+
+    #[rstest]
+    #[case("abc123")]
+    #[case(r#"w/"abc123""#)]
+    #[case("W/abc123")]
+    #[case(r#""abc"123""#)]
+    #[case(r#""abc 123""#)]
+    #[case(r#"""#)]
+    fn parse_invalid(#[case] s: &str) {
+        assert_eq!(s.parse::<EntityTag>(), Err(ParseEntityTagError));
+    }
+
+    #[rstest]
+    #[case(r#""1""#, r#""1""#, true, true)]
+    #[case(r#""1""#, r#"W/"1""#, false, true)]
+    #[case(r#"W/"1""#, r#"W/"1""#, false, true)]
+    #[case(r#"W/"1""#, r#"W/"2""#, false, false)]
+    #[case(r#""1""#, r#""2""#, false, false)]
+    fn comparison(
+        #[case] a: EntityTag,
+        #[case] b: EntityTag,
+        #[case] strong: bool,
+        #[case] weak: bool,
+    ) {
+        assert_eq!(a.strong_eq(&b), strong);
+        assert_eq!(b.strong_eq(&a), strong);
+        assert_eq!(a.weak_eq(&b), weak);
+        assert_eq!(b.weak_eq(&a), weak);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            EntityTag::strong("xyzzy").unwrap().to_string(),
+            r#""xyzzy""#
+        );
+        assert_eq!(
+            EntityTag::weak("xyzzy").unwrap().to_string(),
+            r#"W/"xyzzy""#
+        );
+    }
+
+    #[test]
+    fn list_header_value() {
+        assert_eq!(EntityTag::list_header_value(&[]), "*");
+        let tags = [
+            EntityTag::strong("a").unwrap(),
+            EntityTag::weak("b").unwrap(),
+        ];
+        assert_eq!(EntityTag::list_header_value(&tags), r#""a", W/"b""#);
+    }
+}
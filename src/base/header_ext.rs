@@ -1,5 +1,6 @@
-use crate::{HttpUrl, util::get_page_number};
-use mime::{JSON, Mime};
+use crate::{EntityTag, HttpUrl, util::get_page_number};
+use std::collections::HashMap;
+use url::Url;
 
 /// Additional utility methods added to [`http::header::HeaderMap`]
 pub trait HeaderMapExt {
@@ -15,21 +16,33 @@ pub trait HeaderMapExt {
     /// Set the value of the `Content-Length` header to the given integer value.
     fn set_content_length(&mut self, length: u64);
 
+    /// Returns the value of the `ETag` header as an [`EntityTag`].  Returns
+    /// `None` if the header is not set or the value could not be parsed.
+    fn etag(&self) -> Option<EntityTag>;
+
     /// Parse the value of the `Link` header and return the links with
     /// `rel` types of "first", "prev", "next", and "last".  If there is no
     /// `Link` header or it could not be parsed, all fields in the returned
     /// structure are `None`.
     fn pagination_links(&self) -> PaginationLinks;
+
+    /// Returns the value of the `x-ratelimit-remaining` header as a `u64`.
+    /// Returns `None` if the header is not set or the value could not be
+    /// parsed into a `u64`.
+    fn ratelimit_remaining(&self) -> Option<u64>;
+
+    /// Returns the value of the `x-ratelimit-reset` header, the time at which
+    /// the current rate limit window resets in seconds since the Unix epoch,
+    /// as a `u64`.  Returns `None` if the header is not set or the value
+    /// could not be parsed into a `u64`.
+    fn ratelimit_reset(&self) -> Option<u64>;
 }
 
 impl HeaderMapExt for http::header::HeaderMap {
     fn content_type_is_json(&self) -> bool {
         self.get(http::header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.parse::<Mime>().ok())
-            .is_some_and(|ct| {
-                ct.type_() == "application" && (ct.subtype() == "json" || ct.suffix() == Some(JSON))
-            })
+            .is_some_and(is_json_media_type)
     }
 
     #[allow(clippy::return_and_then)]
@@ -49,31 +62,115 @@ impl HeaderMapExt for http::header::HeaderMap {
         );
     }
 
+    #[allow(clippy::return_and_then)]
+    fn etag(&self) -> Option<EntityTag> {
+        self.get(http::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<EntityTag>().ok())
+    }
+
+    #[allow(clippy::return_and_then)]
+    fn ratelimit_remaining(&self) -> Option<u64> {
+        self.get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    }
+
+    #[allow(clippy::return_and_then)]
+    fn ratelimit_reset(&self) -> Option<u64> {
+        self.get("x-ratelimit-reset")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    }
+
     fn pagination_links(&self) -> PaginationLinks {
         let Some(mut links) = self
             .get(http::header::LINK)
             .and_then(|v| v.to_str().ok())
-            .and_then(|v| parse_link_header::parse_with_rel(v).ok())
+            .and_then(parse_links)
         else {
             return PaginationLinks::default();
         };
         PaginationLinks {
             first: links
                 .remove("first")
-                .and_then(|lnk| HttpUrl::try_from(lnk.uri).ok()),
+                .and_then(|url| HttpUrl::try_from(url).ok()),
             prev: links
                 .remove("prev")
-                .and_then(|lnk| HttpUrl::try_from(lnk.uri).ok()),
+                .and_then(|url| HttpUrl::try_from(url).ok()),
             next: links
                 .remove("next")
-                .and_then(|lnk| HttpUrl::try_from(lnk.uri).ok()),
+                .and_then(|url| HttpUrl::try_from(url).ok()),
             last: links
                 .remove("last")
-                .and_then(|lnk| HttpUrl::try_from(lnk.uri).ok()),
+                .and_then(|url| HttpUrl::try_from(url).ok()),
         }
     }
 }
 
+/// [Private] Returns true if the given `Content-Type` value is
+/// "application/json" or "application/{something}+json"
+#[cfg(feature = "mime")]
+fn is_json_media_type(value: &str) -> bool {
+    use mime::{JSON, Mime};
+    value.parse::<Mime>().is_ok_and(|ct| {
+        ct.type_() == "application" && (ct.subtype() == "json" || ct.suffix() == Some(JSON))
+    })
+}
+
+/// [Private] Returns true if the given `Content-Type` value is
+/// "application/json" or "application/{something}+json"
+///
+/// Without the `mime` crate, only the media type before any parameters is
+/// examined, and the value is otherwise not validated.
+#[cfg(not(feature = "mime"))]
+fn is_json_media_type(value: &str) -> bool {
+    let essence = value.split(';').next().unwrap_or_default();
+    let Some((type_, subtype)) = essence.trim().split_once('/') else {
+        return false;
+    };
+    let subtype = subtype.to_ascii_lowercase();
+    type_.eq_ignore_ascii_case("application") && (subtype == "json" || subtype.ends_with("+json"))
+}
+
+/// [Private] Parse a `Link` header value into a map from `rel` types to URLs.
+/// Returns `None` if the value could not be parsed.
+#[cfg(feature = "link-header")]
+fn parse_links(value: &str) -> Option<HashMap<String, Url>> {
+    parse_link_header::parse_with_rel(value)
+        .ok()
+        .map(|links| links.into_iter().map(|(rel, lnk)| (rel, lnk.uri)).collect())
+}
+
+/// [Private] Parse a `Link` header value into a map from `rel` types to URLs.
+/// Returns `None` if the value could not be parsed.
+///
+/// Without the `parse_link_header` crate, only the subset of the `Link` syntax
+/// used by GitHub is supported: each link must consist of a URL in angle
+/// brackets followed by semicolon-separated parameters that do not contain
+/// commas.
+#[cfg(not(feature = "link-header"))]
+fn parse_links(value: &str) -> Option<HashMap<String, Url>> {
+    let mut links = HashMap::new();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (target, after) = rest.strip_prefix('<')?.split_once('>')?;
+        let (params, after) = after.split_once(',').unwrap_or((after, ""));
+        let url = Url::parse(target).ok()?;
+        for param in params.split(';') {
+            if let Some((name, rels)) = param.split_once('=')
+                && name.trim().eq_ignore_ascii_case("rel")
+            {
+                for rel in rels.trim().trim_matches('"').split_ascii_whitespace() {
+                    links.insert(rel.to_owned(), url.clone());
+                }
+            }
+        }
+        rest = after.trim_start();
+    }
+    Some(links)
+}
+
 /// A set of pagination-related URLs parsed from a `Link` header
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct PaginationLinks {
@@ -127,3 +224,76 @@ impl PaginationLinks {
         self.last.as_ref().and_then(get_page_number)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::{HeaderMap, HeaderValue};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("application/json", true)]
+    #[case("application/json; charset=utf-8", true)]
+    #[case("Application/JSON", true)]
+    #[case("application/vnd.github+json", true)]
+    #[case("text/json", false)]
+    #[case("application/jsonx", false)]
+    #[case("text/plain", false)]
+    #[case("json", false)]
+    fn test_content_type_is_json(#[case] value: &'static str, #[case] r: bool) {
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::CONTENT_TYPE, HeaderValue::from_static(value));
+        assert_eq!(headers.content_type_is_json(), r);
+    }
+
+    #[test]
+    fn test_pagination_links() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::LINK,
+            HeaderValue::from_static(concat!(
+                r#"<https://api.github.com/issues?labels=bug&page=2>; rel="prev", "#,
+                r#"<https://api.github.com/issues?labels=bug&page=4>; rel="next", "#,
+                r#"<https://api.github.com/issues?labels=bug&page=5>; rel="last", "#,
+                r#"<https://api.github.com/issues?labels=bug&page=1>; rel="first""#,
+            )),
+        );
+        let links = headers.pagination_links();
+        assert_eq!(links.first_page_number(), Some(1));
+        assert_eq!(links.prev_page_number(), Some(2));
+        assert_eq!(links.next_page_number(), Some(4));
+        assert_eq!(links.last_page_number(), Some(5));
+        assert_eq!(
+            links.next.unwrap().as_str(),
+            "https://api.github.com/issues?labels=bug&page=4"
+        );
+    }
+
+    #[cfg(not(feature = "link-header"))]
+    #[test]
+    fn test_pagination_links_with_commas() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::LINK,
+            HeaderValue::from_static(concat!(
+                r#"<https://api.github.com/issues?labels=bug,ui&page=2>; rel="next", "#,
+                r#"<https://api.github.com/issues?labels=bug,ui&page=5>; rel="last""#,
+            )),
+        );
+        let links = headers.pagination_links();
+        assert_eq!(links.last_page_number(), Some(5));
+        assert_eq!(
+            links.next.unwrap().as_str(),
+            "https://api.github.com/issues?labels=bug,ui&page=2"
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("https://api.github.com/issues?page=2; rel=\"next\"")]
+    fn test_pagination_links_invalid(#[case] value: &'static str) {
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::LINK, HeaderValue::from_static(value));
+        assert_eq!(headers.pagination_links(), PaginationLinks::default());
+    }
+}
@@ -69,12 +69,20 @@ impl HttpUrl {
 
     pub fn join_endpoint(&self, endpoint: Endpoint) -> HttpUrl {
         match endpoint {
-            Endpoint::Url(url) => url,
+            Endpoint::Url(url) | Endpoint::Absolute(url) => url,
             Endpoint::Path(path) => {
                 let mut url = self.clone();
                 url.extend(path);
                 url
             }
+            Endpoint::PathWithQuery { path, query } => {
+                let mut url = self.clone();
+                url.extend(path);
+                for (key, value) in query {
+                    url.append_query_param(&key, &value);
+                }
+                url
+            }
         }
     }
 
@@ -104,12 +112,13 @@ impl TryFrom<Url> for HttpUrl {
     }
 }
 
-impl From<HttpUrl> for http::uri::Uri {
-    fn from(value: HttpUrl) -> http::uri::Uri {
-        value
-            .to_string()
-            .parse::<http::uri::Uri>()
-            .expect("HttpUrl should be a valid URI")
+impl TryFrom<HttpUrl> for http::uri::Uri {
+    type Error = http::uri::InvalidUri;
+
+    /// Convert an `HttpUrl` to an [`http::uri::Uri`].  This fails if the URL
+    /// is one that `http` cannot represent, such as one longer than 64 KiB.
+    fn try_from(value: HttpUrl) -> Result<http::uri::Uri, http::uri::InvalidUri> {
+        http::uri::Uri::try_from(String::from(value.0))
     }
 }
 
@@ -237,4 +246,28 @@ mod tests {
             "https://api.github.com/foo?bar=baz&quux=with+space&bar=rod"
         );
     }
+
+    #[test]
+    fn join_endpoint_with_query() {
+        let base = "https://api.github.com/base".parse::<HttpUrl>().unwrap();
+        let endpoint = Endpoint::from_iter(["foo", "bar"])
+            .with_query_param("state", "all")
+            .with_query_param("q", "a b");
+        assert_eq!(
+            base.join_endpoint(endpoint).as_str(),
+            "https://api.github.com/base/foo/bar?state=all&q=a+b"
+        );
+    }
+
+    #[test]
+    fn to_uri() {
+        let url = "https://api.github.com/foo?bar=baz"
+            .parse::<HttpUrl>()
+            .unwrap();
+        let uri = http::uri::Uri::try_from(url).unwrap();
+        assert_eq!(uri.to_string(), "https://api.github.com/foo?bar=baz");
+        let mut url = "https://api.github.com".parse::<HttpUrl>().unwrap();
+        url.push("x".repeat(70_000));
+        assert!(http::uri::Uri::try_from(url).is_err());
+    }
 }
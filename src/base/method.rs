@@ -1,8 +1,14 @@
 use std::fmt;
 use thiserror::Error;
 
-/// An enum of the HTTP methods supported by the GitHub REST API
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+/// An enum of HTTP methods.
+///
+/// The methods used by the GitHub REST API have dedicated variants, as does
+/// `OPTIONS`, which some gateways in front of GitHub Enterprise Server
+/// deployments require for CORS preflight requests.  Any other method (e.g.,
+/// one used by a GitHub Enterprise Server extension) can be given via
+/// [`Method::Other`].
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub enum Method {
     Get,
     Head,
@@ -10,11 +16,21 @@ pub enum Method {
     Put,
     Patch,
     Delete,
+    Options,
+
+    /// Any other method.
+    ///
+    /// This variant should not be used for methods that have their own
+    /// variants, as, e.g., `Method::Other(http::Method::GET)` does not compare
+    /// equal to `Method::Get`.  Converting from an [`http::Method`] or parsing
+    /// a string always produces the dedicated variant when there is one.
+    Other(http::Method),
 }
 
 impl Method {
-    /// Returns the name of the method as an uppercase ASCII string
-    pub fn as_str(&self) -> &'static str {
+    /// Returns the name of the method.  For the dedicated variants, this is
+    /// an uppercase ASCII string.
+    pub fn as_str(&self) -> &str {
         match self {
             Method::Get => "GET",
             Method::Head => "HEAD",
@@ -22,16 +38,19 @@ impl Method {
             Method::Put => "PUT",
             Method::Patch => "PATCH",
             Method::Delete => "DELETE",
+            Method::Options => "OPTIONS",
+            Method::Other(m) => m.as_str(),
         }
     }
 
-    /// Returns true if this is a mutating method (i.e., POST, PUT, PATCH, or
-    /// DELETE).
+    /// Returns true if this is a mutating method (i.e., POST, PUT, PATCH,
+    /// DELETE, or any other method not defined as "safe" by RFC 9110).
     pub fn is_mutating(&self) -> bool {
-        matches!(
-            self,
-            Method::Post | Method::Put | Method::Patch | Method::Delete
-        )
+        match self {
+            Method::Get | Method::Head | Method::Options => false,
+            Method::Post | Method::Put | Method::Patch | Method::Delete => true,
+            Method::Other(m) => !m.is_safe(),
+        }
     }
 }
 
@@ -44,7 +63,9 @@ impl fmt::Display for Method {
 impl std::str::FromStr for Method {
     type Err = ParseMethodError;
 
-    /// Parse a method from its name, case insensitive
+    /// Parse a method from its name.  The names of the methods with dedicated
+    /// variants are matched case-insensitively; any other valid method name is
+    /// converted to [`Method::Other`] as-is.
     fn from_str(s: &str) -> Result<Method, ParseMethodError> {
         match s.to_ascii_uppercase().as_str() {
             "GET" => Ok(Method::Get),
@@ -53,7 +74,10 @@ impl std::str::FromStr for Method {
             "PUT" => Ok(Method::Put),
             "PATCH" => Ok(Method::Patch),
             "DELETE" => Ok(Method::Delete),
-            _ => Err(ParseMethodError),
+            "OPTIONS" => Ok(Method::Options),
+            _ => http::Method::from_bytes(s.as_bytes())
+                .map(Method::Other)
+                .map_err(|_| ParseMethodError),
         }
     }
 }
@@ -68,28 +92,25 @@ impl From<Method> for http::Method {
             Method::Put => http::Method::PUT,
             Method::Patch => http::Method::PATCH,
             Method::Delete => http::Method::DELETE,
+            Method::Options => http::Method::OPTIONS,
+            Method::Other(m) => m,
         }
     }
 }
 
-impl TryFrom<http::Method> for Method {
-    type Error = MethodConvertError;
-
-    /// Convert an [`http::Method`] to a `Method`
-    ///
-    /// # Errors
-    ///
-    /// Returns `Err` if the input method does not correspond to one of the
-    /// variants of `Method`.
-    fn try_from(value: http::Method) -> Result<Method, MethodConvertError> {
+impl From<http::Method> for Method {
+    /// Convert an [`http::Method`] to a `Method`, using [`Method::Other`] only
+    /// for methods without a dedicated variant
+    fn from(value: http::Method) -> Method {
         match value {
-            http::Method::GET => Ok(Method::Get),
-            http::Method::HEAD => Ok(Method::Head),
-            http::Method::POST => Ok(Method::Post),
-            http::Method::PUT => Ok(Method::Put),
-            http::Method::PATCH => Ok(Method::Patch),
-            http::Method::DELETE => Ok(Method::Delete),
-            other => Err(MethodConvertError(other)),
+            http::Method::GET => Method::Get,
+            http::Method::HEAD => Method::Head,
+            http::Method::POST => Method::Post,
+            http::Method::PUT => Method::Put,
+            http::Method::PATCH => Method::Patch,
+            http::Method::DELETE => Method::Delete,
+            http::Method::OPTIONS => Method::Options,
+            other => Method::Other(other),
         }
     }
 }
@@ -99,15 +120,6 @@ impl TryFrom<http::Method> for Method {
 #[error("invalid method name")]
 pub struct ParseMethodError;
 
-/// Error returned when trying to convert an [`http::Method`] that does not
-/// exist in [`Method`] to the latter type
-#[derive(Clone, Debug, Eq, Error, PartialEq)]
-#[error("method {0} is not supported by ghreq")]
-pub struct MethodConvertError(
-    /// The input [`http::Method`] that could not be converted
-    pub http::Method,
-);
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +132,7 @@ mod tests {
     #[case(Method::Put)]
     #[case(Method::Patch)]
     #[case(Method::Delete)]
+    #[case(Method::Options)]
     fn parse_display_roundtrip(#[case] m: Method) {
         assert_eq!(m.to_string().parse::<Method>().unwrap(), m);
     }
@@ -146,25 +159,48 @@ mod tests {
     #[case("dELETE", Method::Delete)]
     #[case("DeLeTe", Method::Delete)]
     #[case("dElEtE", Method::Delete)]
+    #[case("options", Method::Options)]
+    #[case("Options", Method::Options)]
+    #[case("oPTIONS", Method::Options)]
     fn parse_crazy_casing(#[case] s: &str, #[case] m: Method) {
         assert_eq!(s.parse::<Method>().unwrap(), m);
     }
 
     #[rstest]
-    #[case("CONNECT")]
-    #[case("OPTIONS")]
-    #[case("TRACE")]
-    #[case("PROPFIND")]
-    fn parse_unsupported(#[case] s: &str) {
-        assert!(s.parse::<Method>().is_err());
+    #[case("CONNECT", http::Method::CONNECT)]
+    #[case("TRACE", http::Method::TRACE)]
+    #[case("PROPFIND", http::Method::from_bytes(b"PROPFIND").unwrap())]
+    fn parse_other(#[case] s: &str, #[case] m: http::Method) {
+        let method = s.parse::<Method>().unwrap();
+        assert_eq!(method, Method::Other(m));
+        assert_eq!(method.to_string(), s);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("GET /")]
+    #[case("{}")]
+    fn parse_invalid(#[case] s: &str) {
+        assert_eq!(s.parse::<Method>(), Err(ParseMethodError));
+    }
+
+    #[rstest]
+    #[case(http::Method::GET, Method::Get)]
+    #[case(http::Method::DELETE, Method::Delete)]
+    #[case(http::Method::OPTIONS, Method::Options)]
+    #[case(http::Method::TRACE, Method::Other(http::Method::TRACE))]
+    fn from_http(#[case] m: http::Method, #[case] method: Method) {
+        assert_eq!(Method::from(m.clone()), method);
+        assert_eq!(http::Method::from(method), m);
     }
 
     #[rstest]
-    #[case(http::Method::CONNECT)]
-    #[case(http::Method::OPTIONS)]
-    #[case(http::Method::TRACE)]
-    fn try_from_unsupported(#[case] m: http::Method) {
-        let m2 = m.clone();
-        assert_eq!(Method::try_from(m), Err(MethodConvertError(m2)));
+    #[case(Method::Get, false)]
+    #[case(Method::Post, true)]
+    #[case(Method::Options, false)]
+    #[case(Method::Other(http::Method::TRACE), false)]
+    #[case(Method::Other(http::Method::from_bytes(b"PROPFIND").unwrap()), true)]
+    fn is_mutating(#[case] m: Method, #[case] mutating: bool) {
+        assert_eq!(m.is_mutating(), mutating);
     }
 }
@@ -1,8 +1,18 @@
+mod accept;
 mod endpoint;
+mod etag;
 mod header_ext;
 mod http_url;
 mod method;
+mod template;
+mod timestamp;
+mod uri_template;
+pub use self::accept::*;
 pub use self::endpoint::*;
+pub use self::etag::*;
 pub use self::header_ext::*;
 pub use self::http_url::*;
 pub use self::method::*;
+pub use self::template::*;
+pub use self::timestamp::*;
+pub use self::uri_template::*;
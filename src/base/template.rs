@@ -0,0 +1,288 @@
+use super::endpoint::PATH_SEGMENT;
+use crate::{Endpoint, EndpointError};
+use percent_encoding::{percent_decode_str, utf8_percent_encode};
+use std::fmt;
+use thiserror::Error;
+
+/// A template for an [`Endpoint::Path`] containing `{name}` placeholders,
+/// such as `"repos/{owner}/{repo}/issues"`
+///
+/// A template is parsed from a string by splitting it on `/` (ignoring a
+/// single leading & trailing slash); each resulting component may contain any
+/// number of placeholders alongside literal text, which is percent-decoded.
+/// Filling in the placeholders with [`EndpointTemplate::fill()`] produces an
+/// `Endpoint` in which each component is a single path segment, so values
+/// containing `?`, `#`, spaces, etc. are percent-encoded when the endpoint is
+/// turned into a URL rather than altering the URL's structure.  This includes
+/// `/`, so a value like the branch name `feature/x` stays a single segment.
+///
+/// # Example
+///
+/// ```
+/// # use ghreq::{Endpoint, EndpointTemplate};
+/// let template = "repos/{owner}/{repo}/issues/{number}"
+///     .parse::<EndpointTemplate>()
+///     .unwrap();
+/// let endpoint = template
+///     .fill([("owner", "octocat"), ("repo", "hello-world"), ("number", "42")])
+///     .unwrap();
+/// assert_eq!(
+///     endpoint,
+///     Endpoint::from_iter(["repos", "octocat", "hello-world", "issues", "42"])
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EndpointTemplate {
+    segments: Vec<Vec<Piece>>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Piece {
+    Literal(String),
+    Variable(String),
+}
+
+impl EndpointTemplate {
+    /// Returns an iterator over the names of the template's placeholders, in
+    /// order of appearance
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().flatten().filter_map(|p| match p {
+            Piece::Variable(name) => Some(name.as_str()),
+            Piece::Literal(_) => None,
+        })
+    }
+
+    /// Fill in the template's placeholders with the given name-value pairs.
+    /// If a name occurs more than once, the last value is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a placeholder has no value or if the resulting
+    /// endpoint fails [`Endpoint::validate()`], e.g., because a value was
+    /// empty.
+    pub fn fill<I, K, V>(&self, values: I) -> Result<Endpoint, FillTemplateError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: fmt::Display,
+    {
+        let values = values
+            .into_iter()
+            .map(|(k, v)| (k, v.to_string()))
+            .collect::<Vec<_>>();
+        self.fill_with(|name| {
+            values
+                .iter()
+                .rev()
+                .find(|(k, _)| k.as_ref() == name)
+                .map(|(_, v)| v.clone())
+        })
+    }
+
+    /// Fill in the template's placeholders with the values returned by `f`
+    /// when called with the placeholders' names
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `f` returns `None` for a placeholder or if the
+    /// resulting endpoint fails [`Endpoint::validate()`].
+    pub fn fill_with<F>(&self, mut f: F) -> Result<Endpoint, FillTemplateError>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut path = Vec::with_capacity(self.segments.len());
+        for seg in &self.segments {
+            let mut s = String::new();
+            for piece in seg {
+                match piece {
+                    Piece::Literal(lit) => s.push_str(lit),
+                    Piece::Variable(name) => match f(name) {
+                        Some(value) => s.push_str(&value),
+                        None => return Err(FillTemplateError::Missing(name.clone())),
+                    },
+                }
+            }
+            path.push(s);
+        }
+        let endpoint = Endpoint::Path(path);
+        endpoint.validate()?;
+        Ok(endpoint)
+    }
+}
+
+impl std::str::FromStr for EndpointTemplate {
+    type Err = ParseTemplateError;
+
+    fn from_str(s: &str) -> Result<EndpointTemplate, ParseTemplateError> {
+        let s = s.strip_prefix('/').unwrap_or(s);
+        let s = s.strip_suffix('/').unwrap_or(s);
+        if s.is_empty() {
+            return Ok(EndpointTemplate {
+                segments: Vec::new(),
+            });
+        }
+        let mut segments = Vec::new();
+        for seg in s.split('/') {
+            let mut pieces = Vec::new();
+            let mut rest = seg;
+            while !rest.is_empty() {
+                if let Some(after) = rest.strip_prefix('{') {
+                    let Some(end) = after.find('}') else {
+                        return Err(ParseTemplateError::Unclosed(seg.to_owned()));
+                    };
+                    let name = &after[..end];
+                    if name.is_empty() || name.contains('{') {
+                        return Err(ParseTemplateError::BadPlaceholder(seg.to_owned()));
+                    }
+                    pieces.push(Piece::Variable(name.to_owned()));
+                    rest = &after[(end + 1)..];
+                } else {
+                    let end = rest.find('{').unwrap_or(rest.len());
+                    let lit = &rest[..end];
+                    if lit.contains('}') {
+                        return Err(ParseTemplateError::Unmatched(seg.to_owned()));
+                    }
+                    let lit = percent_decode_str(lit)
+                        .decode_utf8()
+                        .map_err(|_| ParseTemplateError::Utf8(seg.to_owned()))?;
+                    pieces.push(Piece::Literal(lit.into_owned()));
+                    rest = &rest[end..];
+                }
+            }
+            segments.push(pieces);
+        }
+        Ok(EndpointTemplate { segments })
+    }
+}
+
+impl fmt::Display for EndpointTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, seg) in self.segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            for piece in seg {
+                match piece {
+                    Piece::Literal(lit) => write!(f, "{}", utf8_percent_encode(lit, PATH_SEGMENT))?,
+                    Piece::Variable(name) => write!(f, "{{{name}}}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`EndpointTemplate`]'s `FromStr` implementation
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ParseTemplateError {
+    /// A path component contained a `{` without a matching `}`
+    #[error("endpoint template component {0:?} contains an unclosed placeholder")]
+    Unclosed(String),
+
+    /// A path component contained a `}` without a preceding `{`
+    #[error("endpoint template component {0:?} contains an unmatched '}}'")]
+    Unmatched(String),
+
+    /// A path component contained an empty or nested placeholder
+    #[error("endpoint template component {0:?} contains an invalid placeholder")]
+    BadPlaceholder(String),
+
+    /// Literal text in a path component did not percent-decode to UTF-8
+    #[error("endpoint template component {0:?} does not percent-decode to UTF-8")]
+    Utf8(String),
+}
+
+/// Error returned by [`EndpointTemplate::fill()`] and
+/// [`EndpointTemplate::fill_with()`]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum FillTemplateError {
+    /// No value was supplied for the placeholder with the given name
+    #[error("no value supplied for endpoint template placeholder {{{0}}}")]
+    Missing(String),
+
+    /// The filled-in endpoint was not valid
+    #[error(transparent)]
+    Invalid(#[from] EndpointError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn fill() {
+        let template = "/repos/{owner}/{repo}/releases/tags/v{version}"
+            .parse::<EndpointTemplate>()
+            .unwrap();
+        assert_eq!(
+            template.variables().collect::<Vec<_>>(),
+            ["owner", "repo", "version"]
+        );
+        assert_eq!(
+            template.to_string(),
+            "repos/{owner}/{repo}/releases/tags/v{version}"
+        );
+        let endpoint = template
+            .fill([
+                ("owner", "octocat"),
+                ("repo", "hello-world"),
+                ("version", "1.0 rc?"),
+            ])
+            .unwrap();
+        assert_eq!(
+            endpoint,
+            Endpoint::from_iter([
+                "repos",
+                "octocat",
+                "hello-world",
+                "releases",
+                "tags",
+                "v1.0 rc?"
+            ])
+        );
+        assert_eq!(
+            endpoint.to_string(),
+            "repos/octocat/hello-world/releases/tags/v1.0%20rc%3F"
+        );
+    }
+
+    #[test]
+    fn fill_missing() {
+        let template = "repos/{owner}/{repo}".parse::<EndpointTemplate>().unwrap();
+        assert_eq!(
+            template.fill([("owner", "octocat")]),
+            Err(FillTemplateError::Missing("repo".into()))
+        );
+        assert_eq!(
+            template.fill([("owner", "octocat"), ("repo", "")]),
+            Err(FillTemplateError::Invalid(EndpointError::EmptySegment))
+        );
+        assert_eq!(
+            template
+                .fill([("owner", "octocat"), ("repo", "a/b")])
+                .unwrap()
+                .to_string(),
+            "repos/octocat/a%2Fb"
+        );
+    }
+
+    #[test]
+    fn fill_with() {
+        let template = "users/{user}/repos".parse::<EndpointTemplate>().unwrap();
+        let endpoint = template
+            .fill_with(|name| (name == "user").then(|| String::from("octocat")))
+            .unwrap();
+        assert_eq!(endpoint, Endpoint::from_iter(["users", "octocat", "repos"]));
+    }
+
+    #[rstest]
+    #[case("repos/{owner", ParseTemplateError::Unclosed("{owner".into()))]
+    #[case("repos/owner}", ParseTemplateError::Unmatched("owner}".into()))]
+    #[case("repos/{}", ParseTemplateError::BadPlaceholder("{}".into()))]
+    #[case("repos/{a{b}", ParseTemplateError::BadPlaceholder("{a{b}".into()))]
+    #[case("repos/%FF", ParseTemplateError::Utf8("%FF".into()))]
+    fn parse_err(#[case] s: &str, #[case] err: ParseTemplateError) {
+        assert_eq!(s.parse::<EndpointTemplate>(), Err(err));
+    }
+}
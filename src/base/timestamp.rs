@@ -0,0 +1,451 @@
+use crate::util::{civil_from_days, days_from_civil};
+use serde::{
+    Deserialize, Serialize, Serializer,
+    de::{Deserializer, Error as _},
+};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// A point in time with one-second precision, as used in GitHub API
+/// timestamps
+///
+/// Timestamps are displayed and serialized in UTC in the ISO 8601 format
+/// `YYYY-MM-DDTHH:MM:SSZ` that GitHub uses in responses and accepts in the
+/// `since` and `until` query parameters of various endpoints.  Parsing also
+/// accepts fractional seconds (which are discarded) and numeric UTC offsets
+/// like `-07:00`, both of which appear in some responses.  Only years 0000
+/// through 9999 are supported.
+///
+/// With the `chrono`, `time`, or `jiff` feature enabled, timestamps can be
+/// converted to & from the UTC timestamp types of the corresponding crate.
+///
+/// # Example
+///
+/// ```
+/// # use ghreq::Timestamp;
+/// let ts = "2011-04-14T16:00:49-07:00".parse::<Timestamp>().unwrap();
+/// assert_eq!(ts.to_string(), "2011-04-14T23:00:49Z");
+/// assert_eq!(ts.as_unix(), 1302822049);
+/// assert_eq!(
+///     ts.since_param(),
+///     (String::from("since"), String::from("2011-04-14T23:00:49Z"))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// The earliest supported timestamp, `0000-01-01T00:00:00Z`
+    pub const MIN: Timestamp = Timestamp(-62_167_219_200);
+
+    /// The latest supported timestamp, `9999-12-31T23:59:59Z`
+    pub const MAX: Timestamp = Timestamp(253_402_300_799);
+
+    /// The Unix epoch, `1970-01-01T00:00:00Z`
+    pub const UNIX_EPOCH: Timestamp = Timestamp(0);
+
+    /// Returns the current time, truncated to the second
+    pub fn now() -> Timestamp {
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+        Timestamp(secs.min(Timestamp::MAX.0))
+    }
+
+    /// Construct a timestamp from a number of seconds since the Unix epoch.
+    /// Returns `None` if the result would be outside the supported range.
+    pub fn from_unix(secs: i64) -> Option<Timestamp> {
+        (Timestamp::MIN.0..=Timestamp::MAX.0)
+            .contains(&secs)
+            .then_some(Timestamp(secs))
+    }
+
+    /// Returns the number of seconds since the Unix epoch
+    pub fn as_unix(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns a `since` query parameter for use in
+    /// [`PaginationRequest::params()`][crate::pagination::PaginationRequest::params]
+    /// and the like, restricting results to those updated at or after the
+    /// timestamp
+    pub fn since_param(&self) -> (String, String) {
+        (String::from("since"), self.to_string())
+    }
+
+    /// Returns an `until` query parameter for use in
+    /// [`PaginationRequest::params()`][crate::pagination::PaginationRequest::params]
+    /// and the like, restricting results to those updated at or before the
+    /// timestamp
+    pub fn until_param(&self) -> (String, String) {
+        (String::from("until"), self.to_string())
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (days, secs) = (self.0.div_euclid(86400), self.0.rem_euclid(86400));
+        let (year, month, day) = civil_from_days(days);
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseTimestampError;
+
+    fn from_str(s: &str) -> Result<Timestamp, ParseTimestampError> {
+        let (datetime, mut rest) = s.split_at_checked(19).ok_or(ParseTimestampError::Syntax)?;
+        let &[
+            y1,
+            y2,
+            y3,
+            y4,
+            b'-',
+            mo1,
+            mo2,
+            b'-',
+            d1,
+            d2,
+            b'T' | b't',
+            h1,
+            h2,
+            b':',
+            mi1,
+            mi2,
+            b':',
+            s1,
+            s2,
+        ] = datetime.as_bytes()
+        else {
+            return Err(ParseTimestampError::Syntax);
+        };
+        let year = digits(&[y1, y2, y3, y4])?;
+        let month = digits(&[mo1, mo2])?;
+        let day = digits(&[d1, d2])?;
+        let hour = digits(&[h1, h2])?;
+        let minute = digits(&[mi1, mi2])?;
+        let second = digits(&[s1, s2])?;
+        if let Some(frac) = rest.strip_prefix('.') {
+            let end = frac
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(frac.len());
+            if end == 0 {
+                return Err(ParseTimestampError::Syntax);
+            }
+            rest = &frac[end..];
+        }
+        let offset = match rest.as_bytes() {
+            b"Z" | b"z" => 0,
+            &[sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+                let (oh, om) = (digits(&[h1, h2])?, digits(&[m1, m2])?);
+                if oh > 23 || om > 59 {
+                    return Err(ParseTimestampError::Field);
+                }
+                let offset = oh * 3600 + om * 60;
+                if sign == b'-' { -offset } else { offset }
+            }
+            _ => return Err(ParseTimestampError::Syntax),
+        };
+        if !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(ParseTimestampError::Field);
+        }
+        let secs =
+            days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+        Timestamp::from_unix(secs).ok_or(ParseTimestampError::Range(TimestampRangeError))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<Timestamp>().map_err(D::Error::custom)
+    }
+}
+
+impl TryFrom<Timestamp> for SystemTime {
+    type Error = TimestampRangeError;
+
+    /// Convert a timestamp to a [`SystemTime`], failing if the platform's
+    /// `SystemTime` cannot represent it
+    fn try_from(ts: Timestamp) -> Result<SystemTime, TimestampRangeError> {
+        let d = Duration::from_secs(ts.0.unsigned_abs());
+        if ts.0 < 0 {
+            SystemTime::UNIX_EPOCH.checked_sub(d)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_add(d)
+        }
+        .ok_or(TimestampRangeError)
+    }
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = TimestampRangeError;
+
+    /// Convert a [`SystemTime`] to a timestamp, truncating it to the second
+    fn try_from(t: SystemTime) -> Result<Timestamp, TimestampRangeError> {
+        let secs = match t.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => i64::try_from(d.as_secs()).ok(),
+            // Round towards negative infinity
+            Err(e) => {
+                let d = e.duration();
+                let secs = d.as_secs() + u64::from(d.subsec_nanos() > 0);
+                i64::try_from(secs).ok().map(|s| -s)
+            }
+        };
+        secs.and_then(Timestamp::from_unix)
+            .ok_or(TimestampRangeError)
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    fn from(ts: Timestamp) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::<chrono::Utc>::UNIX_EPOCH + chrono::TimeDelta::seconds(ts.0)
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for Timestamp {
+    type Error = TimestampRangeError;
+
+    /// Convert a [`chrono::DateTime`] to a timestamp, truncating it to the
+    /// second
+    fn try_from(dt: chrono::DateTime<chrono::Utc>) -> Result<Timestamp, TimestampRangeError> {
+        Timestamp::from_unix(dt.timestamp()).ok_or(TimestampRangeError)
+    }
+}
+
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl From<Timestamp> for time::OffsetDateTime {
+    fn from(ts: Timestamp) -> time::OffsetDateTime {
+        time::OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(ts.0)
+    }
+}
+
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl TryFrom<time::OffsetDateTime> for Timestamp {
+    type Error = TimestampRangeError;
+
+    /// Convert a [`time::OffsetDateTime`] to a timestamp, truncating it to
+    /// the second
+    fn try_from(dt: time::OffsetDateTime) -> Result<Timestamp, TimestampRangeError> {
+        Timestamp::from_unix(dt.unix_timestamp()).ok_or(TimestampRangeError)
+    }
+}
+
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl From<Timestamp> for jiff::Timestamp {
+    fn from(ts: Timestamp) -> jiff::Timestamp {
+        jiff::Timestamp::UNIX_EPOCH + jiff::SignedDuration::from_secs(ts.0)
+    }
+}
+
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl TryFrom<jiff::Timestamp> for Timestamp {
+    type Error = TimestampRangeError;
+
+    /// Convert a [`jiff::Timestamp`] to a timestamp, truncating it to the
+    /// second
+    fn try_from(ts: jiff::Timestamp) -> Result<Timestamp, TimestampRangeError> {
+        let mut secs = ts.as_second();
+        if ts.subsec_nanosecond() < 0 {
+            // `as_second()` rounds towards zero
+            secs -= 1;
+        }
+        Timestamp::from_unix(secs).ok_or(TimestampRangeError)
+    }
+}
+
+/// [Private] Parse a sequence of ASCII digits as a nonnegative integer
+fn digits(ds: &[u8]) -> Result<i64, ParseTimestampError> {
+    ds.iter().try_fold(0, |n, &d| {
+        if d.is_ascii_digit() {
+            Ok(n * 10 + i64::from(d - b'0'))
+        } else {
+            Err(ParseTimestampError::Syntax)
+        }
+    })
+}
+
+/// [Private] Returns the number of days in the given month of the given year
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Error returned by [`Timestamp`]'s `FromStr` implementation
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+pub enum ParseTimestampError {
+    /// The string was not of the form `YYYY-MM-DDTHH:MM:SS[.fff](Z|±HH:MM)`
+    #[error("timestamp is not of the form YYYY-MM-DDTHH:MM:SSZ")]
+    Syntax,
+
+    /// A component of the timestamp, such as the month or the hour, was out
+    /// of range
+    #[error("timestamp contains an invalid date or time")]
+    Field,
+
+    /// The timestamp was outside the supported range of [`Timestamp`] after
+    /// applying its UTC offset
+    #[error(transparent)]
+    Range(#[from] TimestampRangeError),
+}
+
+/// Error returned when converting a time outside the supported range of
+/// [`Timestamp`] into a `Timestamp`
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+#[error("timestamp is outside the supported range")]
+pub struct TimestampRangeError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("1970-01-01T00:00:00Z", 0)]
+    #[case("2000-02-29T00:00:00Z", 951_782_400)]
+    #[case("2019-06-03T00:57:16Z", 1_559_523_436)]
+    #[case("2099-12-31T23:59:59Z", 4_102_444_799)]
+    #[case("1969-12-31T23:59:59Z", -1)]
+    #[case("0000-01-01T00:00:00Z", -62_167_219_200)]
+    #[case("9999-12-31T23:59:59Z", 253_402_300_799)]
+    fn roundtrip(#[case] s: &str, #[case] secs: i64) {
+        let ts = s.parse::<Timestamp>().unwrap();
+        assert_eq!(ts.as_unix(), secs);
+        assert_eq!(ts.to_string(), s);
+        assert_eq!(Timestamp::from_unix(secs), Some(ts));
+    }
+
+    #[rstest]
+    #[case("2024-01-02T03:04:05.1234567Z", "2024-01-02T03:04:05Z")]
+    #[case("2024-01-02t03:04:05z", "2024-01-02T03:04:05Z")]
+    #[case("2011-04-14T16:00:49-07:00", "2011-04-14T23:00:49Z")]
+    #[case("2011-04-15T01:30:49+02:30", "2011-04-14T23:00:49Z")]
+    #[case("2024-01-01T00:00:00.5+00:00", "2024-01-01T00:00:00Z")]
+    fn parse_normalized(#[case] s: &str, #[case] normalized: &str) {
+        assert_eq!(s.parse::<Timestamp>().unwrap().to_string(), normalized);
+    }
+
+    #[rstest]
+    #[case("", ParseTimestampError::Syntax)]
+    #[case("2024-01-02", ParseTimestampError::Syntax)]
+    #[case("2024-01-02 03:04:05Z", ParseTimestampError::Syntax)]
+    #[case("2024-01-02T03:04:05", ParseTimestampError::Syntax)]
+    #[case("2024-01-02T03:04:05.Z", ParseTimestampError::Syntax)]
+    #[case("2024-01-02T03:04:05+0700", ParseTimestampError::Syntax)]
+    #[case("2024-1-02T03:04:05Z", ParseTimestampError::Syntax)]
+    #[case("+024-01-02T03:04:05Z", ParseTimestampError::Syntax)]
+    #[case("2023-02-29T00:00:00Z", ParseTimestampError::Field)]
+    #[case("2024-13-01T00:00:00Z", ParseTimestampError::Field)]
+    #[case("2024-01-01T24:00:00Z", ParseTimestampError::Field)]
+    #[case("2024-01-01T00:00:60Z", ParseTimestampError::Field)]
+    #[case(
+        "9999-12-31T23:59:59-00:01",
+        ParseTimestampError::Range(TimestampRangeError)
+    )]
+    fn parse_invalid(#[case] s: &str, #[case] err: ParseTimestampError) {
+        assert_eq!(s.parse::<Timestamp>(), Err(err));
+    }
+
+    #[test]
+    fn system_time() {
+        let ts = "2019-06-03T00:57:16Z".parse::<Timestamp>().unwrap();
+        let t = SystemTime::try_from(ts).unwrap();
+        assert_eq!(Timestamp::try_from(t), Ok(ts));
+        assert_eq!(Timestamp::try_from(t + Duration::from_millis(999)), Ok(ts));
+        let t = SystemTime::UNIX_EPOCH - Duration::from_millis(500);
+        assert_eq!(Timestamp::try_from(t).unwrap().as_unix(), -1);
+    }
+
+    #[test]
+    fn system_time_min() {
+        // Whether year 0 fits in a `SystemTime` is platform-dependent, but
+        // the conversion must never panic.
+        match SystemTime::try_from(Timestamp::MIN) {
+            Ok(t) => assert_eq!(Timestamp::try_from(t), Ok(Timestamp::MIN)),
+            Err(e) => assert_eq!(e, TimestampRangeError),
+        }
+        let t = SystemTime::try_from(Timestamp::MAX).unwrap();
+        assert_eq!(Timestamp::try_from(t), Ok(Timestamp::MAX));
+    }
+
+    #[test]
+    fn serde() {
+        #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+        struct Issue {
+            created_at: Timestamp,
+            closed_at: Option<Timestamp>,
+        }
+
+        let issue = serde_json::from_str::<Issue>(
+            r#"{"created_at": "2024-01-02T03:04:05Z", "closed_at": null}"#,
+        )
+        .unwrap();
+        assert_eq!(issue.created_at.as_unix(), 1_704_164_645);
+        assert_eq!(
+            serde_json::to_string(&issue).unwrap(),
+            r#"{"created_at":"2024-01-02T03:04:05Z","closed_at":null}"#
+        );
+        assert!(serde_json::from_str::<Timestamp>(r#""yesterday""#).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        let ts = "2011-04-14T23:00:49Z".parse::<Timestamp>().unwrap();
+        let dt = chrono::DateTime::<chrono::Utc>::from(ts);
+        assert_eq!(dt.timestamp(), 1_302_822_049);
+        assert_eq!(Timestamp::try_from(dt), Ok(ts));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        let ts = "2011-04-14T23:00:49Z".parse::<Timestamp>().unwrap();
+        let dt = time::OffsetDateTime::from(ts);
+        assert_eq!(dt.unix_timestamp(), 1_302_822_049);
+        assert_eq!(Timestamp::try_from(dt), Ok(ts));
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn jiff() {
+        let ts = "2011-04-14T23:00:49Z".parse::<Timestamp>().unwrap();
+        let jts = jiff::Timestamp::from(ts);
+        assert_eq!(jts.to_string(), "2011-04-14T23:00:49Z");
+        assert_eq!(Timestamp::try_from(jts), Ok(ts));
+        let before_epoch = jiff::Timestamp::from_millisecond(-500).unwrap();
+        assert_eq!(Timestamp::try_from(before_epoch).unwrap().as_unix(), -1);
+    }
+}
@@ -0,0 +1,466 @@
+use crate::{Endpoint, HttpUrl, ParseHttpUrlError};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use serde::{
+    Deserialize, Serialize,
+    de::{Deserializer, Error as _},
+    ser::Serializer,
+};
+use std::fmt;
+use thiserror::Error;
+
+/// The characters percent-encoded in values substituted by most expression
+/// types: everything except RFC 6570's "unreserved" characters
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The characters percent-encoded in values substituted by `{+var}` and
+/// `{#var}` expressions: everything except RFC 6570's "unreserved" and
+/// "reserved" characters
+const RESERVED: &AsciiSet = &UNRESERVED
+    .remove(b':')
+    .remove(b'/')
+    .remove(b'?')
+    .remove(b'#')
+    .remove(b'[')
+    .remove(b']')
+    .remove(b'@')
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    .remove(b'=');
+
+/// A hypermedia URI template, as found in the `*_url` fields of many GitHub
+/// API responses, such as
+/// `"https://api.github.com/repos/octocat/hello-world/issues{/number}"` or
+/// `"https://uploads.github.com/repos/octocat/hello-world/releases/1/assets{?name,label}"`
+///
+/// Templates are parsed according to [RFC 6570](https://www.rfc-editor.org/rfc/rfc6570)
+/// up to & including Level 3 (i.e., all expression operators, but no `*` or
+/// `:N` modifiers).  Filling in a template with [`UriTemplate::fill()`]
+/// produces an [`Endpoint::Url`]; as specified by the RFC, variables that
+/// are not given a value are omitted from the expansion along with any
+/// separators they would have introduced, so a template can be used to
+/// construct both a collection URL and a URL for a single member.
+///
+/// `UriTemplate` implements `Deserialize`, and so it can be used directly as
+/// the type of a field in a response struct.
+///
+/// # Example
+///
+/// ```
+/// # use ghreq::{Endpoint, UriTemplate};
+/// let template = "https://api.github.com/repos/octocat/hello-world/issues{/number}"
+///     .parse::<UriTemplate>()
+///     .unwrap();
+/// let Endpoint::Url(url) = template.fill([("number", 42)]).unwrap() else {
+///     unreachable!()
+/// };
+/// assert_eq!(
+///     url.as_str(),
+///     "https://api.github.com/repos/octocat/hello-world/issues/42"
+/// );
+/// let Endpoint::Url(url) = template.fill::<_, &str, &str>([]).unwrap() else {
+///     unreachable!()
+/// };
+/// assert_eq!(
+///     url.as_str(),
+///     "https://api.github.com/repos/octocat/hello-world/issues"
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UriTemplate {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Piece {
+    Literal(String),
+    Expression { op: Operator, names: Vec<String> },
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Operator {
+    Simple,
+    Reserved,
+    Fragment,
+    Label,
+    Path,
+    PathParam,
+    Query,
+    QueryContinuation,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Operator> {
+        match c {
+            '+' => Some(Operator::Reserved),
+            '#' => Some(Operator::Fragment),
+            '.' => Some(Operator::Label),
+            '/' => Some(Operator::Path),
+            ';' => Some(Operator::PathParam),
+            '?' => Some(Operator::Query),
+            '&' => Some(Operator::QueryContinuation),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Operator::Simple => "",
+            Operator::Reserved => "+",
+            Operator::Fragment => "#",
+            Operator::Label => ".",
+            Operator::Path => "/",
+            Operator::PathParam => ";",
+            Operator::Query => "?",
+            Operator::QueryContinuation => "&",
+        }
+    }
+
+    /// The string to insert before the first defined variable
+    fn first(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved => "",
+            _ => self.as_str(),
+        }
+    }
+
+    /// The string to insert between defined variables
+    fn separator(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved | Operator::Fragment => ",",
+            Operator::Label => ".",
+            Operator::Path => "/",
+            Operator::PathParam => ";",
+            Operator::Query | Operator::QueryContinuation => "&",
+        }
+    }
+
+    /// Whether variables are expanded as `name=value` pairs
+    fn named(self) -> bool {
+        matches!(
+            self,
+            Operator::PathParam | Operator::Query | Operator::QueryContinuation
+        )
+    }
+
+    /// The set of characters to percent-encode in values
+    fn encode_set(self) -> &'static AsciiSet {
+        match self {
+            Operator::Reserved | Operator::Fragment => RESERVED,
+            _ => UNRESERVED,
+        }
+    }
+}
+
+impl UriTemplate {
+    /// Return the template as a string
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns an iterator over the names of the template's variables, in
+    /// order of appearance
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.pieces
+            .iter()
+            .flat_map(|p| match p {
+                Piece::Expression { names, .. } => names.as_slice(),
+                Piece::Literal(_) => &[],
+            })
+            .map(String::as_str)
+    }
+
+    /// Fill in the template's variables with the given name-value pairs and
+    /// return the resulting URL as an `Endpoint`.  Variables without a value
+    /// are omitted.  If a name occurs more than once, the last value is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the expanded template is not a valid HTTP(S) URL.
+    pub fn fill<I, K, V>(&self, values: I) -> Result<Endpoint, ExpandUriTemplateError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: fmt::Display,
+    {
+        let values = values
+            .into_iter()
+            .map(|(k, v)| (k, v.to_string()))
+            .collect::<Vec<_>>();
+        self.fill_with(|name| {
+            values
+                .iter()
+                .rev()
+                .find(|(k, _)| k.as_ref() == name)
+                .map(|(_, v)| v.clone())
+        })
+    }
+
+    /// Fill in the template's variables with the values returned by `f` when
+    /// called with the variables' names and return the resulting URL as an
+    /// `Endpoint`.  Variables for which `f` returns `None` are omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the expanded template is not a valid HTTP(S) URL.
+    pub fn fill_with<F>(&self, mut f: F) -> Result<Endpoint, ExpandUriTemplateError>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut s = String::with_capacity(self.source.len());
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(lit) => s.push_str(lit),
+                Piece::Expression { op, names } => {
+                    let mut first = true;
+                    for name in names {
+                        let Some(value) = f(name) else {
+                            continue;
+                        };
+                        s.push_str(if first { op.first() } else { op.separator() });
+                        first = false;
+                        if op.named() {
+                            s.push_str(name);
+                            if value.is_empty() {
+                                if *op != Operator::PathParam {
+                                    s.push('=');
+                                }
+                                continue;
+                            }
+                            s.push('=');
+                        }
+                        s.extend(utf8_percent_encode(&value, op.encode_set()));
+                    }
+                }
+            }
+        }
+        match s.parse::<HttpUrl>() {
+            Ok(url) => Ok(Endpoint::Url(url)),
+            Err(source) => Err(ExpandUriTemplateError { url: s, source }),
+        }
+    }
+}
+
+impl std::str::FromStr for UriTemplate {
+    type Err = ParseUriTemplateError;
+
+    fn from_str(s: &str) -> Result<UriTemplate, ParseUriTemplateError> {
+        let mut pieces = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('{') {
+                let Some(end) = after.find('}') else {
+                    return Err(ParseUriTemplateError::Unclosed(s.to_owned()));
+                };
+                let expr = &after[..end];
+                let mut chars = expr.chars();
+                let (op, body) = match chars.next().and_then(Operator::from_char) {
+                    Some(op) => (op, chars.as_str()),
+                    None => (Operator::Simple, expr),
+                };
+                if body.contains(['*', ':']) || expr.starts_with(['=', ',', '!', '@', '|']) {
+                    return Err(ParseUriTemplateError::Unsupported(format!("{{{expr}}}")));
+                }
+                let names = body.split(',').map(String::from).collect::<Vec<_>>();
+                if names.iter().any(|name| !is_varname(name)) {
+                    return Err(ParseUriTemplateError::BadExpression(format!("{{{expr}}}")));
+                }
+                pieces.push(Piece::Expression { op, names });
+                rest = &after[(end + 1)..];
+            } else {
+                let end = rest.find('{').unwrap_or(rest.len());
+                let lit = &rest[..end];
+                if lit.contains('}') {
+                    return Err(ParseUriTemplateError::Unmatched(s.to_owned()));
+                }
+                pieces.push(Piece::Literal(lit.to_owned()));
+                rest = &rest[end..];
+            }
+        }
+        Ok(UriTemplate {
+            source: s.to_owned(),
+            pieces,
+        })
+    }
+}
+
+/// [Private] Test whether a string is a valid RFC 6570 variable name
+fn is_varname(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with('.')
+        && !s.ends_with('.')
+        && !s.contains("..")
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '%'))
+}
+
+impl fmt::Display for UriTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Serialize for UriTemplate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for UriTemplate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<UriTemplate>().map_err(D::Error::custom)
+    }
+}
+
+/// Error returned by [`UriTemplate`]'s `FromStr` implementation
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ParseUriTemplateError {
+    /// The template contained a `{` without a matching `}`
+    #[error("URI template {0:?} contains an unclosed expression")]
+    Unclosed(String),
+
+    /// The template contained a `}` without a preceding `{`
+    #[error("URI template {0:?} contains an unmatched '}}'")]
+    Unmatched(String),
+
+    /// The template contained an expression with an empty or invalid variable
+    /// name
+    #[error("URI template expression {0:?} is invalid")]
+    BadExpression(String),
+
+    /// The template contained an expression using a feature beyond RFC 6570
+    /// Level 3, such as a `*` or `:N` modifier, or a reserved operator
+    #[error("URI template expression {0:?} is not supported")]
+    Unsupported(String),
+}
+
+/// Error returned by [`UriTemplate::fill()`] and [`UriTemplate::fill_with()`]
+/// when the expanded template is not a valid HTTP(S) URL
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("URI template expanded to invalid URL {url:?}")]
+pub struct ExpandUriTemplateError {
+    /// The expanded template
+    pub url: String,
+
+    /// The error that occurred when parsing the expanded template
+    #[source]
+    pub source: ParseHttpUrlError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn expand(template: &str, values: &[(&str, &str)]) -> String {
+        let template = template.parse::<UriTemplate>().unwrap();
+        match template.fill(values.iter().copied()).unwrap() {
+            Endpoint::Url(url) => url.to_string(),
+            e => panic!("Expected Endpoint::Url, got {e:?}"),
+        }
+    }
+
+    #[rstest]
+    #[case("https://example.com/{var}", &[("var", "value")], "https://example.com/value")]
+    #[case("https://example.com/{var}", &[("var", "a b/c")], "https://example.com/a%20b%2Fc")]
+    #[case("https://example.com/{+var}", &[("var", "a b/c")], "https://example.com/a%20b/c")]
+    #[case("https://example.com/x{#var}", &[("var", "frag")], "https://example.com/x#frag")]
+    #[case("https://example.com/x{.var,y}", &[("var", "json")], "https://example.com/x.json")]
+    #[case("https://example.com/x{;var,y}", &[("var", ""), ("y", "1")], "https://example.com/x;var;y=1")]
+    #[case("https://example.com/issues{/number}", &[("number", "42")], "https://example.com/issues/42")]
+    #[case("https://example.com/issues{/number}", &[], "https://example.com/issues")]
+    #[case("https://example.com/a{/b,c}", &[("c", "z")], "https://example.com/a/z")]
+    #[case("https://example.com/assets{?name,label}", &[("name", "x.zip"), ("label", "My File")], "https://example.com/assets?name=x.zip&label=My%20File")]
+    #[case("https://example.com/assets{?name,label}", &[("label", "")], "https://example.com/assets?label=")]
+    #[case("https://example.com/assets{?name,label}", &[], "https://example.com/assets")]
+    #[case("https://example.com/x?page=1{&per_page}", &[("per_page", "100")], "https://example.com/x?page=1&per_page=100")]
+    fn test_expand(
+        #[case] template: &str,
+        #[case] values: &[(&str, &str)],
+        #[case] expected: &str,
+    ) {
+        assert_eq!(expand(template, values), expected);
+    }
+
+    #[test]
+    fn github_template() {
+        let template = "https://api.github.com/repos/octocat/Hello-World/compare/{base}...{head}"
+            .parse::<UriTemplate>()
+            .unwrap();
+        assert_eq!(template.variables().collect::<Vec<_>>(), ["base", "head"]);
+        assert_eq!(
+            template.to_string(),
+            "https://api.github.com/repos/octocat/Hello-World/compare/{base}...{head}"
+        );
+        let endpoint = template
+            .fill_with(|name| Some(format!("{name}-branch")))
+            .unwrap();
+        assert_eq!(
+            endpoint,
+            Endpoint::Url(
+                "https://api.github.com/repos/octocat/Hello-World/compare/base-branch...head-branch"
+                    .parse()
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        #[derive(Deserialize)]
+        struct Repo {
+            issues_url: UriTemplate,
+        }
+
+        let repo = serde_json::from_str::<Repo>(
+            r#"{"issues_url": "https://api.github.com/repos/octocat/Hello-World/issues{/number}"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            repo.issues_url.fill([("number", 1347)]).unwrap(),
+            Endpoint::Url(
+                "https://api.github.com/repos/octocat/Hello-World/issues/1347"
+                    .parse()
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn expand_invalid() {
+        let template = "{scheme}://example.com".parse::<UriTemplate>().unwrap();
+        assert_eq!(
+            template.fill([("scheme", "ftp")]),
+            Err(ExpandUriTemplateError {
+                url: "ftp://example.com".into(),
+                source: ParseHttpUrlError::NotHttp,
+            })
+        );
+    }
+
+    #[rstest]
+    #[case("https://example.com/{var", ParseUriTemplateError::Unclosed("https://example.com/{var".into()))]
+    #[case("https://example.com/var}", ParseUriTemplateError::Unmatched("https://example.com/var}".into()))]
+    #[case("https://example.com/{}", ParseUriTemplateError::BadExpression("{}".into()))]
+    #[case("https://example.com/{a,}", ParseUriTemplateError::BadExpression("{a,}".into()))]
+    #[case("https://example.com/{a-b}", ParseUriTemplateError::BadExpression("{a-b}".into()))]
+    #[case("https://example.com/{a*}", ParseUriTemplateError::Unsupported("{a*}".into()))]
+    #[case("https://example.com/{a:3}", ParseUriTemplateError::Unsupported("{a:3}".into()))]
+    #[case("https://example.com/{=a}", ParseUriTemplateError::Unsupported("{=a}".into()))]
+    fn parse_err(#[case] s: &str, #[case] err: ParseUriTemplateError) {
+        assert_eq!(s.parse::<UriTemplate>(), Err(err));
+    }
+}
@@ -0,0 +1,356 @@
+use super::{Backend, Client, ClientResult};
+use crate::{
+    Endpoint, Method,
+    errors::CommonError,
+    parser::{JsonResponse, ResponseParser, Utf8Text},
+    request::{AdHocRequest, JsonBody, Request},
+};
+use http::header::{HeaderName, HeaderValue};
+use serde::{Serialize, de::DeserializeOwned};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+impl<B: Backend> Client<B> {
+    /// Start building a `GET` request to the given endpoint
+    pub fn get(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Get, endpoint)
+    }
+
+    /// Start building a `HEAD` request to the given endpoint
+    pub fn head(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Head, endpoint)
+    }
+
+    /// Start building a `POST` request to the given endpoint
+    pub fn post(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Post, endpoint)
+    }
+
+    /// Start building a `PUT` request to the given endpoint
+    pub fn put(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Put, endpoint)
+    }
+
+    /// Start building a `PATCH` request to the given endpoint
+    pub fn patch(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Patch, endpoint)
+    }
+
+    /// Start building a `DELETE` request to the given endpoint
+    pub fn delete(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Delete, endpoint)
+    }
+
+    /// Start building an `OPTIONS` request to the given endpoint.
+    ///
+    /// This can be used to send a CORS preflight request by also setting the
+    /// `Origin` and `Access-Control-Request-Method` headers.
+    pub fn options(&self, endpoint: Endpoint) -> RequestBuilder<'_, B> {
+        RequestBuilder::new(self, Method::Options, endpoint)
+    }
+
+    /// Perform a `GET` request to the given endpoint and deserialize the
+    /// response body as JSON
+    pub fn get_json<T: DeserializeOwned + Send>(&self, endpoint: Endpoint) -> ClientResult<T, B> {
+        self.get(endpoint).send_json()
+    }
+
+    /// Perform a `POST` request to the given endpoint with `body` serialized
+    /// as JSON, and deserialize the response body as JSON
+    pub fn post_json<In: Serialize + Sync, Out: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+        body: &In,
+    ) -> ClientResult<Out, B> {
+        self.request(JsonRequest::new(Method::Post, endpoint, body))
+    }
+
+    /// Perform a `PUT` request to the given endpoint with `body` serialized
+    /// as JSON, and deserialize the response body as JSON
+    pub fn put_json<In: Serialize + Sync, Out: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+        body: &In,
+    ) -> ClientResult<Out, B> {
+        self.request(JsonRequest::new(Method::Put, endpoint, body))
+    }
+
+    /// Perform a `PATCH` request to the given endpoint with `body` serialized
+    /// as JSON, and deserialize the response body as JSON
+    pub fn patch_json<In: Serialize + Sync, Out: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+        body: &In,
+    ) -> ClientResult<Out, B> {
+        self.request(JsonRequest::new(Method::Patch, endpoint, body))
+    }
+
+    /// Perform a `DELETE` request to the given endpoint and discard the
+    /// response body
+    pub fn delete_ignored(&self, endpoint: Endpoint) -> ClientResult<(), B> {
+        self.delete(endpoint).send_ignored()
+    }
+}
+
+/// [Private] A request that sends a borrowed value as a JSON body and parses
+/// the response as JSON, used by the `*_json()` convenience methods
+pub(super) struct JsonRequest<'a, In, Out> {
+    method: Method,
+    endpoint: Endpoint,
+    body: &'a In,
+    _output: PhantomData<fn() -> Out>,
+}
+
+impl<'a, In, Out> JsonRequest<'a, In, Out> {
+    pub(super) fn new(method: Method, endpoint: Endpoint, body: &'a In) -> Self {
+        JsonRequest {
+            method,
+            endpoint,
+            body,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<'a, In, Out> Request for JsonRequest<'a, In, Out>
+where
+    In: Serialize + Sync,
+    Out: DeserializeOwned + Send,
+{
+    type Output = Out;
+    type Error = CommonError;
+    type Body = JsonBody<&'a In>;
+
+    fn endpoint(&self) -> Endpoint {
+        self.endpoint.clone()
+    }
+
+    fn method(&self) -> Method {
+        self.method.clone()
+    }
+
+    fn body(&self) -> Self::Body {
+        JsonBody::new(self.body)
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        JsonResponse::new()
+    }
+}
+
+/// A builder for a one-off request, returned by [`Client::get()`] and
+/// similar methods
+///
+/// Customize the request with the builder's methods, and then send it with
+/// one of the `send_*()` methods, which differ in how they parse the response
+/// body.
+#[derive(Debug)]
+pub struct RequestBuilder<'a, B> {
+    client: &'a Client<B>,
+    request: AdHocRequest,
+}
+
+impl<'a, B: Backend> RequestBuilder<'a, B> {
+    fn new(client: &'a Client<B>, method: Method, endpoint: Endpoint) -> Self {
+        RequestBuilder {
+            client,
+            request: AdHocRequest::new(method, endpoint),
+        }
+    }
+
+    /// Add a query parameter to the request
+    pub fn param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.request = self.request.with_param(key, value);
+        self
+    }
+
+    /// Add a header to the request.  Any previous values for the header are
+    /// replaced.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.request = self.request.with_header(name, value);
+        self
+    }
+
+    /// Set a JSON value to send as the request body
+    pub fn json_body(mut self, body: serde_json::Value) -> Self {
+        self.request = self.request.with_json_body(body);
+        self
+    }
+
+    /// Set the request's timeout, overriding the client's default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.request = self.request.with_timeout(timeout);
+        self
+    }
+
+    /// Returns the request built so far
+    pub fn into_request(self) -> AdHocRequest {
+        self.request
+    }
+
+    /// Send the request and deserialize the response body as JSON
+    pub fn send_json<T: DeserializeOwned + Send>(self) -> ClientResult<T, B> {
+        self.client
+            .request(self.request.with_parser::<JsonResponse<T>>())
+    }
+
+    /// Send the request and return the response body as a string
+    pub fn send_text(self) -> ClientResult<String, B> {
+        self.client.request(self.request.with_parser::<Utf8Text>())
+    }
+
+    /// Send the request and discard the response body
+    pub fn send_ignored(self) -> ClientResult<(), B> {
+        self.client.request(self.request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{ClientConfig, FnBackend};
+    use crate::{Endpoint, Method};
+    use http::header::{HeaderName, HeaderValue};
+    use serde::Serialize;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn send_json() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/issues?state=closed"
+            );
+            assert_eq!(parts.method, Method::Patch);
+            assert_eq!(parts.headers["content-type"], "application/json");
+            assert_eq!(parts.headers["x-custom"], "yes");
+            assert_eq!(parts.timeout, Some(Duration::from_secs(5)));
+            assert_eq!(body, br#"{"title":"Bug"}"#);
+            http::Response::new(br#"{"number": 42}"#.to_vec())
+        }));
+        let value = client
+            .patch(Endpoint::from_iter([
+                "repos",
+                "octocat",
+                "hello-world",
+                "issues",
+            ]))
+            .param("state", "closed")
+            .header(
+                HeaderName::from_static("x-custom"),
+                HeaderValue::from_static("yes"),
+            )
+            .json_body(json!({"title": "Bug"}))
+            .timeout(Duration::from_secs(5))
+            .send_json::<serde_json::Value>()
+            .unwrap();
+        assert_eq!(value, json!({"number": 42}));
+    }
+
+    #[test]
+    fn send_text() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(parts.method, Method::Get);
+            assert!(!parts.headers.contains_key("content-type"));
+            http::Response::new(b"Hello!".to_vec())
+        }));
+        let text = client
+            .get(Endpoint::from_iter(["zen"]))
+            .send_text()
+            .unwrap();
+        assert_eq!(text, "Hello!");
+    }
+
+    #[test]
+    fn post_json() {
+        #[derive(Serialize)]
+        struct NewLabel<'a> {
+            name: &'a str,
+        }
+
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/labels"
+            );
+            assert_eq!(parts.method, Method::Post);
+            assert_eq!(parts.headers["content-type"], "application/json");
+            assert_eq!(body, br#"{"name":"bug"}"#);
+            http::Response::new(br#"{"id": 1, "name": "bug"}"#.to_vec())
+        }));
+        let value: serde_json::Value = client
+            .post_json(
+                Endpoint::from_iter(["repos", "octocat", "hello-world", "labels"]),
+                &NewLabel { name: "bug" },
+            )
+            .unwrap();
+        assert_eq!(value, json!({"id": 1, "name": "bug"}));
+    }
+
+    #[test]
+    fn get_json() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(parts.method, Method::Get);
+            assert!(body.is_empty());
+            http::Response::new(b"[1, 2, 3]".to_vec())
+        }));
+        let value = client
+            .get_json::<Vec<u32>>(Endpoint::from_iter(["numbers"]))
+            .unwrap();
+        assert_eq!(value, [1, 2, 3]);
+    }
+
+    #[test]
+    fn send_ignored() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/labels/bug"
+            );
+            assert_eq!(parts.method, Method::Delete);
+            assert!(body.is_empty());
+            http::Response::builder()
+                .status(http::status::StatusCode::NO_CONTENT)
+                .body(Vec::new())
+                .unwrap()
+        }));
+        client
+            .delete(Endpoint::from_iter([
+                "repos",
+                "octocat",
+                "hello-world",
+                "labels",
+                "bug",
+            ]))
+            .send_ignored()
+            .unwrap();
+    }
+
+    #[test]
+    fn options_preflight() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(parts.method, Method::Options);
+            assert_eq!(parts.headers["origin"], "https://example.com");
+            assert_eq!(parts.headers["access-control-request-method"], "POST");
+            assert!(body.is_empty());
+            http::Response::builder()
+                .status(http::status::StatusCode::NO_CONTENT)
+                .body(Vec::new())
+                .unwrap()
+        }));
+        client
+            .options(Endpoint::from_iter(["graphql"]))
+            .header(
+                http::header::ORIGIN,
+                HeaderValue::from_static("https://example.com"),
+            )
+            .header(
+                http::header::ACCESS_CONTROL_REQUEST_METHOD,
+                HeaderValue::from_static("POST"),
+            )
+            .send_ignored()
+            .unwrap();
+    }
+}
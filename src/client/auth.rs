@@ -0,0 +1,210 @@
+use http::header::{HeaderValue, InvalidHeaderValue};
+use std::fmt;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[cfg(feature = "tokio")]
+use futures_util::future::BoxFuture;
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroizing;
+
+/// A source of `Authorization` header values for outgoing requests
+///
+/// A client configured with an `AuthProvider` (via
+/// [`ClientConfig::with_auth_provider()`][super::ClientConfig::with_auth_provider])
+/// asks it for a header value before every request, so implementors can
+/// rotate credentials at runtime — e.g., by rereading a token file or by
+/// refreshing an expiring installation token — without the client needing to
+/// be rebuilt.
+///
+/// This trait is implemented for closures of type `Fn() ->
+/// Option<HeaderValue>`.
+pub trait AuthProvider: Send + Sync {
+    /// Returns the value to send in the `Authorization` header of the next
+    /// request, or `None` to send the request unauthenticated
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if credentials that should be sent could not be
+    /// obtained.  The request is then not sent, and the client returns an
+    /// [`ErrorPayload::Auth`][crate::errors::ErrorPayload::Auth] error.
+    fn auth_header(&self) -> Result<Option<HeaderValue>, AuthError>;
+
+    /// Asynchronously return the value to send in the `Authorization` header
+    /// of the next request made by an
+    /// [`AsyncClient`][super::tokio::AsyncClient].
+    ///
+    /// The default implementation calls [`AuthProvider::auth_header()`].
+    /// Implementors that need to perform I/O in order to obtain a value should
+    /// override this method so as not to block the async runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn auth_header_async(&self) -> BoxFuture<'_, Result<Option<HeaderValue>, AuthError>> {
+        Box::pin(std::future::ready(self.auth_header()))
+    }
+}
+
+impl<F> AuthProvider for F
+where
+    F: Fn() -> Option<HeaderValue> + Send + Sync,
+{
+    fn auth_header(&self) -> Result<Option<HeaderValue>, AuthError> {
+        Ok(self())
+    }
+}
+
+/// Error returned by an [`AuthProvider`] that failed to obtain credentials
+/// for a request
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct AuthError(Box<dyn std::error::Error + Send + Sync>);
+
+impl AuthError {
+    pub fn new<E: std::error::Error + Send + Sync + 'static>(e: E) -> AuthError {
+        AuthError(Box::new(e))
+    }
+
+    /// Returns the underlying error
+    pub fn into_inner(self) -> Box<dyn std::error::Error + Send + Sync> {
+        self.0
+    }
+}
+
+/// An [`AuthProvider`] that always returns the same header value
+///
+/// The value is marked as sensitive so that it is not included in `Debug`
+/// output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StaticAuth(HeaderValue);
+
+impl StaticAuth {
+    pub fn new(mut value: HeaderValue) -> StaticAuth {
+        value.set_sensitive(true);
+        StaticAuth(value)
+    }
+
+    /// Construct a `StaticAuth` that sends the header value `"Bearer
+    /// {token}"`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the header value cannot be parsed into a
+    /// [`HeaderValue`].
+    pub fn bearer(token: &str) -> Result<StaticAuth, InvalidHeaderValue> {
+        let value = format!("Bearer {token}").parse::<HeaderValue>()?;
+        Ok(StaticAuth::new(value))
+    }
+}
+
+impl AuthProvider for StaticAuth {
+    fn auth_header(&self) -> Result<Option<HeaderValue>, AuthError> {
+        Ok(Some(self.0.clone()))
+    }
+}
+
+/// An [`AuthProvider`] that sends a bearer token stored in memory that is
+/// zeroed out when the `SecretToken` is dropped
+///
+/// Unlike [`StaticAuth`], which holds a complete [`HeaderValue`] for the life
+/// of the client, a `SecretToken` holds only the token itself and constructs
+/// a new (sensitive) header value for each request.  Note that these header
+/// values, as well as any copies of the token made by backends, are not
+/// zeroed.
+///
+/// When the `zeroize` feature is enabled, tokens passed to
+/// [`ClientConfig::with_auth_token()`][super::ClientConfig::with_auth_token]
+/// are stored in a `SecretToken`.
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+#[derive(Clone)]
+pub struct SecretToken(Zeroizing<String>);
+
+#[cfg(feature = "zeroize")]
+impl SecretToken {
+    /// # Errors
+    ///
+    /// Returns `Err` if the string `"Bearer {token}"` cannot be parsed into a
+    /// [`HeaderValue`].
+    pub fn new(token: String) -> Result<SecretToken, InvalidHeaderValue> {
+        let token = Zeroizing::new(token);
+        bearer_value(&token)?;
+        Ok(SecretToken(token))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl fmt::Debug for SecretToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretToken(<redacted>)")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl AuthProvider for SecretToken {
+    fn auth_header(&self) -> Result<Option<HeaderValue>, AuthError> {
+        bearer_value(&self.0).map(Some).map_err(AuthError::new)
+    }
+}
+
+/// [Private] Construct a sensitive `"Bearer {token}"` header value without
+/// leaving any unzeroed intermediate copies of the token behind
+#[cfg(feature = "zeroize")]
+fn bearer_value(token: &str) -> Result<HeaderValue, InvalidHeaderValue> {
+    // Allocate the full capacity up front so that the string is never
+    // reallocated (which would leave a copy in the old allocation).
+    let mut s = Zeroizing::new(String::with_capacity(7 + token.len()));
+    s.push_str("Bearer ");
+    s.push_str(token);
+    let mut value = HeaderValue::from_str(&s)?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// [Private] Construct a sensitive `"Bearer {token}"` header value from a
+/// token that is not needed afterwards, zeroing the token if the `zeroize`
+/// feature is enabled
+#[cfg(feature = "zeroize")]
+pub(super) fn consume_bearer_token(token: String) -> Result<HeaderValue, InvalidHeaderValue> {
+    let token = SecretToken::new(token)?;
+    bearer_value(&token.0)
+}
+
+/// [Private] Construct a sensitive `"Bearer {token}"` header value from a
+/// token that is not needed afterwards, zeroing the token if the `zeroize`
+/// feature is enabled
+#[cfg(not(feature = "zeroize"))]
+pub(super) fn consume_bearer_token(token: String) -> Result<HeaderValue, InvalidHeaderValue> {
+    StaticAuth::bearer(&token).map(|auth| auth.0)
+}
+
+/// [Private] Construct the `AuthProvider` used for plain access tokens
+#[cfg(feature = "zeroize")]
+pub(super) fn token_provider(token: &str) -> Result<SecretToken, InvalidHeaderValue> {
+    SecretToken::new(token.to_owned())
+}
+
+/// [Private] Construct the `AuthProvider` used for plain access tokens
+#[cfg(not(feature = "zeroize"))]
+pub(super) fn token_provider(token: &str) -> Result<StaticAuth, InvalidHeaderValue> {
+    StaticAuth::bearer(token)
+}
+
+/// [Private] An `AuthProvider` stored in a `ClientConfig`.  Two instances are
+/// equal iff they share the same provider.
+#[derive(Clone)]
+pub(super) struct AuthSlot(pub(super) Arc<dyn AuthProvider>);
+
+impl fmt::Debug for AuthSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthProvider").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for AuthSlot {
+    fn eq(&self, other: &AuthSlot) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for AuthSlot {}
@@ -0,0 +1,82 @@
+use pin_project_lite::pin_project;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+
+pin_project! {
+    /// [Private] A wrapper around a response body reader that fails all reads
+    /// with a [`std::io::ErrorKind::TimedOut`] error once a deadline has
+    /// passed.  The deadline is only checked when a read is attempted, so a
+    /// single read that blocks forever is not interrupted; that case is left
+    /// to the request timeout.
+    pub(super) struct ParseDeadline<R> {
+        #[pin]
+        inner: R,
+        deadline: Option<Instant>,
+    }
+}
+
+impl<R> ParseDeadline<R> {
+    /// Wrap `inner` so that reads fail once `timeout` has elapsed from now.
+    /// If `timeout` is `None`, reads are passed through unchanged.
+    pub(super) fn new(inner: R, timeout: Option<Duration>) -> ParseDeadline<R> {
+        ParseDeadline {
+            inner,
+            deadline: timeout.and_then(|d| Instant::now().checked_add(d)),
+        }
+    }
+}
+
+/// [Private] Return an error if `deadline` has passed
+fn check(deadline: Option<Instant>) -> std::io::Result<()> {
+    if deadline.is_some_and(|dl| Instant::now() >= dl) {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "response parse timeout exceeded",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for ParseDeadline<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        check(self.deadline)?;
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for ParseDeadline<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.project();
+        check(*this.deadline)?;
+        this.inner.poll_read(cx, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn no_deadline() {
+        let mut reader = ParseDeadline::new(&b"Hello, world!"[..], None);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "Hello, world!");
+    }
+
+    #[test]
+    fn deadline_passed() {
+        let mut reader = ParseDeadline::new(std::io::repeat(b'x'), Some(Duration::ZERO));
+        let e = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::TimedOut);
+    }
+}
@@ -0,0 +1,213 @@
+use super::{Backend, BackendResponse, RequestParts};
+use crate::{HttpUrl, response::TlsInfo};
+use http::header::HeaderMap;
+use std::fmt;
+use std::io::{Cursor, Read};
+
+/// A synchronous [`Backend`] that passes each request to a closure instead of
+/// sending it over the network
+///
+/// The closure is called with the parts of the request and the complete
+/// request body, and it must return the response to report to the client.
+/// The URL of the response is always the URL of the request.
+///
+/// This is intended for stubbing out responses in examples and small tests.
+#[derive(Clone)]
+pub struct FnBackend<F> {
+    func: F,
+}
+
+impl<F> FnBackend<F>
+where
+    F: Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>>,
+{
+    pub fn new(func: F) -> FnBackend<F> {
+        FnBackend { func }
+    }
+}
+
+impl<F> fmt::Debug for FnBackend<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnBackend").finish_non_exhaustive()
+    }
+}
+
+impl<F> Backend for FnBackend<F>
+where
+    F: Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>>,
+{
+    type Request = RequestParts;
+    type Response = FnResponse;
+    type Error = std::io::Error;
+
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
+        Ok(r)
+    }
+
+    fn send<R: Read>(&self, r: Self::Request, mut body: R) -> Result<Self::Response, Self::Error> {
+        let mut buf = Vec::new();
+        body.read_to_end(&mut buf)?;
+        let url = r.url.clone();
+        let response = (self.func)(r, buf);
+        Ok(FnResponse { url, response })
+    }
+}
+
+/// A response returned by the closure of a [`FnBackend`] or
+/// [`AsyncFnBackend`][super::tokio::AsyncFnBackend]
+#[derive(Clone, Debug)]
+pub struct FnResponse {
+    pub(super) url: HttpUrl,
+    pub(super) response: http::Response<Vec<u8>>,
+}
+
+impl FnResponse {
+    /// Return a reference to the response returned by the closure
+    pub fn response(&self) -> &http::Response<Vec<u8>> {
+        &self.response
+    }
+}
+
+impl BackendResponse for FnResponse {
+    fn url(&self) -> HttpUrl {
+        self.url.clone()
+    }
+
+    fn status(&self) -> http::status::StatusCode {
+        self.response.status()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.response.headers().clone()
+    }
+
+    fn body_reader(self) -> impl Read {
+        Cursor::new(self.response.into_body())
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.response.extensions().get::<TlsInfo>().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Endpoint, Method,
+        client::{ClientConfig, RedirectPolicy},
+        errors::{CommonError, ErrorPayload, ErrorResponse},
+        parser::{JsonResponse, ResponseParser},
+        request::Request,
+    };
+    use http::status::StatusCode;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Repository {
+        full_name: String,
+    }
+
+    struct GetRepo;
+
+    impl Request for GetRepo {
+        type Output = Repository;
+        type Error = CommonError;
+        type Body = ();
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["repos", "octocat", "hello-world"])
+        }
+
+        fn method(&self) -> Method {
+            Method::Get
+        }
+
+        fn body(&self) -> Self::Body {}
+
+        fn parser(
+            &self,
+        ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+            JsonResponse::new()
+        }
+    }
+
+    fn json_response(status: StatusCode, body: &str) -> http::Response<Vec<u8>> {
+        http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(body.as_bytes().to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn success() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world"
+            );
+            assert_eq!(parts.method, Method::Get);
+            assert!(body.is_empty());
+            json_response(StatusCode::OK, r#"{"full_name": "octocat/hello-world"}"#)
+        }));
+        let repo = client.request(GetRepo).unwrap();
+        assert_eq!(repo.full_name, "octocat/hello-world");
+    }
+
+    #[test]
+    fn error_status() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|_, _| {
+            json_response(StatusCode::NOT_FOUND, r#"{"message": "Not Found"}"#)
+        }));
+        let e = client.request(GetRepo).unwrap_err();
+        let ErrorPayload::Status(resp) = e.payload_ref() else {
+            panic!("Expected Status error, got {e:?}");
+        };
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            e.as_status().map(ErrorResponse::status),
+            Some(StatusCode::NOT_FOUND)
+        );
+        assert!(e.as_send().is_none());
+        assert!(e.payload_ref().as_parse_response().is_none());
+    }
+
+    #[test]
+    fn unfollowed_redirect() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|_, _| {
+            http::Response::builder()
+                .status(StatusCode::MOVED_PERMANENTLY)
+                .header(http::header::LOCATION, "/repositories/1296269")
+                .body(Vec::new())
+                .unwrap()
+        }));
+        let e = client.request(GetRepo).unwrap_err();
+        let ErrorPayload::Redirect(r) = e.payload_ref() else {
+            panic!("Expected Redirect error, got {e:?}");
+        };
+        assert_eq!(
+            r.location().unwrap().as_str(),
+            "https://api.github.com/repositories/1296269"
+        );
+    }
+
+    #[test]
+    fn followed_redirect() {
+        let client = ClientConfig::new()
+            .with_redirect_policy(RedirectPolicy::Follow(1))
+            .with_backend(FnBackend::new(|parts, _| {
+                if parts.url.as_str() == "https://api.github.com/repositories/1296269" {
+                    json_response(StatusCode::OK, r#"{"full_name": "octocat/Hello-World"}"#)
+                } else {
+                    http::Response::builder()
+                        .status(StatusCode::MOVED_PERMANENTLY)
+                        .header(http::header::LOCATION, "/repositories/1296269")
+                        .body(Vec::new())
+                        .unwrap()
+                }
+            }));
+        let repo = client.request(GetRepo).unwrap();
+        assert_eq!(repo.full_name, "octocat/Hello-World");
+    }
+}
@@ -0,0 +1,88 @@
+use crate::errors::ResponseTooLargeError;
+use pin_project_lite::pin_project;
+
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+
+pin_project! {
+    /// [Private] A wrapper around a response body reader that fails with a
+    /// [`ResponseTooLargeError`] once more than a given number of bytes have
+    /// been read through it
+    pub(super) struct SizeLimit<R> {
+        #[pin]
+        inner: R,
+        limit: Option<u64>,
+        read: u64,
+    }
+}
+
+impl<R> SizeLimit<R> {
+    /// Wrap `inner` so that reads fail once more than `limit` bytes have been
+    /// read.  If `limit` is `None`, reads are passed through unchanged.
+    pub(super) fn new(inner: R, limit: Option<u64>) -> SizeLimit<R> {
+        SizeLimit {
+            inner,
+            limit,
+            read: 0,
+        }
+    }
+}
+
+/// [Private] Add `n` to the running total `read` and return an error if the
+/// total now exceeds `limit`
+fn check(limit: Option<u64>, read: &mut u64, n: usize) -> std::io::Result<()> {
+    *read = read.saturating_add(n as u64);
+    match limit {
+        Some(limit) if *read > limit => Err(ResponseTooLargeError::new(limit).into_io_error()),
+        _ => Ok(()),
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for SizeLimit<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        check(self.limit, &mut self.read, n)?;
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for SizeLimit<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        std::task::ready!(this.inner.poll_read(cx, buf))?;
+        check(*this.limit, this.read, buf.filled().len() - before)?;
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn within_limit() {
+        let mut reader = SizeLimit::new(&b"Hello, world!"[..], Some(13));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "Hello, world!");
+    }
+
+    #[test]
+    fn limit_exceeded() {
+        let mut reader = SizeLimit::new(std::io::repeat(b'x'), Some(1024));
+        let e = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::FileTooLarge);
+        let inner = e
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<ResponseTooLargeError>())
+            .unwrap();
+        assert_eq!(inner.limit(), 1024);
+    }
+}
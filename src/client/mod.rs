@@ -2,20 +2,59 @@
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
+mod adhoc;
+mod auth;
+mod deadline;
+mod fn_backend;
+mod identity;
+mod limit;
+#[cfg(feature = "log")]
+mod preview;
+mod probe;
+mod ratelimit;
+mod sleep;
+mod token_store;
+pub use self::adhoc::*;
+use self::auth::AuthSlot;
+pub use self::auth::*;
+pub use self::fn_backend::*;
+use self::identity::IdentitySlot;
+pub use self::identity::*;
+use self::ratelimit::RateLimiterSlot;
+pub use self::ratelimit::*;
+pub use self::sleep::*;
+use self::sleep::{SleeperSlot, default_sleeper};
+pub use self::token_store::*;
 use crate::{
-    HttpUrl, Method,
+    HttpUrl, Method, ParseHttpUrlError,
     consts::{
         API_VERSION_HEADER, DEFAULT_ACCEPT, DEFAULT_API_URL, DEFAULT_API_VERSION,
-        DEFAULT_USER_AGENT,
+        DEFAULT_USER_AGENT, GITHUB_COM_URL, GRAPHQL_URL, RETAINED_RESPONSE_HEADERS, UPLOADS_URL,
     },
-    errors::{Error, ErrorPayload, ErrorResponseParser},
-    pagination::{PaginationIter, PaginationRequest},
-    parser::ResponseParserExt,
-    request::{Request, RequestBody},
-    response::{Response, ResponseParts},
+    errors::{
+        CapturedBody, CommonError, Error, ErrorPayload, ErrorResponseParser, RedirectResponse,
+    },
+    pagination::{
+        PageCursor, PageIter, PaginationIter, PaginationRequest, SearchIter, SearchRequest,
+    },
+    parser::{JsonArrayIter, ResponseParserExt, ToFile},
+    request::{BaseUrl, Request, RequestBody},
+    response::{Response, ResponseParts, TlsInfo},
+    util::{RedactedHeaders, encode_query},
 };
 use http::header::{HeaderMap, HeaderName, HeaderValue};
+use pin_project_lite::pin_project;
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
+use std::fmt;
+use std::net::IpAddr;
+#[cfg(feature = "reqwest")]
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
 use std::time::Duration;
+use thiserror::Error;
+use url::Url;
 
 #[cfg(feature = "tokio")]
 use self::tokio::AsyncClient;
@@ -46,11 +85,28 @@ let client = ClientConfig::new()
 ```
 "#
 )]
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct ClientConfig {
-    base_url: HttpUrl,
+    base_url: BaseUrlSlot,
+    uploads_url: Option<HttpUrl>,
+    graphql_url: Option<HttpUrl>,
+    web_url: Option<HttpUrl>,
     headers: HeaderMap,
+    auth: Option<AuthSlot>,
+    response_headers: Option<Vec<HeaderName>>,
     timeout: Option<Duration>,
+    parse_timeout: Option<Duration>,
+    max_response_size: Option<u64>,
+    redirect_policy: RedirectPolicy,
+    ip_preference: IpPreference,
+    local_address: Option<IpAddr>,
+    request_body_capture: usize,
+    parse_error_context: bool,
+    sleeper: Option<SleeperSlot>,
+    rate_limiter: Option<RateLimiterSlot>,
+    identity: IdentitySlot,
+    #[cfg(feature = "log")]
+    body_preview: usize,
 }
 
 impl ClientConfig {
@@ -63,9 +119,7 @@ impl ClientConfig {
             }
         }
 
-        let Ok(base_url) = DEFAULT_API_URL.parse::<HttpUrl>() else {
-            unreachable!("DEFAULT_API_URL should be a valid URL");
-        };
+        let base_url = parse_const_url(DEFAULT_API_URL, "DEFAULT_API_URL");
         let mut headers = HeaderMap::new();
         headers.insert(
             http::header::ACCEPT,
@@ -80,12 +134,99 @@ impl ClientConfig {
             parse_const_value(DEFAULT_USER_AGENT, "DEFAULT_USER_AGENT"),
         );
         ClientConfig {
-            base_url,
+            base_url: BaseUrlSlot::new(base_url),
+            uploads_url: None,
+            graphql_url: None,
+            web_url: None,
             headers,
+            auth: None,
+            response_headers: None,
             timeout: None,
+            parse_timeout: None,
+            max_response_size: None,
+            redirect_policy: RedirectPolicy::default(),
+            ip_preference: IpPreference::default(),
+            local_address: None,
+            request_body_capture: 0,
+            parse_error_context: false,
+            sleeper: None,
+            rate_limiter: None,
+            identity: IdentitySlot::default(),
+            #[cfg(feature = "log")]
+            body_preview: 0,
         }
     }
 
+    /// Create a new `ClientConfig` configured from environment variables, as
+    /// set in GitHub Actions runners:
+    ///
+    /// - If `GITHUB_TOKEN` or (failing that) `GH_TOKEN` is set to a nonempty
+    ///   value, it is used as the access token, as though passed to
+    ///   [`ClientConfig::with_auth_token()`].
+    ///
+    /// - If `GITHUB_API_URL` is set to a nonempty value, it is used as the
+    ///   base API URL.  The uploads, GraphQL, and web URLs are then derived
+    ///   from it as described in [`ClientConfig::uploads_url()`] et alii.
+    ///
+    /// All other settings have their default values.
+    ///
+    /// Note that proxy settings are not read by this method, as the backends
+    /// provided by `ghreq` honor the standard `HTTPS_PROXY`, `ALL_PROXY`, and
+    /// `NO_PROXY` environment variables on their own.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the token is not a valid header value or if the API
+    /// URL is not a valid HTTP(S) URL.
+    pub fn from_env() -> Result<ClientConfig, FromEnvError> {
+        ClientConfig::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// [Private] Implementation of [`ClientConfig::from_env()`] that takes a
+    /// function for looking up variables
+    fn from_vars<F>(getvar: F) -> Result<ClientConfig, FromEnvError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let getvar = |name: &str| getvar(name).filter(|v| !v.is_empty());
+        let mut config = ClientConfig::new();
+        for name in ["GITHUB_TOKEN", "GH_TOKEN"] {
+            if let Some(token) = getvar(name) {
+                let auth =
+                    token_provider(&token).map_err(|_| FromEnvError::InvalidToken { var: name })?;
+                config = config.with_auth_provider(auth);
+                break;
+            }
+        }
+        if let Some(url) = getvar("GITHUB_API_URL") {
+            let url = url
+                .parse::<HttpUrl>()
+                .map_err(|source| FromEnvError::InvalidApiUrl { source })?;
+            config = config.with_base_url(url);
+        }
+        Ok(config)
+    }
+
+    /// Use the GitHub access token that the [`gh`](https://cli.github.com)
+    /// command-line tool would use, as though passed to
+    /// [`ClientConfig::with_auth_token()`].
+    ///
+    /// The token is looked up with [`gh_token::get()`], which checks the
+    /// `GH_TOKEN` and `GITHUB_TOKEN` environment variables and then the `gh`
+    /// configuration (via `gh auth token` or `hosts.yml`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if no token could be found or if the token found is not
+    /// a valid header value.
+    #[cfg(feature = "gh-token")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gh-token")))]
+    pub fn with_gh_cli_token(self) -> Result<Self, GhTokenError> {
+        let token = gh_token::get().map_err(GhTokenError::NotFound)?;
+        let auth = token_provider(&token).map_err(|_| GhTokenError::Invalid)?;
+        Ok(self.with_auth_provider(auth))
+    }
+
     /// Set the base API URL for making API requests.
     ///
     /// When the resulting client is given a request whose
@@ -94,14 +235,185 @@ impl ClientConfig {
     ///
     /// The default base API URL is given by [`DEFAULT_API_URL`].
     pub fn with_base_url(mut self, url: HttpUrl) -> Self {
-        self.base_url = url;
+        self.base_url = BaseUrlSlot::new(url);
+        self
+    }
+
+    /// Configure the client for use with the GitHub Enterprise Server instance
+    /// at the given host (e.g., `"github.example.com"` or
+    /// `"github.example.com:8443"`).
+    ///
+    /// This sets the base API URL to `https://{host}/api/v3`, from which the
+    /// uploads, GraphQL, and web URLs are then derived as described in
+    /// [`ClientConfig::uploads_url()`] et alii.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `host` is not a bare hostname with an optional port,
+    /// e.g., if it includes a URL scheme or path.
+    pub fn with_github_enterprise(self, host: &str) -> Result<Self, InvalidHostError> {
+        let err = || InvalidHostError(host.to_owned());
+        if host.is_empty()
+            || host
+                .chars()
+                .any(|c| matches!(c, '/' | '\\' | '?' | '#' | '@') || c.is_whitespace())
+        {
+            return Err(err());
+        }
+        let url = format!("https://{host}/api/v3")
+            .parse::<HttpUrl>()
+            .map_err(|_| err())?;
+        if url.as_url().host_str().is_none_or(str::is_empty) {
+            return Err(err());
+        }
+        Ok(self.with_base_url(url))
+    }
+
+    /// Set the base URL for uploading release assets, overriding the URL
+    /// derived from the base API URL (see [`ClientConfig::uploads_url()`])
+    pub fn with_uploads_url(mut self, url: HttpUrl) -> Self {
+        self.uploads_url = Some(url);
+        self
+    }
+
+    /// Set the URL of the GraphQL API endpoint, overriding the URL derived
+    /// from the base API URL (see [`ClientConfig::graphql_url()`])
+    pub fn with_graphql_url(mut self, url: HttpUrl) -> Self {
+        self.graphql_url = Some(url);
+        self
+    }
+
+    /// Set the URL of the GitHub website, overriding the URL derived from the
+    /// base API URL (see [`ClientConfig::web_url()`])
+    pub fn with_web_url(mut self, url: HttpUrl) -> Self {
+        self.web_url = Some(url);
         self
     }
 
+    /// Returns the base API URL
+    pub fn base_url(&self) -> HttpUrl {
+        self.base_url.get()
+    }
+
+    /// Returns the base URL for uploading release assets.  Requests whose
+    /// [`Request::base_url()`] method returns [`BaseUrl::Uploads`] are
+    /// resolved against this URL.
+    ///
+    /// Unless set with [`ClientConfig::with_uploads_url()`], this is derived
+    /// from the base API URL as follows:
+    ///
+    /// - If the base API URL is [`DEFAULT_API_URL`], the result is
+    ///   [`UPLOADS_URL`].
+    ///
+    /// - If the base API URL has the form `{host}/api/v3`, as on GitHub
+    ///   Enterprise Server, the result is `{host}/api/uploads`.
+    ///
+    /// - Otherwise, the result is the base API URL.
+    pub fn uploads_url(&self) -> HttpUrl {
+        if let Some(url) = self.uploads_url.as_ref() {
+            url.clone()
+        } else if self.is_github_com() {
+            parse_const_url(UPLOADS_URL, "UPLOADS_URL")
+        } else if let Some(url) = self.ghes_url("uploads") {
+            url
+        } else {
+            self.base_url()
+        }
+    }
+
+    /// Returns the URL of the GraphQL API endpoint.
+    ///
+    /// Unless set with [`ClientConfig::with_graphql_url()`], this is derived
+    /// from the base API URL as follows:
+    ///
+    /// - If the base API URL is [`DEFAULT_API_URL`], the result is
+    ///   [`GRAPHQL_URL`].
+    ///
+    /// - If the base API URL has the form `{host}/api/v3`, as on GitHub
+    ///   Enterprise Server, the result is `{host}/api/graphql`.
+    ///
+    /// - Otherwise, the result is the base API URL with `graphql` appended.
+    pub fn graphql_url(&self) -> HttpUrl {
+        if let Some(url) = self.graphql_url.as_ref() {
+            url.clone()
+        } else if self.is_github_com() {
+            parse_const_url(GRAPHQL_URL, "GRAPHQL_URL")
+        } else if let Some(url) = self.ghes_url("graphql") {
+            url
+        } else {
+            let mut url = self.base_url();
+            url.push("graphql");
+            url
+        }
+    }
+
+    /// Returns the URL of the GitHub website.
+    ///
+    /// Unless set with [`ClientConfig::with_web_url()`], this is derived from
+    /// the base API URL as follows:
+    ///
+    /// - If the base API URL is [`DEFAULT_API_URL`], the result is
+    ///   [`GITHUB_COM_URL`].
+    ///
+    /// - Otherwise, the result is the base API URL with its path removed.
+    pub fn web_url(&self) -> HttpUrl {
+        if let Some(url) = self.web_url.as_ref() {
+            url.clone()
+        } else if self.is_github_com() {
+            parse_const_url(GITHUB_COM_URL, "GITHUB_COM_URL")
+        } else {
+            let mut url = Url::from(self.base_url());
+            url.set_path("");
+            url.set_query(None);
+            url.set_fragment(None);
+            let Ok(url) = HttpUrl::try_from(url) else {
+                unreachable!("Changing the path of an HttpUrl should not change its scheme");
+            };
+            url
+        }
+    }
+
+    /// [Private] Returns the URL that a request with the given [`BaseUrl`]
+    /// resolves its endpoint against
+    fn resolve_base_url(&self, base: BaseUrl) -> HttpUrl {
+        match base {
+            BaseUrl::Api => self.base_url(),
+            BaseUrl::Uploads => self.uploads_url(),
+            BaseUrl::Graphql => self.graphql_url(),
+        }
+    }
+
+    /// [Private] Returns true if the base API URL is that of github.com
+    fn is_github_com(&self) -> bool {
+        self.base_url() == parse_const_url(DEFAULT_API_URL, "DEFAULT_API_URL")
+    }
+
+    /// [Private] If the base API URL ends in `/api/v3`, return the URL with
+    /// the `v3` replaced by `last`
+    fn ghes_url(&self, last: &str) -> Option<HttpUrl> {
+        let base_url = self.base_url();
+        let segments = base_url
+            .as_url()
+            .path_segments()?
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        let [prefix @ .., "api", "v3"] = segments.as_slice() else {
+            return None;
+        };
+        let mut url = Url::from(base_url.clone());
+        url.set_path("");
+        let mut url = HttpUrl::try_from(url).ok()?;
+        url.extend(prefix).extend(["api", last]);
+        Some(url)
+    }
+
     /// Send the given access token in the "Authorization" header of outgoing
     /// requests.
     ///
-    /// By default, no access token is sent.
+    /// This is equivalent to calling [`ClientConfig::with_auth_provider()`]
+    /// with a [`StaticAuth::bearer()`] value (or, if the `zeroize` feature is
+    /// enabled, a `SecretToken` value, which keeps the token in memory that is
+    /// zeroed on drop).  By default, no access token is sent.
     ///
     /// # Errors
     ///
@@ -109,17 +421,25 @@ impl ClientConfig {
     /// [`HeaderValue`], then `Err` is returned, containing the unmodified
     /// `ClientConfig`.
     #[allow(clippy::result_large_err)]
-    pub fn with_auth_token(mut self, token: &str) -> Result<Self, Self> {
-        let value = format!("Bearer {token}");
-        match value.parse::<HeaderValue>() {
-            Ok(value) => {
-                self.headers.insert(http::header::AUTHORIZATION, value);
-                Ok(self)
-            }
+    pub fn with_auth_token(self, token: &str) -> Result<Self, Self> {
+        match token_provider(token) {
+            Ok(auth) => Ok(self.with_auth_provider(auth)),
             Err(_) => Err(self),
         }
     }
 
+    /// Obtain the "Authorization" header for each outgoing request from the
+    /// given [`AuthProvider`], replacing any previously-configured provider or
+    /// token.
+    ///
+    /// Note that an "Authorization" header set via
+    /// [`ClientConfig::with_header()`] takes precedence over the provider.
+    pub fn with_auth_provider<A: AuthProvider + 'static>(mut self, provider: A) -> Self {
+        self.auth = Some(AuthSlot(Arc::new(provider)));
+        self.identity = IdentitySlot::default();
+        self
+    }
+
     /// Set the value to use for the `User-Agent` header in outgoing requests.
     ///
     /// The default setting is given by [`DEFAULT_USER_AGENT`].
@@ -147,6 +467,9 @@ impl ClientConfig {
 
     /// Add the given HTTP header & value to all outgoing requests.
     pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        if name == http::header::AUTHORIZATION {
+            self.identity = IdentitySlot::default();
+        }
         self.headers.insert(name, value);
         self
     }
@@ -162,6 +485,199 @@ impl ClientConfig {
         self
     }
 
+    /// Limit the time spent reading & parsing each response body to the given
+    /// duration, measured from when the response headers are received.
+    ///
+    /// Unlike the request timeout, which backends typically apply to each
+    /// individual read, this is a deadline for the body as a whole, so a
+    /// misbehaving server or proxy that keeps sending data cannot keep the
+    /// parser reading forever.  Once the deadline passes, the next read of the
+    /// body fails with an I/O error of kind
+    /// [`std::io::ErrorKind::TimedOut`], which is reported as an
+    /// [`ErrorPayload::ParseResponse`] error.
+    ///
+    /// By default, there is no parse timeout.
+    pub fn with_parse_timeout(mut self, timeout: Duration) -> Self {
+        self.parse_timeout = Some(timeout);
+        self
+    }
+
+    /// Stop reading a response body once it exceeds `size` bytes, protecting
+    /// memory when a misbehaving endpoint or a wrong URL returns an enormous
+    /// payload.  The read that crosses the limit fails with an I/O error of
+    /// kind [`std::io::ErrorKind::FileTooLarge`] wrapping a
+    /// [`ResponseTooLargeError`][crate::errors::ResponseTooLargeError], which
+    /// is reported as an [`ErrorPayload::ParseResponse`] error.
+    ///
+    /// This applies to the bodies of all responses, including error
+    /// responses.  To limit only the responses to certain requests, wrap their
+    /// parsers in [`Limited`][crate::parser::Limited] instead.
+    ///
+    /// By default, there is no limit.
+    pub fn with_max_response_size(mut self, size: u64) -> Self {
+        self.max_response_size = Some(size);
+        self
+    }
+
+    /// Only retain the given response headers (plus those listed in
+    /// [`RETAINED_RESPONSE_HEADERS`]) in the [`ResponseParts`] and
+    /// [`ErrorResponse`][crate::errors::ErrorResponse] values produced by the
+    /// client, discarding all others as soon as the response is received.
+    ///
+    /// This reduces memory usage in services that keep many responses around.
+    /// By default, all response headers are retained.
+    pub fn with_response_header_allowlist<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        let mut allowed = RETAINED_RESPONSE_HEADERS
+            .iter()
+            .map(|name| HeaderName::from_static(name))
+            .collect::<Vec<_>>();
+        for name in names {
+            if !allowed.contains(&name) {
+                allowed.push(name);
+            }
+        }
+        self.response_headers = Some(allowed);
+        self
+    }
+
+    /// Log up to the first `limit` bytes of each response body at the DEBUG
+    /// level once the response has been parsed, so that the cause of parse
+    /// failures can be investigated without intercepting traffic.
+    ///
+    /// The bytes are captured as they are read from the backend, after any
+    /// content decoding (e.g., gzip decompression) performed by the backend.
+    /// Bodies that are not UTF-8 text are logged only as a byte count.  A
+    /// limit of 0 (the default) disables body previews.
+    #[cfg(feature = "log")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+    pub fn with_body_preview(mut self, limit: usize) -> Self {
+        self.body_preview = limit;
+        self
+    }
+
+    /// Retain a copy of each request body of at most `limit` bytes and attach
+    /// it to any [`Error`][struct@Error] resulting from the request, where it
+    /// can be retrieved with [`Error::request_body()`].
+    ///
+    /// Only bodies held in memory (see
+    /// [`RequestBody::in_memory()`][crate::request::RequestBody::in_memory]),
+    /// such as byte vectors, strings, and JSON bodies, are captured; bodies
+    /// read from files or other streams are not.  Sensitive fields of JSON
+    /// bodies are redacted as described in [`CapturedBody`].  A limit of 0
+    /// (the default) disables capturing.
+    pub fn with_request_body_capture(mut self, limit: usize) -> Self {
+        self.request_body_capture = limit;
+        self
+    }
+
+    /// Attach a [`ParseErrorContext`][crate::errors::ParseErrorContext]
+    /// describing the response — its final URL, status, `Content-Type`, and
+    /// the number of body bytes read — to every
+    /// [`ErrorPayload::ParseResponse`] error produced by the client, so that
+    /// errors like a bare `serde_json::Error` can be traced back to the
+    /// response that caused them.  The context can be retrieved with
+    /// [`Error::parse_context()`] and is included in the error's `Display`
+    /// output.
+    ///
+    /// This is disabled by default.
+    pub fn with_parse_error_context(mut self, flag: bool) -> Self {
+        self.parse_error_context = flag;
+        self
+    }
+
+    /// Use the given [`Sleeper`] whenever the client needs to wait between
+    /// requests, replacing the default sleeper for the current platform.
+    ///
+    /// This is mainly useful for testing, e.g., with
+    /// [`FakeSleeper`][crate::testing::FakeSleeper].
+    pub fn with_sleeper<S: Sleeper + 'static>(mut self, sleeper: S) -> Self {
+        self.sleeper = Some(SleeperSlot(Arc::new(sleeper)));
+        self
+    }
+
+    /// Returns the [`Sleeper`] that the client uses to wait between requests:
+    /// the one set with [`ClientConfig::with_sleeper()`], if any, or else the
+    /// default for the current platform.  Returns `None` if no sleeper was
+    /// set and there is no default, i.e., on native targets without the
+    /// `tokio` feature.
+    pub fn sleeper(&self) -> Option<Arc<dyn Sleeper>> {
+        match self.sleeper {
+            Some(SleeperSlot(ref sleeper)) => Some(Arc::clone(sleeper)),
+            None => default_sleeper(),
+        }
+    }
+
+    /// Throttle requests using the given [`RateLimiter`], which keeps a
+    /// separate budget for each rate limit resource (core, search, GraphQL,
+    /// etc.) and delays a request only when the budget of the resource it
+    /// counts against is exhausted.  The same limiter can be shared by
+    /// several clients.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(RateLimiterSlot(limiter));
+        self
+    }
+
+    /// Returns the [`RateLimiter`] set with
+    /// [`ClientConfig::with_rate_limiter()`], if any
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref().map(|slot| &slot.0)
+    }
+
+    /// [Private] Returns the configured rate limiter, if any, along with the
+    /// resource that a request to `url` is expected to count against
+    fn rate_limit_route(&self, url: &HttpUrl) -> Option<(&RateLimiter, RateLimitResource)> {
+        let limiter = self.rate_limiter.as_ref()?;
+        let resource = if *url == self.graphql_url() {
+            RateLimitResource::Graphql
+        } else {
+            let base_url = self.base_url();
+            let base = base_url.as_url().path().trim_end_matches('/');
+            let path = url.as_url().path();
+            path.strip_prefix(base)
+                .map_or(RateLimitResource::Core, RateLimitResource::for_path)
+        };
+        Some((&limiter.0, resource))
+    }
+
+    /// Set how the client should handle 3xx responses that the backend did not
+    /// follow itself.
+    ///
+    /// The default policy is [`RedirectPolicy::Error`].
+    pub fn with_redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = policy;
+        self
+    }
+
+    /// Restrict which IP address families are used when connecting to the
+    /// server, e.g., for environments with broken IPv6 routes.
+    ///
+    /// This setting is applied to the backends created by
+    /// [`ClientConfig::with_ureq()`] and [`ClientConfig::with_reqwest()`]; it
+    /// has no effect on backends passed to [`ClientConfig::with_backend()`] or
+    /// [`ClientConfig::with_async_backend()`], which must be configured
+    /// directly.  The default is [`IpPreference::Any`].
+    pub fn with_ip_preference(mut self, preference: IpPreference) -> Self {
+        self.ip_preference = preference;
+        self
+    }
+
+    /// Bind outgoing connections to the given local IP address.
+    ///
+    /// This setting is applied to the backend created by
+    /// [`ClientConfig::with_reqwest()`], where it takes precedence over
+    /// [`ClientConfig::with_ip_preference()`].  It is ignored by
+    /// [`ClientConfig::with_ureq()`], as `ureq` does not support binding to a
+    /// local address, and it has no effect on backends passed to
+    /// [`ClientConfig::with_backend()`] or
+    /// [`ClientConfig::with_async_backend()`].
+    pub fn with_local_address(mut self, addr: IpAddr) -> Self {
+        self.local_address = Some(addr);
+        self
+    }
+
     /// Combine the `ClientConfig` with the given synchronous backend (ideally
     /// an implementor of [`Backend`]) to acquire a synchronous [`Client`].
     pub fn with_backend<B>(self, backend: B) -> Client<B> {
@@ -177,10 +693,7 @@ impl ClientConfig {
     #[cfg(feature = "tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
     pub fn with_async_backend<B>(self, backend: B) -> AsyncClient<B> {
-        AsyncClient {
-            config: self,
-            backend,
-        }
+        AsyncClient::new(self, backend)
     }
 
     /// Combine the `ClientConfig` with a default [`ureq::Agent`] to acquire an
@@ -188,15 +701,76 @@ impl ClientConfig {
     #[cfg(feature = "ureq")]
     #[cfg_attr(docsrs, doc(cfg(feature = "ureq")))]
     pub fn with_ureq(self) -> crate::ureq::UreqClient {
-        self.with_backend(ureq::Agent::new_with_defaults())
+        let ip_family = match self.ip_preference {
+            IpPreference::Any => ureq::config::IpFamily::Any,
+            IpPreference::Ipv4Only => ureq::config::IpFamily::Ipv4Only,
+            IpPreference::Ipv6Only => ureq::config::IpFamily::Ipv6Only,
+        };
+        let agent = ureq::Agent::config_builder()
+            .ip_family(ip_family)
+            .build()
+            .new_agent();
+        self.with_backend(agent)
     }
 
     /// Combine the `ClientConfig` with a default [`reqwest::Client`] to
     /// acquire a [`ReqwestClient`][crate::reqwest::ReqwestClient].
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`reqwest::Client::new()`], i.e.,
+    /// if a TLS backend cannot be initialized or the resolver cannot load the
+    /// system configuration.
     #[cfg(feature = "reqwest")]
     #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
     pub fn with_reqwest(self) -> crate::reqwest::ReqwestClient {
-        self.with_async_backend(reqwest::Client::default())
+        let client = reqwest::Client::builder()
+            .local_address(self.bind_address())
+            .build()
+            .expect("reqwest client should be buildable");
+        self.with_async_backend(client)
+    }
+
+    /// [Private] Determine the local address that a `reqwest` backend should
+    /// bind to.  Binding to an unspecified address of a given family causes
+    /// `reqwest` to only connect to server addresses of that family.
+    #[cfg(feature = "reqwest")]
+    fn bind_address(&self) -> Option<IpAddr> {
+        self.local_address.or(match self.ip_preference {
+            IpPreference::Any => None,
+            IpPreference::Ipv4Only => Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            IpPreference::Ipv6Only => Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        })
+    }
+
+    /// [Private] Obtain the value of the "Authorization" header for the next
+    /// request
+    fn auth_header(&self) -> Result<Option<HeaderValue>, AuthError> {
+        match self.auth.as_ref() {
+            Some(auth) => auth.0.auth_header(),
+            None => Ok(None),
+        }
+    }
+
+    /// [Private] Asynchronously obtain the value of the "Authorization"
+    /// header for the next request
+    #[cfg(feature = "tokio")]
+    async fn auth_header_async(&self) -> Result<Option<HeaderValue>, AuthError> {
+        match self.auth.as_ref() {
+            Some(auth) => auth.0.auth_header_async().await,
+            None => Ok(None),
+        }
+    }
+
+    /// [Private] Return the headers to send with every request: the
+    /// configured headers plus the "Authorization" header, if any
+    fn base_headers(&self, auth: Option<HeaderValue>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = auth {
+            headers.insert(http::header::AUTHORIZATION, value);
+        }
+        headers.extend(self.headers.clone());
+        headers
     }
 
     /// [Private] Convert a [`Request`] instance into a [`PreparedRequest`]
@@ -204,28 +778,53 @@ impl ClientConfig {
     fn prepare_request<R, BE>(
         &self,
         req: &R,
+        auth: Result<Option<HeaderValue>, AuthError>,
     ) -> Result<PreparedRequest<impl std::io::Read + 'static + use<R, BE>>, Error<BE, R::Error>>
     where
         R: Request<Body: RequestBody<Error: Into<R::Error>>>,
     {
-        let mut url = self.base_url.join_endpoint(req.endpoint());
+        let endpoint = req.endpoint();
+        let base_url = self.resolve_base_url(req.base_url());
+        let validity = endpoint.validate_against(&base_url);
+        let mut url = base_url.join_endpoint(endpoint);
+        if let Err(e) = validity {
+            let payload = ErrorPayload::InvalidEndpoint(Box::new(e));
+            return Err(Error::new(url, req.method(), payload));
+        }
+        let auth = match auth {
+            Ok(auth) => auth,
+            Err(e) => return Err(Error::new(url, req.method(), ErrorPayload::Auth(e))),
+        };
         for (name, value) in req.params() {
             url.append_query_param(&name, &value);
         }
+        match encode_query(&req.query()) {
+            Ok(query) => {
+                for (name, value) in query {
+                    url.append_query_param(&name, &value);
+                }
+            }
+            Err(e) => {
+                let payload = ErrorPayload::PrepareRequest(e.into());
+                return Err(Error::new(url, req.method(), payload));
+            }
+        }
         let method = req.method();
         let timeout = req.timeout().or(self.timeout);
         let body = req.body();
         // Set the body headers first so that the Request can override them if
         // it wants
-        let mut headers = self.headers.clone();
+        let mut headers = self.base_headers(auth);
         headers.extend(body.headers());
         headers.extend(req.headers());
+        req.extra_accept().apply_to(&mut headers);
         let parts = RequestParts {
             url: url.clone(),
             method,
             headers,
             timeout,
         };
+        let captured = self.capture_body(body.in_memory());
         let body = match body.into_read() {
             Ok(body) => body,
             Err(e) => {
@@ -233,46 +832,250 @@ impl ClientConfig {
                 return Err(Error::new(parts.url, parts.method, payload));
             }
         };
-        Ok(PreparedRequest::from_parts(parts, body))
+        let mut prepped = PreparedRequest::from_parts(parts, body);
+        prepped.captured_body = captured;
+        Ok(prepped)
     }
 
     /// [Private] Convert a [`Request`] instance into a [`PreparedRequest`]
-    /// with a [`tokio::io::AsyncRead`] for a body.
+    /// holding the request's body, which the caller must then convert into a
+    /// [`tokio::io::AsyncRead`] with [`AsyncRequestBody::into_async_read()`]
     #[cfg(feature = "tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
     fn prepare_async_request<R, BE>(
         &self,
         req: &R,
-    ) -> Result<PreparedRequest<impl ::tokio::io::AsyncRead + Send + 'static>, Error<BE, R::Error>>
+        auth: Result<Option<HeaderValue>, AuthError>,
+    ) -> Result<PreparedRequest<R::Body>, Error<BE, R::Error>>
     where
         R: Request<Body: AsyncRequestBody<Error: Into<<R as Request>::Error>>>,
     {
-        let mut url = self.base_url.join_endpoint(req.endpoint());
+        let endpoint = req.endpoint();
+        let base_url = self.resolve_base_url(req.base_url());
+        let validity = endpoint.validate_against(&base_url);
+        let mut url = base_url.join_endpoint(endpoint);
+        if let Err(e) = validity {
+            let payload = ErrorPayload::InvalidEndpoint(Box::new(e));
+            return Err(Error::new(url, req.method(), payload));
+        }
+        let auth = match auth {
+            Ok(auth) => auth,
+            Err(e) => return Err(Error::new(url, req.method(), ErrorPayload::Auth(e))),
+        };
         for (name, value) in req.params() {
             url.append_query_param(&name, &value);
         }
+        match encode_query(&req.query()) {
+            Ok(query) => {
+                for (name, value) in query {
+                    url.append_query_param(&name, &value);
+                }
+            }
+            Err(e) => {
+                let payload = ErrorPayload::PrepareRequest(e.into());
+                return Err(Error::new(url, req.method(), payload));
+            }
+        }
         let method = req.method();
         let timeout = req.timeout().or(self.timeout);
         let body = req.body();
         // Set the body headers first so that the Request can override them if
         // it wants
-        let mut headers = self.headers.clone();
+        let mut headers = self.base_headers(auth);
         headers.extend(body.headers());
         headers.extend(req.headers());
+        req.extra_accept().apply_to(&mut headers);
         let parts = RequestParts {
             url: url.clone(),
             method,
             headers,
             timeout,
         };
-        let body = match body.into_async_read() {
-            Ok(body) => body,
-            Err(e) => {
-                let payload = ErrorPayload::PrepareRequest(e.into());
-                return Err(Error::new(parts.url, parts.method, payload));
+        let captured = self.capture_body(body.in_memory());
+        let mut prepped = PreparedRequest::from_parts(parts, body);
+        prepped.captured_body = captured;
+        Ok(prepped)
+    }
+
+    /// [Private] Capture a copy of an in-memory request body if request body
+    /// capture is enabled and the body is within the size limit
+    fn capture_body(&self, body: Option<Cow<'_, [u8]>>) -> Option<CapturedBody> {
+        body.filter(|b| self.request_body_capture > 0 && b.len() <= self.request_body_capture)
+            .map(|b| CapturedBody::new(&b))
+    }
+
+    /// [Private] Remove any response headers not in the allow-list, if one is
+    /// configured
+    fn filter_response_headers(&self, mut headers: HeaderMap) -> HeaderMap {
+        if let Some(allowed) = self.response_headers.as_ref() {
+            let mut filtered = HeaderMap::with_capacity(allowed.len());
+            for name in allowed {
+                if let http::header::Entry::Occupied(e) = headers.entry(name) {
+                    let (name, values) = e.remove_entry_mult();
+                    for v in values {
+                        filtered.append(&name, v);
+                    }
+                }
             }
+            filtered
+        } else {
+            headers
+        }
+    }
+
+    /// [Private] Returns true if a response with the given status should be
+    /// treated as a redirect rather than passed to the request's parser
+    fn handles_redirect(&self, status: http::status::StatusCode) -> bool {
+        status.is_redirection()
+            && status != http::status::StatusCode::NOT_MODIFIED
+            && self.redirect_policy != RedirectPolicy::Parse
+    }
+
+    /// [Private] Given the parts of a request and the redirect response it
+    /// received, return the parts of the request to make next and whether to
+    /// send the request body again.  Returns `None` if the redirect should not
+    /// be followed.
+    fn follow_redirect(
+        &self,
+        reqparts: &RequestParts,
+        redirect: &RedirectResponse,
+        redirects: usize,
+    ) -> Option<(RequestParts, bool)> {
+        let RedirectPolicy::Follow(limit) = self.redirect_policy else {
+            return None;
         };
-        Ok(PreparedRequest::from_parts(parts, body))
+        if redirects >= limit {
+            return None;
+        }
+        let location = redirect.location()?;
+        let mut parts = reqparts.clone();
+        let mut keep_body = true;
+        if redirect.status() == http::status::StatusCode::SEE_OTHER && parts.method != Method::Head
+        {
+            parts.method = Method::Get;
+            keep_body = false;
+            for name in [
+                http::header::CONTENT_ENCODING,
+                http::header::CONTENT_LENGTH,
+                http::header::CONTENT_TYPE,
+            ] {
+                parts.headers.remove(name);
+            }
+        }
+        if parts.url.as_url().origin() != location.as_url().origin() {
+            // Don't leak credentials to other hosts
+            parts.headers.remove(http::header::AUTHORIZATION);
+        }
+        parts.url = location.clone();
+        Some((parts, keep_body))
+    }
+}
+
+/// [Private] Parse a URL constant
+fn parse_const_url(value: &str, name: &str) -> HttpUrl {
+    match value.parse::<HttpUrl>() {
+        Ok(url) => url,
+        Err(_) => unreachable!("{name} should be a valid URL"),
+    }
+}
+
+/// Error returned by [`ClientConfig::from_env()`]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum FromEnvError {
+    /// The token in the given environment variable could not be used in a
+    /// header value
+    #[error("value of ${var} is not a valid token")]
+    InvalidToken { var: &'static str },
+
+    /// The value of `$GITHUB_API_URL` is not a valid HTTP(S) URL
+    #[error("value of $GITHUB_API_URL is not a valid HTTP(S) URL")]
+    InvalidApiUrl { source: ParseHttpUrlError },
+}
+
+/// Error returned by [`ClientConfig::with_gh_cli_token()`]
+#[cfg(feature = "gh-token")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gh-token")))]
+#[derive(Debug, Error)]
+pub enum GhTokenError {
+    /// No token could be found.  Run `gh auth login` or set `GH_TOKEN` to
+    /// provide one.
+    #[error("no GitHub token found; run `gh auth login` or set $GH_TOKEN")]
+    NotFound(#[source] gh_token::Error),
+
+    /// The token found could not be used in a header value
+    #[error("GitHub token used by gh is not a valid header value")]
+    Invalid,
+}
+
+/// Error returned by [`ClientConfig::with_github_enterprise()`] when given an
+/// invalid host
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("invalid GitHub Enterprise Server host: {0:?}")]
+pub struct InvalidHostError(String);
+
+/// [Private] The base API URL stored in a `ClientConfig`, kept behind a lock
+/// so that it can be changed via [`Client::set_base_url()`] without
+/// exclusive access to the client.  Cloning a slot copies the current URL
+/// into a new lock rather than sharing it.
+struct BaseUrlSlot(RwLock<HttpUrl>);
+
+impl BaseUrlSlot {
+    fn new(url: HttpUrl) -> BaseUrlSlot {
+        BaseUrlSlot(RwLock::new(url))
+    }
+
+    fn get(&self) -> HttpUrl {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set(&self, url: HttpUrl) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = url;
+    }
+}
+
+impl Clone for BaseUrlSlot {
+    fn clone(&self) -> BaseUrlSlot {
+        BaseUrlSlot::new(self.get())
+    }
+}
+
+impl PartialEq for BaseUrlSlot {
+    fn eq(&self, other: &BaseUrlSlot) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl Eq for BaseUrlSlot {}
+
+/// The `Debug` output masks the values of the `Authorization` header and
+/// any other credential-bearing or sensitive headers.
+impl fmt::Debug for ClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("ClientConfig");
+        s.field("base_url", &self.base_url.get())
+            .field("uploads_url", &self.uploads_url)
+            .field("graphql_url", &self.graphql_url)
+            .field("web_url", &self.web_url)
+            .field("headers", &RedactedHeaders(&self.headers))
+            .field("auth", &self.auth)
+            .field("response_headers", &self.response_headers)
+            .field("timeout", &self.timeout)
+            .field("parse_timeout", &self.parse_timeout)
+            .field("max_response_size", &self.max_response_size)
+            .field("redirect_policy", &self.redirect_policy)
+            .field("ip_preference", &self.ip_preference)
+            .field("local_address", &self.local_address)
+            .field("request_body_capture", &self.request_body_capture)
+            .field("parse_error_context", &self.parse_error_context)
+            .field("sleeper", &self.sleeper)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("identity", &self.identity);
+        #[cfg(feature = "log")]
+        s.field("body_preview", &self.body_preview);
+        s.finish()
     }
 }
 
@@ -282,10 +1085,55 @@ impl Default for ClientConfig {
     }
 }
 
+/// How a client handles 3xx responses that the backend did not follow itself
+///
+/// Most backends follow redirects automatically, but they may decline to do so
+/// (e.g., when redirects are disabled, when the redirect limit is exceeded, or
+/// when the redirect crosses protocols), in which case the 3xx response is
+/// handed to the client.  A `304 Not Modified` response is never treated as a
+/// redirect.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum RedirectPolicy {
+    /// Fail with an [`ErrorPayload::Redirect`] error containing the response's
+    /// `Location`
+    #[default]
+    Error,
+
+    /// Follow up to the given number of consecutive redirects, failing with an
+    /// [`ErrorPayload::Redirect`] error once the limit is exceeded or if a
+    /// response lacks a usable `Location` header.
+    ///
+    /// The request is resent with the same method & body, except that a
+    /// `303 See Other` response causes the redirect to be followed with a
+    /// bodiless GET.  The `Authorization` header is not sent to a different
+    /// origin than the one that issued the redirect.
+    Follow(usize),
+
+    /// Pass the 3xx response to the request's parser as though it were a
+    /// successful response
+    Parse,
+}
+
+/// Which IP address families a client's backend may use when connecting to
+/// the server
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum IpPreference {
+    /// Connect over IPv4 or IPv6, as determined by the backend
+    #[default]
+    Any,
+
+    /// Only connect to IPv4 addresses
+    Ipv4Only,
+
+    /// Only connect to IPv6 addresses
+    Ipv6Only,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PreparedRequest<T> {
     parts: RequestParts,
     body: T,
+    captured_body: Option<CapturedBody>,
 }
 
 impl<T> PreparedRequest<T> {
@@ -294,13 +1142,32 @@ impl<T> PreparedRequest<T> {
     }
 
     pub fn method(&self) -> Method {
-        self.parts.method
+        self.parts.method.clone()
     }
 
     pub fn headers(&self) -> &HeaderMap {
         &self.parts.headers
     }
 
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.parts.headers
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.parts.timeout
+    }
+
+    /// Replace the URL that the request will be sent to
+    pub fn set_url(&mut self, url: HttpUrl) {
+        self.parts.url = url;
+    }
+
+    /// Replace the request's timeout; `None` means that the backend's own
+    /// timeout applies
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.parts.timeout = timeout;
+    }
+
     pub fn body_ref(&self) -> &T {
         &self.body
     }
@@ -309,6 +1176,12 @@ impl<T> PreparedRequest<T> {
         &mut self.body
     }
 
+    /// Returns the copy of the request body captured for diagnostics, if any;
+    /// see [`ClientConfig::with_request_body_capture()`]
+    pub fn captured_body(&self) -> Option<&CapturedBody> {
+        self.captured_body.as_ref()
+    }
+
     pub fn into_body(self) -> T {
         self.body
     }
@@ -318,11 +1191,15 @@ impl<T> PreparedRequest<T> {
     }
 
     pub fn from_parts(parts: RequestParts, body: T) -> PreparedRequest<T> {
-        PreparedRequest { parts, body }
+        PreparedRequest {
+            parts,
+            body,
+            captured_body: None,
+        }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct RequestParts {
     pub url: HttpUrl,
     pub method: Method,
@@ -330,12 +1207,30 @@ pub struct RequestParts {
     pub timeout: Option<Duration>,
 }
 
+/// The `Debug` output masks the values of the `Authorization` header and
+/// any other credential-bearing or sensitive headers.
+impl fmt::Debug for RequestParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestParts")
+            .field("url", &self.url)
+            .field("method", &self.method)
+            .field("headers", &RedactedHeaders(&self.headers))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 pub trait Backend {
     type Request;
     type Response: BackendResponse;
     type Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request;
+    /// Convert `r` into a request for the backend.  Backends that cannot
+    /// represent the request, e.g., because of a header value or URL that
+    /// the underlying HTTP library rejects, should return an error here
+    /// rather than panicking or waiting until [`send()`][Self::send] to
+    /// report it.
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error>;
 
     fn send<R: std::io::Read>(
         &self,
@@ -349,7 +1244,7 @@ impl<T: Backend + ?Sized> Backend for &T {
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (*self).prepare_request(r)
     }
 
@@ -367,7 +1262,7 @@ impl<T: Backend + ?Sized> Backend for &mut T {
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (**self).prepare_request(r)
     }
 
@@ -380,12 +1275,12 @@ impl<T: Backend + ?Sized> Backend for &mut T {
     }
 }
 
-impl<T: Backend + ?Sized> Backend for std::sync::Arc<T> {
+impl<T: Backend + ?Sized> Backend for Arc<T> {
     type Request = T::Request;
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (**self).prepare_request(r)
     }
 
@@ -403,7 +1298,7 @@ impl<T: Backend + ?Sized> Backend for Box<T> {
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (**self).prepare_request(r)
     }
 
@@ -421,8 +1316,65 @@ pub trait BackendResponse {
     fn status(&self) -> http::status::StatusCode;
     fn headers(&self) -> HeaderMap;
     fn body_reader(self) -> impl std::io::Read;
+
+    /// Returns details of the TLS connection over which the response was
+    /// received, if known.  The default implementation returns `None`.
+    fn tls_info(&self) -> Option<TlsInfo> {
+        None
+    }
+}
+
+pin_project! {
+    /// [Private] A request body that is dropped when following a redirect that
+    /// changes the request method to GET
+    #[project = RedirectBodyProj]
+    enum RedirectBody<R> {
+        Body { #[pin] body: R },
+        Empty,
+    }
+}
+
+impl<R> RedirectBody<R> {
+    fn new(body: R) -> Self {
+        RedirectBody::Body { body }
+    }
 }
 
+impl<R: std::io::Read> std::io::Read for RedirectBody<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            RedirectBody::Body { body } => body.read(buf),
+            RedirectBody::Empty => Ok(0),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: ::tokio::io::AsyncRead> ::tokio::io::AsyncRead for RedirectBody<R> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut ::tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        match self.project() {
+            RedirectBodyProj::Body { body } => body.poll_read(cx, buf),
+            RedirectBodyProj::Empty => std::task::Poll::Ready(Ok(())),
+        }
+    }
+}
+
+/// Error type returned by the methods of a [`Client`] with backend `B`.
+///
+/// The `E` parameter is the `Error` type of the input [`Request`] provided to
+/// a method.
+pub type ClientError<B, E = CommonError> = Error<<B as Backend>::Error, E>;
+
+/// Result type returned by the methods of a [`Client`] with backend `B`.
+///
+/// The `E` parameter is the `Error` type of the input [`Request`] provided to
+/// a method.
+pub type ClientResult<T, B, E = CommonError> = Result<T, ClientError<B, E>>;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Client<B> {
     config: ClientConfig,
@@ -441,70 +1393,1009 @@ impl<B> Client<B> {
     pub fn backend_mut(&mut self) -> &mut B {
         &mut self.backend
     }
+
+    /// Returns the base API URL
+    pub fn base_url(&self) -> HttpUrl {
+        self.config.base_url()
+    }
+
+    /// Change the base API URL used by subsequent requests, keeping the same
+    /// backend (and thus any connections it has pooled).
+    ///
+    /// This only requires a shared reference, so a client shared between
+    /// threads can be repointed without being rebuilt; requests already in
+    /// progress are unaffected.  Clones of the client each have their own
+    /// base URL.
+    ///
+    /// To point only some requests at a different base URL while other
+    /// threads keep using the client as-is, either clone the client (if the
+    /// backend is cheaply cloneable, as `ureq::Agent` and `reqwest::Client`
+    /// are) and change the clone's base URL, or wrap the
+    /// requests in [`WithBaseUrl`][crate::request::WithBaseUrl].
+    pub fn set_base_url(&self, url: HttpUrl) {
+        self.config.base_url.set(url);
+    }
 }
 
 impl<B: Backend> Client<B> {
-    pub fn request<R>(&self, req: R) -> Result<R::Output, Error<B::Error, R::Error>>
+    pub fn request<R>(&self, req: R) -> ClientResult<R::Output, B, R::Error>
+    where
+        R: Request<Body: RequestBody<Error: Into<R::Error>>>,
+    {
+        let (response, failure) = self.send(&req)?;
+        let parser = req.parser();
+        parser
+            .parse_response(response)
+            .map_err(|e| failure.error(e))
+    }
+
+    /// Make the given request and return an iterator over the elements of
+    /// the JSON array in the response body, each of which is deserialized as
+    /// soon as it has been received in full.  This allows processing very
+    /// large arrays (e.g., big tree listings) without buffering the entire
+    /// response in memory.
+    ///
+    /// The request's parser is not used.  Errors that occur while reading or
+    /// parsing the body are yielded by the iterator, after which it ends.
+    pub fn request_json_array<T, R>(
+        &self,
+        req: R,
+    ) -> ClientResult<JsonArrayIter<impl std::io::Read + use<B, T, R>, T>, B, R::Error>
+    where
+        T: DeserializeOwned,
+        R: Request<Body: RequestBody<Error: Into<R::Error>>>,
+    {
+        let (response, _) = self.send(&req)?;
+        Ok(JsonArrayIter::new(response.into_body()))
+    }
+
+    /// Make the given request and write the response body to the file at
+    /// `path` atomically using a [`ToFile`] parser, so that an interrupted
+    /// download never leaves a truncated file behind.
+    ///
+    /// The request's parser is not used.
+    pub fn request_to_file<R>(&self, req: R, path: &Path) -> ClientResult<(), B, R::Error>
+    where
+        R: Request<Body: RequestBody<Error: Into<R::Error>>>,
+    {
+        let (response, failure) = self.send(&req)?;
+        ToFile::new(path)
+            .parse_response(response)
+            .map_err(|e| failure.error(e))
+    }
+
+    /// [Private] Send the given request, following redirects as configured,
+    /// and return the successful response along with the information needed
+    /// to report a failure to parse it.  Error responses are parsed and
+    /// returned as errors.
+    fn send<R>(
+        &self,
+        req: &R,
+    ) -> ClientResult<
+        (
+            Response<impl std::io::Read + use<B, R>>,
+            probe::ParseFailure,
+        ),
+        B,
+        R::Error,
+    >
     where
         R: Request<Body: RequestBody<Error: Into<R::Error>>>,
     {
-        let (reqparts, reqbody) = self.config.prepare_request(&req)?.into_parts();
+        let auth = self.config.auth_header();
+        let prepped = self.config.prepare_request(req, auth)?;
+        let captured = prepped.captured_body().cloned();
+        let (mut reqparts, reqbody) = prepped.into_parts();
+        let mut reqbody = RedirectBody::new(reqbody);
         let initial_url = reqparts.url.clone();
-        let method = reqparts.method;
-        let backreq = self.backend.prepare_request(reqparts);
-        let resp = match self.backend.send(backreq, reqbody) {
-            Ok(resp) => resp,
-            Err(e) => {
-                let payload = ErrorPayload::Send(e);
-                return Err(Error::new(initial_url, method, payload));
+        let method = reqparts.method.clone();
+        let mut redirects = 0;
+        let (resp, parts) = loop {
+            #[cfg(feature = "log")]
+            log::debug!("Sending {} request to {}", reqparts.method, reqparts.url);
+            let ratelimit = self.config.rate_limit_route(&reqparts.url);
+            if let Some((limiter, resource)) = &ratelimit
+                && let Some(delay) = limiter.delay(resource)
+            {
+                #[cfg(feature = "log")]
+                log::debug!("Waiting {delay:?} for {resource} rate limit to reset");
+                std::thread::sleep(delay);
             }
-        };
-        let parts = ResponseParts {
-            initial_url: initial_url.clone(),
-            method,
-            url: resp.url(),
-            status: resp.status(),
-            headers: resp.headers(),
+            let resp = match self
+                .backend
+                .prepare_request(reqparts.clone())
+                .and_then(|backreq| self.backend.send(backreq, reqbody))
+            {
+                Ok(resp) => resp,
+                Err(e) => {
+                    let payload = ErrorPayload::Send(e);
+                    return Err(
+                        Error::new(initial_url, method, payload).with_request_body(captured)
+                    );
+                }
+            };
+            let url = resp.url();
+            let headers = resp.headers();
+            if let Some((limiter, resource)) = &ratelimit {
+                limiter.record(resource, &headers);
+            }
+            let parts = ResponseParts {
+                initial_url: initial_url.clone(),
+                method: reqparts.method.clone(),
+                host_changed: url.as_url().origin() != reqparts.url.as_url().origin(),
+                url,
+                status: resp.status(),
+                headers: self.config.filter_response_headers(headers),
+                tls: resp.tls_info(),
+            };
+            #[cfg(feature = "log")]
+            log::debug!("Received {} response from {}", parts.status, parts.url);
+            #[cfg(feature = "log")]
+            if parts.host_changed {
+                log::warn!(
+                    "Request to {} was answered by a different origin: {}",
+                    reqparts.url,
+                    parts.url
+                );
+            }
+            if !self.config.handles_redirect(parts.status) {
+                break (resp, parts);
+            }
+            let redirect = RedirectResponse::new(parts);
+            let Some((next, keep_body)) =
+                self.config.follow_redirect(&reqparts, &redirect, redirects)
+            else {
+                let payload = ErrorPayload::Redirect(Box::new(redirect));
+                return Err(Error::new(initial_url, method, payload).with_request_body(captured));
+            };
+            reqbody = if keep_body {
+                match self.config.prepare_request(req, Ok(None)) {
+                    Ok(prepped) => RedirectBody::new(prepped.into_body()),
+                    Err(e) => {
+                        return Err(e
+                            .with_request_body(captured)
+                            .with_redirect_url(Some(next.url)));
+                    }
+                }
+            } else {
+                RedirectBody::Empty
+            };
+            reqparts = next;
+            redirects += 1;
         };
         let body = resp.body_reader();
+        #[cfg(feature = "log")]
+        let body = preview::BodyPreview::new(body, &parts, self.config.body_preview);
+        let body = deadline::ParseDeadline::new(body, self.config.parse_timeout);
+        let body = limit::SizeLimit::new(body, self.config.max_response_size);
+        let probe = self
+            .config
+            .parse_error_context
+            .then(|| probe::ParseProbe::new(&parts));
+        let body = probe::CountingBody::new(body, probe.as_ref().map(probe::ParseProbe::counter));
         let response = Response::from_parts(parts, body);
+        let failure = probe::ParseFailure {
+            url: initial_url,
+            method,
+            captured,
+            probe,
+        };
         if response.status().is_client_error() || response.status().is_server_error() {
             let parser = ErrorResponseParser::new();
-            let err_resp = parser.parse_response(response).map_err(|e| {
-                Error::new(
-                    initial_url.clone(),
-                    method,
-                    ErrorPayload::ParseResponse(e.convert_parse_error::<R::Error>()),
-                )
-            })?;
+            let err_resp = parser
+                .parse_response(response)
+                .map_err(|e| failure.clone().error::<_, R::Error, _>(e))?;
             Err(Error::new(
-                initial_url,
-                method,
+                failure.url,
+                failure.method,
                 ErrorPayload::Status(Box::new(err_resp)),
-            ))
+            )
+            .with_request_body(failure.captured))
         } else {
-            let parser = req.parser();
-            parser.parse_response(response).map_err(|e| {
-                Error::new(
-                    initial_url,
-                    method,
-                    ErrorPayload::ParseResponse(e.convert_parse_error()),
-                )
-            })
+            Ok((response, failure))
         }
     }
 
     pub fn paginate<R: PaginationRequest>(&self, req: R) -> PaginationIter<'_, B, R> {
         PaginationIter::new(self, req)
     }
+
+    /// Paginate over the given request one page at a time, yielding a
+    /// [`PageResponse`][crate::pagination::PageResponse] for each page rather
+    /// than the individual items
+    pub fn paginate_pages<R: PaginationRequest>(&self, req: R) -> PageIter<'_, B, R> {
+        PageIter::new(self, req)
+    }
+
+    /// Return a [`PageCursor`] for fetching the pages of the given request
+    /// one at a time under manual control
+    pub fn page_cursor<R: PaginationRequest>(&self, req: R) -> PageCursor<&Client<B>, R> {
+        PageCursor::new(self, req)
+    }
+
+    /// Paginate over the results of a [`SearchRequest`], splitting its range
+    /// as needed to work around GitHub's limit on the number of results per
+    /// search; see [`SearchIter`] for details
+    pub fn search<T: DeserializeOwned + Send>(
+        &self,
+        req: SearchRequest<T>,
+    ) -> SearchIter<'_, B, T> {
+        SearchIter::new(self, req)
+    }
+
+    /// Paginate over the given request and collect all of the items into a
+    /// `Vec`, stopping at and returning the first error encountered
+    pub fn paginate_all<R>(&self, req: R) -> ClientResult<Vec<R::Item>, B>
+    where
+        R: PaginationRequest<Item: DeserializeOwned + Send>,
+    {
+        self.paginate(req).collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn test_client_config_new_succeeds() {
         let _ = ClientConfig::new();
     }
+
+    #[test]
+    fn from_vars() {
+        let config = ClientConfig::from_vars(|name| match name {
+            "GITHUB_TOKEN" => Some(String::new()),
+            "GH_TOKEN" => Some("hunter2".into()),
+            "GITHUB_API_URL" => Some("https://ghe.example.com/api/v3".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(config.auth_header().unwrap().unwrap(), "Bearer hunter2");
+        assert_eq!(config.base_url().as_str(), "https://ghe.example.com/api/v3");
+        assert_eq!(
+            config.graphql_url().as_str(),
+            "https://ghe.example.com/api/graphql"
+        );
+    }
+
+    #[test]
+    fn from_vars_empty() {
+        let config = ClientConfig::from_vars(|_| None).unwrap();
+        assert_eq!(config, ClientConfig::new());
+    }
+
+    #[test]
+    fn from_vars_bad_url() {
+        let r = ClientConfig::from_vars(|name| {
+            (name == "GITHUB_API_URL").then(|| String::from("ftp://example.com"))
+        });
+        assert!(matches!(r, Err(FromEnvError::InvalidApiUrl { .. })));
+    }
+
+    #[test]
+    fn github_com_urls() {
+        let config = ClientConfig::new();
+        assert_eq!(config.uploads_url().as_str(), "https://uploads.github.com/");
+        assert_eq!(
+            config.graphql_url().as_str(),
+            "https://api.github.com/graphql"
+        );
+        assert_eq!(config.web_url().as_str(), "https://github.com/");
+    }
+
+    #[rstest]
+    #[case("https://ghe.example.com/api/v3")]
+    #[case("https://ghe.example.com/api/v3/")]
+    fn ghes_urls(#[case] base_url: HttpUrl) {
+        let config = ClientConfig::new().with_base_url(base_url);
+        assert_eq!(
+            config.uploads_url().as_str(),
+            "https://ghe.example.com/api/uploads"
+        );
+        assert_eq!(
+            config.graphql_url().as_str(),
+            "https://ghe.example.com/api/graphql"
+        );
+        assert_eq!(config.web_url().as_str(), "https://ghe.example.com/");
+    }
+
+    #[rstest]
+    #[case("ghe.example.com", "https://ghe.example.com/api/v3")]
+    #[case("GHE.example.com:8443", "https://ghe.example.com:8443/api/v3")]
+    #[case("10.0.0.1", "https://10.0.0.1/api/v3")]
+    fn with_github_enterprise(#[case] host: &str, #[case] base_url: &str) {
+        let config = ClientConfig::new().with_github_enterprise(host).unwrap();
+        assert_eq!(config.base_url().as_str(), base_url);
+        assert_eq!(
+            config.graphql_url().as_str(),
+            base_url.replace("/v3", "/graphql")
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("https://ghe.example.com")]
+    #[case("ghe.example.com/api/v3")]
+    #[case("user@ghe.example.com")]
+    #[case("ghe example.com")]
+    #[case("ghe.example.com:port")]
+    #[case(":8443")]
+    fn with_github_enterprise_invalid(#[case] host: &str) {
+        assert_eq!(
+            ClientConfig::new().with_github_enterprise(host),
+            Err(InvalidHostError(host.to_owned()))
+        );
+    }
+
+    #[test]
+    fn other_urls() {
+        let config = ClientConfig::new()
+            .with_base_url("http://localhost:8080/gh".parse().unwrap())
+            .with_web_url("http://localhost:8081".parse().unwrap());
+        assert_eq!(config.uploads_url().as_str(), "http://localhost:8080/gh");
+        assert_eq!(
+            config.graphql_url().as_str(),
+            "http://localhost:8080/gh/graphql"
+        );
+        assert_eq!(config.web_url().as_str(), "http://localhost:8081/");
+    }
+
+    #[test]
+    fn auth_provider_rotation() {
+        use crate::{Endpoint, parser::Ignore};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Ping;
+
+        impl Request for Ping {
+            type Output = ();
+            type Error = CommonError;
+            type Body = ();
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["ping"])
+            }
+
+            fn method(&self) -> Method {
+                Method::Get
+            }
+
+            fn body(&self) -> Self::Body {}
+
+            fn parser(
+                &self,
+            ) -> impl crate::parser::ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send
+            {
+                Ignore
+            }
+        }
+
+        let generation = Arc::new(AtomicUsize::new(0));
+        let gen2 = Arc::clone(&generation);
+        let seen = std::sync::Mutex::new(Vec::new());
+        let client = ClientConfig::new()
+            .with_auth_provider(move || {
+                let n = gen2.load(Ordering::SeqCst);
+                format!("token t{n}").parse().ok()
+            })
+            .with_backend(FnBackend::new(|parts, _| {
+                seen.lock()
+                    .unwrap()
+                    .push(parts.headers[http::header::AUTHORIZATION].clone());
+                http::Response::new(Vec::new())
+            }));
+        client.request(Ping).unwrap();
+        generation.store(1, Ordering::SeqCst);
+        client.request(Ping).unwrap();
+        assert_eq!(*seen.lock().unwrap(), ["token t0", "token t1"]);
+    }
+
+    #[test]
+    fn debug_redacts_credentials() {
+        let config = ClientConfig::new().with_header(
+            http::header::AUTHORIZATION,
+            HeaderValue::from_static("token hunter2"),
+        );
+        assert!(!format!("{config:?}").contains("hunter2"));
+        let (parts, _) = config
+            .prepare_request::<_, ()>(
+                &crate::pagination::PageRequest::<serde_json::Value>::new(
+                    crate::Endpoint::from_iter(["user"]),
+                ),
+                Ok(None),
+            )
+            .unwrap()
+            .into_parts();
+        let s = format!("{parts:?}");
+        assert!(!s.contains("hunter2"), "{s}");
+        assert!(s.contains("<redacted>"), "{s}");
+    }
+
+    #[test]
+    fn auth_token_is_sensitive() {
+        let config = ClientConfig::new().with_auth_token("hunter2").unwrap();
+        let value = config.auth_header().unwrap().unwrap();
+        assert_eq!(value, "Bearer hunter2");
+        assert!(value.is_sensitive());
+        assert!(!format!("{config:?}").contains("hunter2"));
+    }
+
+    #[test]
+    fn invalid_endpoint() {
+        use crate::{Endpoint, EndpointError, pagination::PageRequest};
+        let client = ClientConfig::new()
+            .with_base_url("https://ghe.example.com/api/v3".parse().unwrap())
+            .with_backend(FnBackend::new(|_, _| -> http::Response<Vec<u8>> {
+                panic!("Request should not have been sent");
+            }));
+        let req = PageRequest::<serde_json::Value>::new(Endpoint::from_iter([
+            "repos", "octocat", "..", "issues",
+        ]));
+        let e = client.request(req).unwrap_err();
+        let ErrorPayload::InvalidEndpoint(err) = e.payload_ref() else {
+            panic!("Expected InvalidEndpoint error, got {e:?}");
+        };
+        assert_eq!(**err, EndpointError::DotSegment("..".into()));
+    }
+
+    #[test]
+    fn endpoint_url_outside_base_url() {
+        use crate::{Endpoint, EndpointError, pagination::PageRequest};
+        let client = ClientConfig::new()
+            .with_base_url("https://ghe.example.com/api/v3".parse().unwrap())
+            .with_backend(FnBackend::new(|parts, _| {
+                assert_eq!(
+                    parts.url.as_str(),
+                    "https://ghe.example.com/repos/octocat/hello-world/issues"
+                );
+                http::Response::new(b"[]".to_vec())
+            }));
+        let url = "https://ghe.example.com/repos/octocat/hello-world/issues"
+            .parse::<HttpUrl>()
+            .unwrap();
+        let req = PageRequest::<serde_json::Value>::new(Endpoint::Url(url.clone()));
+        let e = client.request(req).unwrap_err();
+        let ErrorPayload::InvalidEndpoint(err) = e.payload_ref() else {
+            panic!("Expected InvalidEndpoint error, got {e:?}");
+        };
+        assert_eq!(
+            **err,
+            EndpointError::OutsideBaseUrl {
+                url: Box::new(url.clone()),
+                base: Box::new("https://ghe.example.com/api/v3".parse().unwrap()),
+            }
+        );
+        let req = PageRequest::<serde_json::Value>::new(Endpoint::Absolute(url));
+        assert!(client.request(req).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn slash_in_path_segment() {
+        use crate::{Endpoint, pagination::PageRequest};
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/branches/feature%2Fx"
+            );
+            http::Response::new(b"[]".to_vec())
+        }));
+        let req = PageRequest::<serde_json::Value>::new(Endpoint::from_iter([
+            "repos",
+            "octocat",
+            "hello-world",
+            "branches",
+            "feature/x",
+        ]));
+        assert!(client.request(req).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn response_header_allowlist() {
+        use crate::{Endpoint, pagination::PageRequest};
+        let client = ClientConfig::new()
+            .with_response_header_allowlist([HeaderName::from_static("x-custom")])
+            .with_backend(FnBackend::new(|_, _| {
+                http::Response::builder()
+                    .status(http::status::StatusCode::NOT_FOUND)
+                    .header("X-Custom", "yes")
+                    .header("X-Other", "no")
+                    .header("X-RateLimit-Remaining", "42")
+                    .header("X-GitHub-Request-Id", "ABCD:1234")
+                    .header(http::header::SERVER, "github.com")
+                    .body(Vec::new())
+                    .unwrap()
+            }));
+        let req = PageRequest::<serde_json::Value>::new(Endpoint::from_iter(["nope"]));
+        let e = client.request(req).unwrap_err();
+        let ErrorPayload::Status(resp) = e.payload_ref() else {
+            panic!("Expected Status error, got {e:?}");
+        };
+        let mut names = resp
+            .headers()
+            .keys()
+            .map(HeaderName::as_str)
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            ["x-custom", "x-github-request-id", "x-ratelimit-remaining"]
+        );
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[case(10, None)]
+    #[case(100, Some(r#"{"name":"ci","token":"<redacted>"}"#))]
+    fn request_body_capture(#[case] limit: usize, #[case] captured: Option<&str>) {
+        use crate::{Endpoint, request::AdHocRequest};
+        let client = ClientConfig::new()
+            .with_request_body_capture(limit)
+            .with_backend(FnBackend::new(|_, _| {
+                http::Response::builder()
+                    .status(422)
+                    .body(br#"{"message": "Validation Failed"}"#.to_vec())
+                    .unwrap()
+            }));
+        let req = AdHocRequest::new(Method::Post, Endpoint::from_iter(["hooks"]))
+            .with_json_body(serde_json::json!({"name": "ci", "token": "hunter2"}));
+        let e = client.request(req).unwrap_err();
+        assert!(matches!(e.payload_ref(), ErrorPayload::Status(_)));
+        assert_eq!(e.request_body().and_then(CapturedBody::as_str), captured);
+    }
+
+    #[test]
+    fn redirect_body_error() {
+        use crate::{
+            Endpoint,
+            parser::{Ignore, ResponseParser},
+            request::RequestBody,
+        };
+        use std::cell::Cell;
+
+        /// A request body that can only be read once
+        struct OnceBody(Option<&'static [u8]>);
+
+        impl RequestBody for OnceBody {
+            type Error = CommonError;
+
+            fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+                self.0.map(Cow::Borrowed)
+            }
+
+            fn into_read(self) -> Result<impl std::io::Read + 'static, CommonError> {
+                self.0
+                    .ok_or_else(|| std::io::Error::other("body already consumed").into())
+            }
+        }
+
+        struct CreateHook(Cell<bool>);
+
+        impl Request for CreateHook {
+            type Output = ();
+            type Error = CommonError;
+            type Body = OnceBody;
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["hooks"])
+            }
+
+            fn method(&self) -> Method {
+                Method::Post
+            }
+
+            fn body(&self) -> OnceBody {
+                OnceBody((!self.0.replace(true)).then_some(br#"{"name":"ci"}"#))
+            }
+
+            fn parser(
+                &self,
+            ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send
+            {
+                Ignore
+            }
+        }
+
+        let client = ClientConfig::new()
+            .with_redirect_policy(RedirectPolicy::Follow(2))
+            .with_request_body_capture(100)
+            .with_backend(FnBackend::new(|parts, _| {
+                assert_eq!(parts.url.as_str(), "https://api.github.com/hooks");
+                http::Response::builder()
+                    .status(307)
+                    .header("Location", "https://api.github.com/v2/hooks")
+                    .body(Vec::new())
+                    .unwrap()
+            }));
+        let e = client.request(CreateHook(Cell::new(false))).unwrap_err();
+        assert!(matches!(
+            e.payload_ref(),
+            ErrorPayload::PrepareRequest(CommonError::Io(_))
+        ));
+        assert_eq!(e.url().as_str(), "https://api.github.com/hooks");
+        assert_eq!(
+            e.redirect_url().map(HttpUrl::as_str),
+            Some("https://api.github.com/v2/hooks")
+        );
+        assert_eq!(
+            e.request_body().and_then(CapturedBody::as_str),
+            Some(r#"{"name":"ci"}"#)
+        );
+        assert_eq!(
+            e.to_string(),
+            "POST request to https://api.github.com/hooks failed while following redirect to https://api.github.com/v2/hooks: failed to prepare request"
+        );
+    }
+
+    #[test]
+    fn prepared_request_mutators() {
+        let parts = RequestParts {
+            url: "https://api.github.com/octocat".parse().unwrap(),
+            method: Method::Get,
+            headers: HeaderMap::new(),
+            timeout: None,
+        };
+        let mut prepped = PreparedRequest::from_parts(parts, ());
+        prepped
+            .headers_mut()
+            .insert("x-signature", HeaderValue::from_static("abc123"));
+        prepped.set_url("https://api.github.com/hubot".parse().unwrap());
+        prepped.set_timeout(Some(Duration::from_secs(5)));
+        assert_eq!(prepped.headers()["x-signature"], "abc123");
+        assert_eq!(prepped.url().as_str(), "https://api.github.com/hubot");
+        assert_eq!(prepped.timeout(), Some(Duration::from_secs(5)));
+        let (parts, ()) = prepped.into_parts();
+        assert_eq!(parts.timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn bind_address() {
+        let config = ClientConfig::new();
+        assert_eq!(config.bind_address(), None);
+        let config = config.with_ip_preference(IpPreference::Ipv4Only);
+        assert_eq!(
+            config.bind_address(),
+            Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+        );
+        let config = config.with_ip_preference(IpPreference::Ipv6Only);
+        assert_eq!(
+            config.bind_address(),
+            Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+        );
+        let addr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let config = config.with_local_address(addr);
+        assert_eq!(config.bind_address(), Some(addr));
+    }
+
+    mod redirects {
+        use super::*;
+        use http::status::StatusCode;
+
+        fn request_parts(method: Method) -> RequestParts {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                http::header::AUTHORIZATION,
+                HeaderValue::from_static("Bearer hunter2"),
+            );
+            headers.insert(
+                http::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            RequestParts {
+                url: "https://api.github.com/repos/octocat/hello-world"
+                    .parse::<HttpUrl>()
+                    .unwrap(),
+                method,
+                headers,
+                timeout: None,
+            }
+        }
+
+        fn redirect(status: StatusCode, location: &str) -> RedirectResponse {
+            let url = "https://api.github.com/repos/octocat/hello-world"
+                .parse::<HttpUrl>()
+                .unwrap();
+            let mut headers = HeaderMap::new();
+            headers.insert(http::header::LOCATION, location.parse().unwrap());
+            RedirectResponse::new(ResponseParts {
+                initial_url: url.clone(),
+                url,
+                method: Method::Post,
+                status,
+                headers,
+                host_changed: false,
+                tls: None,
+            })
+        }
+
+        #[test]
+        fn error_policy() {
+            let config = ClientConfig::new();
+            assert!(config.handles_redirect(StatusCode::FOUND));
+            assert!(!config.handles_redirect(StatusCode::NOT_MODIFIED));
+            let r = redirect(StatusCode::FOUND, "/repositories/1296269");
+            assert_eq!(
+                r.location().unwrap().as_str(),
+                "https://api.github.com/repositories/1296269"
+            );
+            assert!(
+                config
+                    .follow_redirect(&request_parts(Method::Post), &r, 0)
+                    .is_none()
+            );
+        }
+
+        #[test]
+        fn parse_policy() {
+            let config = ClientConfig::new().with_redirect_policy(RedirectPolicy::Parse);
+            assert!(!config.handles_redirect(StatusCode::FOUND));
+        }
+
+        #[test]
+        fn follow_same_method() {
+            let config = ClientConfig::new().with_redirect_policy(RedirectPolicy::Follow(2));
+            let r = redirect(StatusCode::TEMPORARY_REDIRECT, "/repositories/1296269");
+            let (parts, keep_body) = config
+                .follow_redirect(&request_parts(Method::Post), &r, 0)
+                .unwrap();
+            assert!(keep_body);
+            assert_eq!(parts.method, Method::Post);
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repositories/1296269"
+            );
+            assert!(parts.headers.contains_key(http::header::AUTHORIZATION));
+            assert!(parts.headers.contains_key(http::header::CONTENT_TYPE));
+        }
+
+        #[test]
+        fn follow_see_other() {
+            let config = ClientConfig::new().with_redirect_policy(RedirectPolicy::Follow(2));
+            let r = redirect(StatusCode::SEE_OTHER, "/repositories/1296269");
+            let (parts, keep_body) = config
+                .follow_redirect(&request_parts(Method::Post), &r, 1)
+                .unwrap();
+            assert!(!keep_body);
+            assert_eq!(parts.method, Method::Get);
+            assert!(!parts.headers.contains_key(http::header::CONTENT_TYPE));
+        }
+
+        #[test]
+        fn follow_cross_origin() {
+            let config = ClientConfig::new().with_redirect_policy(RedirectPolicy::Follow(2));
+            let r = redirect(StatusCode::FOUND, "https://example.com/download");
+            let (parts, _) = config
+                .follow_redirect(&request_parts(Method::Get), &r, 0)
+                .unwrap();
+            assert!(!parts.headers.contains_key(http::header::AUTHORIZATION));
+        }
+
+        #[test]
+        fn follow_limit() {
+            let config = ClientConfig::new().with_redirect_policy(RedirectPolicy::Follow(2));
+            let r = redirect(StatusCode::FOUND, "/repositories/1296269");
+            assert!(
+                config
+                    .follow_redirect(&request_parts(Method::Get), &r, 2)
+                    .is_none()
+            );
+        }
+    }
+
+    #[test]
+    fn parse_timeout() {
+        use crate::{Endpoint, errors::ParseResponseError, pagination::PageRequest};
+        let client = ClientConfig::new()
+            .with_parse_timeout(Duration::ZERO)
+            .with_backend(FnBackend::new(|_, _| {
+                http::Response::new(b"[1, 2, 3]".to_vec())
+            }));
+        let req = PageRequest::<u32>::new(Endpoint::from_iter(["numbers"]));
+        let e = client.request(req).unwrap_err();
+        let ErrorPayload::ParseResponse(ParseResponseError::Read(err)) = e.payload_ref() else {
+            panic!("Expected read error, got {e:?}");
+        };
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn max_response_size() {
+        use crate::{
+            Endpoint,
+            errors::{ParseResponseError, ResponseTooLargeError},
+            pagination::PageRequest,
+        };
+        let client = |size| {
+            ClientConfig::new()
+                .with_max_response_size(size)
+                .with_backend(FnBackend::new(|_, _| {
+                    http::Response::new(b"[1, 2, 3]".to_vec())
+                }))
+        };
+        let req = PageRequest::<u32>::new(Endpoint::from_iter(["numbers"]));
+        assert_eq!(client(9).request(&req).unwrap().items, [1, 2, 3]);
+        let e = client(8).request(&req).unwrap_err();
+        let ErrorPayload::ParseResponse(ParseResponseError::Read(err)) = e.payload_ref() else {
+            panic!("Expected read error, got {e:?}");
+        };
+        assert_eq!(err.kind(), std::io::ErrorKind::FileTooLarge);
+        let inner = err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<ResponseTooLargeError>())
+            .unwrap();
+        assert_eq!(inner.limit(), 8);
+    }
+
+    #[test]
+    fn parse_error_context() {
+        use crate::{Endpoint, pagination::PageRequest};
+        let backend = || {
+            FnBackend::new(|_, _| {
+                http::Response::builder()
+                    .header(http::header::CONTENT_TYPE, "text/html")
+                    .body(b"<html>Unicorn!</html>".to_vec())
+                    .unwrap()
+            })
+        };
+        let req = PageRequest::<u32>::new(Endpoint::from_iter(["numbers"]));
+        let e = ClientConfig::new()
+            .with_parse_error_context(true)
+            .with_backend(backend())
+            .request(&req)
+            .unwrap_err();
+        let ctx = e.parse_context().unwrap();
+        assert_eq!(ctx.url().as_str(), "https://api.github.com/numbers");
+        assert_eq!(ctx.status(), http::StatusCode::OK);
+        assert_eq!(ctx.content_type(), Some("text/html"));
+        assert_eq!(ctx.body_length(), 21);
+        assert_eq!(
+            e.to_string(),
+            "GET request to https://api.github.com/numbers failed: error parsing response body (200 OK response from https://api.github.com/numbers, Content-Type text/html, 21 body bytes read)"
+        );
+        let e = ClientConfig::new()
+            .with_backend(backend())
+            .request(&req)
+            .unwrap_err();
+        assert!(e.parse_context().is_none());
+    }
+
+    #[rstest]
+    #[case("https://proxy.example.com/zen", true)]
+    #[case("https://api.github.com:8443/zen", true)]
+    #[case("http://api.github.com/zen", true)]
+    #[case("https://api.github.com:443/zen", false)]
+    #[case("https://api.github.com/zen", false)]
+    fn host_changed(#[case] answered_by: &'static str, #[case] changed: bool) {
+        use crate::{
+            Endpoint,
+            parser::{Ignore, WithParts},
+            request::AdHocRequest,
+        };
+
+        /// A backend whose responses claim to come from the given URL
+        #[derive(Debug)]
+        struct Diverted(&'static str);
+
+        impl Backend for Diverted {
+            type Request = RequestParts;
+            type Response = FnResponse;
+            type Error = std::io::Error;
+
+            fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
+                Ok(r)
+            }
+
+            fn send<R: std::io::Read>(
+                &self,
+                _r: Self::Request,
+                _body: R,
+            ) -> Result<Self::Response, Self::Error> {
+                Ok(FnResponse {
+                    url: self.0.parse().unwrap(),
+                    response: http::Response::new(Vec::new()),
+                })
+            }
+        }
+
+        let req = AdHocRequest::new(Method::Get, Endpoint::from_iter(["zen"]))
+            .with_parser::<WithParts<Ignore>>();
+        let resp = ClientConfig::new()
+            .with_backend(Diverted(answered_by))
+            .request(&req)
+            .unwrap();
+        assert_eq!(resp.host_changed(), changed);
+        assert_eq!(resp.tls_info(), None);
+    }
+
+    #[test]
+    fn prepare_request_error() {
+        use crate::{Endpoint, request::AdHocRequest};
+
+        /// A backend that rejects every request before sending it
+        #[derive(Debug)]
+        struct Rejecting;
+
+        impl Backend for Rejecting {
+            type Request = RequestParts;
+            type Response = FnResponse;
+            type Error = std::io::Error;
+
+            fn prepare_request(&self, _r: RequestParts) -> Result<Self::Request, Self::Error> {
+                Err(std::io::Error::other("unsupported header"))
+            }
+
+            fn send<R: std::io::Read>(
+                &self,
+                _r: Self::Request,
+                _body: R,
+            ) -> Result<Self::Response, Self::Error> {
+                panic!("send() should not be called");
+            }
+        }
+
+        let req = AdHocRequest::new(Method::Get, Endpoint::from_iter(["zen"]));
+        let e = ClientConfig::new()
+            .with_backend(Rejecting)
+            .request(&req)
+            .unwrap_err();
+        assert_eq!(e.as_send().unwrap().to_string(), "unsupported header");
+        assert_eq!(e.url().as_str(), "https://api.github.com/zen");
+    }
+
+    #[test]
+    fn tls_info() {
+        use crate::{
+            Endpoint, Timestamp,
+            parser::{Ignore, WithParts},
+            request::AdHocRequest,
+            response::TlsVersion,
+        };
+        let not_after = "2030-01-01T00:00:00Z".parse::<Timestamp>().unwrap();
+        let tls = TlsInfo::new()
+            .with_version(TlsVersion::Tls1_3)
+            .with_peer_cert_validity(Timestamp::UNIX_EPOCH, not_after);
+        let backend_tls = tls.clone();
+        let client = ClientConfig::new().with_backend(FnBackend::new(move |_, _| {
+            let mut resp = http::Response::new(Vec::new());
+            resp.extensions_mut().insert(backend_tls.clone());
+            resp
+        }));
+        let req = AdHocRequest::new(Method::Get, Endpoint::from_iter(["zen"]))
+            .with_parser::<WithParts<Ignore>>();
+        let resp = client.request(&req).unwrap();
+        assert_eq!(resp.tls_info(), Some(&tls));
+        let version = resp.tls_info().and_then(TlsInfo::version).unwrap();
+        assert!(version >= TlsVersion::Tls1_2);
+        assert_eq!(version.to_string(), "TLSv1.3");
+        assert_eq!(
+            resp.tls_info().unwrap().peer_cert_not_after(),
+            Some(not_after)
+        );
+    }
+
+    #[test]
+    fn request_json_array() {
+        use crate::{Endpoint, request::AdHocRequest};
+
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/labels"
+            );
+            http::Response::new(br#"[{"name": "bug"}, {"name": "]"}]"#.to_vec())
+        }));
+        let req = AdHocRequest::new(
+            Method::Get,
+            Endpoint::from_iter(["repos", "octocat", "hello-world", "labels"]),
+        );
+        let names = client
+            .request_json_array::<serde_json::Value, _>(req)
+            .unwrap()
+            .map(|r| r.map(|v| v["name"].as_str().unwrap().to_owned()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(names, ["bug", "]"]);
+    }
 }
@@ -0,0 +1,143 @@
+use crate::{HttpUrl, response::ResponseParts};
+use http::status::StatusCode;
+use pin_project_lite::pin_project;
+
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+
+pin_project! {
+    /// [Private] A wrapper around a response body reader that records the
+    /// first `limit` bytes read and logs them at the DEBUG level when dropped
+    pub(super) struct BodyPreview<R> {
+        #[pin]
+        inner: R,
+        url: HttpUrl,
+        status: StatusCode,
+        limit: usize,
+        buf: Vec<u8>,
+        total: u64,
+    }
+
+    impl<R> PinnedDrop for BodyPreview<R> {
+        fn drop(this: Pin<&mut Self>) {
+            let this = this.project();
+            if *this.limit > 0 {
+                log::debug!(
+                    "Response body from {} ({}): {}",
+                    this.url,
+                    this.status,
+                    format_preview(this.buf, *this.total)
+                );
+            }
+        }
+    }
+}
+
+impl<R> BodyPreview<R> {
+    pub(super) fn new(inner: R, parts: &ResponseParts, limit: usize) -> BodyPreview<R> {
+        BodyPreview {
+            inner,
+            url: parts.url().clone(),
+            status: parts.status(),
+            limit,
+            buf: Vec::new(),
+            total: 0,
+        }
+    }
+}
+
+/// [Private] Record the bytes in `data` that fall within the first `limit`
+/// bytes of the body
+fn record(buf: &mut Vec<u8>, total: &mut u64, limit: usize, data: &[u8]) {
+    let room = limit.saturating_sub(buf.len());
+    buf.extend_from_slice(&data[..room.min(data.len())]);
+    *total = total.saturating_add(u64::try_from(data.len()).unwrap_or(u64::MAX));
+}
+
+impl<R: std::io::Read> std::io::Read for BodyPreview<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        record(&mut self.buf, &mut self.total, self.limit, &buf[..n]);
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for BodyPreview<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        let r = this.inner.poll_read(cx, buf);
+        if matches!(r, std::task::Poll::Ready(Ok(()))) {
+            record(this.buf, this.total, *this.limit, &buf.filled()[before..]);
+        }
+        r
+    }
+}
+
+/// [Private] Render the captured start of a response body for logging.  Text
+/// is shown as a quoted string; anything that is not valid UTF-8 or that
+/// contains control characters other than whitespace is summarized as binary.
+fn format_preview(buf: &[u8], total: u64) -> String {
+    let text = match std::str::from_utf8(buf) {
+        Ok(s) => Some(s),
+        // The capture may have cut a multibyte character in half.
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&buf[..e.valid_up_to()]).ok(),
+        Err(_) => None,
+    }
+    .filter(|s| {
+        !s.chars()
+            .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+    });
+    let truncated = u64::try_from(buf.len()).is_ok_and(|n| n < total);
+    match (text, truncated) {
+        (Some(s), false) => format!("{s:?}"),
+        (Some(s), true) => format!("{s:?} (first {} of {total} bytes)", buf.len()),
+        (None, _) => format!("<binary data, {total} bytes>"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        b"{\"message\": \"Not Found\"}\n",
+        25,
+        r#""{\"message\": \"Not Found\"}\n""#
+    )]
+    #[case(b"[1, 2", 12, r#""[1, 2" (first 5 of 12 bytes)"#)]
+    #[case(b"caf\xC3", 10, r#""caf" (first 4 of 10 bytes)"#)]
+    #[case(b"\x1F\x8B\x08\x00", 4, "<binary data, 4 bytes>")]
+    #[case(b"\xFF\xFEabc", 200, "<binary data, 200 bytes>")]
+    #[case(b"", 0, r#""""#)]
+    fn test_format_preview(#[case] buf: &[u8], #[case] total: u64, #[case] s: &str) {
+        assert_eq!(format_preview(buf, total), s);
+    }
+
+    #[test]
+    fn read_records_prefix() {
+        use std::io::Read;
+        let parts = ResponseParts {
+            initial_url: "https://api.github.com/foo".parse().unwrap(),
+            method: crate::Method::Get,
+            url: "https://api.github.com/foo".parse().unwrap(),
+            status: StatusCode::OK,
+            headers: http::header::HeaderMap::new(),
+            host_changed: false,
+            tls: None,
+        };
+        let mut reader = BodyPreview::new(&b"Hello, world!"[..], &parts, 5);
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "Hello, world!");
+        assert_eq!(reader.buf, b"Hello");
+        assert_eq!(reader.total, 13);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    HttpUrl, Method,
+    errors::{CapturedBody, Error, ErrorPayload, ParseErrorContext, ParseResponseError},
+    response::ResponseParts,
+};
+use http::StatusCode;
+use pin_project_lite::pin_project;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+
+pin_project! {
+    /// [Private] A wrapper around a response body reader that counts the
+    /// bytes read through it, if given a counter
+    pub(super) struct CountingBody<R> {
+        #[pin]
+        inner: R,
+        count: Option<Arc<AtomicU64>>,
+    }
+}
+
+impl<R> CountingBody<R> {
+    pub(super) fn new(inner: R, count: Option<Arc<AtomicU64>>) -> CountingBody<R> {
+        CountingBody { inner, count }
+    }
+}
+
+/// [Private] Add `n` to `count`, if any
+fn add(count: Option<&Arc<AtomicU64>>, n: usize) {
+    if let Some(count) = count {
+        count.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for CountingBody<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        add(self.count.as_ref(), n);
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for CountingBody<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        let r = this.inner.poll_read(cx, buf);
+        add(this.count.as_ref(), buf.filled().len() - before);
+        r
+    }
+}
+
+/// [Private] Details of a response recorded before parsing it, from which a
+/// [`ParseErrorContext`] can be produced if parsing fails
+#[derive(Clone, Debug)]
+pub(super) struct ParseProbe {
+    url: HttpUrl,
+    status: StatusCode,
+    content_type: Option<String>,
+    count: Arc<AtomicU64>,
+}
+
+impl ParseProbe {
+    pub(super) fn new(parts: &ResponseParts) -> ParseProbe {
+        ParseProbe {
+            url: parts.url().clone(),
+            status: parts.status(),
+            content_type: parts
+                .headers()
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+            count: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the counter to pass to [`CountingBody::new()`]
+    pub(super) fn counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.count)
+    }
+
+    fn context(&self) -> ParseErrorContext {
+        ParseErrorContext::new(
+            self.url.clone(),
+            self.status,
+            self.content_type.clone(),
+            self.count.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// [Private] The information needed to construct an [`Error`] for a failure
+/// to parse a response, as returned by the clients' internal `send()`
+/// methods
+#[derive(Clone, Debug)]
+pub(super) struct ParseFailure {
+    pub(super) url: HttpUrl,
+    pub(super) method: Method,
+    pub(super) captured: Option<CapturedBody>,
+    pub(super) probe: Option<ParseProbe>,
+}
+
+impl ParseFailure {
+    /// Construct an [`ErrorPayload::ParseResponse`] error from `e`, with the
+    /// request body and parse context attached
+    pub(super) fn error<BE, E, E2>(self, e: ParseResponseError<E2>) -> Error<BE, E>
+    where
+        E2: Into<E>,
+    {
+        let context = self.probe.as_ref().map(ParseProbe::context);
+        Error::new(
+            self.url,
+            self.method,
+            ErrorPayload::ParseResponse(e.convert_parse_error()),
+        )
+        .with_request_body(self.captured)
+        .with_parse_context(context)
+    }
+}
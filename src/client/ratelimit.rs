@@ -0,0 +1,276 @@
+use http::header::HeaderMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+/// A category of GitHub API requests with its own rate limit, as reported in
+/// the `x-ratelimit-resource` response header
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RateLimitResource {
+    /// Most REST API endpoints (`core`)
+    Core,
+
+    /// The search endpoints other than code search (`search`)
+    Search,
+
+    /// The code search endpoint (`code_search`)
+    CodeSearch,
+
+    /// The GraphQL API (`graphql`)
+    Graphql,
+
+    /// The endpoint for creating a GitHub App from a manifest
+    /// (`integration_manifest`)
+    IntegrationManifest,
+
+    /// Any other resource, identified by its name
+    Other(String),
+}
+
+impl RateLimitResource {
+    /// Returns the resource that a request to the given path, relative to the
+    /// base API URL (e.g., `"search/issues"`), is expected to count against
+    pub fn for_path(path: &str) -> RateLimitResource {
+        let path = path.trim_start_matches('/');
+        if path == "search/code" || path.starts_with("search/code/") {
+            RateLimitResource::CodeSearch
+        } else if path.starts_with("search/") {
+            RateLimitResource::Search
+        } else if path == "graphql" {
+            RateLimitResource::Graphql
+        } else if path.starts_with("app-manifests/") && path.ends_with("/conversions") {
+            RateLimitResource::IntegrationManifest
+        } else {
+            RateLimitResource::Core
+        }
+    }
+
+    /// Returns the name of the resource as used in the `x-ratelimit-resource`
+    /// header
+    pub fn as_str(&self) -> &str {
+        match self {
+            RateLimitResource::Core => "core",
+            RateLimitResource::Search => "search",
+            RateLimitResource::CodeSearch => "code_search",
+            RateLimitResource::Graphql => "graphql",
+            RateLimitResource::IntegrationManifest => "integration_manifest",
+            RateLimitResource::Other(s) => s,
+        }
+    }
+}
+
+impl From<&str> for RateLimitResource {
+    fn from(s: &str) -> RateLimitResource {
+        match s {
+            "core" => RateLimitResource::Core,
+            "search" => RateLimitResource::Search,
+            "code_search" => RateLimitResource::CodeSearch,
+            "graphql" => RateLimitResource::Graphql,
+            "integration_manifest" => RateLimitResource::IntegrationManifest,
+            s => RateLimitResource::Other(s.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for RateLimitResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The most recently reported state of the rate limit for a
+/// [`RateLimitResource`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RateLimitBudget {
+    /// The number of requests remaining in the current window
+    pub remaining: u64,
+
+    /// The time at which the current window resets, in seconds since the
+    /// Unix epoch
+    pub reset: u64,
+}
+
+impl RateLimitBudget {
+    /// [Private] Returns the time until the window resets, if it has not
+    /// already done so
+    fn until_reset(&self) -> Option<Duration> {
+        let reset = SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(self.reset))?;
+        reset
+            .duration_since(SystemTime::now())
+            .ok()
+            .filter(|d| !d.is_zero())
+    }
+}
+
+/// A client-side throttle that keeps a separate budget for each
+/// [`RateLimitResource`]
+///
+/// GitHub tracks separate rate limits for core REST endpoints, search, code
+/// search, GraphQL, and a few other resources.  When a `RateLimiter` is
+/// attached to a client with
+/// [`ClientConfig::with_rate_limiter()`][super::ClientConfig::with_rate_limiter],
+/// the client records the `x-ratelimit-*` headers of every response in the
+/// budget for the resource named by the response's `x-ratelimit-resource`
+/// header.  Before sending a request, the client determines which resource
+/// the request will count against (see [`RateLimitResource::for_path()`]),
+/// and, if that resource's budget is at or below the configured floor, waits
+/// until its window resets, capped at the configured maximum delay.  Budgets
+/// for other resources are unaffected, so exhausting the search limit does
+/// not delay core requests.
+///
+/// Synchronous clients wait by blocking the current thread; asynchronous
+/// clients wait using the client's [`Sleeper`][super::Sleeper] and do not
+/// wait if there is none.  A `RateLimiter` can be shared between clients
+/// that use the same credentials by wrapping it in an [`Arc`][std::sync::Arc].
+#[derive(Debug)]
+pub struct RateLimiter {
+    floor: u64,
+    max_delay: Duration,
+    budgets: Mutex<HashMap<RateLimitResource, RateLimitBudget>>,
+}
+
+impl RateLimiter {
+    /// Create a `RateLimiter` that waits only once a resource's budget is
+    /// exhausted, for at most one hour
+    pub fn new() -> RateLimiter {
+        RateLimiter {
+            floor: 0,
+            max_delay: Duration::from_secs(3600),
+            budgets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the number of remaining requests at or below which requests for a
+    /// resource are delayed until its window resets
+    pub fn with_floor(mut self, remaining: u64) -> Self {
+        self.floor = remaining;
+        self
+    }
+
+    /// Set the maximum time to wait before a request
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Returns the most recently recorded budget for the given resource, if
+    /// any
+    pub fn budget(&self, resource: &RateLimitResource) -> Option<RateLimitBudget> {
+        self.budgets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(resource)
+            .copied()
+    }
+
+    /// Returns how long to wait before sending a request that counts against
+    /// the given resource, or `None` if it can be sent immediately
+    pub fn delay(&self, resource: &RateLimitResource) -> Option<Duration> {
+        let budget = self.budget(resource)?;
+        if budget.remaining > self.floor {
+            return None;
+        }
+        Some(budget.until_reset()?.min(self.max_delay)).filter(|d| !d.is_zero())
+    }
+
+    /// Record the rate limit headers of a response to a request that was
+    /// expected to count against `resource`.  The resource named in the
+    /// `x-ratelimit-resource` header, if any, takes precedence.  Responses
+    /// without `x-ratelimit-remaining` and `x-ratelimit-reset` headers are
+    /// ignored.
+    pub fn record(&self, resource: &RateLimitResource, headers: &HeaderMap) {
+        use crate::HeaderMapExt;
+        let (Some(remaining), Some(reset)) =
+            (headers.ratelimit_remaining(), headers.ratelimit_reset())
+        else {
+            return;
+        };
+        let resource = headers
+            .get("x-ratelimit-resource")
+            .and_then(|v| v.to_str().ok())
+            .map_or_else(|| resource.clone(), RateLimitResource::from);
+        self.budgets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(resource, RateLimitBudget { remaining, reset });
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> RateLimiter {
+        RateLimiter::new()
+    }
+}
+
+/// [Private] A wrapper around a shared [`RateLimiter`] that compares by
+/// identity, so that [`ClientConfig`][super::ClientConfig] can implement `Eq`
+#[derive(Clone, Debug)]
+pub(super) struct RateLimiterSlot(pub(super) Arc<RateLimiter>);
+
+impl PartialEq for RateLimiterSlot {
+    fn eq(&self, other: &RateLimiterSlot) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for RateLimiterSlot {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("repos/octocat/hello-world", RateLimitResource::Core)]
+    #[case("/search/issues", RateLimitResource::Search)]
+    #[case("search/code", RateLimitResource::CodeSearch)]
+    #[case("search/commits", RateLimitResource::Search)]
+    #[case("graphql", RateLimitResource::Graphql)]
+    #[case(
+        "app-manifests/abc123/conversions",
+        RateLimitResource::IntegrationManifest
+    )]
+    fn for_path(#[case] path: &str, #[case] resource: RateLimitResource) {
+        assert_eq!(RateLimitResource::for_path(path), resource);
+    }
+
+    fn headers(resource: Option<&str>, remaining: u64, reset: u64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", remaining.into());
+        headers.insert("x-ratelimit-reset", reset.into());
+        if let Some(r) = resource {
+            headers.insert("x-ratelimit-resource", r.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn independent_budgets() {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let limiter = RateLimiter::new().with_max_delay(Duration::from_secs(30));
+        limiter.record(
+            &RateLimitResource::Core,
+            &headers(Some("search"), 0, now + 45),
+        );
+        limiter.record(&RateLimitResource::Core, &headers(None, 4999, now + 3600));
+        assert_eq!(
+            limiter.budget(&RateLimitResource::Search),
+            Some(RateLimitBudget {
+                remaining: 0,
+                reset: now + 45
+            })
+        );
+        let delay = limiter.delay(&RateLimitResource::Search).unwrap();
+        assert_eq!(delay, Duration::from_secs(30));
+        assert_eq!(limiter.delay(&RateLimitResource::Core), None);
+        assert_eq!(limiter.delay(&RateLimitResource::Graphql), None);
+
+        // A budget whose window has already reset causes no delay
+        limiter.record(&RateLimitResource::Search, &headers(None, 0, now - 1));
+        assert_eq!(limiter.delay(&RateLimitResource::Search), None);
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The future returned by [`Sleeper::sleep()`]
+///
+/// On WebAssembly targets, timer futures cannot be sent between threads, and
+/// so this type is only `Send` on other platforms.
+#[cfg(not(target_arch = "wasm32"))]
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// The future returned by [`Sleeper::sleep()`]
+///
+/// On WebAssembly targets, timer futures cannot be sent between threads, and
+/// so this type is only `Send` on other platforms.
+#[cfg(target_arch = "wasm32")]
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+/// A trait for asynchronously waiting for a period of time
+///
+/// Client features that need to pause between requests (e.g., retrying
+/// failed requests or waiting out rate limits) do so via the `Sleeper`
+/// configured with [`ClientConfig::with_sleeper()`][super::ClientConfig::with_sleeper].
+/// If no sleeper is configured, a default implementation for the current
+/// platform is used: [`TokioSleeper`] on native targets when the `tokio`
+/// feature is enabled, and `GlooSleeper` on `wasm32` targets.
+///
+/// Custom implementations are primarily useful in tests, where
+/// [`FakeSleeper`][crate::testing::FakeSleeper] (available with the `testing`
+/// feature) can be used to record requested delays without actually waiting.
+pub trait Sleeper: fmt::Debug + Send + Sync {
+    /// Return a future that completes once `duration` has elapsed
+    fn sleep(&self, duration: Duration) -> SleepFuture;
+}
+
+impl<T: Sleeper + ?Sized> Sleeper for Arc<T> {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        (**self).sleep(duration)
+    }
+}
+
+/// A [`Sleeper`] that waits using [`tokio::time::sleep()`]
+///
+/// This requires that the sleep futures be polled within a Tokio runtime with
+/// the time driver enabled.
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TokioSleeper;
+
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+impl Sleeper for TokioSleeper {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A [`Sleeper`] for WebAssembly targets that waits using JavaScript's
+/// `setTimeout()` via [`gloo_timers`]
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GlooSleeper;
+
+#[cfg(target_arch = "wasm32")]
+impl Sleeper for GlooSleeper {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        let millis = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+        Box::pin(gloo_timers::future::TimeoutFuture::new(millis))
+    }
+}
+
+/// [Private] Returns the default [`Sleeper`] for the current platform, if any
+#[cfg(target_arch = "wasm32")]
+#[allow(clippy::unnecessary_wraps)]
+pub(super) fn default_sleeper() -> Option<Arc<dyn Sleeper>> {
+    Some(Arc::new(GlooSleeper))
+}
+
+/// [Private] Returns the default [`Sleeper`] for the current platform, if any
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+#[allow(clippy::unnecessary_wraps)]
+pub(super) fn default_sleeper() -> Option<Arc<dyn Sleeper>> {
+    Some(Arc::new(TokioSleeper))
+}
+
+/// [Private] Returns the default [`Sleeper`] for the current platform, if any
+#[cfg(not(any(feature = "tokio", target_arch = "wasm32")))]
+pub(super) fn default_sleeper() -> Option<Arc<dyn Sleeper>> {
+    None
+}
+
+/// [Private] A `Sleeper` stored in a `ClientConfig`.  Two instances are equal
+/// iff they share the same sleeper.
+#[derive(Clone)]
+pub(super) struct SleeperSlot(pub(super) Arc<dyn Sleeper>);
+
+impl fmt::Debug for SleeperSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for SleeperSlot {
+    fn eq(&self, other: &SleeperSlot) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SleeperSlot {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::ClientConfig, testing::FakeSleeper};
+
+    #[tokio::test]
+    async fn custom_sleeper() {
+        let fake = FakeSleeper::new();
+        let config = ClientConfig::new().with_sleeper(fake.clone());
+        let sleeper = config.sleeper().unwrap();
+        sleeper.sleep(Duration::from_secs(5)).await;
+        sleeper.sleep(Duration::from_millis(250)).await;
+        assert_eq!(
+            fake.sleeps(),
+            [Duration::from_secs(5), Duration::from_millis(250)]
+        );
+        assert_eq!(fake.total(), Duration::from_millis(5250));
+        assert_eq!(config.clone(), config);
+        assert_ne!(config, ClientConfig::new().with_sleeper(fake));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn default_sleeper() {
+        let sleeper = ClientConfig::new().sleeper().unwrap();
+        assert_eq!(format!("{sleeper:?}"), "TokioSleeper");
+        sleeper.sleep(Duration::from_millis(1)).await;
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    #[test]
+    fn no_default_sleeper() {
+        assert!(ClientConfig::new().sleeper().is_none());
+    }
+}
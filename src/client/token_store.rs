@@ -0,0 +1,416 @@
+use super::{AuthError, AuthProvider, auth::consume_bearer_token};
+use crate::Timestamp;
+use http::header::{HeaderValue, InvalidHeaderValue};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use thiserror::Error;
+
+#[cfg(feature = "tokio")]
+use futures_util::future::BoxFuture;
+
+/// An OAuth user access token, along with its expiration time and refresh
+/// token, if any, as persisted by a [`TokenStore`]
+///
+/// Tokens for GitHub Apps with expiring user tokens enabled expire after
+/// eight hours and come with a refresh token that can be exchanged for a new
+/// access token; other OAuth tokens have neither an expiration time nor a
+/// refresh token.
+///
+/// The `Debug` output redacts the tokens.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StoredToken {
+    pub access_token: String,
+
+    /// The time at which the access token expires, if it expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+
+    /// The time at which the refresh token expires, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token_expires_at: Option<Timestamp>,
+}
+
+impl StoredToken {
+    /// Create a `StoredToken` for an access token that does not expire
+    pub fn new<S: Into<String>>(access_token: S) -> StoredToken {
+        StoredToken {
+            access_token: access_token.into(),
+            expires_at: None,
+            refresh_token: None,
+            refresh_token_expires_at: None,
+        }
+    }
+
+    /// Returns true if the access token has an expiration time that has
+    /// passed
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|t| t <= Timestamp::now())
+    }
+
+    /// Returns true if the token has a refresh token that has not expired
+    pub fn can_refresh(&self) -> bool {
+        self.refresh_token.is_some()
+            && self
+                .refresh_token_expires_at
+                .is_none_or(|t| t > Timestamp::now())
+    }
+}
+
+impl fmt::Debug for StoredToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredToken")
+            .field("access_token", &"<redacted>")
+            .field("expires_at", &self.expires_at)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("refresh_token_expires_at", &self.refresh_token_expires_at)
+            .finish()
+    }
+}
+
+/// Persistent storage for an OAuth user access token, such as the OS
+/// keychain or a configuration file
+///
+/// Implementing this trait provides a single integration point through which
+/// an application saves the token it obtains from the user, loads it again
+/// on later runs, replaces it after refreshing it, and deletes it on logout.
+/// A store can be used to authenticate a client by wrapping it in a
+/// [`TokenStoreAuth`].
+pub trait TokenStore: Send + Sync {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Load the stored token, returning `None` if there is none
+    fn load(&self) -> Result<Option<StoredToken>, Self::Error>;
+
+    /// Store `token`, replacing any previously-stored token
+    fn save(&self, token: &StoredToken) -> Result<(), Self::Error>;
+
+    /// Delete the stored token, if any
+    fn delete(&self) -> Result<(), Self::Error>;
+
+    /// Exchange the refresh token of the expired token `token` for a new
+    /// access token, returning `None` if the store does not support
+    /// refreshing.
+    ///
+    /// This is called by [`TokenStoreAuth`] when the stored access token has
+    /// expired and [`StoredToken::can_refresh()`] is true; the returned token
+    /// is then saved with [`TokenStore::save()`].  Implementations typically
+    /// `POST` the refresh token to `https://github.com/login/oauth/access_token`
+    /// along with the app's client ID and secret.  The default implementation
+    /// returns `None`.
+    fn refresh(&self, token: &StoredToken) -> Result<Option<StoredToken>, Self::Error> {
+        let _ = token;
+        Ok(None)
+    }
+}
+
+/// A [`TokenStore`] that keeps the token in memory, mainly useful for testing
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore(Mutex<Option<StoredToken>>);
+
+impl MemoryTokenStore {
+    pub fn new() -> MemoryTokenStore {
+        MemoryTokenStore::default()
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    type Error = Infallible;
+
+    fn load(&self) -> Result<Option<StoredToken>, Infallible> {
+        Ok(self
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone())
+    }
+
+    fn save(&self, token: &StoredToken) -> Result<(), Infallible> {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(token.clone());
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<(), Infallible> {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        Ok(())
+    }
+}
+
+/// An [`AuthProvider`] that loads the access token from a [`TokenStore`]
+/// before every request
+///
+/// Because the store is consulted for each request, a token that is
+/// refreshed and saved by another part of the application takes effect
+/// immediately.  If the stored access token has expired, it is refreshed
+/// with [`TokenStore::refresh()`] and the new token is saved.  Requests are
+/// sent unauthenticated if the store is empty; if accessing the store fails
+/// or the token has expired and cannot be refreshed, the request is not sent,
+/// and the client returns an
+/// [`ErrorPayload::Auth`][crate::errors::ErrorPayload::Auth] error wrapping
+/// a [`TokenStoreAuthError`].
+///
+/// When used by an [`AsyncClient`][super::tokio::AsyncClient], the store is
+/// accessed on a blocking thread via [`tokio::task::spawn_blocking()`].
+#[derive(Debug)]
+pub struct TokenStoreAuth<S>(Arc<S>);
+
+impl<S: TokenStore> TokenStoreAuth<S> {
+    pub fn new(store: S) -> TokenStoreAuth<S> {
+        TokenStoreAuth(Arc::new(store))
+    }
+
+    /// Returns a reference to the underlying store
+    pub fn store(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S> Clone for TokenStoreAuth<S> {
+    fn clone(&self) -> TokenStoreAuth<S> {
+        TokenStoreAuth(Arc::clone(&self.0))
+    }
+}
+
+impl<S: TokenStore + 'static> AuthProvider for TokenStoreAuth<S> {
+    fn auth_header(&self) -> Result<Option<HeaderValue>, AuthError> {
+        load_header(&*self.0).map_err(AuthError::new)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn auth_header_async(&self) -> BoxFuture<'_, Result<Option<HeaderValue>, AuthError>> {
+        let store = Arc::clone(&self.0);
+        Box::pin(async move {
+            match tokio::task::spawn_blocking(move || load_header(&*store)).await {
+                Ok(r) => r.map_err(AuthError::new),
+                Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Err(e) => Err(AuthError::new(e)),
+            }
+        })
+    }
+}
+
+/// [Private] Load the token from `store`, refreshing it if it has expired,
+/// and construct the `Authorization` header value for it
+fn load_header<S: TokenStore>(
+    store: &S,
+) -> Result<Option<HeaderValue>, TokenStoreAuthError<S::Error>> {
+    let Some(mut token) = store.load().map_err(TokenStoreAuthError::Store)? else {
+        return Ok(None);
+    };
+    if token.is_expired() {
+        if !token.can_refresh() {
+            return Err(TokenStoreAuthError::Expired);
+        }
+        token = store
+            .refresh(&token)
+            .map_err(TokenStoreAuthError::Store)?
+            .filter(|t| !t.is_expired())
+            .ok_or(TokenStoreAuthError::Expired)?;
+        store.save(&token).map_err(TokenStoreAuthError::Store)?;
+    }
+    consume_bearer_token(token.access_token)
+        .map(Some)
+        .map_err(TokenStoreAuthError::InvalidToken)
+}
+
+/// Error returned (wrapped in an [`AuthError`]) by [`TokenStoreAuth`] when it
+/// cannot supply an access token
+#[derive(Debug, Error)]
+pub enum TokenStoreAuthError<E> {
+    /// Loading, saving, or refreshing the token failed
+    #[error("failed to access token store")]
+    Store(#[source] E),
+
+    /// The stored access token has expired and could not be refreshed
+    #[error("stored access token has expired and could not be refreshed")]
+    Expired,
+
+    /// The stored access token is not valid in an HTTP header
+    #[error("stored access token is not a valid header value")]
+    InvalidToken(#[source] InvalidHeaderValue),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A store that refreshes tokens by appending "-refreshed" and counts
+    /// the refreshes
+    #[derive(Debug, Default)]
+    struct RefreshingStore {
+        inner: MemoryTokenStore,
+        refreshes: AtomicUsize,
+    }
+
+    impl TokenStore for RefreshingStore {
+        type Error = Infallible;
+
+        fn load(&self) -> Result<Option<StoredToken>, Infallible> {
+            self.inner.load()
+        }
+
+        fn save(&self, token: &StoredToken) -> Result<(), Infallible> {
+            self.inner.save(token)
+        }
+
+        fn delete(&self) -> Result<(), Infallible> {
+            self.inner.delete()
+        }
+
+        fn refresh(&self, token: &StoredToken) -> Result<Option<StoredToken>, Infallible> {
+            self.refreshes.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(StoredToken::new(format!(
+                "{}-refreshed",
+                token.access_token
+            ))))
+        }
+    }
+
+    /// A store whose every operation fails
+    #[derive(Debug)]
+    struct BrokenStore;
+
+    impl TokenStore for BrokenStore {
+        type Error = std::io::Error;
+
+        fn load(&self) -> Result<Option<StoredToken>, std::io::Error> {
+            Err(std::io::Error::other("keychain is locked"))
+        }
+
+        fn save(&self, _: &StoredToken) -> Result<(), std::io::Error> {
+            Err(std::io::Error::other("keychain is locked"))
+        }
+
+        fn delete(&self) -> Result<(), std::io::Error> {
+            Err(std::io::Error::other("keychain is locked"))
+        }
+    }
+
+    fn expired_token() -> StoredToken {
+        let mut token = StoredToken::new("ghu_abc123");
+        token.refresh_token = Some("ghr_def456".into());
+        token.expires_at = Timestamp::from_unix(Timestamp::now().as_unix() - 1);
+        token
+    }
+
+    #[test]
+    fn store_auth() {
+        let auth = TokenStoreAuth::new(MemoryTokenStore::new());
+        assert_eq!(auth.auth_header().unwrap(), None);
+
+        let mut token = StoredToken::new("ghu_abc123");
+        token.refresh_token = Some("ghr_def456".into());
+        auth.store().save(&token).unwrap();
+        let value = auth.auth_header().unwrap().unwrap();
+        assert_eq!(value, "Bearer ghu_abc123");
+        assert!(value.is_sensitive());
+        assert!(token.can_refresh());
+        assert!(!format!("{token:?}").contains("abc123"));
+
+        auth.store().delete().unwrap();
+        assert_eq!(auth.store().load().unwrap(), None);
+    }
+
+    #[test]
+    fn expired_without_refresh() {
+        let auth = TokenStoreAuth::new(MemoryTokenStore::new());
+        let mut token = expired_token();
+        token.refresh_token_expires_at = token.expires_at;
+        assert!(!token.can_refresh());
+        auth.store().save(&token).unwrap();
+        let e = auth.auth_header().unwrap_err();
+        let e = e
+            .into_inner()
+            .downcast::<TokenStoreAuthError<Infallible>>()
+            .unwrap();
+        assert!(matches!(*e, TokenStoreAuthError::Expired));
+
+        // The default `refresh()` does not support refreshing
+        auth.store().save(&expired_token()).unwrap();
+        let e = auth.auth_header().unwrap_err();
+        let e = e
+            .into_inner()
+            .downcast::<TokenStoreAuthError<Infallible>>()
+            .unwrap();
+        assert!(matches!(*e, TokenStoreAuthError::Expired));
+    }
+
+    #[test]
+    fn expired_with_refresh() {
+        let auth = TokenStoreAuth::new(RefreshingStore::default());
+        auth.store().save(&expired_token()).unwrap();
+        assert_eq!(
+            auth.auth_header().unwrap().unwrap(),
+            "Bearer ghu_abc123-refreshed"
+        );
+        assert_eq!(
+            auth.store().load().unwrap().unwrap().access_token,
+            "ghu_abc123-refreshed"
+        );
+        // The refreshed token is saved, so it is not refreshed again
+        assert_eq!(
+            auth.auth_header().unwrap().unwrap(),
+            "Bearer ghu_abc123-refreshed"
+        );
+        assert_eq!(auth.store().refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn load_error() {
+        let auth = TokenStoreAuth::new(BrokenStore);
+        let e = auth.auth_header().unwrap_err();
+        assert_eq!(e.to_string(), "failed to access token store");
+        let e = e
+            .into_inner()
+            .downcast::<TokenStoreAuthError<std::io::Error>>()
+            .unwrap();
+        assert!(matches!(*e, TokenStoreAuthError::Store(_)));
+    }
+
+    #[test]
+    fn load_error_fails_request() {
+        use crate::{
+            Endpoint, client::ClientConfig, client::FnBackend, errors::ErrorPayload,
+            pagination::PageRequest,
+        };
+        let client = ClientConfig::new()
+            .with_auth_provider(TokenStoreAuth::new(BrokenStore))
+            .with_backend(FnBackend::new(|_, _| -> http::Response<Vec<u8>> {
+                panic!("Request should not have been sent");
+            }));
+        let req = PageRequest::<serde_json::Value>::new(Endpoint::from_iter(["user", "repos"]));
+        let e = client.request(req).unwrap_err();
+        assert!(matches!(e.payload_ref(), ErrorPayload::Auth(_)));
+        assert_eq!(e.url().as_str(), "https://api.github.com/user/repos");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn store_auth_async() {
+        let auth = TokenStoreAuth::new(RefreshingStore::default());
+        assert_eq!(auth.auth_header_async().await.unwrap(), None);
+        auth.store().save(&expired_token()).unwrap();
+        assert_eq!(
+            auth.auth_header_async().await.unwrap().unwrap(),
+            "Bearer ghu_abc123-refreshed"
+        );
+        let auth = TokenStoreAuth::new(BrokenStore);
+        assert!(auth.auth_header_async().await.is_err());
+    }
+
+    #[test]
+    fn serde() {
+        let token = StoredToken::new("ghu_abc123");
+        let s = serde_json::to_string(&token).unwrap();
+        assert_eq!(s, r#"{"access_token":"ghu_abc123"}"#);
+        assert_eq!(serde_json::from_str::<StoredToken>(&s).unwrap(), token);
+    }
+}
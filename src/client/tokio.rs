@@ -1,90 +1,558 @@
-use super::{ClientConfig, RequestParts};
+use super::{ClientConfig, FnResponse, RedirectBody, RequestParts, adhoc::JsonRequest};
 use crate::{
-    HttpUrl,
-    errors::{Error, ErrorPayload, ErrorResponseParser},
-    pagination::{PaginationRequest, PaginationStream},
-    parser::ResponseParserExt,
-    request::{AsyncRequestBody, Request},
-    response::{Response, ResponseParts},
+    Endpoint, HttpUrl, Method,
+    errors::{CommonError, Error, ErrorPayload, ErrorResponseParser, RedirectResponse},
+    pagination::{
+        PageCursor, PageStream, PaginationRequest, PaginationStream, ParallelPaginationStream,
+        SearchRequest, SearchStream,
+    },
+    parser::{
+        AsyncToFile, JsonArrayStream, JsonResponse, ResponseParserExt, ToAsyncWriter, Utf8Text,
+    },
+    request::{AdHocRequest, AsyncRequestBody, Request},
+    response::{Response, ResponseParts, TlsInfo},
 };
+use futures_util::TryStreamExt;
+use http::header::{HeaderName, HeaderValue};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
 use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Error type returned by the methods of an [`AsyncClient`] with backend
+/// `B`.
+///
+/// The `E` parameter is the `Error` type of the input [`Request`] provided to
+/// a method.
+pub type AsyncClientError<B, E = CommonError> = Error<<B as AsyncBackend>::Error, E>;
+
+/// Result type returned by the methods of an [`AsyncClient`] with backend
+/// `B`.
+///
+/// The `E` parameter is the `Error` type of the input [`Request`] provided to
+/// a method.
+pub type AsyncClientResult<T, B, E = CommonError> = Result<T, AsyncClientError<B, E>>;
+
+/// An asynchronous client for the GitHub REST API
+///
+/// The client's configuration and backend are stored behind [`Arc`]s, and so
+/// cloning an `AsyncClient` is cheap and does not require the backend to
+/// implement `Clone`.  Streams returned by methods like
+/// [`AsyncClient::paginate()`] hold such a clone of the client.
+#[derive(Debug, Eq, PartialEq)]
 pub struct AsyncClient<B> {
-    pub(super) config: ClientConfig,
-    pub(super) backend: B,
+    pub(super) config: Arc<ClientConfig>,
+    pub(super) backend: Arc<B>,
 }
 
 impl<B> AsyncClient<B> {
     pub fn new(config: ClientConfig, backend: B) -> AsyncClient<B> {
-        AsyncClient { config, backend }
+        AsyncClient {
+            config: Arc::new(config),
+            backend: Arc::new(backend),
+        }
     }
 
     pub fn backend_ref(&self) -> &B {
         &self.backend
     }
 
+    /// Returns a mutable reference to the backend if this client is the only
+    /// clone of itself in existence (including clones held by streams that
+    /// have not yet been dropped); otherwise, returns `None`
+    pub fn get_backend_mut(&mut self) -> Option<&mut B> {
+        Arc::get_mut(&mut self.backend)
+    }
+
+    /// Returns the base API URL
+    pub fn base_url(&self) -> HttpUrl {
+        self.config.base_url()
+    }
+
+    /// Returns the [`Sleeper`][super::Sleeper] used by the client to wait
+    /// between requests; see [`ClientConfig::sleeper()`]
+    pub fn sleeper(&self) -> Option<Arc<dyn super::Sleeper>> {
+        self.config.sleeper()
+    }
+
+    /// Change the base API URL used by subsequent requests, keeping the same
+    /// backend.  See [`Client::set_base_url()`][super::Client::set_base_url]
+    /// for more information.
+    ///
+    /// Unlike clones of a [`Client`][super::Client], clones of an
+    /// `AsyncClient` share their configuration, so this changes the base URL
+    /// of every clone, including those held by streams that have not yet
+    /// been dropped.
+    pub fn set_base_url(&self, url: HttpUrl) {
+        self.config.base_url.set(url);
+    }
+}
+
+impl<B: Clone> AsyncClient<B> {
+    /// Returns a mutable reference to the backend.  If other clones of this
+    /// client exist, the backend is first cloned so that they are unaffected.
     pub fn backend_mut(&mut self) -> &mut B {
-        &mut self.backend
+        Arc::make_mut(&mut self.backend)
     }
 }
 
-impl<B: AsyncBackend + Sync> AsyncClient<B> {
-    pub async fn request<R>(&self, req: R) -> Result<R::Output, Error<B::Error, R::Error>>
+impl<B> Clone for AsyncClient<B> {
+    fn clone(&self) -> AsyncClient<B> {
+        AsyncClient {
+            config: Arc::clone(&self.config),
+            backend: Arc::clone(&self.backend),
+        }
+    }
+}
+
+impl<B: AsyncBackend + Send + Sync> AsyncClient<B> {
+    pub async fn request<R>(&self, req: R) -> AsyncClientResult<R::Output, B, R::Error>
     where
         R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
     {
-        let (reqparts, reqbody) = self.config.prepare_async_request(&req)?.into_parts();
+        let (response, failure, req) = self.send(req).await?;
+        let parser = req.parser();
+        parser
+            .parse_async_response(response)
+            .await
+            .map_err(|e| failure.error(e))
+    }
+
+    /// Make the given request and return a stream of the elements of the
+    /// JSON array in the response body, each of which is deserialized as soon
+    /// as it has been received in full.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`Client::request_json_array()`][super::Client::request_json_array];
+    /// see there for details.
+    pub async fn request_json_array<T, R>(
+        &self,
+        req: R,
+    ) -> AsyncClientResult<
+        JsonArrayStream<impl tokio::io::AsyncRead + Send + 'static + use<B, T, R>, T>,
+        B,
+        R::Error,
+    >
+    where
+        T: DeserializeOwned,
+        R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
+    {
+        let (response, _, _) = self.send(req).await?;
+        Ok(JsonArrayStream::new(response.into_body()))
+    }
+
+    /// Make the given request and write the response body to the file at
+    /// `path` atomically using an [`AsyncToFile`], so that an interrupted
+    /// download never leaves a truncated file behind.
+    ///
+    /// The request's parser is not used.
+    pub async fn request_to_file<R>(
+        &self,
+        req: R,
+        path: &Path,
+    ) -> AsyncClientResult<(), B, R::Error>
+    where
+        R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
+    {
+        let (response, failure, _) = self.send(req).await?;
+        AsyncToFile::new(path)
+            .parse_async_response(response)
+            .await
+            .map_err(|e| failure.error(e))
+    }
+
+    /// Make the given request and stream the response body into `writer`
+    /// using a [`ToAsyncWriter`]
+    ///
+    /// The request's parser is not used.
+    pub async fn request_to_writer<R, W>(
+        &self,
+        req: R,
+        writer: W,
+    ) -> AsyncClientResult<(), B, R::Error>
+    where
+        R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
+        W: tokio::io::AsyncWrite + Unpin + Send,
+    {
+        let (response, failure, _) = self.send(req).await?;
+        ToAsyncWriter::new(writer)
+            .parse_async_response(response)
+            .await
+            .map_err(|e| failure.error(e))
+    }
+
+    /// [Private] Send the given request, following redirects as configured,
+    /// and return the successful response along with the information needed
+    /// to report a failure to parse it.  Error responses are parsed and
+    /// returned as errors.
+    ///
+    /// The request is taken by value and handed back so that the returned
+    /// future does not hold a reference to it, which would require `R: Sync`
+    /// for the future to be `Send`.
+    async fn send<R>(
+        &self,
+        req: R,
+    ) -> AsyncClientResult<
+        (
+            Response<impl tokio::io::AsyncRead + Send + 'static + use<B, R>>,
+            super::probe::ParseFailure,
+            R,
+        ),
+        B,
+        R::Error,
+    >
+    where
+        R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
+    {
+        let auth = self.config.auth_header_async().await;
+        // Scoped so that the future does not hold `prepped` across the await
+        // below, which would require the request body to be `Send`
+        let (captured, mut reqparts, reqbody) = {
+            let prepped = self.config.prepare_async_request(&req, auth)?;
+            let captured = prepped.captured_body().cloned();
+            let (reqparts, reqbody) = prepped.into_parts();
+            (captured, reqparts, reqbody)
+        };
         let initial_url = reqparts.url.clone();
-        let method = reqparts.method;
-        let backreq = self.backend.prepare_request(reqparts);
-        let resp = match self.backend.send(backreq, reqbody).await {
-            Ok(resp) => resp,
+        let method = reqparts.method.clone();
+        let mut reqbody = match reqbody.into_async_read().await {
+            Ok(body) => RedirectBody::new(body),
             Err(e) => {
-                let payload = ErrorPayload::Send(e);
-                return Err(Error::new(initial_url, method, payload));
+                let payload = ErrorPayload::PrepareRequest(e.into());
+                return Err(Error::new(initial_url, method, payload).with_request_body(captured));
             }
         };
-        let parts = ResponseParts {
-            initial_url: initial_url.clone(),
-            method,
-            url: resp.url(),
-            status: resp.status(),
-            headers: resp.headers(),
+        let mut redirects = 0;
+        let (resp, parts) = loop {
+            #[cfg(feature = "log")]
+            log::debug!("Sending {} request to {}", reqparts.method, reqparts.url);
+            let ratelimit = self.config.rate_limit_route(&reqparts.url);
+            if let Some((limiter, resource)) = &ratelimit
+                && let Some(delay) = limiter.delay(resource)
+                && let Some(sleeper) = self.config.sleeper()
+            {
+                #[cfg(feature = "log")]
+                log::debug!("Waiting {delay:?} for {resource} rate limit to reset");
+                sleeper.sleep(delay).await;
+            }
+            let backreq = match self.backend.prepare_request(reqparts.clone()) {
+                Ok(backreq) => backreq,
+                Err(e) => {
+                    let payload = ErrorPayload::Send(e);
+                    return Err(
+                        Error::new(initial_url, method, payload).with_request_body(captured)
+                    );
+                }
+            };
+            let resp = match self.backend.send(backreq, reqbody).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    let payload = ErrorPayload::Send(e);
+                    return Err(
+                        Error::new(initial_url, method, payload).with_request_body(captured)
+                    );
+                }
+            };
+            let url = resp.url();
+            let headers = resp.headers();
+            if let Some((limiter, resource)) = &ratelimit {
+                limiter.record(resource, &headers);
+            }
+            let parts = ResponseParts {
+                initial_url: initial_url.clone(),
+                method: reqparts.method.clone(),
+                host_changed: url.as_url().origin() != reqparts.url.as_url().origin(),
+                url,
+                status: resp.status(),
+                headers: self.config.filter_response_headers(headers),
+                tls: resp.tls_info(),
+            };
+            #[cfg(feature = "log")]
+            log::debug!("Received {} response from {}", parts.status, parts.url);
+            #[cfg(feature = "log")]
+            if parts.host_changed {
+                log::warn!(
+                    "Request to {} was answered by a different origin: {}",
+                    reqparts.url,
+                    parts.url
+                );
+            }
+            if !self.config.handles_redirect(parts.status) {
+                break (resp, parts);
+            }
+            let redirect = RedirectResponse::new(parts);
+            let Some((next, keep_body)) =
+                self.config.follow_redirect(&reqparts, &redirect, redirects)
+            else {
+                let payload = ErrorPayload::Redirect(Box::new(redirect));
+                return Err(Error::new(initial_url, method, payload).with_request_body(captured));
+            };
+            reqbody = if keep_body {
+                let body = match self.config.prepare_async_request(&req, Ok(None)) {
+                    Ok(prepped) => prepped.into_body(),
+                    Err(e) => {
+                        return Err(e
+                            .with_request_body(captured)
+                            .with_redirect_url(Some(next.url)));
+                    }
+                };
+                match body.into_async_read().await {
+                    Ok(body) => RedirectBody::new(body),
+                    Err(e) => {
+                        let payload = ErrorPayload::PrepareRequest(e.into());
+                        return Err(Error::new(initial_url, method, payload)
+                            .with_request_body(captured)
+                            .with_redirect_url(Some(next.url)));
+                    }
+                }
+            } else {
+                RedirectBody::Empty
+            };
+            reqparts = next;
+            redirects += 1;
         };
         let body = resp.body_reader();
+        #[cfg(feature = "log")]
+        let body = super::preview::BodyPreview::new(body, &parts, self.config.body_preview);
+        let body = super::deadline::ParseDeadline::new(body, self.config.parse_timeout);
+        let body = super::limit::SizeLimit::new(body, self.config.max_response_size);
+        let probe = self
+            .config
+            .parse_error_context
+            .then(|| super::probe::ParseProbe::new(&parts));
+        let body = super::probe::CountingBody::new(
+            body,
+            probe.as_ref().map(super::probe::ParseProbe::counter),
+        );
         let response = Response::from_parts(parts, body);
+        let failure = super::probe::ParseFailure {
+            url: initial_url,
+            method,
+            captured,
+            probe,
+        };
         if response.status().is_client_error() || response.status().is_server_error() {
             let parser = ErrorResponseParser::new();
-            let err_resp = parser.parse_async_response(response).await.map_err(|e| {
-                Error::new(
-                    initial_url.clone(),
-                    method,
-                    ErrorPayload::ParseResponse(e.convert_parse_error::<R::Error>()),
-                )
-            })?;
+            let err_resp = parser
+                .parse_async_response(response)
+                .await
+                .map_err(|e| failure.clone().error::<_, R::Error, _>(e))?;
             Err(Error::new(
-                initial_url,
-                method,
+                failure.url,
+                failure.method,
                 ErrorPayload::Status(Box::new(err_resp)),
-            ))
+            )
+            .with_request_body(failure.captured))
         } else {
-            let parser = req.parser();
-            parser.parse_async_response(response).await.map_err(|e| {
-                Error::new(
-                    initial_url,
-                    method,
-                    ErrorPayload::ParseResponse(e.convert_parse_error()),
-                )
-            })
+            Ok((response, failure, req))
         }
     }
 }
 
-impl<B: AsyncBackend + Clone + Sync> AsyncClient<B> {
+impl<B: AsyncBackend + Send + Sync> AsyncClient<B> {
     pub fn paginate<R: PaginationRequest>(&self, req: R) -> PaginationStream<B, R> {
         PaginationStream::new(self.clone(), req)
     }
+
+    /// Paginate over the given request one page at a time, yielding a
+    /// [`PageResponse`][crate::pagination::PageResponse] for each page rather
+    /// than the individual items
+    pub fn paginate_pages<R: PaginationRequest>(&self, req: R) -> PageStream<B, R> {
+        PageStream::new(self.clone(), req)
+    }
+
+    /// Return a [`PageCursor`] for fetching the pages of the given request
+    /// one at a time under manual control
+    pub fn page_cursor<R: PaginationRequest>(&self, req: R) -> PageCursor<AsyncClient<B>, R> {
+        PageCursor::new(self.clone(), req)
+    }
+
+    /// Paginate over the results of a [`SearchRequest`], splitting its range
+    /// as needed to work around GitHub's limit on the number of results per
+    /// search; see [`SearchIter`][crate::pagination::SearchIter] for details
+    pub fn search<T: DeserializeOwned + Send>(&self, req: SearchRequest<T>) -> SearchStream<B, T> {
+        SearchStream::new(self.clone(), req)
+    }
+
+    /// Paginate over the given request, fetching pages concurrently with at
+    /// most `concurrency` requests in flight at a time once the number of the
+    /// last page is known; see [`ParallelPaginationStream`] for details
+    pub fn paginate_parallel<R>(&self, req: R, concurrency: usize) -> ParallelPaginationStream<B, R>
+    where
+        B: Send + 'static,
+        B::Error: Send,
+        R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+    {
+        ParallelPaginationStream::new(self.clone(), req, concurrency)
+    }
+
+    /// Paginate over the given request and collect all of the items into a
+    /// `Vec`, stopping at and returning the first error encountered
+    pub async fn paginate_all<R>(&self, req: R) -> AsyncClientResult<Vec<R::Item>, B>
+    where
+        B: Send + 'static,
+        R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+    {
+        self.paginate(req).try_collect().await
+    }
+}
+
+impl<B: AsyncBackend + Send + Sync> AsyncClient<B> {
+    /// Start building a `GET` request to the given endpoint
+    pub fn get(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Get, endpoint)
+    }
+
+    /// Start building a `HEAD` request to the given endpoint
+    pub fn head(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Head, endpoint)
+    }
+
+    /// Start building a `POST` request to the given endpoint
+    pub fn post(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Post, endpoint)
+    }
+
+    /// Start building a `PUT` request to the given endpoint
+    pub fn put(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Put, endpoint)
+    }
+
+    /// Start building a `PATCH` request to the given endpoint
+    pub fn patch(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Patch, endpoint)
+    }
+
+    /// Start building a `DELETE` request to the given endpoint
+    pub fn delete(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Delete, endpoint)
+    }
+
+    /// Start building an `OPTIONS` request to the given endpoint.
+    ///
+    /// This can be used to send a CORS preflight request by also setting the
+    /// `Origin` and `Access-Control-Request-Method` headers.
+    pub fn options(&self, endpoint: Endpoint) -> AsyncRequestBuilder<'_, B> {
+        AsyncRequestBuilder::new(self, Method::Options, endpoint)
+    }
+
+    /// Perform a `GET` request to the given endpoint and deserialize the
+    /// response body as JSON
+    pub async fn get_json<T: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+    ) -> AsyncClientResult<T, B> {
+        self.get(endpoint).send_json().await
+    }
+
+    /// Perform a `POST` request to the given endpoint with `body` serialized
+    /// as JSON, and deserialize the response body as JSON
+    pub async fn post_json<In: Serialize + Sync, Out: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+        body: &In,
+    ) -> AsyncClientResult<Out, B> {
+        self.request(JsonRequest::new(Method::Post, endpoint, body))
+            .await
+    }
+
+    /// Perform a `PUT` request to the given endpoint with `body` serialized
+    /// as JSON, and deserialize the response body as JSON
+    pub async fn put_json<In: Serialize + Sync, Out: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+        body: &In,
+    ) -> AsyncClientResult<Out, B> {
+        self.request(JsonRequest::new(Method::Put, endpoint, body))
+            .await
+    }
+
+    /// Perform a `PATCH` request to the given endpoint with `body` serialized
+    /// as JSON, and deserialize the response body as JSON
+    pub async fn patch_json<In: Serialize + Sync, Out: DeserializeOwned + Send>(
+        &self,
+        endpoint: Endpoint,
+        body: &In,
+    ) -> AsyncClientResult<Out, B> {
+        self.request(JsonRequest::new(Method::Patch, endpoint, body))
+            .await
+    }
+
+    /// Perform a `DELETE` request to the given endpoint and discard the
+    /// response body
+    pub async fn delete_ignored(&self, endpoint: Endpoint) -> AsyncClientResult<(), B> {
+        self.delete(endpoint).send_ignored().await
+    }
+}
+
+/// A builder for a one-off request, returned by [`AsyncClient::get()`] and
+/// similar methods.  This is the async counterpart of
+/// [`RequestBuilder`][super::RequestBuilder].
+#[derive(Debug)]
+pub struct AsyncRequestBuilder<'a, B> {
+    client: &'a AsyncClient<B>,
+    request: AdHocRequest,
+}
+
+impl<'a, B: AsyncBackend + Send + Sync> AsyncRequestBuilder<'a, B> {
+    fn new(client: &'a AsyncClient<B>, method: Method, endpoint: Endpoint) -> Self {
+        AsyncRequestBuilder {
+            client,
+            request: AdHocRequest::new(method, endpoint),
+        }
+    }
+
+    /// Add a query parameter to the request
+    pub fn param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.request = self.request.with_param(key, value);
+        self
+    }
+
+    /// Add a header to the request.  Any previous values for the header are
+    /// replaced.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.request = self.request.with_header(name, value);
+        self
+    }
+
+    /// Set a JSON value to send as the request body
+    pub fn json_body(mut self, body: serde_json::Value) -> Self {
+        self.request = self.request.with_json_body(body);
+        self
+    }
+
+    /// Set the request's timeout, overriding the client's default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.request = self.request.with_timeout(timeout);
+        self
+    }
+
+    /// Returns the request built so far
+    pub fn into_request(self) -> AdHocRequest {
+        self.request
+    }
+
+    /// Send the request and deserialize the response body as JSON
+    pub async fn send_json<T: DeserializeOwned + Send>(self) -> AsyncClientResult<T, B> {
+        self.client
+            .request(self.request.with_parser::<JsonResponse<T>>())
+            .await
+    }
+
+    /// Send the request and return the response body as a string
+    pub async fn send_text(self) -> AsyncClientResult<String, B> {
+        self.client
+            .request(self.request.with_parser::<Utf8Text>())
+            .await
+    }
+
+    /// Send the request and discard the response body
+    pub async fn send_ignored(self) -> AsyncClientResult<(), B> {
+        self.client.request(self.request).await
+    }
 }
 
 pub trait AsyncBackend {
@@ -92,7 +560,12 @@ pub trait AsyncBackend {
     type Response: AsyncBackendResponse;
     type Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request;
+    /// Convert `r` into a request for the backend.  Backends that cannot
+    /// represent the request, e.g., because of a header value or URL that
+    /// the underlying HTTP library rejects, should return an error here
+    /// rather than panicking or waiting until [`send()`][Self::send] to
+    /// report it.
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error>;
 
     fn send<R: tokio::io::AsyncRead + Send + 'static>(
         &self,
@@ -106,7 +579,7 @@ impl<T: AsyncBackend + Sync + ?Sized> AsyncBackend for &T {
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (*self).prepare_request(r)
     }
 
@@ -124,7 +597,7 @@ impl<T: AsyncBackend + ?Sized> AsyncBackend for &mut T {
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (**self).prepare_request(r)
     }
 
@@ -137,12 +610,12 @@ impl<T: AsyncBackend + ?Sized> AsyncBackend for &mut T {
     }
 }
 
-impl<T: AsyncBackend + ?Sized> AsyncBackend for std::sync::Arc<T> {
+impl<T: AsyncBackend + ?Sized> AsyncBackend for Arc<T> {
     type Request = T::Request;
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (**self).prepare_request(r)
     }
 
@@ -160,7 +633,7 @@ impl<T: AsyncBackend + ?Sized> AsyncBackend for Box<T> {
     type Response = T::Response;
     type Error = T::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         (**self).prepare_request(r)
     }
 
@@ -178,6 +651,100 @@ pub trait AsyncBackendResponse: Send {
     fn status(&self) -> http::status::StatusCode;
     fn headers(&self) -> http::header::HeaderMap;
     fn body_reader(self) -> impl tokio::io::AsyncRead + Send + 'static;
+
+    /// Returns details of the TLS connection over which the response was
+    /// received, if known.  The default implementation returns `None`.
+    fn tls_info(&self) -> Option<TlsInfo> {
+        None
+    }
+}
+
+/// An asynchronous [`AsyncBackend`] that passes each request to a closure
+/// instead of sending it over the network
+///
+/// The closure is called with the parts of the request and the complete
+/// request body, and it must return the response to report to the client.
+/// The URL of the response is always the URL of the request.
+///
+/// This is the asynchronous counterpart of [`FnBackend`][super::FnBackend].
+pub struct AsyncFnBackend<F> {
+    func: Arc<F>,
+}
+
+impl<F> AsyncFnBackend<F>
+where
+    F: Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>> + Send + Sync + 'static,
+{
+    pub fn new(func: F) -> AsyncFnBackend<F> {
+        AsyncFnBackend {
+            func: Arc::new(func),
+        }
+    }
+}
+
+impl<F> Clone for AsyncFnBackend<F> {
+    fn clone(&self) -> AsyncFnBackend<F> {
+        AsyncFnBackend {
+            func: Arc::clone(&self.func),
+        }
+    }
+}
+
+impl<F> fmt::Debug for AsyncFnBackend<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncFnBackend").finish_non_exhaustive()
+    }
+}
+
+impl<F> AsyncBackend for AsyncFnBackend<F>
+where
+    F: Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>> + Send + Sync + 'static,
+{
+    type Request = RequestParts;
+    type Response = FnResponse;
+    type Error = std::io::Error;
+
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
+        Ok(r)
+    }
+
+    fn send<R: tokio::io::AsyncRead + Send + 'static>(
+        &self,
+        r: Self::Request,
+        body: R,
+    ) -> impl Future<Output = Result<Self::Response, Self::Error>> + Send + 'static {
+        let func = Arc::clone(&self.func);
+        async move {
+            let mut buf = Vec::new();
+            tokio::pin!(body);
+            body.read_to_end(&mut buf).await?;
+            let url = r.url.clone();
+            let response = func(r, buf);
+            Ok(FnResponse { url, response })
+        }
+    }
+}
+
+impl AsyncBackendResponse for FnResponse {
+    fn url(&self) -> HttpUrl {
+        self.url.clone()
+    }
+
+    fn status(&self) -> http::status::StatusCode {
+        self.response.status()
+    }
+
+    fn headers(&self) -> http::header::HeaderMap {
+        self.response.headers().clone()
+    }
+
+    fn body_reader(self) -> impl tokio::io::AsyncRead + Send + 'static {
+        std::io::Cursor::new(self.response.into_body())
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.response.extensions().get::<TlsInfo>().cloned()
+    }
 }
 
 #[cfg(test)]
@@ -192,10 +759,254 @@ mod tests {
         #[allow(dead_code)]
         fn check<B, R>(client: AsyncClient<B>, req: R)
         where
-            B: AsyncBackend + Sync,
+            B: AsyncBackend + Send + Sync,
             R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
         {
             require_send(client.request(req));
         }
+
+        #[allow(dead_code)]
+        fn check_json_array<B, R>(client: AsyncClient<B>, req: R)
+        where
+            B: AsyncBackend + Send + Sync,
+            R: Request<Body: AsyncRequestBody<Error: Into<R::Error>>> + Send,
+        {
+            require_send(client.request_json_array::<serde_json::Value, R>(req));
+        }
+    }
+
+    #[tokio::test]
+    async fn fn_backend() {
+        use crate::{
+            Endpoint, Method,
+            errors::CommonError,
+            parser::{ResponseParser, Utf8Text},
+        };
+
+        struct Echo;
+
+        impl Request for Echo {
+            type Output = String;
+            type Error = CommonError;
+            type Body = String;
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["markdown", "raw"])
+            }
+
+            fn method(&self) -> Method {
+                Method::Post
+            }
+
+            fn body(&self) -> Self::Body {
+                String::from("Hello, **world**!")
+            }
+
+            fn parser(
+                &self,
+            ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send
+            {
+                Utf8Text::new()
+            }
+        }
+
+        let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(|parts, body| {
+            assert_eq!(parts.url.as_str(), "https://api.github.com/markdown/raw");
+            assert_eq!(parts.method, Method::Post);
+            http::Response::new(body)
+        }));
+        assert_eq!(client.request(Echo).await.unwrap(), "Hello, **world**!");
+    }
+
+    #[tokio::test]
+    async fn redirect_body_error() {
+        use crate::{
+            Endpoint, Method,
+            client::RedirectPolicy,
+            errors::CapturedBody,
+            parser::{Ignore, ResponseParser},
+        };
+        use std::borrow::Cow;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        /// A request body that can only be read once
+        struct OnceBody(Option<&'static [u8]>);
+
+        impl AsyncRequestBody for OnceBody {
+            type Error = CommonError;
+
+            fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+                self.0.map(Cow::Borrowed)
+            }
+
+            fn into_async_read(
+                self,
+            ) -> impl Future<
+                Output = Result<impl tokio::io::AsyncRead + Send + 'static, CommonError>,
+            > + Send {
+                std::future::ready(
+                    self.0
+                        .map(std::io::Cursor::new)
+                        .ok_or_else(|| std::io::Error::other("body already consumed").into()),
+                )
+            }
+        }
+
+        struct CreateHook(AtomicBool);
+
+        impl Request for CreateHook {
+            type Output = ();
+            type Error = CommonError;
+            type Body = OnceBody;
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["hooks"])
+            }
+
+            fn method(&self) -> Method {
+                Method::Post
+            }
+
+            fn body(&self) -> OnceBody {
+                OnceBody((!self.0.swap(true, Ordering::SeqCst)).then_some(br#"{"name":"ci"}"#))
+            }
+
+            fn parser(
+                &self,
+            ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send
+            {
+                Ignore
+            }
+        }
+
+        let client = ClientConfig::new()
+            .with_redirect_policy(RedirectPolicy::Follow(2))
+            .with_request_body_capture(100)
+            .with_async_backend(AsyncFnBackend::new(|parts, _| {
+                assert_eq!(parts.url.as_str(), "https://api.github.com/hooks");
+                http::Response::builder()
+                    .status(307)
+                    .header("Location", "https://api.github.com/v2/hooks")
+                    .body(Vec::new())
+                    .unwrap()
+            }));
+        let e = client
+            .request(CreateHook(AtomicBool::new(false)))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            e.payload_ref(),
+            ErrorPayload::PrepareRequest(CommonError::Io(_))
+        ));
+        assert_eq!(
+            e.redirect_url().map(HttpUrl::as_str),
+            Some("https://api.github.com/v2/hooks")
+        );
+        assert_eq!(
+            e.request_body().and_then(CapturedBody::as_str),
+            Some(r#"{"name":"ci"}"#)
+        );
+    }
+
+    #[test]
+    fn set_base_url_shared_by_clones() {
+        let client = ClientConfig::new()
+            .with_async_backend(AsyncFnBackend::new(|_, body| http::Response::new(body)));
+        let other = client.clone();
+        client.set_base_url("https://ghe.example.com/api/v3".parse().unwrap());
+        assert_eq!(client.base_url().as_str(), "https://ghe.example.com/api/v3");
+        assert_eq!(other.base_url().as_str(), "https://ghe.example.com/api/v3");
+    }
+
+    #[tokio::test]
+    async fn ad_hoc_request() {
+        let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(|parts, body| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/labels"
+            );
+            assert_eq!(parts.method, Method::Post);
+            assert_eq!(body, br#"{"name":"bug"}"#);
+            http::Response::new(br#"{"id": 1}"#.to_vec())
+        }));
+        let value = client
+            .post(Endpoint::from_iter([
+                "repos",
+                "octocat",
+                "hello-world",
+                "labels",
+            ]))
+            .json_body(serde_json::json!({"name": "bug"}))
+            .send_json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(value, serde_json::json!({"id": 1}));
+    }
+
+    #[tokio::test]
+    async fn request_json_array() {
+        use crate::request::AdHocRequest;
+        use futures_util::TryStreamExt;
+
+        let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(|_, _| {
+            http::Response::new(b"[1, 2, 3]".to_vec())
+        }));
+        let req = AdHocRequest::new(Method::Get, Endpoint::from_iter(["numbers"]));
+        let numbers = client
+            .request_json_array::<u32, _>(req)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(numbers, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn rate_limiter() {
+        use crate::{
+            client::{RateLimitResource, RateLimiter},
+            request::AdHocRequest,
+            testing::FakeSleeper,
+        };
+        use std::sync::Arc;
+        use std::time::{Duration, SystemTime};
+
+        let reset = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let sleeper = FakeSleeper::new();
+        let limiter = Arc::new(RateLimiter::new().with_max_delay(Duration::from_secs(5)));
+        let client = ClientConfig::new()
+            .with_sleeper(sleeper.clone())
+            .with_rate_limiter(Arc::clone(&limiter))
+            .with_async_backend(AsyncFnBackend::new(move |parts, _| {
+                let (resource, remaining) = if parts.url.as_str().contains("/search/") {
+                    ("search", 0)
+                } else {
+                    ("core", 4999)
+                };
+                http::Response::builder()
+                    .header("x-ratelimit-resource", resource)
+                    .header("x-ratelimit-remaining", remaining)
+                    .header("x-ratelimit-reset", reset)
+                    .body(b"{}".to_vec())
+                    .unwrap()
+            }));
+        let search = || AdHocRequest::new(Method::Get, Endpoint::from_iter(["search", "issues"]));
+        let core = || AdHocRequest::new(Method::Get, Endpoint::from_iter(["user"]));
+        client.request(search()).await.unwrap();
+        client.request(core()).await.unwrap();
+        assert!(sleeper.sleeps().is_empty());
+        assert_eq!(
+            limiter
+                .budget(&RateLimitResource::Core)
+                .map(|b| b.remaining),
+            Some(4999)
+        );
+        client.request(search()).await.unwrap();
+        assert_eq!(sleeper.sleeps(), [Duration::from_secs(5)]);
     }
 }
@@ -1,9 +1,23 @@
 /// The default `Accept` header sent in requests
 pub static DEFAULT_ACCEPT: &str = "application/vnd.github+json";
 
+/// The media type that historically had to be included in the `Accept`
+/// header in order for repository responses to include a `topics` field; see
+/// [`AcceptList`][crate::AcceptList]
+pub static TOPICS_PREVIEW_ACCEPT: &str = "application/vnd.github.mercy-preview+json";
+
 /// The default base API URL to which to append path endpoints
 pub static DEFAULT_API_URL: &str = "https://api.github.com";
 
+/// The base URL for uploading release assets to github.com
+pub static UPLOADS_URL: &str = "https://uploads.github.com";
+
+/// The URL of the github.com GraphQL API endpoint
+pub static GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// The URL of the github.com website, used for things like OAuth flows
+pub static GITHUB_COM_URL: &str = "https://github.com";
+
 /// The name of the HTTP header used by the GitHub REST API to communicate the
 /// API version
 pub static API_VERSION_HEADER: &str = "X-GitHub-Api-Version";
@@ -23,6 +37,29 @@ pub static DEFAULT_USER_AGENT: &str = concat!(
     ")",
 );
 
+/// The names of the response headers that are always retained when a client
+/// is configured with a response header allow-list via
+/// [`ClientConfig::with_response_header_allowlist()`][crate::client::ClientConfig::with_response_header_allowlist]
+///
+/// These consist of the headers that `ghreq` itself inspects (e.g., for
+/// parsing, pagination, and redirects) plus the rate-limit, `ETag`, and
+/// request ID headers.
+pub static RETAINED_RESPONSE_HEADERS: &[&str] = &[
+    "content-length",
+    "content-range",
+    "content-type",
+    "etag",
+    "link",
+    "location",
+    "retry-after",
+    "x-github-request-id",
+    "x-ratelimit-limit",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "x-ratelimit-resource",
+    "x-ratelimit-used",
+];
+
 /// The maximum number of bytes read at once from a response body.
 ///
 /// This value may change at any time between releases.
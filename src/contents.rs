@@ -0,0 +1,465 @@
+//! Requests for reading & writing files via the repository contents API
+use crate::{
+    Endpoint, Method,
+    client::{Backend, Client},
+    errors::{CommonError, Error, ErrorClass},
+    parser::{JsonResponse, ResponseParser},
+    request::{JsonBody, Request},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[cfg(feature = "tokio")]
+use crate::client::tokio::{AsyncBackend, AsyncClient};
+
+/// The default maximum number of attempts made by
+/// [`PutContents::put_with_merge()`]
+pub const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// A request to fetch a file via `GET /repos/{owner}/{repo}/contents/{path}`
+///
+/// Only files up to 1 MB in size can be fetched this way.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GetContents {
+    owner: String,
+    repo: String,
+    path: String,
+    reference: Option<String>,
+}
+
+impl GetContents {
+    pub fn new<S, T, U>(owner: S, repo: T, path: U) -> GetContents
+    where
+        S: Into<String>,
+        T: Into<String>,
+        U: Into<String>,
+    {
+        GetContents {
+            owner: owner.into(),
+            repo: repo.into(),
+            path: path.into(),
+            reference: None,
+        }
+    }
+
+    /// Fetch the file as of the given branch, tag, or commit instead of the
+    /// repository's default branch
+    pub fn with_ref<S: Into<String>>(mut self, reference: S) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+}
+
+impl Request for GetContents {
+    type Output = FileContents;
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        contents_endpoint(&self.owner, &self.repo, &self.path)
+    }
+
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.reference
+            .iter()
+            .map(|r| (String::from("ref"), r.clone()))
+            .collect()
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        JsonResponse::new()
+    }
+}
+
+/// A file fetched with [`GetContents`]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(try_from = "RawFileContents")]
+pub struct FileContents {
+    pub path: String,
+
+    /// The blob SHA of the file
+    pub sha: String,
+
+    /// The decoded contents of the file
+    pub content: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct RawFileContents {
+    path: String,
+    sha: String,
+    content: String,
+    encoding: String,
+}
+
+impl TryFrom<RawFileContents> for FileContents {
+    type Error = String;
+
+    fn try_from(value: RawFileContents) -> Result<FileContents, String> {
+        if value.encoding != "base64" {
+            return Err(format!(
+                "unsupported file content encoding {:?}",
+                value.encoding
+            ));
+        }
+        // GitHub wraps the base64 text across multiple lines.
+        let b64 = value
+            .content
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<String>();
+        let content = STANDARD.decode(b64).map_err(|e| e.to_string())?;
+        Ok(FileContents {
+            path: value.path,
+            sha: value.sha,
+            content,
+        })
+    }
+}
+
+/// A request to create or update a file via `PUT
+/// /repos/{owner}/{repo}/contents/{path}`
+///
+/// When updating an existing file, the blob SHA of the version being replaced
+/// must be supplied with [`PutContents::with_sha()`].  If the file has since
+/// been changed by someone else, the request fails with an error whose
+/// [`ErrorClass`] is [`ShaMismatch`][ErrorClass::ShaMismatch].  Use
+/// [`PutContents::put_with_merge()`] to automatically fetch the latest
+/// version of the file and retry in such cases.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PutContents {
+    owner: String,
+    repo: String,
+    path: String,
+    message: String,
+    content: Vec<u8>,
+    sha: Option<String>,
+    branch: Option<String>,
+}
+
+impl PutContents {
+    pub fn new<S, T, U, V>(owner: S, repo: T, path: U, message: V, content: Vec<u8>) -> PutContents
+    where
+        S: Into<String>,
+        T: Into<String>,
+        U: Into<String>,
+        V: Into<String>,
+    {
+        PutContents {
+            owner: owner.into(),
+            repo: repo.into(),
+            path: path.into(),
+            message: message.into(),
+            content,
+            sha: None,
+            branch: None,
+        }
+    }
+
+    /// Set the blob SHA of the file being replaced
+    pub fn with_sha<S: Into<String>>(mut self, sha: S) -> Self {
+        self.sha = Some(sha.into());
+        self
+    }
+
+    /// Commit to the given branch instead of the repository's default branch
+    pub fn with_branch<S: Into<String>>(mut self, branch: S) -> Self {
+        self.branch = Some(branch.into());
+        self
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    pub fn sha(&self) -> Option<&str> {
+        self.sha.as_deref()
+    }
+
+    /// Perform the request, and, if it fails because the file was changed
+    /// since `sha` was obtained, fetch the current version of the file, pass
+    /// it and the content being written to `merge`, and retry with the
+    /// content returned by `merge`.
+    ///
+    /// If `merge` returns `None`, or if the request fails for any other
+    /// reason, or if `max_attempts` requests have been made, the error is
+    /// returned.
+    pub fn put_with_merge<B, F>(
+        mut self,
+        client: &Client<B>,
+        max_attempts: usize,
+        mut merge: F,
+    ) -> Result<ContentsUpdate, Error<B::Error>>
+    where
+        B: Backend,
+        F: FnMut(&FileContents, &[u8]) -> Option<Vec<u8>>,
+    {
+        let mut attempts = 0;
+        loop {
+            let e = match client.request(&self) {
+                Ok(r) => return Ok(r),
+                Err(e) => e,
+            };
+            attempts += 1;
+            if attempts >= max_attempts || e.class() != Some(ErrorClass::ShaMismatch) {
+                return Err(e);
+            }
+            let current = client.request(self.refetch())?;
+            if !self.merge(current, &mut merge) {
+                return Err(e);
+            }
+        }
+    }
+
+    /// Asynchronously perform the request, merging and retrying on SHA
+    /// mismatches.  See [`PutContents::put_with_merge()`] for more
+    /// information.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn put_with_merge_async<B, F>(
+        mut self,
+        client: &AsyncClient<B>,
+        max_attempts: usize,
+        mut merge: F,
+    ) -> Result<ContentsUpdate, Error<B::Error>>
+    where
+        B: AsyncBackend + Send + Sync,
+        F: FnMut(&FileContents, &[u8]) -> Option<Vec<u8>>,
+    {
+        let mut attempts = 0;
+        loop {
+            let e = match client.request(&self).await {
+                Ok(r) => return Ok(r),
+                Err(e) => e,
+            };
+            attempts += 1;
+            if attempts >= max_attempts || e.class() != Some(ErrorClass::ShaMismatch) {
+                return Err(e);
+            }
+            let current = client.request(self.refetch()).await?;
+            if !self.merge(current, &mut merge) {
+                return Err(e);
+            }
+        }
+    }
+
+    /// [Private] Construct a request for the current version of the file
+    fn refetch(&self) -> GetContents {
+        GetContents {
+            owner: self.owner.clone(),
+            repo: self.repo.clone(),
+            path: self.path.clone(),
+            reference: self.branch.clone(),
+        }
+    }
+
+    /// [Private] Update the request to replace `current`, using `merge` to
+    /// compute the new content.  Returns `false` if `merge` gave up.
+    fn merge<F>(&mut self, current: FileContents, merge: &mut F) -> bool
+    where
+        F: FnMut(&FileContents, &[u8]) -> Option<Vec<u8>>,
+    {
+        let Some(content) = merge(&current, &self.content) else {
+            return false;
+        };
+        self.content = content;
+        self.sha = Some(current.sha);
+        true
+    }
+}
+
+impl Request for PutContents {
+    type Output = ContentsUpdate;
+    type Error = CommonError;
+    type Body = JsonBody<serde_json::Value>;
+
+    fn endpoint(&self) -> Endpoint {
+        contents_endpoint(&self.owner, &self.repo, &self.path)
+    }
+
+    fn method(&self) -> Method {
+        Method::Put
+    }
+
+    fn body(&self) -> Self::Body {
+        let mut payload = json!({
+            "message": self.message,
+            "content": STANDARD.encode(&self.content),
+        });
+        if let Some(sha) = self.sha.as_ref() {
+            payload["sha"] = json!(sha);
+        }
+        if let Some(branch) = self.branch.as_ref() {
+            payload["branch"] = json!(branch);
+        }
+        JsonBody::new(payload)
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        JsonResponse::new()
+    }
+}
+
+/// The result of a successful [`PutContents`] request
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ContentsUpdate {
+    pub content: ContentsEntry,
+    pub commit: ContentsCommit,
+}
+
+/// The file written by a [`PutContents`] request
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ContentsEntry {
+    pub path: String,
+
+    /// The new blob SHA of the file
+    pub sha: String,
+}
+
+/// The commit created by a [`PutContents`] request
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ContentsCommit {
+    pub sha: String,
+}
+
+fn contents_endpoint(owner: &str, repo: &str, path: &str) -> Endpoint {
+    ["repos", owner, repo, "contents"]
+        .into_iter()
+        .chain(path.split('/').filter(|s| !s.is_empty()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend};
+    use http::status::StatusCode;
+    use std::sync::Mutex;
+
+    const URL: &str = "https://api.github.com/repos/octocat/hello-world/contents/docs/notes.txt";
+
+    fn json_response(status: StatusCode, body: &serde_json::Value) -> http::Response<Vec<u8>> {
+        http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(body).unwrap())
+            .unwrap()
+    }
+
+    fn put_success(sha: &str) -> http::Response<Vec<u8>> {
+        json_response(
+            StatusCode::OK,
+            &json!({
+                "content": {"path": "docs/notes.txt", "sha": sha},
+                "commit": {"sha": "c0ffee"},
+            }),
+        )
+    }
+
+    #[test]
+    fn merge_on_sha_mismatch() {
+        // The file on the server, as (sha, content)
+        let server = Mutex::new(("v2", b"theirs\n".to_vec()));
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(parts.url.as_str(), URL);
+            let mut server = server.lock().unwrap();
+            if parts.method == Method::Get {
+                return json_response(
+                    StatusCode::OK,
+                    &json!({
+                        "path": "docs/notes.txt",
+                        "sha": server.0,
+                        "content": STANDARD.encode(&server.1),
+                        "encoding": "base64",
+                    }),
+                );
+            }
+            let payload = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+            if payload["sha"] != server.0 {
+                return json_response(
+                    StatusCode::CONFLICT,
+                    &json!({"message": "docs/notes.txt does not match v1"}),
+                );
+            }
+            let content = STANDARD
+                .decode(payload["content"].as_str().unwrap())
+                .unwrap();
+            *server = ("v3", content);
+            put_success("v3")
+        }));
+        let r = PutContents::new(
+            "octocat",
+            "hello-world",
+            "docs/notes.txt",
+            "Add note",
+            b"mine\n".to_vec(),
+        )
+        .with_sha("v1")
+        .put_with_merge(&client, DEFAULT_MAX_ATTEMPTS, |current, ours| {
+            assert_eq!(current.sha, "v2");
+            let mut merged = current.content.clone();
+            merged.extend_from_slice(ours);
+            Some(merged)
+        })
+        .unwrap();
+        assert_eq!(r.content.sha, "v3");
+        assert_eq!(server.lock().unwrap().1, b"theirs\nmine\n");
+    }
+
+    #[test]
+    fn merge_declined() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            if parts.method == Method::Get {
+                json_response(
+                    StatusCode::OK,
+                    &json!({
+                        "path": "docs/notes.txt",
+                        "sha": "v2",
+                        "content": "dGhlaXJzCg==\n",
+                        "encoding": "base64",
+                    }),
+                )
+            } else {
+                json_response(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    &json!({"message": "Invalid request.\n\n\"sha\" wasn't supplied."}),
+                )
+            }
+        }));
+        let e = PutContents::new(
+            "octocat",
+            "hello-world",
+            "docs/notes.txt",
+            "Add note",
+            b"mine\n".to_vec(),
+        )
+        .put_with_merge(&client, DEFAULT_MAX_ATTEMPTS, |current, _| {
+            assert_eq!(current.content, b"theirs\n");
+            None
+        })
+        .unwrap_err();
+        assert_eq!(e.class(), Some(ErrorClass::ShaMismatch));
+    }
+
+    #[test]
+    fn unrelated_conflict_not_classified() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|_, _| {
+            json_response(StatusCode::CONFLICT, &json!({"message": "Conflict"}))
+        }));
+        let e = client
+            .request(GetContents::new("octocat", "hello-world", "docs/notes.txt"))
+            .unwrap_err();
+        assert_eq!(e.class(), None);
+    }
+}
@@ -0,0 +1,629 @@
+//! Resumable downloads of large resources
+use crate::{
+    Endpoint, EntityTag, HeaderMapExt, Method,
+    client::{Backend, Client},
+    errors::{CommonError, Error, ErrorPayload, ParseResponseError},
+    parser::ResponseParser,
+    request::Request,
+    response::ResponseParts,
+};
+use http::{
+    header::{HeaderMap, HeaderValue},
+    status::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use thiserror::Error;
+
+#[cfg(feature = "tokio")]
+use crate::client::tokio::{AsyncBackend, AsyncClient};
+
+/// The default maximum number of attempts made by a [`ResumableDownload`]
+pub const DEFAULT_MAX_ATTEMPTS: usize = 5;
+
+/// A download of a resource to a file that picks up where it left off after a
+/// network failure
+///
+/// Each attempt after the first requests only the remainder of the resource by
+/// sending a `Range` header along with an `If-Range` header containing the
+/// resource's `ETag`.  If the resource changed in the meantime, the server
+/// sends the complete new resource, and the file is rewritten from the start.
+/// Once the download finishes, the size of the file is checked against the
+/// total length reported by the server.
+///
+/// Only failures to send a request or to read a response body are retried;
+/// any other error is returned immediately.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResumableDownload {
+    endpoint: Endpoint,
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    max_attempts: usize,
+}
+
+impl ResumableDownload {
+    pub fn new(endpoint: Endpoint) -> ResumableDownload {
+        ResumableDownload {
+            endpoint,
+            headers: HeaderMap::new(),
+            timeout: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    /// Send the given headers in each request.  Use this to set an `Accept`
+    /// header of `application/octet-stream` when downloading release assets.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Set the timeout for each individual request
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the maximum number of requests to make before giving up.  The
+    /// default is [`DEFAULT_MAX_ATTEMPTS`].
+    pub fn with_max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Download the resource to `path`, overwriting any existing file, and
+    /// return the final state of the download
+    pub fn download<B: Backend>(
+        &self,
+        client: &Client<B>,
+        path: &Path,
+    ) -> Result<DownloadState, DownloadError<B::Error>> {
+        let mut state = DownloadState::default();
+        self.resume(client, path, &mut state)?;
+        Ok(state)
+    }
+
+    /// Continue the download described by `state` into `path`.
+    ///
+    /// `state` is updated as the download progresses, so if this method
+    /// returns an error, it can be called again later with the same `state`
+    /// (e.g., after restoring it from disk) to continue where it left off.
+    /// Passing a default `DownloadState` starts the download from the
+    /// beginning.
+    pub fn resume<B: Backend>(
+        &self,
+        client: &Client<B>,
+        path: &Path,
+        state: &mut DownloadState,
+    ) -> Result<(), DownloadError<B::Error>> {
+        let mut attempts = 0;
+        loop {
+            let Some(req) = self.next_request(path, state)? else {
+                return state.verify(path);
+            };
+            let shared = Arc::clone(&req.state);
+            let r = client.request(req);
+            *state = shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            attempts += 1;
+            if let Some(r) = handle_result(r, path, state, attempts >= self.max_attempts) {
+                return r;
+            }
+        }
+    }
+
+    /// Download the resource to `path` asynchronously, overwriting any
+    /// existing file, and return the final state of the download
+    ///
+    /// Note that the file is written to using blocking I/O.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn download_async<B: AsyncBackend + Send + Sync>(
+        &self,
+        client: &AsyncClient<B>,
+        path: &Path,
+    ) -> Result<DownloadState, DownloadError<B::Error>> {
+        let mut state = DownloadState::default();
+        self.resume_async(client, path, &mut state).await?;
+        Ok(state)
+    }
+
+    /// Asynchronously continue the download described by `state` into
+    /// `path`.  See [`ResumableDownload::resume()`] for more information.
+    ///
+    /// Note that the file is written to using blocking I/O.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn resume_async<B: AsyncBackend + Send + Sync>(
+        &self,
+        client: &AsyncClient<B>,
+        path: &Path,
+        state: &mut DownloadState,
+    ) -> Result<(), DownloadError<B::Error>> {
+        let mut attempts = 0;
+        loop {
+            let Some(req) = self.next_request(path, state)? else {
+                return state.verify(path);
+            };
+            let shared = Arc::clone(&req.state);
+            let r = client.request(req).await;
+            *state = shared
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            attempts += 1;
+            if let Some(r) = handle_result(r, path, state, attempts >= self.max_attempts) {
+                return r;
+            }
+        }
+    }
+
+    /// [Private] Construct the request for the next attempt, or return `None`
+    /// if the download is already complete
+    fn next_request<BE>(
+        &self,
+        path: &Path,
+        state: &mut DownloadState,
+    ) -> Result<Option<RangeRequest>, DownloadError<BE>> {
+        if state.etag.is_some() {
+            state.received = match std::fs::metadata(path) {
+                Ok(md) => md.len(),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+                Err(e) => return Err(DownloadError::Io(e)),
+            };
+        } else {
+            // Without a validator, a partial file cannot be safely resumed.
+            state.received = 0;
+        }
+        if state.total.is_some_and(|total| state.received >= total) {
+            return Ok(None);
+        }
+        Ok(Some(RangeRequest {
+            endpoint: self.endpoint.clone(),
+            headers: self.headers.clone(),
+            timeout: self.timeout,
+            path: path.to_owned(),
+            state: Arc::new(Mutex::new(state.clone())),
+        }))
+    }
+}
+
+/// [Private] Process the result of a download attempt.  Returns `Some` if the
+/// download should stop with the given result.
+fn handle_result<BE>(
+    r: Result<(), Error<BE>>,
+    path: &Path,
+    state: &mut DownloadState,
+    last_attempt: bool,
+) -> Option<Result<(), DownloadError<BE>>> {
+    let e = match r {
+        Ok(()) => {
+            // If the server didn't tell us the total length, the response that
+            // just completed is all there is.
+            state.total.get_or_insert(state.received);
+            // Only a body that ended short of the reported length is worth
+            // another attempt; in particular, a complete download without an
+            // `ETag` must not be fetched again from the start.
+            return (state.is_complete() || last_attempt).then(|| state.verify(path));
+        }
+        Err(e) => e,
+    };
+    match e.payload_ref() {
+        ErrorPayload::Status(resp)
+            if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE
+                && state.total == Some(state.received) =>
+        {
+            Some(state.verify(path))
+        }
+        ErrorPayload::Send(_) | ErrorPayload::ParseResponse(ParseResponseError::Read(_))
+            if !last_attempt =>
+        {
+            None
+        }
+        _ => Some(Err(DownloadError::Request(e))),
+    }
+}
+
+/// The progress of a [`ResumableDownload`]
+///
+/// This can be serialized in order to resume an interrupted download in a
+/// later process.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DownloadState {
+    /// The strong `ETag` of the resource, if the server provided one.  A
+    /// download can only be resumed if this is set.
+    pub etag: Option<EntityTag>,
+
+    /// The number of bytes of the resource written to the file so far
+    pub received: u64,
+
+    /// The total size of the resource in bytes, if known
+    pub total: Option<u64>,
+}
+
+impl DownloadState {
+    /// Returns true if the total size is known and all of the bytes have been
+    /// received
+    pub fn is_complete(&self) -> bool {
+        self.total == Some(self.received)
+    }
+
+    /// [Private] Check that the downloaded file has the expected size
+    fn verify<BE>(&self, path: &Path) -> Result<(), DownloadError<BE>> {
+        let actual = std::fs::metadata(path).map_err(DownloadError::Io)?.len();
+        match self.total {
+            Some(expected) if expected != actual => {
+                Err(DownloadError::LengthMismatch { expected, actual })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Error returned by [`ResumableDownload`] methods
+///
+/// New variants may be added in future versions.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DownloadError<BackendError> {
+    /// A request failed, either with a non-retryable error or on the final
+    /// attempt
+    #[error(transparent)]
+    Request(Error<BackendError>),
+
+    /// An I/O error occurred while inspecting the downloaded file
+    #[error("failed to inspect downloaded file")]
+    Io(#[source] std::io::Error),
+
+    /// The size of the downloaded file did not match the size reported by the
+    /// server
+    #[error("downloaded file is {actual} bytes long, but server reported {expected} bytes")]
+    LengthMismatch { expected: u64, actual: u64 },
+}
+
+/// The value of a `Content-Range` header in a `bytes` unit
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ContentRange {
+    /// The first and last byte positions (inclusive) of the range, or `None`
+    /// if the header was of the form `bytes */{total}`
+    pub range: Option<(u64, u64)>,
+
+    /// The total length of the resource, if known
+    pub total: Option<u64>,
+}
+
+impl std::str::FromStr for ContentRange {
+    type Err = ParseContentRangeError;
+
+    fn from_str(s: &str) -> Result<ContentRange, ParseContentRangeError> {
+        let rest = s
+            .trim()
+            .strip_prefix("bytes ")
+            .ok_or(ParseContentRangeError)?;
+        let (range, total) = rest.split_once('/').ok_or(ParseContentRangeError)?;
+        let total = match total {
+            "*" => None,
+            n => Some(n.parse::<u64>().map_err(|_| ParseContentRangeError)?),
+        };
+        let range = match range {
+            "*" => None,
+            r => {
+                let (first, last) = r.split_once('-').ok_or(ParseContentRangeError)?;
+                let first = first.parse::<u64>().map_err(|_| ParseContentRangeError)?;
+                let last = last.parse::<u64>().map_err(|_| ParseContentRangeError)?;
+                if first > last {
+                    return Err(ParseContentRangeError);
+                }
+                Some((first, last))
+            }
+        };
+        if range.is_none() && total.is_none() {
+            return Err(ParseContentRangeError);
+        }
+        Ok(ContentRange { range, total })
+    }
+}
+
+/// Error returned by [`ContentRange`]'s `FromStr` implementation
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, PartialEq)]
+#[error("invalid Content-Range value")]
+pub struct ParseContentRangeError;
+
+/// [Private] A GET request for the remainder of a resource
+#[derive(Debug)]
+struct RangeRequest {
+    endpoint: Endpoint,
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    path: PathBuf,
+    state: Arc<Mutex<DownloadState>>,
+}
+
+impl Request for RangeRequest {
+    type Output = ();
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        self.endpoint.clone()
+    }
+
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(etag) = state.etag.clone().filter(|_| state.received > 0)
+            && let Ok(range) = HeaderValue::from_str(&format!("bytes={}-", state.received))
+        {
+            headers.insert(http::header::RANGE, range);
+            headers.insert(http::header::IF_RANGE, etag.into());
+        }
+        headers
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        RangeWriter {
+            path: self.path.clone(),
+            state: Arc::clone(&self.state),
+            file: None,
+            err: None,
+        }
+    }
+}
+
+/// [Private] Parser that writes a full or partial response body to a file
+#[derive(Debug)]
+struct RangeWriter {
+    path: PathBuf,
+    state: Arc<Mutex<DownloadState>>,
+    file: Option<File>,
+    err: Option<std::io::Error>,
+}
+
+impl RangeWriter {
+    fn open(&self, parts: &ResponseParts, state: &mut DownloadState) -> std::io::Result<File> {
+        let headers = parts.headers();
+        // `If-Range` requires a strong comparison, so weak tags are useless
+        let etag = headers.etag().filter(EntityTag::is_strong);
+        let range = headers
+            .get(http::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<ContentRange>().ok());
+        if parts.status() == StatusCode::PARTIAL_CONTENT && state.received > 0 {
+            let Some(ContentRange {
+                range: Some((first, _)),
+                total,
+            }) = range
+            else {
+                return Err(std::io::Error::other(
+                    "partial response lacked a valid Content-Range header",
+                ));
+            };
+            if first != state.received {
+                return Err(std::io::Error::other(format!(
+                    "server resumed download at byte {first} instead of byte {}",
+                    state.received
+                )));
+            }
+            let mut fp = OpenOptions::new().write(true).open(&self.path)?;
+            fp.set_len(first)?;
+            fp.seek(SeekFrom::Start(first))?;
+            state.total = total;
+            Ok(fp)
+        } else {
+            let fp = File::create(&self.path)?;
+            state.received = 0;
+            state.total = headers.content_length();
+            state.etag = etag;
+            Ok(fp)
+        }
+    }
+}
+
+impl ResponseParser for RangeWriter {
+    type Output = ();
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        let state = Arc::clone(&self.state);
+        let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+        match self.open(parts, &mut state) {
+            Ok(fp) => self.file = Some(fp),
+            Err(e) => self.err = Some(e),
+        }
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        if self.err.is_none()
+            && let Some(fp) = self.file.as_mut()
+        {
+            match fp.write_all(buf) {
+                Ok(()) => {
+                    let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+                    state.received += buf.len() as u64;
+                }
+                Err(e) => self.err = Some(e),
+            }
+        }
+    }
+
+    fn end(mut self) -> Result<Self::Output, Self::Error> {
+        if let Some(e) = self.err.take() {
+            return Err(e.into());
+        }
+        if let Some(mut fp) = self.file.take() {
+            fp.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend};
+    use rstest::rstest;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[rstest]
+    #[case("bytes 0-499/1234", Some((0, 499)), Some(1234))]
+    #[case("bytes 500-1233/1234", Some((500, 1233)), Some(1234))]
+    #[case("bytes 500-1233/*", Some((500, 1233)), None)]
+    #[case("bytes */1234", None, Some(1234))]
+    fn parse_content_range(
+        #[case] s: &str,
+        #[case] range: Option<(u64, u64)>,
+        #[case] total: Option<u64>,
+    ) {
+        assert_eq!(s.parse::<ContentRange>(), Ok(ContentRange { range, total }));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("bytes */*")]
+    #[case("bytes 500-100/1234")]
+    #[case("items 0-1/2")]
+    #[case("bytes 0-499")]
+    fn parse_bad_content_range(#[case] s: &str) {
+        assert_eq!(s.parse::<ContentRange>(), Err(ParseContentRangeError));
+    }
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ghreq-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn resume_after_failure() {
+        const CONTENT: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        let calls = AtomicUsize::new(0);
+        let backend = FnBackend::new(|parts, _| {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            if call == 0 {
+                assert!(!parts.headers.contains_key(http::header::RANGE));
+                // Claim the full length but only send part of it
+                http::Response::builder()
+                    .header(http::header::ETAG, "\"abc123\"")
+                    .header(http::header::CONTENT_LENGTH, CONTENT.len())
+                    .body(CONTENT[..10].to_vec())
+                    .unwrap()
+            } else {
+                assert_eq!(parts.headers[http::header::RANGE], "bytes=10-");
+                assert_eq!(parts.headers[http::header::IF_RANGE], "\"abc123\"");
+                http::Response::builder()
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(http::header::ETAG, "\"abc123\"")
+                    .header(
+                        http::header::CONTENT_RANGE,
+                        format!("bytes 10-{}/{}", CONTENT.len() - 1, CONTENT.len()),
+                    )
+                    .body(CONTENT[10..].to_vec())
+                    .unwrap()
+            }
+        });
+        let client = ClientConfig::new().with_backend(backend);
+        let path = scratch_path("resume-after-failure");
+        let state = ResumableDownload::new(Endpoint::from_iter(["asset"]))
+            .download(&client, &path)
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(state.etag, Some(EntityTag::strong("abc123").unwrap()));
+        assert!(state.is_complete());
+        assert_eq!(std::fs::read(&path).unwrap(), CONTENT);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(5))]
+    fn complete_without_etag(#[case] content_length: Option<usize>) {
+        let calls = AtomicUsize::new(0);
+        let backend = FnBackend::new(|_, _| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            let mut resp = http::Response::builder();
+            if let Some(len) = content_length {
+                resp = resp.header(http::header::CONTENT_LENGTH, len);
+            }
+            resp.body(b"hello".to_vec()).unwrap()
+        });
+        let client = ClientConfig::new().with_backend(backend);
+        let path = scratch_path(&format!("complete-without-etag-{content_length:?}"));
+        let state = ResumableDownload::new(Endpoint::from_iter(["asset"]))
+            .download(&client, &path)
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(state.etag, None);
+        assert_eq!(state.total, Some(5));
+        assert!(state.is_complete());
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn give_up_on_short_responses() {
+        let calls = AtomicUsize::new(0);
+        let backend = FnBackend::new(|_, _| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            http::Response::builder()
+                .header(http::header::CONTENT_LENGTH, 100)
+                .body(b"short".to_vec())
+                .unwrap()
+        });
+        let client = ClientConfig::new().with_backend(backend);
+        let path = scratch_path("give-up-on-short-responses");
+        let r = ResumableDownload::new(Endpoint::from_iter(["asset"]))
+            .with_max_attempts(3)
+            .download(&client, &path);
+        assert!(matches!(
+            r,
+            Err(DownloadError::LengthMismatch {
+                expected: 100,
+                actual: 5
+            })
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn resource_changed() {
+        let backend = FnBackend::new(|parts, _| {
+            assert_eq!(parts.headers[http::header::RANGE], "bytes=5-");
+            http::Response::builder()
+                .header(http::header::ETAG, "\"new\"")
+                .body(b"brand new content".to_vec())
+                .unwrap()
+        });
+        let client = ClientConfig::new().with_backend(backend);
+        let path = scratch_path("resource-changed");
+        std::fs::write(&path, b"stale").unwrap();
+        let mut state = DownloadState {
+            etag: Some(EntityTag::strong("old").unwrap()),
+            received: 5,
+            total: Some(10),
+        };
+        ResumableDownload::new(Endpoint::from_iter(["asset"]))
+            .resume(&client, &path, &mut state)
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"brand new content");
+        assert_eq!(state.etag, Some(EntityTag::strong("new").unwrap()));
+        assert!(state.is_complete());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use serde_json::Value;
+use std::fmt;
+
+/// [Private] Substrings of JSON object keys whose values are redacted in a
+/// [`CapturedBody`]
+const SENSITIVE_KEYS: [&str; 6] = [
+    "password",
+    "secret",
+    "token",
+    "private_key",
+    "encrypted_value",
+    "credential",
+];
+
+/// A copy of the body of a request, retained for diagnosing failed requests
+///
+/// Request bodies are only captured if enabled with
+/// [`ClientConfig::with_request_body_capture()`][crate::client::ClientConfig::with_request_body_capture]
+/// and if the body is held in memory (see
+/// [`RequestBody::in_memory()`][crate::request::RequestBody::in_memory]).
+///
+/// If the body is valid JSON, the values of any object fields whose names
+/// contain `password`, `secret`, `token`, `private_key`, `encrypted_value`,
+/// or `credential` (case-insensitively) are replaced with the string
+/// `"<redacted>"`, and the JSON is re-serialized in compact form.  Other
+/// bodies are captured as-is.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CapturedBody(Vec<u8>);
+
+impl CapturedBody {
+    /// Capture the given body, redacting it if it is JSON
+    pub fn new(body: &[u8]) -> CapturedBody {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut value) => {
+                redact(&mut value);
+                CapturedBody(value.to_string().into_bytes())
+            }
+            Err(_) => CapturedBody(body.to_vec()),
+        }
+    }
+
+    /// Returns the captured bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the captured body as a string if it is valid UTF-8
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+}
+
+/// The captured body is displayed as text if it is valid UTF-8 and as a byte
+/// count otherwise.
+impl fmt::Display for CapturedBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_str() {
+            Some(s) => write!(f, "{s}"),
+            None => write!(f, "<binary data, {} bytes>", self.0.len()),
+        }
+    }
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SENSITIVE_KEYS.iter().any(|&s| key.contains(s)) {
+                    *v = Value::String(String::from("<redacted>"));
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_json() {
+        let body = CapturedBody::new(
+            br#"{"name": "ci", "config": {"url": "https://example.com", "Secret": "hunter2"}, "tokens": ["a", "b"], "users": [{"password": "x"}]}"#,
+        );
+        assert_eq!(
+            body.as_str(),
+            Some(
+                r#"{"config":{"Secret":"<redacted>","url":"https://example.com"},"name":"ci","tokens":"<redacted>","users":[{"password":"<redacted>"}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn non_json() {
+        let body = CapturedBody::new(b"password=hunter2");
+        assert_eq!(body.as_bytes(), b"password=hunter2");
+        assert_eq!(body.to_string(), "password=hunter2");
+        let body = CapturedBody::new(b"\xFF\xFE\x00");
+        assert_eq!(body.as_str(), None);
+        assert_eq!(body.to_string(), "<binary data, 3 bytes>");
+    }
+}
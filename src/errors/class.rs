@@ -0,0 +1,62 @@
+use super::{ErrorBody, ErrorResponse};
+use crate::Method;
+use http::status::StatusCode;
+
+/// A classification of error responses that callers commonly need to handle
+/// specially
+///
+/// New classes may be added in future versions.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorClass {
+    /// The server responded with 412 Precondition Failed, indicating that a
+    /// conditional request's precondition (e.g., an `If-Match` header) did not
+    /// hold because the resource was modified since it was last fetched
+    PreconditionFailed,
+
+    /// A `PUT` request to the repository contents API failed because the
+    /// supplied blob SHA was missing or did not match the file's current SHA
+    /// (reported by GitHub as either 409 Conflict or 422 Unprocessable Entity)
+    ShaMismatch,
+}
+
+impl ErrorClass {
+    /// Classify an error response.  Returns `None` if the response does not
+    /// fall into any of the known classes.
+    pub fn of(resp: &ErrorResponse) -> Option<ErrorClass> {
+        match resp.status() {
+            StatusCode::PRECONDITION_FAILED => Some(ErrorClass::PreconditionFailed),
+            StatusCode::CONFLICT if is_contents_put(resp) => Some(ErrorClass::ShaMismatch),
+            StatusCode::UNPROCESSABLE_ENTITY
+                if is_contents_put(resp) && message_mentions_sha(resp.body_ref()) =>
+            {
+                Some(ErrorClass::ShaMismatch)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if `resp` is a response to a `PUT
+/// /repos/{owner}/{repo}/contents/{path}` request
+fn is_contents_put(resp: &ErrorResponse) -> bool {
+    resp.method() == Method::Put
+        && resp
+            .initial_url()
+            .as_url()
+            .path_segments()
+            .is_some_and(|segments| {
+                let segments = segments.collect::<Vec<_>>();
+                segments.len() > 4 && segments[0] == "repos" && segments[3] == "contents"
+            })
+}
+
+fn message_mentions_sha(body: &ErrorBody) -> bool {
+    let ErrorBody::Json(value) = body else {
+        return false;
+    };
+    value
+        .get("message")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|msg| msg.contains("sha"))
+}
@@ -0,0 +1,67 @@
+use crate::HttpUrl;
+use http::StatusCode;
+use std::fmt;
+
+/// Details of the response that was being parsed when a
+/// [`ParseResponse`][super::ErrorPayload::ParseResponse] error occurred
+///
+/// Parse errors such as a bare `serde_json::Error` say nothing about the
+/// response that caused them.  When
+/// [`ClientConfig::with_parse_error_context()`][crate::client::ClientConfig::with_parse_error_context]
+/// is enabled, the client attaches a `ParseErrorContext` to every such error,
+/// where it can be retrieved with
+/// [`Error::parse_context()`][super::Error::parse_context] and is included in
+/// the error's `Display` output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseErrorContext {
+    url: HttpUrl,
+    status: StatusCode,
+    content_type: Option<String>,
+    body_length: u64,
+}
+
+impl ParseErrorContext {
+    pub(crate) fn new(
+        url: HttpUrl,
+        status: StatusCode,
+        content_type: Option<String>,
+        body_length: u64,
+    ) -> ParseErrorContext {
+        ParseErrorContext {
+            url,
+            status,
+            content_type,
+            body_length,
+        }
+    }
+
+    /// Returns the URL of the response, after following any redirects
+    pub fn url(&self) -> &HttpUrl {
+        &self.url
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the value of the response's `Content-Type` header, if any
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Returns the number of bytes of the response body that were read
+    /// before the error occurred
+    pub fn body_length(&self) -> u64 {
+        self.body_length
+    }
+}
+
+impl fmt::Display for ParseErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} response from {}", self.status, self.url)?;
+        if let Some(ct) = &self.content_type {
+            write!(f, ", Content-Type {ct}")?;
+        }
+        write!(f, ", {} body bytes read", self.body_length)
+    }
+}
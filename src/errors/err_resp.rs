@@ -1,12 +1,16 @@
-use super::CommonError;
+use super::{CommonError, ErrorClass};
 use crate::{
     HeaderMapExt, HttpUrl, Method,
     parser::ResponseParser,
     response::{Response, ResponseParts},
+    util::json_from_slice,
 };
 use std::borrow::Cow;
 use thiserror::Error;
 
+/// An HTTP response with a 4xx or 5xx status, as reported in
+/// [`ErrorPayload::Status`][super::ErrorPayload::Status] and as returned by
+/// [`ErrorResponseParser`]
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
 #[error("server responded with status {}", self.status())]
 pub struct ErrorResponse(Response<ErrorBody>);
@@ -51,6 +55,12 @@ impl ErrorResponse {
     pub fn pretty_text(&self) -> Option<Cow<'_, str>> {
         self.body_ref().pretty_text()
     }
+
+    /// Classify the response.  Returns `None` if the response does not fall
+    /// into any of the known classes.
+    pub fn class(&self) -> Option<ErrorClass> {
+        ErrorClass::of(self)
+    }
 }
 
 impl From<Response<ErrorBody>> for ErrorResponse {
@@ -65,16 +75,53 @@ impl From<ErrorResponse> for Response<ErrorBody> {
     }
 }
 
+/// The body of an [`ErrorResponse`]
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub enum ErrorBody {
+    /// The body was empty or consisted entirely of whitespace
     #[default]
     Empty,
+
+    /// The body was not JSON and was not valid UTF-8
     Bytes(Vec<u8>),
+
+    /// The body was not JSON but was valid UTF-8
     Text(String),
+
+    /// The response's `Content-Type` was JSON, and the body was parsed as
+    /// such
     Json(serde_json::Value),
 }
 
 impl ErrorBody {
+    /// Construct an `ErrorBody` from the complete body of a response with the
+    /// given parts.
+    ///
+    /// If the response's `Content-Type` is JSON, the body is parsed as JSON,
+    /// and an error is returned if this fails.  Otherwise, the body is
+    /// [`ErrorBody::Empty`] if it consists entirely of whitespace,
+    /// [`ErrorBody::Text`] if it is valid UTF-8, or [`ErrorBody::Bytes`] if it
+    /// is not.
+    pub fn from_parts_and_bytes(
+        parts: &ResponseParts,
+        mut body: Vec<u8>,
+    ) -> Result<ErrorBody, CommonError> {
+        if parts.headers().content_type_is_json() {
+            json_from_slice::<serde_json::Value>(&mut body).map(ErrorBody::Json)
+        } else {
+            match String::from_utf8(body) {
+                Ok(s) => {
+                    if s.chars().all(char::is_whitespace) {
+                        Ok(ErrorBody::Empty)
+                    } else {
+                        Ok(ErrorBody::Text(s))
+                    }
+                }
+                Err(e) => Ok(ErrorBody::Bytes(e.into_bytes())),
+            }
+        }
+    }
+
     pub fn pretty_text(&self) -> Option<Cow<'_, str>> {
         match self {
             ErrorBody::Empty => None,
@@ -90,6 +137,41 @@ impl ErrorBody {
     }
 }
 
+/// A [`ResponseParser`] that parses any response into an [`ErrorResponse`],
+/// with the body decoded as by [`ErrorBody::from_parts_and_bytes()`]
+///
+/// This is the parser that clients use to construct the
+/// [`ErrorPayload::Status`][super::ErrorPayload::Status] errors for 4xx and
+/// 5xx responses.  It can also be used by custom
+/// [`ResponseParser`] implementations or error handling code that needs to
+/// decode an error response in the same manner, e.g., for a request whose
+/// parser treats certain error statuses as expected outcomes.
+///
+/// # Example
+///
+/// ```
+/// use ghreq::{
+///     Method,
+///     errors::{ErrorBody, ErrorResponseParser},
+///     parser::ResponseParserExt,
+///     response::{Response, ResponseParts},
+/// };
+/// use http::{StatusCode, header::CONTENT_TYPE};
+///
+/// let parts = ResponseParts::new(
+///     "https://api.github.com/repos/octocat/hello-world/merges".parse().unwrap(),
+///     Method::Post,
+///     StatusCode::CONFLICT,
+/// )
+/// .with_header(CONTENT_TYPE, "application/json".parse().unwrap());
+/// let response = Response::from_parts(parts, &br#"{"message": "Merge conflict"}"#[..]);
+/// let err_resp = ErrorResponseParser::new().parse_response(response).unwrap();
+/// assert_eq!(err_resp.status(), StatusCode::CONFLICT);
+/// let ErrorBody::Json(value) = err_resp.body_ref() else {
+///     panic!("Body was not parsed as JSON");
+/// };
+/// assert_eq!(value["message"], "Merge conflict");
+/// ```
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct ErrorResponseParser {
     parts: Option<ResponseParts>,
@@ -117,23 +199,83 @@ impl ResponseParser for ErrorResponseParser {
 
     fn end(self) -> Result<Self::Output, Self::Error> {
         let parts = self.parts.expect("handle_parts() should have been called");
-        let body = if parts.headers().content_type_is_json() {
-            match serde_json::from_slice::<serde_json::Value>(&self.body) {
-                Ok(value) => ErrorBody::Json(value),
-                Err(e) => return Err(e.into()),
-            }
-        } else {
-            match String::from_utf8(self.body) {
-                Ok(s) => {
-                    if s.chars().all(char::is_whitespace) {
-                        ErrorBody::Empty
-                    } else {
-                        ErrorBody::Text(s)
-                    }
-                }
-                Err(e) => ErrorBody::Bytes(e.into_bytes()),
-            }
-        };
+        let body = ErrorBody::from_parts_and_bytes(&parts, self.body)?;
         Ok(ErrorResponse(Response::from_parts(parts, body)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{StatusCode, header::CONTENT_TYPE};
+    use rstest::rstest;
+
+    fn parts(content_type: &str) -> ResponseParts {
+        let url = "https://api.github.com/repos/octocat/hello-world/merges"
+            .parse::<HttpUrl>()
+            .unwrap();
+        ResponseParts::new(url, Method::Post, StatusCode::CONFLICT)
+            .with_header(CONTENT_TYPE, content_type.parse().unwrap())
+    }
+
+    #[rstest]
+    #[case("application/json", b"{\"message\": \"Merge conflict\"}", ErrorBody::Json(serde_json::json!({"message": "Merge conflict"})))]
+    #[case(
+        "text/plain",
+        b"Merge conflict",
+        ErrorBody::Text(String::from("Merge conflict"))
+    )]
+    #[case("text/plain", b" \r\n", ErrorBody::Empty)]
+    #[case("application/octet-stream", b"\xFF\x00", ErrorBody::Bytes(vec![0xFF, 0]))]
+    fn from_parts_and_bytes(
+        #[case] content_type: &str,
+        #[case] body: &[u8],
+        #[case] expected: ErrorBody,
+    ) {
+        let parts = parts(content_type);
+        assert_eq!(
+            ErrorBody::from_parts_and_bytes(&parts, body.to_vec()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn invalid_json() {
+        let parts = parts("application/json");
+        assert!(ErrorBody::from_parts_and_bytes(&parts, b"Merge conflict".to_vec()).is_err());
+    }
+
+    #[cfg(feature = "simd-json")]
+    #[test]
+    fn parse_response_simd_json() {
+        let mut parser = ErrorResponseParser::new();
+        parser.handle_parts(&parts("application/json"));
+        parser.handle_bytes(
+            br#"{"message": "Validation Failed", "errors": [{"code": "missing\u005ffield"}]}"#,
+        );
+        let resp = parser.end().unwrap();
+        assert_eq!(
+            resp.body_ref(),
+            &ErrorBody::Json(serde_json::json!({
+                "message": "Validation Failed",
+                "errors": [{"code": "missing_field"}],
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_response() {
+        let parts = parts("application/json; charset=utf-8");
+        let mut parser = ErrorResponseParser::new();
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"{\"message\": ");
+        parser.handle_bytes(b"\"Merge conflict\"}");
+        let resp = parser.end().unwrap();
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert_eq!(resp.method(), Method::Post);
+        assert_eq!(
+            resp.pretty_text().as_deref(),
+            Some("{\n  \"message\": \"Merge conflict\"\n}")
+        );
+    }
+}
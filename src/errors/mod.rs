@@ -1,12 +1,25 @@
+mod captured;
+mod class;
+mod context;
 mod err_resp;
+mod redirect;
+pub use self::captured::*;
+pub use self::class::*;
+pub use self::context::*;
 pub use self::err_resp::*;
-use crate::{HttpUrl, Method};
+pub use self::redirect::*;
+use crate::{EndpointError, HttpUrl, Method};
 use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt;
 use thiserror::Error;
 
+/// Errors that can occur while preparing requests and parsing responses
+/// with the parsers provided by this crate
+///
+/// New variants may be added in future versions.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum CommonError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -16,30 +29,116 @@ pub enum CommonError {
 
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+
+    /// A JSON response body could not be deserialized by `simd-json`.
+    ///
+    /// With the `simd-json` feature enabled, this takes the place of
+    /// [`CommonError::Json`] for the buffering JSON parsers;
+    /// [`simd_json::Error::is_syntax()`], [`is_data()`][simd_json::Error::is_data],
+    /// and [`index()`][simd_json::Error::index] describe the failure.
+    #[cfg(feature = "simd-json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "simd-json")))]
+    #[error(transparent)]
+    SimdJson(simd_json::Error),
+
+    /// A request's [`query()`][crate::request::Request::query] could not be
+    /// encoded as query parameters
+    #[error("failed to encode query parameters")]
+    Query(#[source] Box<serde_urlencoded::ser::Error>),
+
+    /// The response body was empty (or only whitespace) where a JSON value
+    /// other than `null` was expected
+    #[error("response body is empty")]
+    EmptyBody,
+
+    /// The response body was the JSON value `null` where some other value was
+    /// expected
+    #[error("response body is JSON null")]
+    NullBody,
+
+    /// The response did not have a JSON `Content-Type` where a JSON body was
+    /// required; see [`StrictJsonResponse`][crate::parser::StrictJsonResponse]
+    #[error("expected JSON response, got {}", .content_type.as_deref().unwrap_or("no Content-Type"))]
+    NotJson { content_type: Option<String> },
 }
 
 #[derive(Debug)]
 pub struct Error<BackendError, E = CommonError> {
+    // Boxed in order to keep `Result`s with this error type small
+    request: Box<FailedRequest>,
+    payload: ErrorPayload<BackendError, E>,
+}
+
+/// [Private] The URL, method, and (if captured) body of the request that an
+/// [`Error`] is for, along with the redirect being followed when it failed
+/// and details of the response if parsing it failed
+#[derive(Debug)]
+struct FailedRequest {
     url: HttpUrl,
     method: Method,
-    payload: ErrorPayload<BackendError, E>,
+    body: Option<CapturedBody>,
+    redirect_url: Option<HttpUrl>,
+    parse_context: Option<ParseErrorContext>,
 }
 
 impl<BackendError, E> Error<BackendError, E> {
     pub fn new(url: HttpUrl, method: Method, payload: ErrorPayload<BackendError, E>) -> Self {
         Error {
-            url,
-            method,
+            request: Box::new(FailedRequest {
+                url,
+                method,
+                body: None,
+                redirect_url: None,
+                parse_context: None,
+            }),
             payload,
         }
     }
 
+    /// Attach a captured copy of the request body to the error
+    pub fn with_request_body(mut self, body: Option<CapturedBody>) -> Self {
+        self.request.body = body;
+        self
+    }
+
+    /// Record that the error occurred while following a redirect to the given
+    /// URL
+    pub fn with_redirect_url(mut self, url: Option<HttpUrl>) -> Self {
+        self.request.redirect_url = url;
+        self
+    }
+
+    /// Attach details of the response whose parsing failed to the error
+    pub fn with_parse_context(mut self, context: Option<ParseErrorContext>) -> Self {
+        self.request.parse_context = context;
+        self
+    }
+
     pub fn url(&self) -> &HttpUrl {
-        &self.url
+        &self.request.url
     }
 
     pub fn method(&self) -> Method {
-        self.method
+        self.request.method.clone()
+    }
+
+    /// Returns the captured body of the failed request, if any; see
+    /// [`ClientConfig::with_request_body_capture()`][crate::client::ClientConfig::with_request_body_capture]
+    pub fn request_body(&self) -> Option<&CapturedBody> {
+        self.request.body.as_ref()
+    }
+
+    /// Returns the URL of the redirect that the client was following when the
+    /// error occurred, if any.  In this case, [`Error::url()`] is still the
+    /// URL of the original request.
+    pub fn redirect_url(&self) -> Option<&HttpUrl> {
+        self.request.redirect_url.as_ref()
+    }
+
+    /// Returns details of the response whose parsing failed, if attached; see
+    /// [`ClientConfig::with_parse_error_context()`][crate::client::ClientConfig::with_parse_error_context]
+    pub fn parse_context(&self) -> Option<&ParseErrorContext> {
+        self.request.parse_context.as_ref()
     }
 
     pub fn payload_ref(&self) -> &ErrorPayload<BackendError, E> {
@@ -57,6 +156,25 @@ impl<BackendError, E> Error<BackendError, E> {
     pub fn pretty_text(&self) -> Option<Cow<'_, str>> {
         self.payload.pretty_text()
     }
+
+    /// Classify the error.  Returns `None` if the error was not caused by an
+    /// error response or if the response does not fall into any of the known
+    /// classes.
+    pub fn class(&self) -> Option<ErrorClass> {
+        self.payload.class()
+    }
+
+    /// Returns the error response if the error was caused by one; see
+    /// [`ErrorPayload::as_status()`]
+    pub fn as_status(&self) -> Option<&ErrorResponse> {
+        self.payload.as_status()
+    }
+
+    /// Returns the backend error if the request could not be sent; see
+    /// [`ErrorPayload::as_send()`]
+    pub fn as_send(&self) -> Option<&BackendError> {
+        self.payload.as_send()
+    }
 }
 
 impl<BackendError: StdError + 'static, E: StdError + 'static> fmt::Display
@@ -65,9 +183,17 @@ impl<BackendError: StdError + 'static, E: StdError + 'static> fmt::Display
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{} request to {} failed: {}",
-            self.method, self.url, self.payload
-        )
+            "{} request to {} failed",
+            self.request.method, self.request.url
+        )?;
+        if let Some(url) = &self.request.redirect_url {
+            write!(f, " while following redirect to {url}")?;
+        }
+        write!(f, ": {}", self.payload)?;
+        if let Some(ctx) = &self.request.parse_context {
+            write!(f, " ({ctx})")?;
+        }
+        Ok(())
     }
 }
 
@@ -77,8 +203,21 @@ impl<BackendError: StdError + 'static, E: StdError + 'static> StdError for Error
     }
 }
 
+/// The cause of an [`Error`][struct@Error]
+///
+/// New variants may be added in future versions; use the `as_*()` methods to
+/// inspect a payload without matching on it exhaustively.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum ErrorPayload<BackendError, E = CommonError> {
+    #[error("invalid endpoint")]
+    InvalidEndpoint(#[source] Box<EndpointError>),
+
+    /// The client's [`AuthProvider`][crate::client::AuthProvider] failed to
+    /// obtain credentials for the request
+    #[error("failed to authenticate request")]
+    Auth(#[source] crate::client::AuthError),
+
     #[error("failed to prepare request")]
     PrepareRequest(#[source] E),
 
@@ -91,21 +230,69 @@ pub enum ErrorPayload<BackendError, E = CommonError> {
     #[error(transparent)]
     Status(Box<ErrorResponse>),
 
+    #[error(transparent)]
+    Redirect(Box<RedirectResponse>),
+
     #[error(transparent)]
     ParseResponse(ParseResponseError<E>),
 }
 
 impl<BackendError, E> ErrorPayload<BackendError, E> {
     pub fn pretty_text(&self) -> Option<Cow<'_, str>> {
+        self.as_status().and_then(ErrorResponse::pretty_text)
+    }
+
+    /// Classify the error.  Returns `None` if the payload is not
+    /// [`ErrorPayload::Status`] or if the response does not fall into any of
+    /// the known classes.
+    pub fn class(&self) -> Option<ErrorClass> {
+        self.as_status().and_then(ErrorResponse::class)
+    }
+
+    /// Returns the error response if the payload is [`ErrorPayload::Status`]
+    pub fn as_status(&self) -> Option<&ErrorResponse> {
         if let ErrorPayload::Status(r) = self {
-            r.pretty_text()
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the backend error if the payload is [`ErrorPayload::Send`]
+    pub fn as_send(&self) -> Option<&BackendError> {
+        if let ErrorPayload::Send(e) = self {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the redirect response if the payload is
+    /// [`ErrorPayload::Redirect`]
+    pub fn as_redirect(&self) -> Option<&RedirectResponse> {
+        if let ErrorPayload::Redirect(r) = self {
+            Some(r)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the parse error if the payload is
+    /// [`ErrorPayload::ParseResponse`]
+    pub fn as_parse_response(&self) -> Option<&ParseResponseError<E>> {
+        if let ErrorPayload::ParseResponse(e) = self {
+            Some(e)
         } else {
             None
         }
     }
 }
 
+/// Error returned when reading or parsing a response body fails
+///
+/// New variants may be added in future versions.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum ParseResponseError<E> {
     #[error("error reading response body")]
     Read(std::io::Error),
@@ -125,3 +312,31 @@ impl<E> ParseResponseError<E> {
         }
     }
 }
+
+/// Error indicating that a response body was larger than permitted by
+/// [`Limited`][crate::parser::Limited] or
+/// [`ClientConfig::with_max_response_size()`][crate::client::ClientConfig::with_max_response_size]
+///
+/// This error is reported inside a [`std::io::Error`] of kind
+/// [`FileTooLarge`][std::io::ErrorKind::FileTooLarge], from which it can be
+/// retrieved with [`std::io::Error::get_ref()`] and `downcast_ref()`.
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, PartialEq)]
+#[error("response body exceeded size limit of {limit} bytes")]
+pub struct ResponseTooLargeError {
+    limit: u64,
+}
+
+impl ResponseTooLargeError {
+    pub(crate) fn new(limit: u64) -> ResponseTooLargeError {
+        ResponseTooLargeError { limit }
+    }
+
+    /// Returns the maximum number of bytes that the body was allowed to have
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub(crate) fn into_io_error(self) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::FileTooLarge, self)
+    }
+}
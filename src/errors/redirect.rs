@@ -0,0 +1,57 @@
+use crate::{HttpUrl, Method, response::ResponseParts};
+use thiserror::Error;
+
+/// A 3xx response that was not followed by either the backend or the client
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("server responded with redirect status {}", self.status())]
+pub struct RedirectResponse {
+    parts: ResponseParts,
+    location: Option<HttpUrl>,
+}
+
+impl RedirectResponse {
+    /// Construct a `RedirectResponse` from the parts of a 3xx response.
+    ///
+    /// The response's `Location` header, if any, is resolved relative to the
+    /// response URL.
+    pub fn new(parts: ResponseParts) -> RedirectResponse {
+        let location = parts
+            .headers()
+            .get(http::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parts.url().as_url().join(v).ok())
+            .and_then(|url| HttpUrl::try_from(url).ok());
+        RedirectResponse { parts, location }
+    }
+
+    pub fn initial_url(&self) -> &HttpUrl {
+        self.parts.initial_url()
+    }
+
+    pub fn url(&self) -> &HttpUrl {
+        self.parts.url()
+    }
+
+    pub fn method(&self) -> Method {
+        self.parts.method()
+    }
+
+    pub fn status(&self) -> http::status::StatusCode {
+        self.parts.status()
+    }
+
+    pub fn headers(&self) -> &http::header::HeaderMap {
+        self.parts.headers()
+    }
+
+    /// Returns the URL that the response redirected to.  Returns `None` if the
+    /// response lacked a `Location` header or its value could not be resolved
+    /// to an HTTP(S) URL.
+    pub fn location(&self) -> Option<&HttpUrl> {
+        self.location.as_ref()
+    }
+
+    pub fn into_parts(self) -> ResponseParts {
+        self.parts
+    }
+}
@@ -0,0 +1,542 @@
+//! Support for the GitHub GraphQL API
+use crate::{
+    Endpoint, Method,
+    errors::CommonError,
+    parser::{JsonResponse, ResponseParser},
+    request::{BaseUrl, JsonBody, Request},
+    response::ResponseParts,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::marker::PhantomData;
+use thiserror::Error;
+
+/// A GraphQL query or mutation to send to the GitHub GraphQL API
+///
+/// When performed, the request is sent as a POST request to the client's
+/// GraphQL URL (see
+/// [`ClientConfig::graphql_url()`][crate::client::ClientConfig::graphql_url]).
+/// If the response contains any errors, the request fails with
+/// [`GraphqlRequestError::Graphql`]; otherwise, the `data` field of the
+/// response is deserialized into `T`.
+/// Use [`GraphqlRequest::allow_partial()`] to receive partial data alongside
+/// any errors instead.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GraphqlRequest<T> {
+    query: String,
+    variables: Map<String, Value>,
+    _output: PhantomData<T>,
+}
+
+impl<T> GraphqlRequest<T> {
+    pub fn new<S: Into<String>>(query: S) -> GraphqlRequest<T> {
+        GraphqlRequest {
+            query: query.into(),
+            variables: Map::new(),
+            _output: PhantomData,
+        }
+    }
+
+    /// Set the variables for the query, replacing any previously set
+    pub fn with_variables(mut self, variables: Map<String, Value>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// Set the value of a single variable for the query
+    pub fn with_variable<S: Into<String>>(mut self, name: S, value: Value) -> Self {
+        self.variables.insert(name.into(), value);
+        self
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn variables(&self) -> &Map<String, Value> {
+        &self.variables
+    }
+}
+
+impl<T: DeserializeOwned + Send> Request for GraphqlRequest<T> {
+    type Output = T;
+    type Error = GraphqlRequestError;
+    type Body = JsonBody<GraphqlPayload>;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Path(Vec::new())
+    }
+
+    fn base_url(&self) -> BaseUrl {
+        BaseUrl::Graphql
+    }
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn body(&self) -> Self::Body {
+        JsonBody::new(GraphqlPayload {
+            query: self.query.clone(),
+            variables: self.variables.clone(),
+        })
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        GraphqlDataParser(GraphqlResponseParser::new())
+    }
+}
+
+impl<T> GraphqlRequest<T> {
+    /// Convert the request into one that succeeds whenever the response can
+    /// be parsed, returning both the data (if any) and the errors (if any).
+    ///
+    /// GitHub frequently responds to queries with partial data alongside
+    /// errors for the fields that could not be resolved; use this method when
+    /// such partial results are useful.
+    pub fn allow_partial(self) -> PartialGraphqlRequest<T> {
+        PartialGraphqlRequest(self)
+    }
+}
+
+/// A GraphQL request that returns partial data alongside any errors
+///
+/// Created by [`GraphqlRequest::allow_partial()`].  Unlike [`GraphqlRequest`],
+/// this request does not fail if the response contains errors; instead, it
+/// returns the complete [`GraphqlResponse`] for the caller to inspect.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartialGraphqlRequest<T>(GraphqlRequest<T>);
+
+impl<T> PartialGraphqlRequest<T> {
+    pub fn inner(&self) -> &GraphqlRequest<T> {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> GraphqlRequest<T> {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + Send> Request for PartialGraphqlRequest<T> {
+    type Output = GraphqlResponse<T>;
+    type Error = CommonError;
+    type Body = JsonBody<GraphqlPayload>;
+
+    fn endpoint(&self) -> Endpoint {
+        self.0.endpoint()
+    }
+
+    fn base_url(&self) -> BaseUrl {
+        self.0.base_url()
+    }
+
+    fn method(&self) -> Method {
+        self.0.method()
+    }
+
+    fn body(&self) -> Self::Body {
+        self.0.body()
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        GraphqlResponseParser::new()
+    }
+}
+
+/// The JSON body of a GraphQL request
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GraphqlPayload {
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub variables: Map<String, Value>,
+}
+
+/// A response from the GraphQL API, separated into data and errors
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(bound(deserialize = "T: DeserializeOwned", serialize = "T: Serialize"))]
+pub struct GraphqlResponse<T> {
+    /// The `data` field of the response, if it was present and not `null`
+    #[serde(default = "none")]
+    pub data: Option<T>,
+
+    /// The errors reported in the response
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphqlError>,
+}
+
+impl<T> GraphqlResponse<T> {
+    /// Returns true if the response contains no errors
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Split the response into its data and errors
+    pub fn into_parts(self) -> (Option<T>, Vec<GraphqlError>) {
+        (self.data, self.errors)
+    }
+}
+
+// Needed because `#[serde(default)]` on an `Option<T>` field requires `T:
+// Default`
+fn none<T>() -> Option<T> {
+    None
+}
+
+/// An error reported in a GraphQL response
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GraphqlError {
+    pub message: String,
+
+    /// The GitHub-specific type of the error, e.g., `"NOT_FOUND"`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+
+    /// The path to the field of the response that the error pertains to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<PathSegment>,
+
+    /// The locations in the query that the error pertains to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<Location>,
+
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub extensions: Map<String, Value>,
+}
+
+impl GraphqlError {
+    /// Classify the error based on its `type` field or, if that is absent,
+    /// the `type` or `code` field of its `extensions`
+    pub fn kind(&self) -> GraphqlErrorKind {
+        let ty = self.error_type.as_deref().or_else(|| {
+            ["type", "code"]
+                .into_iter()
+                .find_map(|key| self.extensions.get(key).and_then(Value::as_str))
+        });
+        match ty {
+            Some(ty) if ty.eq_ignore_ascii_case("RATE_LIMITED") => GraphqlErrorKind::RateLimited,
+            Some(ty) if ty.eq_ignore_ascii_case("NOT_FOUND") => GraphqlErrorKind::NotFound,
+            Some(ty) if ty.eq_ignore_ascii_case("FORBIDDEN") => GraphqlErrorKind::Forbidden,
+            _ => GraphqlErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for GraphqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ty) = self.error_type.as_ref() {
+            write!(f, "{ty}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// A classification of a [`GraphqlError`], as returned by
+/// [`GraphqlError::kind()`]
+///
+/// New kinds may be added in future versions.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum GraphqlErrorKind {
+    /// The query exceeded the GraphQL API's rate limit (`RATE_LIMITED`)
+    RateLimited,
+
+    /// A requested resource could not be found (`NOT_FOUND`)
+    NotFound,
+
+    /// The caller lacks permission to access a requested resource
+    /// (`FORBIDDEN`)
+    Forbidden,
+
+    /// Any other error
+    Other,
+}
+
+/// An element of [`GraphqlError::path`]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Field(String),
+    Index(u64),
+}
+
+/// A location in a GraphQL query
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Location {
+    pub line: u64,
+    pub column: u64,
+}
+
+/// A parser for GraphQL responses that returns both the data and the errors
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GraphqlResponseParser<T>(JsonResponse<GraphqlResponse<T>>);
+
+impl<T> GraphqlResponseParser<T> {
+    pub fn new() -> GraphqlResponseParser<T> {
+        GraphqlResponseParser(JsonResponse::new())
+    }
+}
+
+impl<T: DeserializeOwned> ResponseParser for GraphqlResponseParser<T> {
+    type Output = GraphqlResponse<T>;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.0.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.0.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        self.0.end()
+    }
+}
+
+/// [Private] A parser for GraphQL responses that fails if there are any
+/// errors
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct GraphqlDataParser<T>(GraphqlResponseParser<T>);
+
+impl<T: DeserializeOwned> ResponseParser for GraphqlDataParser<T> {
+    type Output = T;
+    type Error = GraphqlRequestError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.0.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.0.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        let resp = self.0.end()?;
+        if !resp.errors.is_empty() {
+            Err(GraphqlRequestError::Graphql(GraphqlErrors(resp.errors)))
+        } else if let Some(data) = resp.data {
+            Ok(data)
+        } else {
+            Err(GraphqlRequestError::NoData)
+        }
+    }
+}
+
+/// Error type for [`GraphqlRequest`]
+///
+/// New variants may be added in future versions.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum GraphqlRequestError {
+    #[error(transparent)]
+    Common(#[from] CommonError),
+
+    /// The response contained one or more errors
+    #[error(transparent)]
+    Graphql(GraphqlErrors),
+
+    /// The response contained neither data nor errors
+    #[error("GraphQL response contained no data")]
+    NoData,
+}
+
+impl From<std::io::Error> for GraphqlRequestError {
+    fn from(e: std::io::Error) -> GraphqlRequestError {
+        GraphqlRequestError::Common(e.into())
+    }
+}
+
+/// A nonempty list of errors from a GraphQL response
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub struct GraphqlErrors(Vec<GraphqlError>);
+
+impl GraphqlErrors {
+    pub fn errors(&self) -> &[GraphqlError] {
+        &self.0
+    }
+
+    pub fn into_errors(self) -> Vec<GraphqlError> {
+        self.0
+    }
+
+    /// Returns true if any of the errors are of the given kind
+    pub fn contains_kind(&self, kind: GraphqlErrorKind) -> bool {
+        self.0.iter().any(|e| e.kind() == kind)
+    }
+}
+
+impl fmt::Display for GraphqlErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GraphQL query failed")?;
+        if let Some(first) = self.0.first() {
+            write!(f, ": {first}")?;
+        }
+        if self.0.len() > 1 {
+            write!(f, " (and {} more errors)", self.0.len() - 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{ClientConfig, FnBackend},
+        errors::{ErrorPayload, ParseResponseError},
+    };
+    use indoc::indoc;
+    use serde_json::json;
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Viewer {
+        viewer: Login,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Login {
+        login: String,
+    }
+
+    #[test]
+    fn deserialize_partial_response() {
+        let src = indoc! {r#"
+        {
+            "data": {"viewer": {"login": "octocat"}},
+            "errors": [
+                {
+                    "type": "NOT_FOUND",
+                    "path": ["repository", 0, "name"],
+                    "locations": [{"line": 7, "column": 3}],
+                    "message": "Could not resolve to a Repository with the name 'octocat/nope'."
+                }
+            ]
+        }
+        "#};
+        let resp = serde_json::from_str::<GraphqlResponse<Viewer>>(src).unwrap();
+        assert_eq!(
+            resp.data,
+            Some(Viewer {
+                viewer: Login {
+                    login: "octocat".into()
+                }
+            })
+        );
+        assert_eq!(resp.errors.len(), 1);
+        let err = &resp.errors[0];
+        assert_eq!(err.error_type.as_deref(), Some("NOT_FOUND"));
+        assert_eq!(
+            err.path,
+            [
+                PathSegment::Field("repository".into()),
+                PathSegment::Index(0),
+                PathSegment::Field("name".into())
+            ]
+        );
+        assert_eq!(err.locations, [Location { line: 7, column: 3 }]);
+    }
+
+    #[test]
+    fn deserialize_null_data() {
+        let src = r#"{"data": null, "errors": [{"message": "Something went wrong"}]}"#;
+        let resp = serde_json::from_str::<GraphqlResponse<Viewer>>(src).unwrap();
+        assert_eq!(resp.data, None);
+        assert_eq!(resp.errors[0].to_string(), "Something went wrong");
+    }
+
+    #[test]
+    fn error_kind() {
+        let errors = serde_json::from_value::<Vec<GraphqlError>>(json!([
+            {"type": "RATE_LIMITED", "message": "API rate limit exceeded"},
+            {"type": "NOT_FOUND", "message": "Could not resolve"},
+            {"message": "Nope", "extensions": {"type": "FORBIDDEN"}},
+            {"message": "Field 'x' doesn't exist", "extensions": {"code": "undefinedField"}},
+            {"message": "Something went wrong"},
+        ]))
+        .unwrap();
+        assert_eq!(
+            errors.iter().map(GraphqlError::kind).collect::<Vec<_>>(),
+            [
+                GraphqlErrorKind::RateLimited,
+                GraphqlErrorKind::NotFound,
+                GraphqlErrorKind::Forbidden,
+                GraphqlErrorKind::Other,
+                GraphqlErrorKind::Other,
+            ]
+        );
+        let errors = GraphqlErrors(errors);
+        assert!(errors.contains_kind(GraphqlErrorKind::RateLimited));
+    }
+
+    #[test]
+    fn request_partial() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|_, _| {
+            http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(
+                    serde_json::to_vec(&json!({
+                        "data": {"viewer": {"login": "octocat"}},
+                        "errors": [{"type": "FORBIDDEN", "message": "Resource not accessible"}],
+                    }))
+                    .unwrap(),
+                )
+                .unwrap()
+        }));
+        let req = GraphqlRequest::<Viewer>::new("{ viewer { login } }").allow_partial();
+        let (data, errors) = client.request(req).unwrap().into_parts();
+        assert_eq!(data.unwrap().viewer.login, "octocat");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), GraphqlErrorKind::Forbidden);
+    }
+
+    #[test]
+    fn request() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+            assert_eq!(parts.url.as_str(), "https://api.github.com/graphql");
+            assert_eq!(parts.method, Method::Post);
+            let payload = serde_json::from_slice::<Value>(&body).unwrap();
+            assert_eq!(
+                payload,
+                json!({
+                    "query": "query($n: Int!) { viewer { login } }",
+                    "variables": {"n": 1},
+                })
+            );
+            http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(br#"{"data": {"viewer": {"login": "octocat"}}}"#.to_vec())
+                .unwrap()
+        }));
+        let req = GraphqlRequest::<Viewer>::new("query($n: Int!) { viewer { login } }")
+            .with_variable("n", json!(1));
+        let data = client.request(req).unwrap();
+        assert_eq!(data.viewer.login, "octocat");
+    }
+
+    #[test]
+    fn request_errors() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|_, _| {
+            http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(br#"{"errors": [{"message": "A"}, {"message": "B"}]}"#.to_vec())
+                .unwrap()
+        }));
+        let req = GraphqlRequest::<Viewer>::new("{ viewer { login } }");
+        let e = client.request(req).unwrap_err();
+        let ErrorPayload::ParseResponse(ParseResponseError::Parse(GraphqlRequestError::Graphql(
+            errors,
+        ))) = e.into_payload()
+        else {
+            panic!("Expected GraphQL errors");
+        };
+        assert_eq!(
+            errors.to_string(),
+            "GraphQL query failed: A (and 1 more errors)"
+        );
+    }
+}
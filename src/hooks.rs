@@ -0,0 +1,388 @@
+//! Requests for inspecting & redelivering repository webhook deliveries
+//!
+//! Unlike most list endpoints, the endpoint for listing a webhook's deliveries
+//! is paginated with opaque cursors rather than page numbers.  Iterating over
+//! a [`ListHookDeliveries`] with [`Client::paginate()`][crate::client::Client::paginate]
+//! follows the cursors automatically; to resume a listing later, extract the
+//! cursor from a page's `next` URL with [`delivery_cursor()`] and pass it to
+//! [`ListHookDeliveries::with_cursor()`].
+use crate::{
+    Endpoint, HttpUrl, Method, Timestamp,
+    errors::CommonError,
+    pagination::PaginationRequest,
+    parser::{Ignore, JsonResponse, ResponseParser},
+    request::Request,
+};
+use serde::{Deserialize, Serialize};
+
+/// A paginated request for the deliveries of a repository webhook via
+/// `GET /repos/{owner}/{repo}/hooks/{hook_id}/deliveries`, most recent first
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ListHookDeliveries {
+    owner: String,
+    repo: String,
+    hook_id: u64,
+    cursor: Option<String>,
+}
+
+impl ListHookDeliveries {
+    pub fn new<S, T>(owner: S, repo: T, hook_id: u64) -> ListHookDeliveries
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        ListHookDeliveries {
+            owner: owner.into(),
+            repo: repo.into(),
+            hook_id,
+            cursor: None,
+        }
+    }
+
+    /// Start the listing at the given cursor, as returned by
+    /// [`delivery_cursor()`], instead of at the most recent delivery
+    pub fn with_cursor<S: Into<String>>(mut self, cursor: S) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+impl PaginationRequest for ListHookDeliveries {
+    type Item = HookDelivery;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter([
+            "repos",
+            &self.owner,
+            &self.repo,
+            "hooks",
+            &self.hook_id.to_string(),
+            "deliveries",
+        ])
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if let Some(ref cursor) = self.cursor {
+            params.push((String::from("cursor"), cursor.clone()));
+        }
+        params
+    }
+}
+
+/// A request for a single delivery of a repository webhook, including the
+/// full request & response, via
+/// `GET /repos/{owner}/{repo}/hooks/{hook_id}/deliveries/{delivery_id}`
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GetHookDelivery {
+    owner: String,
+    repo: String,
+    hook_id: u64,
+    delivery_id: u64,
+}
+
+impl GetHookDelivery {
+    pub fn new<S, T>(owner: S, repo: T, hook_id: u64, delivery_id: u64) -> GetHookDelivery
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        GetHookDelivery {
+            owner: owner.into(),
+            repo: repo.into(),
+            hook_id,
+            delivery_id,
+        }
+    }
+}
+
+impl Request for GetHookDelivery {
+    type Output = HookDeliveryDetails;
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter([
+            "repos",
+            &self.owner,
+            &self.repo,
+            "hooks",
+            &self.hook_id.to_string(),
+            "deliveries",
+            &self.delivery_id.to_string(),
+        ])
+    }
+
+    fn method(&self) -> Method {
+        Method::Get
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        JsonResponse::new()
+    }
+}
+
+/// A request to redeliver a delivery of a repository webhook via
+/// `POST /repos/{owner}/{repo}/hooks/{hook_id}/deliveries/{delivery_id}/attempts`
+///
+/// GitHub accepts the request and performs the redelivery asynchronously; the
+/// new attempt shows up as a separate delivery with `redelivery` set to
+/// `true`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RedeliverHookDelivery {
+    owner: String,
+    repo: String,
+    hook_id: u64,
+    delivery_id: u64,
+}
+
+impl RedeliverHookDelivery {
+    pub fn new<S, T>(owner: S, repo: T, hook_id: u64, delivery_id: u64) -> RedeliverHookDelivery
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        RedeliverHookDelivery {
+            owner: owner.into(),
+            repo: repo.into(),
+            hook_id,
+            delivery_id,
+        }
+    }
+}
+
+impl Request for RedeliverHookDelivery {
+    type Output = ();
+    type Error = CommonError;
+    type Body = ();
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::from_iter([
+            "repos",
+            &self.owner,
+            &self.repo,
+            "hooks",
+            &self.hook_id.to_string(),
+            "deliveries",
+            &self.delivery_id.to_string(),
+            "attempts",
+        ])
+    }
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn body(&self) -> Self::Body {}
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        Ignore
+    }
+}
+
+/// Extract the pagination cursor from a URL for a page of webhook deliveries,
+/// such as the `next_url` of a
+/// [`PageResponse`][crate::pagination::PageResponse]
+pub fn delivery_cursor(url: &HttpUrl) -> Option<String> {
+    url.as_url()
+        .query_pairs()
+        .filter_map(|(k, v)| (k == "cursor").then_some(v))
+        .last()
+        .map(Into::into)
+}
+
+/// A summary of a webhook delivery, as returned by [`ListHookDeliveries`]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HookDelivery {
+    pub id: u64,
+
+    /// The GUID of the event that triggered the delivery; redeliveries share
+    /// the GUID of the original delivery
+    pub guid: String,
+
+    pub delivered_at: Timestamp,
+    pub redelivery: bool,
+
+    /// The time in seconds that the delivery took
+    pub duration: f64,
+
+    /// A description of the delivery's outcome, e.g., `"OK"`
+    pub status: String,
+
+    /// The HTTP status code returned by the webhook's receiver
+    pub status_code: u16,
+
+    pub event: String,
+    pub action: Option<String>,
+    pub installation_id: Option<u64>,
+    pub repository_id: Option<u64>,
+}
+
+/// A webhook delivery along with the request sent & response received, as
+/// returned by [`GetHookDelivery`]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "RawHookDeliveryDetails")]
+pub struct HookDeliveryDetails {
+    #[serde(flatten)]
+    pub delivery: HookDelivery,
+
+    /// The URL that the delivery was sent to
+    pub url: Option<String>,
+
+    pub request: HookDeliveryRequest,
+    pub response: HookDeliveryResponse,
+}
+
+// `HookDeliveryDetails` is deserialized by way of `serde_json::Value` rather
+// than with `#[serde(flatten)]` so that deserialization still works when
+// serde_json's `arbitrary_precision` feature is enabled, as flattened fields
+// cannot buffer numbers in that case.
+#[derive(Deserialize)]
+#[serde(transparent)]
+struct RawHookDeliveryDetails(serde_json::Map<String, serde_json::Value>);
+
+impl TryFrom<RawHookDeliveryDetails> for HookDeliveryDetails {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawHookDeliveryDetails) -> Result<HookDeliveryDetails, serde_json::Error> {
+        let mut map = raw.0;
+        let mut take = |key: &str| map.remove(key).unwrap_or_default();
+        let url = serde_json::from_value(take("url"))?;
+        let request = serde_json::from_value(take("request"))?;
+        let response = serde_json::from_value(take("response"))?;
+        Ok(HookDeliveryDetails {
+            delivery: serde_json::from_value(map.into())?,
+            url,
+            request,
+            response,
+        })
+    }
+}
+
+/// The request sent for a webhook delivery
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HookDeliveryRequest {
+    pub headers: Option<serde_json::Map<String, serde_json::Value>>,
+    pub payload: Option<serde_json::Value>,
+}
+
+/// The response received for a webhook delivery
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HookDeliveryResponse {
+    pub headers: Option<serde_json::Map<String, serde_json::Value>>,
+    pub payload: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend};
+    use serde_json::json;
+
+    fn delivery(id: u64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "guid": "0b989ba4-242f-11e5-81e1-c7b6966d2516",
+            "delivered_at": "2019-06-03T00:57:16Z",
+            "redelivery": false,
+            "duration": 0.27,
+            "status": "OK",
+            "status_code": 200,
+            "event": "issues",
+            "action": "opened",
+            "installation_id": null,
+            "repository_id": 1296269,
+            "throttled_at": null,
+        })
+    }
+
+    #[test]
+    fn list_hook_deliveries() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            let (body, link) = if parts.url.as_str().contains("cursor=v1_2") {
+                (json!([delivery(2)]), None)
+            } else {
+                assert_eq!(
+                    parts.url.as_str(),
+                    "https://api.github.com/repos/octocat/hello-world/hooks/12/deliveries?per_page=100"
+                );
+                (
+                    json!([delivery(1)]),
+                    Some(
+                        r#"<https://api.github.com/repositories/1296269/hooks/12/deliveries?per_page=100&cursor=v1_2>; rel="next""#,
+                    ),
+                )
+            };
+            let mut resp = http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/json");
+            if let Some(link) = link {
+                resp = resp.header(http::header::LINK, link);
+            }
+            resp.body(serde_json::to_vec(&body).unwrap()).unwrap()
+        }));
+        let ids = client
+            .paginate(ListHookDeliveries::new("octocat", "hello-world", 12))
+            .map(|r| r.map(|d| d.id))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[test]
+    fn list_hook_deliveries_with_cursor() {
+        let req = ListHookDeliveries::new("octocat", "hello-world", 12).with_cursor("v1_2");
+        assert_eq!(
+            req.params(),
+            [(String::from("cursor"), String::from("v1_2"))]
+        );
+    }
+
+    #[test]
+    fn redeliver() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/hooks/12/deliveries/42/attempts"
+            );
+            assert_eq!(parts.method, Method::Post);
+            http::Response::builder()
+                .status(http::status::StatusCode::ACCEPTED)
+                .body(b"{}".to_vec())
+                .unwrap()
+        }));
+        client
+            .request(RedeliverHookDelivery::new("octocat", "hello-world", 12, 42))
+            .unwrap();
+    }
+
+    #[test]
+    fn get_delivery_details() {
+        let mut body = delivery(42);
+        body["url"] = json!("https://example.com/hook");
+        body["request"] =
+            json!({"headers": {"X-GitHub-Event": "issues"}, "payload": {"action": "opened"}});
+        body["response"] = json!({"headers": {}, "payload": "ok"});
+        let details = serde_json::from_str::<HookDeliveryDetails>(&body.to_string()).unwrap();
+        assert_eq!(details.delivery.id, 42);
+        assert_eq!(details.response.payload.as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn test_delivery_cursor() {
+        let url =
+            "https://api.github.com/repositories/1/hooks/12/deliveries?per_page=100&cursor=v1_2"
+                .parse::<HttpUrl>()
+                .unwrap();
+        assert_eq!(delivery_cursor(&url).as_deref(), Some("v1_2"));
+        let url = "https://api.github.com/repositories/1/hooks/12/deliveries"
+            .parse::<HttpUrl>()
+            .unwrap();
+        assert_eq!(delivery_cursor(&url), None);
+    }
+}
@@ -1,8 +1,19 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+// Allow the code generated by `#[derive(Request)]` to refer to `::ghreq` in
+// this crate's own tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as ghreq;
+
+pub mod apps;
+pub mod artifacts;
 mod base;
 pub mod client;
 pub mod consts;
+pub mod contents;
+pub mod download;
 pub mod errors;
+pub mod graphql;
+pub mod hooks;
 pub mod pagination;
 pub mod parser;
 pub mod request;
@@ -20,6 +31,10 @@ pub use http::status;
 #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
 pub mod reqwest;
 
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
 #[cfg(feature = "ureq")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ureq")))]
 pub mod ureq;
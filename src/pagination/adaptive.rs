@@ -0,0 +1,443 @@
+use super::{PageParser, PageResponse, PaginationRequest, PaginationStyle};
+use crate::{
+    AcceptList, Endpoint,
+    util::{get_page_number, set_page_number, set_query_param},
+};
+use http::header::HeaderMap;
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Configuration for adapting the page size and request rate of a pagination
+/// to the server's responsiveness and the client's remaining rate limit, as
+/// returned by [`PaginationRequest::adaptive()`]
+///
+/// When adaptive pagination is enabled, the time taken to receive each page
+/// is measured.  If a page takes at least the "slow" threshold, the
+/// `per_page` parameter for the following pages is halved (down to the
+/// configured minimum); if it takes at most the "fast" threshold, `per_page`
+/// is doubled (up to the configured maximum).  For endpoints that paginate by
+/// page number, the page size is only changed when the number of items
+/// fetched so far is a multiple of the new page size, so that no items are
+/// skipped or repeated.
+///
+/// In addition, once a page reports that the number of requests remaining in
+/// the current rate limit window is at or below the configured floor, the
+/// next page is requested only after a delay chosen to spread the remaining
+/// requests evenly over the rest of the window, capped at the configured
+/// maximum delay.  Synchronous clients wait by blocking the current thread;
+/// asynchronous clients wait using the client's
+/// [`Sleeper`][crate::client::Sleeper] and do not wait if there is none.
+///
+/// Adaptive pagination is not applied when paginating in reverse or by
+/// [`AsyncClient::paginate_parallel()`][crate::client::tokio::AsyncClient::paginate_parallel].
+/// Note that [`PaginationRequest::end_page()`] limits the number of requests
+/// made, which may then cover a different number of items than the same
+/// number of pages at the initial page size.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AdaptivePaging {
+    min_per_page: u8,
+    max_per_page: u8,
+    fast: Duration,
+    slow: Duration,
+    ratelimit_floor: u64,
+    max_delay: Duration,
+}
+
+impl AdaptivePaging {
+    /// Create an `AdaptivePaging` with the default settings: page sizes
+    /// between 25 and 100, "fast" and "slow" thresholds of 1 and 5 seconds, a
+    /// rate limit floor of 100 remaining requests, and a maximum delay of 60
+    /// seconds
+    pub fn new() -> AdaptivePaging {
+        AdaptivePaging {
+            min_per_page: 25,
+            max_per_page: 100,
+            fast: Duration::from_secs(1),
+            slow: Duration::from_secs(5),
+            ratelimit_floor: 100,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+
+    /// Set the range of page sizes to use.  The values are clamped to the
+    /// range 1 through 100, and `max` is raised to `min` if it is lower.
+    pub fn with_per_page_range(mut self, min: u8, max: u8) -> Self {
+        self.min_per_page = min.clamp(1, 100);
+        self.max_per_page = max.clamp(self.min_per_page, 100);
+        self
+    }
+
+    /// Set the response times at or below which the page size is increased
+    /// and at or above which it is decreased
+    pub fn with_response_times(mut self, fast: Duration, slow: Duration) -> Self {
+        self.fast = fast;
+        self.slow = slow;
+        self
+    }
+
+    /// Set the number of remaining requests in the rate limit window at or
+    /// below which requests for further pages are delayed
+    pub fn with_ratelimit_floor(mut self, remaining: u64) -> Self {
+        self.ratelimit_floor = remaining;
+        self
+    }
+
+    /// Set the maximum delay before requesting a page when the rate limit is
+    /// low
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// [Private] Returns the delay to wait before the next request, given the
+    /// rate limit information of the most recent page
+    fn delay(&self, remaining: Option<u64>, reset: Option<u64>) -> Option<Duration> {
+        let remaining = remaining.filter(|&n| n <= self.ratelimit_floor)?;
+        let reset = SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(reset?))?;
+        let window = reset.duration_since(SystemTime::now()).ok()?;
+        let delay = window / u32::try_from(remaining.saturating_add(1)).unwrap_or(u32::MAX);
+        Some(delay.min(self.max_delay)).filter(|d| !d.is_zero())
+    }
+}
+
+impl Default for AdaptivePaging {
+    fn default() -> AdaptivePaging {
+        AdaptivePaging::new()
+    }
+}
+
+/// [Private] The state of an adaptive pagination within a `PageCursor`
+#[derive(Clone, Debug)]
+pub(super) struct AdaptiveState {
+    paging: AdaptivePaging,
+    per_page: u8,
+    sent: Option<Instant>,
+    delay: Option<Duration>,
+    delayed: Duration,
+}
+
+impl AdaptiveState {
+    pub(super) fn new<R: PaginationRequest>(req: &R) -> Option<AdaptiveState> {
+        let paging = req.adaptive()?;
+        let per_page = req
+            .per_page()
+            .unwrap_or(paging.max_per_page)
+            .clamp(paging.min_per_page, paging.max_per_page);
+        Some(AdaptiveState {
+            paging,
+            per_page,
+            sent: None,
+            delay: None,
+            delayed: Duration::ZERO,
+        })
+    }
+
+    /// Returns the page size to request
+    pub(super) fn per_page(&self) -> u8 {
+        self.per_page
+    }
+
+    /// Record that a page is about to be requested
+    pub(super) fn request_sent(&mut self) {
+        self.sent = Some(Instant::now());
+        self.delayed = Duration::ZERO;
+    }
+
+    /// Returns the delay to wait before sending the request for the page most
+    /// recently passed to `request_sent()`.  The delay is excluded from the
+    /// page's response time.
+    pub(super) fn take_delay(&mut self) -> Option<Duration> {
+        let delay = self.delay.take();
+        self.delayed = delay.unwrap_or_default();
+        delay
+    }
+
+    /// Update the state with a newly-received page and return the URL to
+    /// request after it, adjusted for any change in page size
+    pub(super) fn page_received<T>(
+        &mut self,
+        req: &impl PaginationRequest,
+        page: &PageResponse<T>,
+        next_url: Option<Endpoint>,
+    ) -> Option<Endpoint> {
+        let elapsed = self
+            .sent
+            .take()
+            .map(|t| t.elapsed().saturating_sub(self.delayed));
+        self.delay = self
+            .paging
+            .delay(page.info.ratelimit_remaining, page.info.ratelimit_reset);
+        let Some(Endpoint::Url(url)) = next_url else {
+            return next_url;
+        };
+        let target = match elapsed {
+            Some(e) if e >= self.paging.slow => (self.per_page / 2).max(self.paging.min_per_page),
+            Some(e) if e <= self.paging.fast => self
+                .per_page
+                .saturating_mul(2)
+                .min(self.paging.max_per_page),
+            _ => self.per_page,
+        };
+        let current = url
+            .as_url()
+            .query_pairs()
+            .filter_map(|(k, v)| (k == "per_page").then(|| v.parse::<u8>().ok()))
+            .last()
+            .flatten()
+            .unwrap_or(self.per_page);
+        if target == current {
+            self.per_page = current;
+            return Some(url.into());
+        }
+        let url =
+            match get_page_number(&url).filter(|_| req.style() == PaginationStyle::PageNumbers) {
+                Some(page) => {
+                    let offset = page.saturating_sub(1).saturating_mul(current.into());
+                    if offset % u64::from(target) != 0 {
+                        self.per_page = current;
+                        return Some(url.into());
+                    }
+                    set_page_number(&url, offset / u64::from(target) + 1)
+                }
+                None => url,
+            };
+        self.per_page = target;
+        Some(set_query_param(&url, "per_page", &target.to_string()).into())
+    }
+}
+
+/// A wrapper around a [`PaginationRequest`] that enables adaptive pagination
+///
+/// See [`AdaptivePaging`] for details.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Adaptive<R> {
+    request: R,
+    paging: AdaptivePaging,
+}
+
+impl<R> Adaptive<R> {
+    /// Enable adaptive pagination for `request` with the given settings
+    pub fn new(request: R, paging: AdaptivePaging) -> Adaptive<R> {
+        Adaptive { request, paging }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.request
+    }
+
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: PaginationRequest> PaginationRequest for Adaptive<R> {
+    type Item = R::Item;
+
+    fn endpoint(&self) -> Endpoint {
+        self.request.endpoint()
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.request.params()
+    }
+
+    fn query(&self) -> impl Serialize {
+        self.request.query()
+    }
+
+    fn per_page(&self) -> Option<u8> {
+        self.request.per_page()
+    }
+
+    fn style(&self) -> PaginationStyle {
+        self.request.style()
+    }
+
+    fn start_page(&self) -> Option<u64> {
+        self.request.start_page()
+    }
+
+    fn end_page(&self) -> Option<u64> {
+        self.request.end_page()
+    }
+
+    fn reverse(&self) -> bool {
+        self.request.reverse()
+    }
+
+    fn adaptive(&self) -> Option<AdaptivePaging> {
+        Some(self.paging)
+    }
+
+    fn should_continue(&self, page: &PageResponse<Self::Item>) -> bool {
+        self.request.should_continue(page)
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.request.headers()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+
+    fn extra_accept(&self) -> AcceptList {
+        self.request.extra_accept()
+    }
+
+    fn page_parser(&self) -> PageParser<Self::Item> {
+        self.request.page_parser()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HttpUrl;
+    use crate::client::{ClientConfig, FnBackend};
+
+    struct ListNumbers;
+
+    impl PaginationRequest for ListNumbers {
+        type Item = u32;
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["numbers"])
+        }
+
+        fn per_page(&self) -> Option<u8> {
+            Some(2)
+        }
+    }
+
+    fn always_fast() -> AdaptivePaging {
+        AdaptivePaging::new()
+            .with_per_page_range(1, 8)
+            .with_response_times(Duration::from_secs(60), Duration::from_secs(120))
+    }
+
+    fn next_url(state: &mut AdaptiveState, url: &str) -> String {
+        let url = url.parse::<HttpUrl>().unwrap();
+        state.request_sent();
+        let page = PageResponse::<u32>::new(Vec::new());
+        match state.page_received(&ListNumbers, &page, Some(url.into())) {
+            Some(Endpoint::Url(url)) => url.to_string(),
+            other => panic!("Unexpected next URL: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn grows_when_aligned() {
+        let mut state = AdaptiveState::new(&Adaptive::new(ListNumbers, always_fast())).unwrap();
+        assert_eq!(state.per_page(), 2);
+        assert_eq!(
+            next_url(
+                &mut state,
+                "https://api.github.com/numbers?page=2&per_page=2"
+            ),
+            "https://api.github.com/numbers?page=2&per_page=2"
+        );
+        assert_eq!(state.per_page(), 2);
+        assert_eq!(
+            next_url(
+                &mut state,
+                "https://api.github.com/numbers?page=3&per_page=2"
+            ),
+            "https://api.github.com/numbers?page=2&per_page=4"
+        );
+        assert_eq!(state.per_page(), 4);
+    }
+
+    #[test]
+    fn shrinks_when_slow() {
+        let paging = AdaptivePaging::new()
+            .with_per_page_range(25, 100)
+            .with_response_times(Duration::ZERO, Duration::ZERO);
+        let mut state = AdaptiveState::new(&Adaptive::new(ListNumbers, paging)).unwrap();
+        assert_eq!(state.per_page(), 25);
+        state.per_page = 100;
+        assert_eq!(
+            next_url(
+                &mut state,
+                "https://api.github.com/numbers?page=2&per_page=100"
+            ),
+            "https://api.github.com/numbers?page=3&per_page=50"
+        );
+        assert_eq!(state.per_page(), 50);
+        assert_eq!(
+            next_url(
+                &mut state,
+                "https://api.github.com/numbers?page=4&per_page=50"
+            ),
+            "https://api.github.com/numbers?page=7&per_page=25"
+        );
+        assert_eq!(
+            next_url(
+                &mut state,
+                "https://api.github.com/numbers?page=8&per_page=25"
+            ),
+            "https://api.github.com/numbers?page=8&per_page=25"
+        );
+    }
+
+    #[test]
+    fn delay_when_ratelimit_low() {
+        let paging = AdaptivePaging::new()
+            .with_ratelimit_floor(9)
+            .with_max_delay(Duration::from_secs(30));
+        let reset = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 101;
+        assert_eq!(paging.delay(Some(10), Some(reset)), None);
+        let d = paging.delay(Some(9), Some(reset)).unwrap();
+        assert!(
+            d > Duration::from_secs(9) && d <= Duration::from_secs(11),
+            "{d:?}"
+        );
+        assert_eq!(
+            paging.delay(Some(0), Some(reset)),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(paging.delay(Some(0), Some(reset - 200)), None);
+        assert_eq!(paging.delay(Some(0), None), None);
+    }
+
+    #[test]
+    fn paginate_adaptive() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            let url = parts.url.as_url();
+            let param = |key: &str| {
+                url.query_pairs()
+                    .find_map(|(k, v)| (k == key).then(|| v.parse::<u32>().unwrap()))
+            };
+            let page = param("page").unwrap_or(1);
+            let per_page = param("per_page").unwrap();
+            let start = (page - 1) * per_page + 1;
+            let end = (page * per_page).min(20);
+            let items = (start..=end).collect::<Vec<_>>();
+            let mut resp = http::Response::builder().header("x-ratelimit-remaining", "4999");
+            if end < 20 {
+                resp = resp.header(
+                    http::header::LINK,
+                    format!(
+                        "<https://api.github.com/numbers?page={}&per_page={per_page}>; rel=\"next\"",
+                        page + 1
+                    ),
+                );
+            }
+            resp.body(serde_json::to_vec(&items).unwrap()).unwrap()
+        }));
+        let mut cursor = client.page_cursor(Adaptive::new(ListNumbers, always_fast()));
+        let mut sizes = Vec::new();
+        let mut numbers = Vec::new();
+        while let Some(page) = cursor.next_page().unwrap() {
+            sizes.push(page.items.len());
+            assert_eq!(page.info.ratelimit_remaining, Some(4999));
+            numbers.extend(page.items);
+        }
+        assert_eq!(sizes, [2, 2, 4, 8, 4]);
+        assert_eq!(numbers, (1..=20).collect::<Vec<_>>());
+    }
+}
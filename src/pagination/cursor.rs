@@ -0,0 +1,396 @@
+use super::{
+    AdaptiveState, PageProgress, PageRequest, PageResponse, PaginationRequest, PaginationState,
+    first_page_params, following_url, is_reverse, page_budget, reverse_start,
+};
+use crate::{
+    Endpoint, HttpUrl, Method,
+    client::{Backend, Client},
+    errors::{Error, ErrorPayload},
+};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use crate::client::tokio::{AsyncBackend, AsyncClient};
+
+/// A cursor over the pages of a [`PaginationRequest`] that is advanced
+/// manually, returned by [`Client::page_cursor()`] and
+/// [`AsyncClient::page_cursor()`]
+///
+/// Each call to `next_page()` fetches the next page of results, following
+/// `Link` headers in the same way as
+/// [`Client::paginate()`][crate::client::Client::paginate] (including
+/// honoring [`PaginationRequest::start_page()`],
+/// [`PaginationRequest::end_page()`], [`PaginationRequest::reverse()`], and
+/// [`PaginationRequest::should_continue()`]), and returns `Ok(None)` once
+/// there are no more pages.  Unlike an iterator, the cursor is not consumed
+/// by an error: if fetching a page fails, the cursor stops, but
+/// [`PageCursor::retry()`] can be called to make the next call to
+/// `next_page()` request the same page again.  This allows consumers to
+/// interleave other work between pages or apply their own retry logic.
+///
+/// If the request enables adaptive pagination (see
+/// [`PaginationRequest::adaptive()`]), `next_page()` also waits before
+/// sending a request when the rate limit is low.
+///
+/// `C` is the client used to make requests: a `&Client<B>` or an
+/// `AsyncClient<B>`.
+#[derive(Clone, Debug)]
+pub struct PageCursor<C, R> {
+    pub(super) client: C,
+    req: R,
+    next_url: Option<Endpoint>,
+    failed: Option<(Endpoint, PaginationState)>,
+    state: PaginationState,
+    pages_left: Option<u64>,
+    adaptive: Option<AdaptiveState>,
+    progress: PageProgress,
+    on_page: Option<OnPage>,
+}
+
+impl<C, R: PaginationRequest> PageCursor<C, R> {
+    pub fn new(client: C, req: R) -> Self {
+        let pages_left = page_budget(&req);
+        let next_url = (pages_left != Some(0)).then(|| req.endpoint());
+        let adaptive = AdaptiveState::new(&req).filter(|_| !is_reverse(&req));
+        PageCursor {
+            client,
+            req,
+            next_url,
+            failed: None,
+            state: PaginationState::NotStarted,
+            pages_left,
+            adaptive,
+            progress: PageProgress::default(),
+            on_page: None,
+        }
+    }
+
+    /// Call `f` after each page is fetched with the progress of the
+    /// pagination so far, e.g., in order to display a progress bar.  Any
+    /// previously-set callback is replaced.
+    ///
+    /// The callback is called before the page is returned to the caller and
+    /// is not called for pages that fail to be fetched.
+    pub fn on_page<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&PageProgress) + Send + Sync + 'static,
+    {
+        self.on_page = Some(OnPage(Arc::new(f)));
+        self
+    }
+
+    /// Returns the progress of the pagination so far
+    pub fn progress(&self) -> PageProgress {
+        self.progress
+    }
+
+    pub fn state(&self) -> PaginationState {
+        self.state
+    }
+
+    /// Returns the request being paginated
+    pub fn request(&self) -> &R {
+        &self.req
+    }
+
+    /// Returns true if the next call to `next_page()` will request a page
+    pub fn has_next_page(&self) -> bool {
+        self.next_url.is_some()
+    }
+
+    /// If the most recent call to `next_page()` failed, arrange for the next
+    /// call to request the same page again and return `true`; otherwise,
+    /// return `false`.
+    pub fn retry(&mut self) -> bool {
+        match self.failed.take() {
+            Some((url, state)) => {
+                self.next_url = Some(url);
+                self.state = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// [Private] Returns the maximum number of further pages to fetch, if
+    /// limited
+    pub(super) fn pages_left(&self) -> Option<u64> {
+        self.pages_left
+    }
+
+    /// [Private] Returns the request for the next page, or `None` if there
+    /// are no more pages.  `base_url` is used to construct the error if the
+    /// request's query parameters cannot be encoded.
+    pub(super) fn next_request<E>(
+        &mut self,
+        base_url: &HttpUrl,
+    ) -> Option<Result<PageRequest<R::Item>, Error<E>>> {
+        self.failed = None;
+        let Some(url) = self.next_url.take() else {
+            self.state = PaginationState::Ended;
+            return None;
+        };
+        let mut req = PageRequest::new(url.clone())
+            .with_headers(self.req.headers())
+            .with_extra_accept(self.req.extra_accept())
+            .with_timeout(self.req.timeout())
+            .with_parser(self.req.page_parser());
+        if self.state == PaginationState::NotStarted {
+            match first_page_params(&self.req) {
+                Ok(mut params) => {
+                    if let Some(adaptive) = self.adaptive.as_ref() {
+                        params.retain(|(k, _)| k != "per_page");
+                        params.push(("per_page".into(), adaptive.per_page().to_string()));
+                    }
+                    req = req.with_params(params);
+                }
+                Err(e) => {
+                    let url = base_url.join_endpoint(url);
+                    let payload = ErrorPayload::PrepareRequest(e);
+                    self.state = PaginationState::Ended;
+                    return Some(Err(Error::new(url, Method::Get, payload)));
+                }
+            }
+        }
+        self.failed = Some((url, self.state));
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            adaptive.request_sent();
+        }
+        Some(Ok(req))
+    }
+
+    /// [Private] Returns the time to wait before sending the request most
+    /// recently returned by `next_request()`, if any
+    pub(super) fn take_delay(&mut self) -> Option<Duration> {
+        self.adaptive.as_mut().and_then(AdaptiveState::take_delay)
+    }
+
+    /// [Private] Update the cursor's state with a newly-received page and
+    /// return `true` if the page should be returned to the caller or `false`
+    /// if it only served to locate the start of a reverse pagination
+    pub(super) fn handle_page(&mut self, page: &mut PageResponse<R::Item>) -> bool {
+        self.failed = None;
+        let first = self.state == PaginationState::NotStarted;
+        self.state = PaginationState::Paging;
+        if first && let Some(last_url) = reverse_start(&self.req, page) {
+            self.next_url = Some(last_url);
+            return false;
+        }
+        self.next_url = following_url(&self.req, page);
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            self.next_url = adaptive.page_received(&self.req, page, self.next_url.take());
+        }
+        if let Some(n) = self.pages_left.as_mut() {
+            *n = n.saturating_sub(1);
+            if *n == 0 {
+                self.next_url = None;
+            }
+        }
+        self.progress.pages += 1;
+        self.progress.items += u64::try_from(page.items.len()).unwrap_or(u64::MAX);
+        self.progress.total_count = page.info.total_count.or(self.progress.total_count);
+        self.progress.last_page = page.info.last_page.or(self.progress.last_page);
+        if let Some(OnPage(f)) = self.on_page.as_ref() {
+            f(&self.progress);
+        }
+        true
+    }
+
+    /// [Private] Update the cursor's state after a failed request
+    pub(super) fn handle_error(&mut self) {
+        self.state = PaginationState::Ended;
+    }
+}
+
+/// [Private] A callback set with `PageCursor::on_page()`.  Clones share the
+/// same callback.
+#[derive(Clone)]
+struct OnPage(Arc<dyn Fn(&PageProgress) + Send + Sync>);
+
+impl fmt::Debug for OnPage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnPage").finish_non_exhaustive()
+    }
+}
+
+impl<B, R> PageCursor<&Client<B>, R>
+where
+    B: Backend,
+    R: PaginationRequest<Item: DeserializeOwned + Send>,
+{
+    /// Fetch the next page, returning `Ok(None)` if there are no more pages
+    pub fn next_page(&mut self) -> Result<Option<PageResponse<R::Item>>, Error<B::Error>> {
+        let client = self.client;
+        loop {
+            let Some(req) = self.next_request(&client.base_url()) else {
+                return Ok(None);
+            };
+            let req = req?;
+            if let Some(delay) = self.take_delay() {
+                std::thread::sleep(delay);
+            }
+            match client.request(req) {
+                Ok(mut page) => {
+                    if self.handle_page(&mut page) {
+                        return Ok(Some(page));
+                    }
+                }
+                Err(e) => {
+                    self.handle_error();
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<B, R> PageCursor<AsyncClient<B>, R>
+where
+    B: AsyncBackend + Send + Sync,
+    R: PaginationRequest<Item: DeserializeOwned + Send> + Send,
+{
+    /// Fetch the next page, returning `Ok(None)` if there are no more pages
+    pub async fn next_page(&mut self) -> Result<Option<PageResponse<R::Item>>, Error<B::Error>> {
+        let client = self.client.clone();
+        loop {
+            let Some(req) = self.next_request(&client.base_url()) else {
+                return Ok(None);
+            };
+            let req = req?;
+            if let Some(delay) = self.take_delay()
+                && let Some(sleeper) = client.sleeper()
+            {
+                sleeper.sleep(delay).await;
+            }
+            match client.request(req).await {
+                Ok(mut page) => {
+                    if self.handle_page(&mut page) {
+                        return Ok(Some(page));
+                    }
+                }
+                Err(e) => {
+                    self.handle_error();
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{ClientConfig, FnBackend, RequestParts},
+        testing::PaginationFixtures,
+    };
+    use serde::Deserialize;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    struct ListItems;
+
+    impl PaginationRequest for ListItems {
+        type Item = Item;
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["items"])
+        }
+    }
+
+    fn fixtures() -> PaginationFixtures {
+        PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+            .with_page_size(10)
+    }
+
+    #[test]
+    fn next_page() {
+        let client = ClientConfig::new().with_backend(fixtures().backend());
+        let mut cursor = client.page_cursor(ListItems);
+        assert_eq!(cursor.state(), PaginationState::NotStarted);
+        assert!(cursor.has_next_page());
+        let mut sizes = Vec::new();
+        while let Some(page) = cursor.next_page().unwrap() {
+            sizes.push(page.items.len());
+        }
+        assert_eq!(sizes, [10, 10, 5]);
+        assert_eq!(cursor.state(), PaginationState::Ended);
+        assert!(!cursor.has_next_page());
+        assert!(cursor.next_page().unwrap().is_none());
+        assert!(!cursor.retry());
+    }
+
+    #[test]
+    fn on_page_progress() {
+        let client = ClientConfig::new().with_backend(fixtures().backend());
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen2 = Arc::clone(&seen);
+        let mut cursor = client
+            .page_cursor(ListItems)
+            .on_page(move |p| seen2.lock().unwrap().push(*p));
+        while cursor.next_page().unwrap().is_some() {}
+        let expected = [(1, 10), (2, 20), (3, 25)].map(|(pages, items)| PageProgress {
+            pages,
+            items,
+            total_count: None,
+            last_page: Some(3),
+        });
+        assert_eq!(*seen.lock().unwrap(), expected);
+        assert_eq!(cursor.progress(), expected[2]);
+    }
+
+    #[test]
+    fn retry_after_error() {
+        let fixtures = fixtures();
+        let failures = Arc::new(AtomicUsize::new(0));
+        let failures2 = Arc::clone(&failures);
+        let client =
+            ClientConfig::new().with_backend(FnBackend::new(move |parts: RequestParts, _| {
+                if parts.url.as_str().ends_with("page=2")
+                    && failures2.fetch_add(1, Ordering::SeqCst) == 0
+                {
+                    http::Response::builder()
+                        .status(502)
+                        .body(b"{\"message\": \"Bad Gateway\"}".to_vec())
+                        .unwrap()
+                } else {
+                    fixtures.response_for(&parts.url)
+                }
+            }));
+        let mut cursor = client.page_cursor(ListItems);
+        assert_eq!(cursor.next_page().unwrap().unwrap().items.len(), 10);
+        assert!(cursor.next_page().is_err());
+        assert_eq!(cursor.state(), PaginationState::Ended);
+        assert!(cursor.retry());
+        assert!(!cursor.retry());
+        let page = cursor.next_page().unwrap().unwrap();
+        assert_eq!(page.items.first(), Some(&Item { id: 11 }));
+        assert_eq!(cursor.next_page().unwrap().unwrap().items.len(), 5);
+        assert!(cursor.next_page().unwrap().is_none());
+        assert_eq!(failures.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn next_page_async() {
+        let client = ClientConfig::new().with_async_backend(fixtures().async_backend());
+        let mut cursor = client.page_cursor(ListItems);
+        let mut ids = Vec::new();
+        while let Some(page) = cursor.next_page().await.unwrap() {
+            ids.extend(page.items.into_iter().map(|item| item.id));
+        }
+        assert_eq!(ids, (1..=25).collect::<Vec<_>>());
+    }
+}
@@ -1,57 +1,164 @@
+mod adaptive;
+mod cursor;
+#[cfg(feature = "tokio")]
+mod parallel;
+mod search;
+mod snapshot;
 #[cfg(feature = "tokio")]
 mod stream;
+pub use adaptive::*;
+pub use cursor::*;
+#[cfg(feature = "tokio")]
+pub use parallel::*;
+pub use search::*;
+pub use snapshot::*;
 #[cfg(feature = "tokio")]
 pub use stream::*;
 
 use crate::{
-    Endpoint, HeaderMapExt, HttpUrl, Method,
+    AcceptList, Endpoint, HeaderMapExt, HttpUrl, Method,
     client::{Backend, Client},
     errors::CommonError,
     parser::ResponseParser,
     request::Request,
     response::ResponseParts,
-    util::get_page_number,
+    util::{encode_query, get_page_number, json_from_slice},
 };
+use bytes::BytesMut;
 use http::header::HeaderMap;
-use serde::{Deserialize, de::DeserializeOwned};
+use serde::{
+    Deserialize, Serialize,
+    de::DeserializeOwned,
+    ser::{SerializeMap, Serializer},
+};
 use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
 use std::time::Duration;
 use thiserror::Error;
 
+/// A single page of results from a paginated endpoint
+///
+/// A `Page` is deserialized from either a JSON array of items or a JSON
+/// object containing exactly one array of items plus, optionally,
+/// `"total_count"` and `"incomplete_results"` fields.  It is serialized as an
+/// array if `total_count` and `incomplete_results` are both `None`, and as an
+/// object with an `"items"` field otherwise, so that serializing and then
+/// deserializing a `Page` produces an equal value.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
-#[serde(bound = "T: DeserializeOwned", try_from = "RawPage<T>")]
+#[serde(bound = "T: DeserializeOwned", try_from = "RawPage")]
 pub struct Page<T> {
     pub items: Vec<T>,
     pub total_count: Option<u64>,
     pub incomplete_results: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
-#[serde(untagged)]
-enum RawPage<T> {
-    Array(Vec<T>),
-    Map(std::collections::HashMap<String, MapPageValue<T>>),
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>) -> Page<T> {
+        Page {
+            items,
+            total_count: None,
+            incomplete_results: None,
+        }
+    }
+
+    pub fn with_total_count(mut self, total_count: u64) -> Self {
+        self.total_count = Some(total_count);
+        self
+    }
+
+    pub fn with_incomplete_results(mut self, incomplete_results: bool) -> Self {
+        self.incomplete_results = Some(incomplete_results);
+        self
+    }
 }
 
-impl<T: DeserializeOwned> TryFrom<RawPage<T>> for Page<T> {
+impl<T: Serialize> Serialize for Page<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.total_count.is_none() && self.incomplete_results.is_none() {
+            return self.items.serialize(serializer);
+        }
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(total_count) = self.total_count {
+            map.serialize_entry("total_count", &total_count)?;
+        }
+        if let Some(incomplete_results) = self.incomplete_results {
+            map.serialize_entry("incomplete_results", &incomplete_results)?;
+        }
+        map.serialize_entry("items", &self.items)?;
+        map.end()
+    }
+}
+
+// Pages are deserialized by way of `serde_json::Value` rather than an
+// untagged enum so that item fields survive intact when serde_json's
+// `arbitrary_precision` feature is enabled, which untagged enums cannot
+// buffer.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(transparent)]
+struct RawPage(serde_json::Value);
+
+impl<T: DeserializeOwned> TryFrom<RawPage> for Page<T> {
     type Error = ParsePageError;
 
-    fn try_from(value: RawPage<T>) -> Result<Page<T>, ParsePageError> {
-        match value {
-            RawPage::Array(items) => Ok(Page {
+    fn try_from(value: RawPage) -> Result<Page<T>, ParsePageError> {
+        value.into_page(&ItemsDetection::AnyArray)
+    }
+}
+
+impl RawPage {
+    /// [Private] Convert the raw page to a `Page`, identifying the array of
+    /// items in a map page according to `detection`
+    fn into_page<T: DeserializeOwned>(
+        self,
+        detection: &ItemsDetection,
+    ) -> Result<Page<T>, ParsePageError> {
+        let mut value = self.0;
+        if let ItemsDetection::Pointer(pointer) = detection
+            && value.is_object()
+        {
+            let total_count = value.get("total_count").and_then(serde_json::Value::as_u64);
+            let incomplete_results = value
+                .get("incomplete_results")
+                .and_then(serde_json::Value::as_bool);
+            let items = match value.pointer_mut(pointer).map(serde_json::Value::take) {
+                Some(serde_json::Value::Null) => Vec::new(),
+                Some(v @ serde_json::Value::Array(_)) => serde_json::from_value(v)?,
+                _ => return Err(ParsePageError::ListQty(0)),
+            };
+            return Ok(Page {
                 items,
+                total_count,
+                incomplete_results,
+            });
+        }
+        match value {
+            serde_json::Value::Array(items) => Ok(Page {
+                items: items
+                    .into_iter()
+                    .map(serde_json::from_value)
+                    .collect::<Result<_, _>>()?,
                 total_count: None,
                 incomplete_results: None,
             }),
-            RawPage::Map(map) => {
-                let total_count = map.get("total_count").and_then(MapPageValue::as_u64);
+            serde_json::Value::Object(map) => {
+                let total_count = map.get("total_count").and_then(serde_json::Value::as_u64);
                 let incomplete_results = map
                     .get("incomplete_results")
-                    .and_then(MapPageValue::as_bool);
+                    .and_then(serde_json::Value::as_bool);
                 let mut lists = map
-                    .into_values()
-                    .filter_map(MapPageValue::into_list)
+                    .into_iter()
+                    .filter_map(|(k, v)| detection.candidate(&k, v))
+                    .filter_map(|v| serde_json::from_value::<Vec<T>>(v).ok())
                     .collect::<Vec<_>>();
+                if *detection == ItemsDetection::NonScalar {
+                    // Empty arrays are only candidates if they're all there
+                    // is, in which case any of them will do.
+                    if lists.iter().all(Vec::is_empty) {
+                        lists.truncate(1);
+                    } else {
+                        lists.retain(|items| !items.is_empty());
+                    }
+                }
                 if lists.len() == 1 {
                     let Some(items) = lists.pop() else {
                         unreachable!("Vec with 1 item should have something to pop");
@@ -65,41 +172,64 @@ impl<T: DeserializeOwned> TryFrom<RawPage<T>> for Page<T> {
                     Err(ParsePageError::ListQty(lists.len()))
                 }
             }
+            _ => Err(ParsePageError::NotPage),
         }
     }
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq)]
-#[serde(untagged)]
-enum MapPageValue<T> {
-    Count(u64),
-    Bool(bool),
-    List(Vec<T>),
-    Other(serde::de::IgnoredAny),
-}
+/// How a [`PageParser`] identifies the array of items in a page whose body
+/// is a JSON object rather than an array
+///
+/// Some endpoints return pages as objects that contain other arrays alongside
+/// the items (e.g., an empty `"errors": []`), which would otherwise make the
+/// page ambiguous.  In all cases, parsing fails if exactly one array of items
+/// cannot be identified.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ItemsDetection {
+    /// The items are the only array in the object that can be deserialized
+    /// as a list of items.  This is the default.
+    #[default]
+    AnyArray,
 
-impl<T> MapPageValue<T> {
-    fn as_u64(&self) -> Option<u64> {
-        if let MapPageValue::Count(value) = self {
-            Some(*value)
-        } else {
-            None
-        }
-    }
+    /// Only arrays whose elements are all objects or arrays are considered,
+    /// and empty arrays are only considered if there is no nonempty
+    /// candidate.  This protects against incidental empty arrays and arrays
+    /// of strings or numbers.
+    NonScalar,
 
-    fn as_bool(&self) -> Option<bool> {
-        if let MapPageValue::Bool(value) = self {
-            Some(*value)
-        } else {
-            None
-        }
-    }
+    /// Only fields whose names start with the given prefix are considered,
+    /// and such a field with a `null` value is treated as an empty list of
+    /// items.
+    KeyPrefix(String),
 
-    fn into_list(self) -> Option<Vec<T>> {
-        if let MapPageValue::List(lst) = self {
-            Some(lst)
-        } else {
-            None
+    /// The items are the array at the given [JSON Pointer][rfc6901] (e.g.,
+    /// `"/data/items"`), which may be nested at any depth.  A `null` value at
+    /// the pointer is treated as an empty list of items.
+    ///
+    /// [rfc6901]: https://www.rfc-editor.org/rfc/rfc6901
+    Pointer(String),
+}
+
+impl ItemsDetection {
+    /// [Private] If the field `key` with value `value` in a map page may be
+    /// the array of items, return the array
+    fn candidate(&self, key: &str, value: serde_json::Value) -> Option<serde_json::Value> {
+        match self {
+            ItemsDetection::AnyArray => value.is_array().then_some(value),
+            ItemsDetection::NonScalar => match value {
+                serde_json::Value::Array(ref items)
+                    if items.iter().all(|v| v.is_object() || v.is_array()) =>
+                {
+                    Some(value)
+                }
+                _ => None,
+            },
+            ItemsDetection::KeyPrefix(prefix) if key.starts_with(prefix.as_str()) => match value {
+                serde_json::Value::Null => Some(serde_json::Value::Array(Vec::new())),
+                serde_json::Value::Array(_) => Some(value),
+                _ => None,
+            },
+            ItemsDetection::KeyPrefix(_) | ItemsDetection::Pointer(_) => None,
         }
     }
 }
@@ -108,9 +238,36 @@ impl<T> MapPageValue<T> {
 enum ParsePageError {
     #[error("expected exactly one array of items in map page response, got {0}")]
     ListQty(usize),
+
+    #[error("expected page response to be an array or map")]
+    NotPage,
+
+    #[error(transparent)]
+    Item(#[from] serde_json::Error),
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The progress of a pagination, as passed to the callback set with
+/// [`PaginationIter::on_page()`] and similar methods after each page is
+/// fetched
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PageProgress {
+    /// The number of pages fetched so far, including the current page
+    pub pages: u64,
+
+    /// The number of items fetched so far, including those on the current
+    /// page
+    pub items: u64,
+
+    /// The total number of items in the listing, if reported by the endpoint
+    /// (e.g., for search results)
+    pub total_count: Option<u64>,
+
+    /// The number of the last page of the listing, if known from a `Link`
+    /// header
+    pub last_page: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct PaginationInfo {
     // When this is None, you're either on the first page (for most endpoints)
     // or the pagination doesn't use page numbers (e.g., /repositories).
@@ -118,20 +275,105 @@ pub struct PaginationInfo {
     pub last_page: Option<u64>,
     pub total_count: Option<u64>,
     pub incomplete_results: Option<bool>,
+    /// The number of requests remaining in the current rate limit window,
+    /// from the `x-ratelimit-remaining` header
+    pub ratelimit_remaining: Option<u64>,
+    /// The time at which the current rate limit window resets, in seconds
+    /// since the Unix epoch, from the `x-ratelimit-reset` header
+    pub ratelimit_reset: Option<u64>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+impl PaginationInfo {
+    /// Create a `PaginationInfo` with all fields set to `None`
+    pub fn new() -> PaginationInfo {
+        PaginationInfo::default()
+    }
+
+    pub fn with_current_page(mut self, current_page: u64) -> Self {
+        self.current_page = Some(current_page);
+        self
+    }
+
+    pub fn with_last_page(mut self, last_page: u64) -> Self {
+        self.last_page = Some(last_page);
+        self
+    }
+
+    pub fn with_total_count(mut self, total_count: u64) -> Self {
+        self.total_count = Some(total_count);
+        self
+    }
+
+    pub fn with_incomplete_results(mut self, incomplete_results: bool) -> Self {
+        self.incomplete_results = Some(incomplete_results);
+        self
+    }
+
+    pub fn with_ratelimit_remaining(mut self, remaining: u64) -> Self {
+        self.ratelimit_remaining = Some(remaining);
+        self
+    }
+
+    pub fn with_ratelimit_reset(mut self, reset: u64) -> Self {
+        self.ratelimit_reset = Some(reset);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct PageResponse<T> {
     pub next_url: Option<HttpUrl>,
+    /// The URL of the previous page, from the `Link` header's `rel="prev"`
+    /// entry
+    pub prev_url: Option<HttpUrl>,
+    /// The URL of the last page, from the `Link` header's `rel="last"` entry
+    pub last_url: Option<HttpUrl>,
     pub items: Vec<T>,
     pub info: PaginationInfo,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+impl<T> PageResponse<T> {
+    /// Create a `PageResponse` for the last page of results, with no
+    /// pagination information
+    pub fn new(items: Vec<T>) -> PageResponse<T> {
+        PageResponse {
+            next_url: None,
+            prev_url: None,
+            last_url: None,
+            items,
+            info: PaginationInfo::new(),
+        }
+    }
+
+    pub fn with_next_url(mut self, next_url: HttpUrl) -> Self {
+        self.next_url = Some(next_url);
+        self
+    }
+
+    pub fn with_prev_url(mut self, prev_url: HttpUrl) -> Self {
+        self.prev_url = Some(prev_url);
+        self
+    }
+
+    pub fn with_last_url(mut self, last_url: HttpUrl) -> Self {
+        self.last_url = Some(last_url);
+        self
+    }
+
+    pub fn with_info(mut self, info: PaginationInfo) -> Self {
+        self.info = info;
+        self
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
 pub struct PageParser<T> {
     next_url: Option<HttpUrl>,
+    prev_url: Option<HttpUrl>,
+    last_url: Option<HttpUrl>,
     info: Option<PaginationInfo>,
-    buf: Vec<u8>,
+    items_detection: ItemsDetection,
+    buf: BytesMut,
     _items: PhantomData<T>,
 }
 
@@ -139,8 +381,33 @@ impl<T> PageParser<T> {
     pub fn new() -> PageParser<T> {
         PageParser {
             next_url: None,
+            prev_url: None,
+            last_url: None,
             info: None,
-            buf: Vec::new(),
+            items_detection: ItemsDetection::AnyArray,
+            buf: BytesMut::new(),
+            _items: PhantomData,
+        }
+    }
+
+    /// Set how the array of items is identified in pages that are JSON
+    /// objects.  The default is [`ItemsDetection::AnyArray`].
+    pub fn with_items_detection(mut self, detection: ItemsDetection) -> Self {
+        self.items_detection = detection;
+        self
+    }
+}
+
+// Implemented manually so as not to require `T: Clone`
+impl<T> Clone for PageParser<T> {
+    fn clone(&self) -> PageParser<T> {
+        PageParser {
+            next_url: self.next_url.clone(),
+            prev_url: self.prev_url.clone(),
+            last_url: self.last_url.clone(),
+            info: self.info,
+            items_detection: self.items_detection.clone(),
+            buf: self.buf.clone(),
             _items: PhantomData,
         }
     }
@@ -165,8 +432,12 @@ impl<T: DeserializeOwned> ResponseParser for PageParser<T> {
             last_page,
             total_count: None,
             incomplete_results: None,
+            ratelimit_remaining: parts.headers().ratelimit_remaining(),
+            ratelimit_reset: parts.headers().ratelimit_reset(),
         });
         self.next_url = links.next;
+        self.prev_url = links.prev;
+        self.last_url = links.last;
         self.buf.handle_parts(parts);
     }
 
@@ -174,13 +445,17 @@ impl<T: DeserializeOwned> ResponseParser for PageParser<T> {
         self.buf.handle_bytes(buf);
     }
 
-    fn end(self) -> Result<Self::Output, Self::Error> {
-        let page = serde_json::from_slice::<Page<T>>(&self.buf)?;
+    fn end(mut self) -> Result<Self::Output, Self::Error> {
+        let page = json_from_slice::<RawPage>(&mut self.buf)?
+            .into_page::<T>(&self.items_detection)
+            .map_err(<serde_json::Error as serde::de::Error>::custom)?;
         let mut info = self.info.expect("handle_parts() should have been called");
         info.total_count = page.total_count;
         info.incomplete_results = page.incomplete_results;
         Ok(PageResponse {
             next_url: self.next_url,
+            prev_url: self.prev_url,
+            last_url: self.last_url,
             info,
             items: page.items,
         })
@@ -191,9 +466,11 @@ impl<T: DeserializeOwned> ResponseParser for PageParser<T> {
 pub struct PageRequest<T> {
     endpoint: Endpoint,
     params: Vec<(String, String)>,
+    per_page: Option<u8>,
     headers: HeaderMap,
+    accept: AcceptList,
     timeout: Option<Duration>,
-    _items: PhantomData<T>,
+    parser: PageParser<T>,
 }
 
 impl<T> PageRequest<T> {
@@ -201,12 +478,21 @@ impl<T> PageRequest<T> {
         PageRequest {
             endpoint,
             params: Vec::new(),
+            per_page: None,
             headers: HeaderMap::new(),
+            accept: AcceptList::new(),
             timeout: None,
-            _items: PhantomData,
+            parser: PageParser::new(),
         }
     }
 
+    /// Set the parser used for the response.  By default,
+    /// [`PageParser::new()`] is used.
+    pub fn with_parser(mut self, parser: PageParser<T>) -> Self {
+        self.parser = parser;
+        self
+    }
+
     pub fn with_params(mut self, params: Vec<(String, String)>) -> Self {
         self.params = params;
         self
@@ -217,6 +503,13 @@ impl<T> PageRequest<T> {
         self
     }
 
+    /// Set the media types to merge into the request's `Accept` header; see
+    /// [`Request::extra_accept()`]
+    pub fn with_extra_accept(mut self, accept: AcceptList) -> Self {
+        self.accept = accept;
+        self
+    }
+
     pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
         self.timeout = timeout;
         self
@@ -226,6 +519,13 @@ impl<T> PageRequest<T> {
         self.params.push(("page".into(), page.to_string()));
         self
     }
+
+    /// Request `per_page` items per page by appending a `per_page` parameter
+    /// to the URL.  By default, no such parameter is added.
+    pub fn with_per_page(mut self, per_page: Option<u8>) -> Self {
+        self.per_page = per_page;
+        self
+    }
 }
 
 impl<T: DeserializeOwned + Send> Request for PageRequest<T> {
@@ -246,22 +546,73 @@ impl<T: DeserializeOwned + Send> Request for PageRequest<T> {
     }
 
     fn params(&self) -> Vec<(String, String)> {
-        self.params.clone()
+        let mut params = self.params.clone();
+        if let Some(n) = self.per_page {
+            params.push(("per_page".into(), n.to_string()));
+        }
+        params
     }
 
     fn timeout(&self) -> Option<Duration> {
         self.timeout
     }
 
+    fn extra_accept(&self) -> AcceptList {
+        self.accept.clone()
+    }
+
     fn body(&self) -> Self::Body {}
 
     fn parser(
         &self,
     ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
-        PageParser::new()
+        self.parser.clone()
     }
 }
 
+/// The manner in which an endpoint selects pages of results, as returned by
+/// [`PaginationRequest::style()`]
+///
+/// In both cases, pagination proceeds by following the `rel="next"` URL in
+/// each response's `Link` header; the style determines which other features
+/// of pagination are available.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum PaginationStyle {
+    /// Pages are selected with a `page` query parameter, and the `Link`
+    /// header may also give the URLs of the previous & last pages.  This is
+    /// the style used by most GitHub endpoints.  Endpoints that paginate with
+    /// opaque cursors in their `Link` URLs (e.g., the webhook deliveries
+    /// endpoint) also use this style, though page numbers cannot be used to
+    /// start in the middle of such listings.
+    #[default]
+    PageNumbers,
+
+    /// Pages are selected with a `since` query parameter giving the ID of the
+    /// last item on the previous page, as used by `GET /repositories`,
+    /// `GET /users`, and `GET /organizations`.  The `Link` headers for such
+    /// endpoints only give the URL of the next page.
+    ///
+    /// With this style, no `page` parameter is sent (and so
+    /// [`PaginationRequest::start_page()`] is ignored), pages are never
+    /// fetched concurrently, and [`PaginationRequest::reverse()`] is ignored.
+    /// [`PaginationRequest::end_page()`] still limits the number of pages
+    /// fetched.  To start a listing partway through, supply a `since`
+    /// parameter via [`PaginationRequest::params()`]; the value to resume from
+    /// can be extracted from a page's `next_url` with [`since_value()`].
+    Since,
+}
+
+/// Extract the value of the `since` query parameter from a URL, such as the
+/// `next_url` of a [`PageResponse`] for an endpoint with a
+/// [`PaginationStyle`] of [`PaginationStyle::Since`]
+pub fn since_value(url: &HttpUrl) -> Option<u64> {
+    url.as_url()
+        .query_pairs()
+        .filter_map(|(k, v)| (k == "since").then(|| v.parse::<u64>().ok()))
+        .last()
+        .flatten()
+}
+
 pub trait PaginationRequest {
     type Item: DeserializeOwned + Send;
 
@@ -271,6 +622,99 @@ pub trait PaginationRequest {
         Vec::new()
     }
 
+    /// Returns a value to encode as additional query parameters for the first
+    /// page; see [`Request::query()`][crate::request::Request::query]
+    fn query(&self) -> impl Serialize {}
+
+    /// Returns the number of items to request per page, which is sent as the
+    /// `per_page` query parameter of the first request (subsequent requests
+    /// inherit it via the `Link` URLs).  Returning `None` leaves the page size
+    /// up to the server, which defaults to 30 for most endpoints.
+    ///
+    /// The parameter is not added if [`PaginationRequest::params()`] or
+    /// [`PaginationRequest::query()`] already supplies a `per_page` value.
+    /// The default implementation returns `Some(100)`, the maximum allowed by
+    /// most GitHub endpoints, in order to minimize the number of requests.
+    fn per_page(&self) -> Option<u8> {
+        Some(100)
+    }
+
+    /// Returns how the endpoint selects pages of results.  The default
+    /// implementation returns [`PaginationStyle::PageNumbers`].
+    ///
+    /// Requests for endpoints that paginate with a `since` parameter, such as
+    /// `GET /repositories` and `GET /users`, should return
+    /// [`PaginationStyle::Since`].
+    fn style(&self) -> PaginationStyle {
+        PaginationStyle::PageNumbers
+    }
+
+    /// Returns the number of the first page to fetch, which is sent as the
+    /// `page` query parameter of the first request (unless
+    /// [`PaginationRequest::params()`] or [`PaginationRequest::query()`]
+    /// already supplies a `page` value).  The default implementation returns
+    /// `None`, causing pagination to start at the first page.
+    ///
+    /// This is ignored for requests with a [`PaginationStyle`] of
+    /// [`PaginationStyle::Since`].
+    fn start_page(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the number of the last page to fetch (inclusive), after which
+    /// pagination stops even if the server reports further pages.  The
+    /// default implementation returns `None`, causing pagination to continue
+    /// until the last page.
+    ///
+    /// Pages are counted from [`PaginationRequest::start_page()`] (or 1) by
+    /// the number of requests made, so this also works for endpoints that use
+    /// cursors rather than page numbers in their `Link` URLs.
+    fn end_page(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns whether to paginate in reverse, starting from the last page and
+    /// following `rel="prev"` links back to the first page.  The items of
+    /// each page are also yielded in reverse order, so that the items of the
+    /// whole listing are yielded last-to-first.  The default implementation
+    /// returns `false`.
+    ///
+    /// As GitHub only reports the URL of the last page in the `Link` header
+    /// of other pages, reverse pagination begins by requesting the first page
+    /// and then discarding it (unless it is the only page).  Page ranges set
+    /// via [`PaginationRequest::start_page()`] and
+    /// [`PaginationRequest::end_page()`] are not applied when paginating in
+    /// reverse.  Requests with a [`PaginationStyle`] of
+    /// [`PaginationStyle::Since`] cannot be paginated in reverse, and this
+    /// method is ignored for them.
+    ///
+    /// This is useful for endpoints that list items oldest-first when only
+    /// the most recent items are of interest.  See also [`Reversed`].
+    fn reverse(&self) -> bool {
+        false
+    }
+
+    /// Returns the configuration for adapting the page size and request rate
+    /// to the server's responsiveness and the remaining rate limit, or `None`
+    /// (the default) to disable adaptive pagination.  See [`AdaptivePaging`]
+    /// and [`Adaptive`] for details.
+    fn adaptive(&self) -> Option<AdaptivePaging> {
+        None
+    }
+
+    /// Called with each page of results as soon as it is received; if this
+    /// returns `false`, no further pages are requested, though the items of
+    /// the given page are still yielded.
+    ///
+    /// This allows stopping a pagination early once a condition is met, e.g.,
+    /// once a page contains an event older than a given timestamp, without
+    /// having to download the rest of the listing.  The default
+    /// implementation always returns `true`.
+    fn should_continue(&self, page: &PageResponse<Self::Item>) -> bool {
+        let _ = page;
+        true
+    }
+
     fn headers(&self) -> HeaderMap {
         HeaderMap::new()
     }
@@ -279,28 +723,98 @@ pub trait PaginationRequest {
     fn timeout(&self) -> Option<Duration> {
         None
     }
+
+    /// Returns media types to merge into the `Accept` header of each page
+    /// request; see [`Request::extra_accept()`].  The default implementation
+    /// returns an empty list.
+    fn extra_accept(&self) -> AcceptList {
+        AcceptList::new()
+    }
+
+    /// Returns the parser to use for each page of results.  The default
+    /// implementation returns [`PageParser::new()`].
+    ///
+    /// Override this to configure how the items are located in pages that
+    /// are JSON objects, e.g., for endpoints whose items are nested more than
+    /// one level deep (see [`ItemsDetection::Pointer`]) or whose pages
+    /// contain other arrays alongside the items.
+    fn page_parser(&self) -> PageParser<Self::Item> {
+        PageParser::new()
+    }
+}
+
+/// An iterator over the pages of a [`PaginationRequest`], returned by
+/// [`Client::paginate_pages()`]
+///
+/// Each page is yielded as a [`PageResponse`] containing the page's items,
+/// its [`PaginationInfo`], and the URL of the next page, if any.
+#[derive(Clone, Debug)]
+pub struct PageIter<'a, B, R> {
+    cursor: PageCursor<&'a Client<B>, R>,
+}
+
+impl<'a, B, R: PaginationRequest> PageIter<'a, B, R> {
+    pub fn new(client: &'a Client<B>, req: R) -> Self {
+        PageIter {
+            cursor: PageCursor::new(client, req),
+        }
+    }
+
+    pub fn state(&self) -> PaginationState {
+        self.cursor.state()
+    }
+
+    /// Call `f` after each page is fetched with the progress of the
+    /// pagination so far; see [`PageCursor::on_page()`]
+    pub fn on_page<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&PageProgress) + Send + Sync + 'static,
+    {
+        self.cursor = self.cursor.on_page(f);
+        self
+    }
+
+    /// Convert the iterator into a [`PageCursor`] positioned at the same
+    /// page
+    pub fn into_cursor(self) -> PageCursor<&'a Client<B>, R> {
+        self.cursor
+    }
+}
+
+impl<B, R> Iterator for PageIter<'_, B, R>
+where
+    B: Backend,
+    R: PaginationRequest<Item: DeserializeOwned + Send>,
+{
+    type Item = Result<PageResponse<R::Item>, crate::errors::Error<B::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next_page().transpose()
+    }
+}
+
+impl<B, R> std::iter::FusedIterator for PageIter<'_, B, R>
+where
+    B: Backend,
+    R: PaginationRequest<Item: DeserializeOwned + Send>,
+{
 }
 
 #[derive(Clone, Debug)]
 pub struct PaginationIter<'a, B, R: PaginationRequest> {
-    client: &'a Client<B>,
-    req: R,
-    next_url: Option<Endpoint>,
-    info: Option<PaginationInfo>,
+    pages: PageCursor<&'a Client<B>, R>,
     items: Option<std::vec::IntoIter<R::Item>>,
-    state: PaginationState,
+    info: Option<PaginationInfo>,
+    page_len: usize,
 }
 
 impl<'a, B, R: PaginationRequest> PaginationIter<'a, B, R> {
     pub fn new(client: &'a Client<B>, req: R) -> Self {
-        let next_url = Some(req.endpoint());
         PaginationIter {
-            client,
-            req,
-            next_url,
-            info: None,
+            pages: PageCursor::new(client, req),
             items: None,
-            state: PaginationState::NotStarted,
+            info: None,
+            page_len: 0,
         }
     }
 
@@ -309,7 +823,23 @@ impl<'a, B, R: PaginationRequest> PaginationIter<'a, B, R> {
     }
 
     pub fn state(&self) -> PaginationState {
-        self.state
+        self.pages.state()
+    }
+
+    /// Call `f` after each page is fetched with the progress of the
+    /// pagination so far, e.g., in order to display a progress bar while
+    /// iterating over a large listing; see [`PageCursor::on_page()`]
+    pub fn on_page<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&PageProgress) + Send + Sync + 'static,
+    {
+        self.pages = self.pages.on_page(f);
+        self
+    }
+
+    /// [Private] Returns the request being paginated
+    pub(super) fn request(&self) -> &R {
+        self.pages.request()
     }
 }
 
@@ -325,35 +855,44 @@ where
             if let Some(item) = self.items.as_mut().and_then(Iterator::next) {
                 return Some(Ok(item));
             }
-            if let Some(url) = self.next_url.as_ref() {
-                let mut req = PageRequest::new(url.clone())
-                    .with_headers(self.req.headers())
-                    .with_timeout(self.req.timeout());
-                if self.state == PaginationState::NotStarted {
-                    req = req.with_params(self.req.params());
+            match self.pages.next_page().transpose() {
+                Some(Ok(page_resp)) => {
+                    self.page_len = page_resp.items.len();
+                    self.items = Some(page_resp.items.into_iter());
+                    self.info = Some(page_resp.info);
+                }
+                Some(Err(e)) => {
+                    self.items = None;
+                    self.info = None;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.items = None;
+                    self.info = None;
+                    return None;
                 }
-                let page_resp = match self.client.request(req) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        self.next_url = None;
-                        self.state = PaginationState::Ended;
-                        self.items = None;
-                        self.info = None;
-                        return Some(Err(e));
-                    }
-                };
-                self.state = PaginationState::Paging;
-                self.next_url = page_resp.next_url.map(Into::into);
-                self.items = Some(page_resp.items.into_iter());
-                self.info = Some(page_resp.info);
-            } else {
-                self.state = PaginationState::Ended;
-                self.items = None;
-                self.info = None;
-                return None;
             }
         }
     }
+
+    /// Estimates the number of remaining items from the `total_count` and
+    /// last page number reported for the most recent page, assuming that all
+    /// pages before the last have the same number of items as the current
+    /// one.  Nothing is known about the number of items until the first page
+    /// has been fetched, and, when paginating in reverse, no estimate is made
+    /// beyond the items of the current page.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.items.as_ref().map_or(0, ExactSizeIterator::len);
+        estimate_remaining(
+            buffered,
+            self.page_len,
+            self.info
+                .as_ref()
+                .filter(|_| !is_reverse(self.pages.request())),
+            self.pages.has_next_page(),
+            self.pages.pages_left(),
+        )
+    }
 }
 
 impl<B, R> std::iter::FusedIterator for PaginationIter<'_, B, R>
@@ -363,6 +902,317 @@ where
 {
 }
 
+/// [Private] If `req` paginates in reverse and `first_page` (the response to
+/// the first request) is not the last page, returns the URL of the last page,
+/// at which reverse pagination actually starts
+fn reverse_start<R: PaginationRequest>(
+    req: &R,
+    first_page: &PageResponse<R::Item>,
+) -> Option<Endpoint> {
+    if is_reverse(req) {
+        first_page.last_url.clone().map(Into::into)
+    } else {
+        None
+    }
+}
+
+/// [Private] Prepare a newly-received page for yielding by reversing its
+/// items if `req` paginates in reverse, and return the URL of the page to
+/// fetch after it, if any
+fn following_url<R: PaginationRequest>(
+    req: &R,
+    page: &mut PageResponse<R::Item>,
+) -> Option<Endpoint> {
+    let reverse = is_reverse(req);
+    if reverse {
+        page.items.reverse();
+    }
+    if !req.should_continue(page) {
+        None
+    } else if reverse {
+        page.prev_url.clone().map(Into::into)
+    } else {
+        page.next_url.clone().map(Into::into)
+    }
+}
+
+/// [Private] Returns true if `req` is to be paginated in reverse, which is
+/// only possible for endpoints that paginate by page number
+fn is_reverse<R: PaginationRequest>(req: &R) -> bool {
+    req.reverse() && req.style() == PaginationStyle::PageNumbers
+}
+
+/// [Private] Returns the query parameters to send with the first page of a
+/// pagination request: those returned by `params()` followed by the encoded
+/// `query()`
+fn first_page_params<R: PaginationRequest>(req: &R) -> Result<Vec<(String, String)>, CommonError> {
+    let mut params = req.params();
+    params.extend(encode_query(&req.query())?);
+    if let Some(n) = req.per_page()
+        && !params.iter().any(|(k, _)| k == "per_page")
+    {
+        params.push(("per_page".into(), n.to_string()));
+    }
+    if let Some(n) = req.start_page()
+        && n > 1
+        && req.style() == PaginationStyle::PageNumbers
+        && !req.reverse()
+        && !params.iter().any(|(k, _)| k == "page")
+    {
+        params.push(("page".into(), n.to_string()));
+    }
+    Ok(params)
+}
+
+/// [Private] Returns bounds on the number of items remaining in a pagination,
+/// given the number of not-yet-yielded items of the current page, the total
+/// number of items on the current page, the current page's pagination
+/// information, whether there are further pages to fetch, and the remaining
+/// page budget.
+///
+/// If `total_count` is known, it is used to compute an exact estimate (capped
+/// by the last page and the page budget, as the search endpoints report more
+/// matches than they will paginate through); otherwise, the number of pages
+/// after the current one gives a range.
+fn estimate_remaining(
+    buffered: usize,
+    page_len: usize,
+    info: Option<&PaginationInfo>,
+    more_pages: bool,
+    pages_left: Option<u64>,
+) -> (usize, Option<usize>) {
+    if !more_pages {
+        return (buffered, Some(buffered));
+    }
+    let Some(info) = info else {
+        return (buffered, None);
+    };
+    if page_len == 0 {
+        return (buffered, None);
+    }
+    let page_size = page_len as u64;
+    let current = info.current_page.unwrap_or(1);
+    let pages_after = match (
+        info.last_page.map(|n| n.saturating_sub(current)),
+        pages_left,
+    ) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    let to_usize = |n: u64| usize::try_from(n).ok();
+    let buffered_u64 = buffered as u64;
+    if let Some(total) = info.total_count {
+        let mut after = total.saturating_sub(current.saturating_mul(page_size));
+        if let Some(pages) = pages_after {
+            after = after.min(pages.saturating_mul(page_size));
+        }
+        let n = buffered_u64.saturating_add(after);
+        (to_usize(n).unwrap_or(usize::MAX), to_usize(n))
+    } else if let Some(pages) = pages_after
+        && info.last_page.is_some()
+    {
+        let upper = buffered_u64.saturating_add(pages.saturating_mul(page_size));
+        let lower = match pages {
+            0 => buffered_u64,
+            _ => upper.saturating_sub(page_size - 1),
+        };
+        (to_usize(lower).unwrap_or(usize::MAX), to_usize(upper))
+    } else {
+        (buffered, None)
+    }
+}
+
+/// [Private] Returns the maximum number of pages to fetch for a pagination
+/// request, or `None` if there is no limit
+fn page_budget<R: PaginationRequest>(req: &R) -> Option<u64> {
+    if is_reverse(req) {
+        return None;
+    }
+    let start = match req.style() {
+        PaginationStyle::PageNumbers => req.start_page().unwrap_or(1).max(1),
+        PaginationStyle::Since => 1,
+    };
+    req.end_page()
+        .map(|end| end.saturating_add(1).saturating_sub(start))
+}
+
+/// A wrapper around a [`PaginationRequest`] that only fetches the pages in a
+/// given range of page numbers
+///
+/// This can be used to resume a pagination from a known page or to fetch only
+/// a slice of a large result set.  See [`PaginationRequest::start_page()`]
+/// and [`PaginationRequest::end_page()`] for details.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithPageRange<R> {
+    request: R,
+    start: Option<u64>,
+    end: Option<u64>,
+}
+
+impl<R> WithPageRange<R> {
+    /// Restrict `request` to the pages in `range`, e.g., `5..=10` or `3..`
+    pub fn new<T: RangeBounds<u64>>(request: R, range: T) -> WithPageRange<R> {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n.saturating_add(1)),
+            Bound::Unbounded => None,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n.saturating_sub(1)),
+            Bound::Unbounded => None,
+        };
+        WithPageRange {
+            request,
+            start,
+            end,
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.request
+    }
+
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: PaginationRequest> PaginationRequest for WithPageRange<R> {
+    type Item = R::Item;
+
+    fn endpoint(&self) -> Endpoint {
+        self.request.endpoint()
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.request.params()
+    }
+
+    fn query(&self) -> impl Serialize {
+        self.request.query()
+    }
+
+    fn per_page(&self) -> Option<u8> {
+        self.request.per_page()
+    }
+
+    fn style(&self) -> PaginationStyle {
+        self.request.style()
+    }
+
+    fn start_page(&self) -> Option<u64> {
+        self.start
+    }
+
+    fn end_page(&self) -> Option<u64> {
+        self.end
+    }
+
+    fn reverse(&self) -> bool {
+        self.request.reverse()
+    }
+
+    fn adaptive(&self) -> Option<AdaptivePaging> {
+        self.request.adaptive()
+    }
+
+    fn should_continue(&self, page: &PageResponse<Self::Item>) -> bool {
+        self.request.should_continue(page)
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.request.headers()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+
+    fn extra_accept(&self) -> AcceptList {
+        self.request.extra_accept()
+    }
+
+    fn page_parser(&self) -> PageParser<Self::Item> {
+        self.request.page_parser()
+    }
+}
+
+/// A wrapper around a [`PaginationRequest`] that paginates in reverse,
+/// yielding the items of the last page first
+///
+/// See [`PaginationRequest::reverse()`] for details.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reversed<R> {
+    request: R,
+}
+
+impl<R> Reversed<R> {
+    pub fn new(request: R) -> Reversed<R> {
+        Reversed { request }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.request
+    }
+
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: PaginationRequest> PaginationRequest for Reversed<R> {
+    type Item = R::Item;
+
+    fn endpoint(&self) -> Endpoint {
+        self.request.endpoint()
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.request.params()
+    }
+
+    fn query(&self) -> impl Serialize {
+        self.request.query()
+    }
+
+    fn per_page(&self) -> Option<u8> {
+        self.request.per_page()
+    }
+
+    fn style(&self) -> PaginationStyle {
+        self.request.style()
+    }
+
+    fn reverse(&self) -> bool {
+        true
+    }
+
+    fn adaptive(&self) -> Option<AdaptivePaging> {
+        self.request.adaptive()
+    }
+
+    fn should_continue(&self, page: &PageResponse<Self::Item>) -> bool {
+        self.request.should_continue(page)
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.request.headers()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+
+    fn extra_accept(&self) -> AcceptList {
+        self.request.extra_accept()
+    }
+
+    fn page_parser(&self) -> PageParser<Self::Item> {
+        self.request.page_parser()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum PaginationState {
     NotStarted,
@@ -374,10 +1224,637 @@ pub enum PaginationState {
 mod tests {
     use super::*;
 
+    #[rstest::rstest]
+    #[case(7, 10, None, true, None, (7, None))]
+    #[case(7, 10, Some(PaginationInfo::new()), true, None, (7, None))]
+    #[case(7, 10, Some(PaginationInfo::new()), false, None, (7, Some(7)))]
+    #[case(7, 10, Some(PaginationInfo::new().with_total_count(95)), true, None, (92, Some(92)))]
+    #[case(
+        7,
+        10,
+        Some(PaginationInfo::new().with_current_page(3).with_total_count(95)),
+        true,
+        None,
+        (72, Some(72))
+    )]
+    #[case(
+        7,
+        10,
+        Some(PaginationInfo::new().with_total_count(95)),
+        true,
+        Some(2),
+        (27, Some(27))
+    )]
+    #[case(
+        7,
+        10,
+        Some(PaginationInfo::new().with_last_page(3).with_total_count(1000)),
+        true,
+        None,
+        (27, Some(27))
+    )]
+    #[case(7, 10, Some(PaginationInfo::new().with_last_page(4)), true, None, (28, Some(37)))]
+    #[case(
+        7,
+        10,
+        Some(PaginationInfo::new().with_current_page(4).with_last_page(4)),
+        true,
+        None,
+        (7, Some(7))
+    )]
+    #[case(7, 10, Some(PaginationInfo::new().with_last_page(4)), true, Some(1), (8, Some(17)))]
+    #[case(0, 0, Some(PaginationInfo::new().with_total_count(95)), true, None, (0, None))]
+    fn test_estimate_remaining(
+        #[case] buffered: usize,
+        #[case] page_len: usize,
+        #[case] info: Option<PaginationInfo>,
+        #[case] more_pages: bool,
+        #[case] pages_left: Option<u64>,
+        #[case] expected: (usize, Option<usize>),
+    ) {
+        assert_eq!(
+            estimate_remaining(buffered, page_len, info.as_ref(), more_pages, pages_left),
+            expected
+        );
+    }
+
+    mod iter {
+        use super::*;
+        use crate::{
+            client::{ClientConfig, RequestParts},
+            errors::ErrorPayload,
+            testing::{PageShape, PaginationFixtures},
+        };
+        use rstest::rstest;
+
+        #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+        struct Item {
+            id: u64,
+        }
+
+        struct ListItems;
+
+        impl PaginationRequest for ListItems {
+            type Item = Item;
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["items"])
+            }
+        }
+
+        #[rstest]
+        fn collect_all(
+            #[values(0, 1, 10, 25)] n: u64,
+            #[values(1, 10, 100)] page_size: usize,
+            #[values(PageShape::Array, PageShape::Object { key: "items".into() })] shape: PageShape,
+        ) {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), n)
+                    .with_page_size(page_size)
+                    .with_shape(shape);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let ids = client
+                .paginate(ListItems)
+                .map(|r| r.map(|item| item.id))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(ids, (1..=n).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn paginate_all() {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                    .with_page_size(10);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let items = client.paginate_all(ListItems).unwrap();
+            assert_eq!(
+                items.into_iter().map(|item| item.id).collect::<Vec<_>>(),
+                (1..=25).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn paginate_all_error() {
+            let client = ClientConfig::new().with_backend(crate::client::FnBackend::new(|_, _| {
+                http::Response::builder()
+                    .status(500)
+                    .body(b"{\"message\": \"Server Error\"}".to_vec())
+                    .unwrap()
+            }));
+            let e = client.paginate_all(ListItems).unwrap_err();
+            assert!(matches!(e.payload_ref(), ErrorPayload::Status(_)));
+        }
+
+        #[rstest]
+        #[case(WithPageRange::new(ListItems, 2..=3), (6..=15).collect())]
+        #[case(WithPageRange::new(ListItems, 4..), (16..=25).collect())]
+        #[case(WithPageRange::new(ListItems, ..2), (1..=5).collect())]
+        #[case(WithPageRange::new(ListItems, 5..=9), (21..=25).collect())]
+        #[case(WithPageRange::new(ListItems, 7..), Vec::new())]
+        fn page_range(#[case] req: WithPageRange<ListItems>, #[case] expected: Vec<u64>) {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                    .with_page_size(5);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let ids = client
+                .paginate(req)
+                .map(|r| r.map(|item| item.id))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(ids, expected);
+        }
+
+        #[rstest]
+        #[case(25, 10)]
+        #[case(25, 5)]
+        #[case(7, 10)]
+        #[case(0, 10)]
+        fn reverse(#[case] n: u64, #[case] page_size: usize) {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), n)
+                    .with_page_size(page_size);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let ids = client
+                .paginate(Reversed::new(ListItems))
+                .map(|r| r.map(|item| item.id))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(ids, (1..=n).rev().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn reverse_pages() {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                    .with_page_size(10);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let pages = client
+                .paginate_pages(Reversed::new(ListItems))
+                .map(|r| r.map(|page| page.info.current_page))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(pages, [Some(3), Some(2), Some(1)]);
+        }
+
+        #[test]
+        fn size_hint() {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                    .with_page_size(10);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let mut iter = client.paginate(ListItems);
+            assert_eq!(iter.size_hint(), (0, None));
+            iter.next().unwrap().unwrap();
+            assert_eq!(iter.size_hint(), (20, Some(29)));
+            let mut iter = iter.skip(9);
+            iter.next().unwrap().unwrap();
+            assert_eq!(iter.size_hint(), (10, Some(19)));
+            let mut iter = iter.skip(9);
+            iter.next().unwrap().unwrap();
+            assert_eq!(iter.size_hint(), (4, Some(4)));
+        }
+
+        #[test]
+        fn paginate_pages() {
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                    .with_page_size(10);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let mut pages = client.paginate_pages(ListItems);
+            assert_eq!(pages.state(), PaginationState::NotStarted);
+            let mut sizes = Vec::new();
+            let mut next_urls = Vec::new();
+            for page in pages.by_ref() {
+                let page = page.unwrap();
+                sizes.push(page.items.len());
+                next_urls.push(page.next_url.map(|u| u.to_string()));
+            }
+            assert_eq!(sizes, [10, 10, 5]);
+            assert_eq!(
+                next_urls,
+                [
+                    Some(String::from("https://api.github.com/items?page=2")),
+                    Some(String::from("https://api.github.com/items?page=3")),
+                    None
+                ]
+            );
+            assert_eq!(pages.state(), PaginationState::Ended);
+        }
+
+        #[test]
+        fn should_continue() {
+            struct ListUntil(u64);
+
+            impl PaginationRequest for ListUntil {
+                type Item = Item;
+
+                fn endpoint(&self) -> Endpoint {
+                    Endpoint::from_iter(["items"])
+                }
+
+                fn should_continue(&self, page: &PageResponse<Item>) -> bool {
+                    page.items.iter().all(|item| item.id < self.0)
+                }
+            }
+
+            let fixtures =
+                PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                    .with_page_size(5);
+            let client = ClientConfig::new().with_backend(fixtures.backend());
+            let ids = client
+                .paginate(ListUntil(8))
+                .map(|r| r.map(|item| item.id))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(ids, (1..=10).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn empty_page_range() {
+            let client = ClientConfig::new().with_backend(crate::client::FnBackend::new(
+                |_, _| -> http::Response<Vec<u8>> { panic!("No request should be made") },
+            ));
+            let mut iter = client.paginate(WithPageRange::new(ListItems, 3..3));
+            assert!(iter.next().is_none());
+            assert_eq!(iter.state(), PaginationState::Ended);
+        }
+
+        #[test]
+        fn query_on_first_page() {
+            struct ListOpen;
+
+            impl PaginationRequest for ListOpen {
+                type Item = Item;
+
+                fn endpoint(&self) -> Endpoint {
+                    Endpoint::from_iter(["items"])
+                }
+
+                fn query(&self) -> impl Serialize {
+                    [("state", "open")]
+                }
+            }
+
+            let client =
+                ClientConfig::new().with_backend(crate::client::FnBackend::new(|parts, _| {
+                    let (body, link) = if parts.url.as_str().contains("page=2") {
+                        (r#"[{"id": 2}]"#, None)
+                    } else {
+                        assert_eq!(
+                            parts.url.as_str(),
+                            "https://api.github.com/items?state=open&per_page=100"
+                        );
+                        (
+                            r#"[{"id": 1}]"#,
+                            Some(r#"<https://api.github.com/items?state=open&page=2>; rel="next""#),
+                        )
+                    };
+                    let mut resp = http::Response::builder();
+                    if let Some(link) = link {
+                        resp = resp.header(http::header::LINK, link);
+                    }
+                    resp.body(body.as_bytes().to_vec()).unwrap()
+                }));
+            let ids = client
+                .paginate(ListOpen)
+                .map(|r| r.map(|item| item.id))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(ids, [1, 2]);
+        }
+
+        #[test]
+        fn per_page_on_first_page() {
+            struct ListSized(Option<u8>, Vec<(String, String)>);
+
+            impl PaginationRequest for ListSized {
+                type Item = Item;
+
+                fn endpoint(&self) -> Endpoint {
+                    Endpoint::from_iter(["items"])
+                }
+
+                fn params(&self) -> Vec<(String, String)> {
+                    self.1.clone()
+                }
+
+                fn per_page(&self) -> Option<u8> {
+                    self.0
+                }
+            }
+
+            let per_page = |n: &str| (String::from("per_page"), String::from(n));
+            assert_eq!(
+                first_page_params(&ListSized(Some(50), Vec::new())).unwrap(),
+                [per_page("50")]
+            );
+            assert_eq!(first_page_params(&ListSized(None, Vec::new())).unwrap(), []);
+            assert_eq!(
+                first_page_params(&ListSized(Some(50), vec![per_page("10")])).unwrap(),
+                [per_page("10")]
+            );
+        }
+
+        struct ListSince;
+
+        impl PaginationRequest for ListSince {
+            type Item = Item;
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["repositories"])
+            }
+
+            fn per_page(&self) -> Option<u8> {
+                None
+            }
+
+            fn style(&self) -> PaginationStyle {
+                PaginationStyle::Since
+            }
+        }
+
+        #[test]
+        fn custom_page_parser() {
+            struct ListNested;
+
+            impl PaginationRequest for ListNested {
+                type Item = Item;
+
+                fn endpoint(&self) -> Endpoint {
+                    Endpoint::from_iter(["nested"])
+                }
+
+                fn page_parser(&self) -> PageParser<Item> {
+                    PageParser::new()
+                        .with_items_detection(ItemsDetection::Pointer("/data/items".into()))
+                }
+            }
+
+            let client =
+                ClientConfig::new().with_backend(crate::client::FnBackend::new(|parts, _| {
+                    let (body, link) = if parts.url.as_str().contains("page=2") {
+                        (r#"{"data": {"items": [{"id": 3}], "tags": []}}"#, None)
+                    } else {
+                        (
+                            r#"{"data": {"items": [{"id": 1}, {"id": 2}], "tags": []}}"#,
+                            Some(r#"<https://api.github.com/nested?page=2>; rel="next""#),
+                        )
+                    };
+                    let mut resp = http::Response::builder();
+                    if let Some(link) = link {
+                        resp = resp.header(http::header::LINK, link);
+                    }
+                    resp.body(body.as_bytes().to_vec()).unwrap()
+                }));
+            let ids = client
+                .paginate(ListNested)
+                .map(|r| r.map(|item| item.id))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(ids, [1, 2, 3]);
+        }
+
+        /// A fake `GET /repositories` endpoint that lists items with IDs
+        /// `1..=25` (skipping multiples of 7) ten at a time
+        fn since_backend()
+        -> crate::client::FnBackend<impl Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>>>
+        {
+            crate::client::FnBackend::new(|parts: RequestParts, _| {
+                assert!(!parts.url.as_url().query_pairs().any(|(k, _)| k == "page"));
+                let since = since_value(&parts.url).unwrap_or(0);
+                let ids = (since + 1..=25)
+                    .filter(|n| n % 7 != 0)
+                    .take(10)
+                    .collect::<Vec<_>>();
+                let body = serde_json::Value::from(
+                    ids.iter()
+                        .map(|&id| serde_json::json!({"id": id}))
+                        .collect::<Vec<_>>(),
+                );
+                let mut resp = http::Response::builder()
+                    .status(200)
+                    .header("content-type", "application/json");
+                if let Some(last) = ids.last()
+                    && *last < 25
+                {
+                    resp = resp.header(
+                        "link",
+                        format!(
+                            "<https://api.github.com/repositories?since={last}>; rel=\"next\", \
+                             <https://api.github.com/repositories{{?since}}>; rel=\"first\""
+                        ),
+                    );
+                }
+                resp.body(body.to_string().into_bytes()).unwrap()
+            })
+        }
+
+        #[test]
+        fn since_pagination() {
+            let client = ClientConfig::new().with_backend(since_backend());
+            let ids = client
+                .paginate(ListSince)
+                .map(|r| r.unwrap().id)
+                .collect::<Vec<_>>();
+            assert_eq!(ids, (1..=25).filter(|n| n % 7 != 0).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn since_ignores_start_page_and_reverse() {
+            let client = ClientConfig::new().with_backend(since_backend());
+            let ids = client
+                .paginate(WithPageRange::new(ListSince, 2..=2))
+                .map(|r| r.unwrap().id)
+                .collect::<Vec<_>>();
+            assert_eq!(ids, (1..=23).filter(|n| n % 7 != 0).collect::<Vec<_>>());
+            let ids = client
+                .paginate(Reversed::new(ListSince))
+                .map(|r| r.unwrap().id)
+                .collect::<Vec<_>>();
+            assert_eq!(ids, (1..=25).filter(|n| n % 7 != 0).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn test_since_value() {
+            let url = "https://api.github.com/users?per_page=50&since=135"
+                .parse::<HttpUrl>()
+                .unwrap();
+            assert_eq!(since_value(&url), Some(135));
+            let url = "https://api.github.com/users?page=2"
+                .parse::<HttpUrl>()
+                .unwrap();
+            assert_eq!(since_value(&url), None);
+        }
+    }
+
+    mod ser_page {
+        use super::*;
+        use serde_json::json;
+
+        #[test]
+        fn array_roundtrip() {
+            let page = Page::new(vec![1, 2, 3]);
+            let value = serde_json::to_value(&page).unwrap();
+            assert_eq!(value, json!([1, 2, 3]));
+            assert_eq!(serde_json::from_value::<Page<u32>>(value).unwrap(), page);
+        }
+
+        #[test]
+        fn map_roundtrip() {
+            let page = Page::new(vec![1, 2, 3])
+                .with_total_count(100)
+                .with_incomplete_results(false);
+            let value = serde_json::to_value(&page).unwrap();
+            assert_eq!(
+                value,
+                json!({
+                    "total_count": 100,
+                    "incomplete_results": false,
+                    "items": [1, 2, 3],
+                })
+            );
+            assert_eq!(serde_json::from_value::<Page<u32>>(value).unwrap(), page);
+        }
+
+        #[test]
+        fn page_response_roundtrip() {
+            let resp = PageResponse::new(vec![String::from("foo")])
+                .with_next_url("https://api.github.com/items?page=3".parse().unwrap())
+                .with_info(PaginationInfo::new().with_current_page(2).with_last_page(5));
+            let s = serde_json::to_string(&resp).unwrap();
+            assert_eq!(
+                serde_json::from_str::<PageResponse<String>>(&s).unwrap(),
+                resp
+            );
+        }
+    }
+
     mod deser_page {
         use super::*;
+        use crate::{parser::ResponseParserExt, response::Response};
         use indoc::indoc;
 
+        fn parse_with<T: DeserializeOwned>(
+            detection: ItemsDetection,
+            src: &str,
+        ) -> Result<Page<T>, ParsePageError> {
+            serde_json::from_str::<RawPage>(src)
+                .unwrap()
+                .into_page(&detection)
+        }
+
+        #[test]
+        fn detection_any_array_ambiguous() {
+            let src = r#"{"total_count": 0, "items": [], "errors": []}"#;
+            assert!(matches!(
+                parse_with::<Widget>(ItemsDetection::AnyArray, src),
+                Err(ParsePageError::ListQty(2))
+            ));
+        }
+
+        #[test]
+        fn detection_non_scalar() {
+            let src = indoc! {r#"
+            {
+                "total_count": 1,
+                "widgets": [{"name": "Steve", "color": "aquamarine", "power": 9001}],
+                "errors": [],
+                "tags": ["a", "b"]
+            }
+            "#};
+            let page = parse_with::<serde_json::Value>(ItemsDetection::NonScalar, src).unwrap();
+            assert_eq!(page.items.len(), 1);
+            assert_eq!(page.total_count, Some(1));
+            let src = r#"{"total_count": 0, "items": [], "errors": []}"#;
+            let page = parse_with::<Widget>(ItemsDetection::NonScalar, src).unwrap();
+            assert!(page.items.is_empty());
+        }
+
+        #[test]
+        fn detection_key_prefix() {
+            let detection = ItemsDetection::KeyPrefix("workflow_".into());
+            let src = indoc! {r#"
+            {
+                "total_count": 1,
+                "workflow_runs": [{"name": "Steve", "color": "aquamarine", "power": 9001}],
+                "errors": []
+            }
+            "#};
+            let page = parse_with::<Widget>(detection.clone(), src).unwrap();
+            assert_eq!(page.items[0].name, "Steve");
+            let src = r#"{"total_count": 0, "workflow_runs": null, "errors": []}"#;
+            let page = parse_with::<Widget>(detection.clone(), src).unwrap();
+            assert!(page.items.is_empty());
+            let src = r#"{"total_count": 0, "errors": []}"#;
+            assert!(matches!(
+                parse_with::<Widget>(detection, src),
+                Err(ParsePageError::ListQty(0))
+            ));
+        }
+
+        #[test]
+        fn detection_pointer() {
+            let detection = ItemsDetection::Pointer("/data/items".into());
+            let src = indoc! {r#"
+            {
+                "total_count": 1,
+                "data": {
+                    "items": [{"name": "Steve", "color": "aquamarine", "power": 9001}],
+                    "errors": []
+                }
+            }
+            "#};
+            let page = parse_with::<Widget>(detection.clone(), src).unwrap();
+            assert_eq!(page.items[0].name, "Steve");
+            assert_eq!(page.total_count, Some(1));
+            let src = r#"{"data": {"items": null}}"#;
+            let page = parse_with::<Widget>(detection.clone(), src).unwrap();
+            assert!(page.items.is_empty());
+            let src = r#"{"data": {}}"#;
+            assert!(matches!(
+                parse_with::<Widget>(detection, src),
+                Err(ParsePageError::ListQty(0))
+            ));
+        }
+
+        #[test]
+        fn parser_items_detection() {
+            let parts = ResponseParts::new(
+                "https://api.github.com/widgets".parse().unwrap(),
+                Method::Get,
+                http::StatusCode::OK,
+            );
+            let body = br#"{"widgets": [{"name": "Steve", "color": "aquamarine", "power": 9001}], "errors": []}"#;
+            let r = PageParser::<Widget>::new()
+                .parse_response(Response::from_parts(parts.clone(), &body[..]));
+            assert!(r.is_err());
+            let page = PageParser::<Widget>::new()
+                .with_items_detection(ItemsDetection::NonScalar)
+                .parse_response(Response::from_parts(parts, &body[..]))
+                .unwrap();
+            assert_eq!(page.items.len(), 1);
+        }
+
+        #[cfg(feature = "simd-json")]
+        #[test]
+        fn parser_simd_json() {
+            let parts = ResponseParts::new(
+                "https://api.github.com/widgets?page=2".parse().unwrap(),
+                Method::Get,
+                http::StatusCode::OK,
+            );
+            let body = br#"{"total_count": 1, "items": [{"name": "St\u00e9ve", "color": "aquamarine", "power": 9001}]}"#;
+            let page = PageParser::<Widget>::new()
+                .parse_response(Response::from_parts(parts.clone(), &body[..]))
+                .unwrap();
+            assert_eq!(page.info.total_count, Some(1));
+            assert_eq!(page.items[0].name, "Stéve");
+            let r = PageParser::<Widget>::new()
+                .parse_response(Response::from_parts(parts, &b"{\"items\": [1]}"[..]));
+            assert!(r.is_err());
+        }
+
         #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
         struct Widget {
             name: String,
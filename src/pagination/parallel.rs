@@ -0,0 +1,417 @@
+use super::{
+    PageParser, PageRequest, PageResponse, PaginationInfo, PaginationRequest, PaginationStyle,
+    first_page_params, following_url, is_reverse, page_budget,
+};
+use crate::{
+    AcceptList, Endpoint, HttpUrl, Method,
+    client::tokio::{AsyncBackend, AsyncClient},
+    errors::{Error, ErrorPayload},
+    util::{get_page_number, set_page_number},
+};
+use futures_util::{
+    FutureExt, Stream, StreamExt,
+    future::BoxFuture,
+    stream::{self, BoxStream},
+};
+use http::header::HeaderMap;
+use pin_project_lite::pin_project;
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+pin_project! {
+    /// A stream of the items of a [`PaginationRequest`] that fetches pages
+    /// concurrently, returned by [`AsyncClient::paginate_parallel()`]
+    ///
+    /// The first page is fetched on its own.  If its `Link` header gives the
+    /// number of the last page and the URL of the next page contains a `page`
+    /// query parameter, the remaining pages are then requested concurrently,
+    /// with at most the configured number of requests in flight at once;
+    /// otherwise (e.g., for endpoints that paginate with cursors), the
+    /// remaining pages are requested one at a time by following `next` links.
+    /// In either case, items are yielded in the same order as by
+    /// [`PaginationStream`][super::PaginationStream].
+    ///
+    /// When paginating in reverse (see [`PaginationRequest::reverse()`]), the
+    /// pages from the last page down to the first are requested concurrently
+    /// if the URL of the last page contains a page number; otherwise, they
+    /// are requested one at a time by following `prev` links.
+    ///
+    /// If [`PaginationRequest::should_continue()`] returns `false` for a page,
+    /// any requests for later pages that are in flight are cancelled.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ParallelPaginationStream<B: AsyncBackend, R: PaginationRequest> {
+        client: AsyncClient<B>,
+        req: R,
+        concurrency: usize,
+        pages_left: Option<u64>,
+        state: ParallelState<R::Item, B::Error>,
+        items: std::vec::IntoIter<R::Item>,
+        info: Option<PaginationInfo>,
+    }
+}
+
+impl<B, R> ParallelPaginationStream<B, R>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    B::Error: Send,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+{
+    /// Create a stream that paginates over `req` with at most `concurrency`
+    /// page requests in flight at once.  A `concurrency` of 0 is treated as 1.
+    pub fn new(client: AsyncClient<B>, req: R, concurrency: usize) -> Self {
+        let pages_left = page_budget(&req);
+        let state = if pages_left == Some(0) {
+            ParallelState::Done
+        } else {
+            let endpoint = req.endpoint();
+            let params = first_page_params(&req);
+            let page_req = PageRequest::new(endpoint.clone())
+                .with_headers(req.headers())
+                .with_extra_accept(req.extra_accept())
+                .with_timeout(req.timeout())
+                .with_parser(req.page_parser());
+            let client = client.clone();
+            ParallelState::First(
+                async move {
+                    match params {
+                        Ok(params) => client.request(page_req.with_params(params)).await,
+                        Err(e) => {
+                            let url = client.base_url().join_endpoint(endpoint);
+                            let payload = ErrorPayload::PrepareRequest(e);
+                            Err(Error::new(url, Method::Get, payload))
+                        }
+                    }
+                }
+                .boxed(),
+            )
+        };
+        ParallelPaginationStream {
+            client,
+            req,
+            concurrency: concurrency.max(1),
+            pages_left,
+            state,
+            items: Vec::new().into_iter(),
+            info: None,
+        }
+    }
+}
+
+impl<B: AsyncBackend, R: PaginationRequest> ParallelPaginationStream<B, R> {
+    /// Returns pagination information for the page whose items are currently
+    /// being yielded
+    pub fn info(&self) -> Option<PaginationInfo> {
+        self.info
+    }
+}
+
+impl<B, R> Stream for ParallelPaginationStream<B, R>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    B::Error: Send,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+{
+    type Item = Result<R::Item, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        loop {
+            if let Some(item) = this.items.next() {
+                return Some(Ok(item)).into();
+            }
+            let mut page_resp = match this.state {
+                ParallelState::First(fut) => match ready!(fut.as_mut().poll(cx)) {
+                    Ok(page_resp) => {
+                        if is_reverse(&*this.req)
+                            && let Some(last_url) = page_resp.last_url.clone()
+                        {
+                            let fetcher = PageFetcher {
+                                client: this.client.clone(),
+                                headers: this.req.headers(),
+                                accept: this.req.extra_accept(),
+                                timeout: this.req.timeout(),
+                                parser: this.req.page_parser(),
+                            };
+                            *this.state = ParallelState::Rest(
+                                fetcher.reverse_pages(last_url, *this.concurrency),
+                            );
+                            continue;
+                        }
+                        let pages_left = this.pages_left.map(|n| n.saturating_sub(1));
+                        *this.state = match page_resp.next_url.clone() {
+                            _ if is_reverse(&*this.req) => ParallelState::Done,
+                            Some(next_url) if pages_left != Some(0) => {
+                                let fetcher = PageFetcher {
+                                    client: this.client.clone(),
+                                    headers: this.req.headers(),
+                                    accept: this.req.extra_accept(),
+                                    timeout: this.req.timeout(),
+                                    parser: this.req.page_parser(),
+                                };
+                                let last_page = page_resp
+                                    .info
+                                    .last_page
+                                    .filter(|_| this.req.style() == PaginationStyle::PageNumbers);
+                                ParallelState::Rest(fetcher.remaining_pages(
+                                    next_url,
+                                    last_page,
+                                    pages_left,
+                                    *this.concurrency,
+                                ))
+                            }
+                            _ => ParallelState::Done,
+                        };
+                        page_resp
+                    }
+                    Err(e) => {
+                        *this.state = ParallelState::Done;
+                        *this.info = None;
+                        return Some(Err(e)).into();
+                    }
+                },
+                ParallelState::Rest(pages) => match ready!(pages.poll_next_unpin(cx)) {
+                    Some(Ok(page_resp)) => page_resp,
+                    Some(Err(e)) => {
+                        *this.state = ParallelState::Done;
+                        *this.info = None;
+                        return Some(Err(e)).into();
+                    }
+                    None => {
+                        *this.state = ParallelState::Done;
+                        *this.info = None;
+                        return None.into();
+                    }
+                },
+                ParallelState::Done => {
+                    *this.info = None;
+                    return None.into();
+                }
+            };
+            if following_url(&*this.req, &mut page_resp).is_none() {
+                *this.state = ParallelState::Done;
+            }
+            *this.items = page_resp.items.into_iter();
+            *this.info = Some(page_resp.info);
+        }
+    }
+}
+
+enum ParallelState<T, BE> {
+    First(BoxFuture<'static, Result<PageResponse<T>, Error<BE>>>),
+    Rest(BoxStream<'static, Result<PageResponse<T>, Error<BE>>>),
+    Done,
+}
+
+/// [Private] The parts of a pagination request needed to fetch the pages after
+/// the first
+struct PageFetcher<B, T> {
+    client: AsyncClient<B>,
+    headers: HeaderMap,
+    accept: AcceptList,
+    timeout: Option<Duration>,
+    parser: PageParser<T>,
+}
+
+impl<B, T> PageFetcher<B, T>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    B::Error: Send,
+    T: DeserializeOwned + Send + 'static,
+{
+    /// Return a stream of the pages starting at `next_url`, fetched
+    /// concurrently if `last_page` is known and `next_url` contains a page
+    /// number or else sequentially
+    fn remaining_pages(
+        self,
+        next_url: HttpUrl,
+        last_page: Option<u64>,
+        pages_left: Option<u64>,
+        concurrency: usize,
+    ) -> BoxStream<'static, Result<PageResponse<T>, Error<B::Error>>> {
+        match (get_page_number(&next_url), last_page) {
+            (Some(next), Some(last)) if next <= last => {
+                let last = match pages_left {
+                    Some(n) => last.min(next.saturating_add(n - 1)),
+                    None => last,
+                };
+                stream::iter(next..=last)
+                    .map(move |page| self.fetch(set_page_number(&next_url, page).into()))
+                    .buffered(concurrency)
+                    .boxed()
+            }
+            _ => self.serial_pages(next_url, pages_left, false),
+        }
+    }
+
+    /// Return a stream of the pages from `last_url` back to the first page,
+    /// fetched concurrently if `last_url` contains a page number or else
+    /// sequentially by following `prev` links
+    fn reverse_pages(
+        self,
+        last_url: HttpUrl,
+        concurrency: usize,
+    ) -> BoxStream<'static, Result<PageResponse<T>, Error<B::Error>>> {
+        match get_page_number(&last_url) {
+            Some(last) => stream::iter((1..=last).rev())
+                .map(move |page| self.fetch(set_page_number(&last_url, page).into()))
+                .buffered(concurrency)
+                .boxed(),
+            None => self.serial_pages(last_url, None, true),
+        }
+    }
+
+    /// Return a stream of the pages starting at `url`, fetched one at a time
+    /// by following `next` links (or `prev` links if `reverse` is true)
+    fn serial_pages(
+        self,
+        url: HttpUrl,
+        pages_left: Option<u64>,
+        reverse: bool,
+    ) -> BoxStream<'static, Result<PageResponse<T>, Error<B::Error>>> {
+        stream::unfold(
+            (self, Some(Endpoint::from(url)), pages_left),
+            move |(fetcher, url, pages_left)| async move {
+                let url = url?;
+                match fetcher.fetch(url).await {
+                    Ok(page_resp) => {
+                        let pages_left = pages_left.map(|n| n.saturating_sub(1));
+                        let next_url = if reverse {
+                            page_resp.prev_url.clone()
+                        } else {
+                            page_resp.next_url.clone()
+                        };
+                        let next_url = next_url.filter(|_| pages_left != Some(0)).map(Into::into);
+                        Some((Ok(page_resp), (fetcher, next_url, pages_left)))
+                    }
+                    Err(e) => Some((Err(e), (fetcher, None, pages_left))),
+                }
+            },
+        )
+        .boxed()
+    }
+
+    fn fetch(&self, url: Endpoint) -> BoxFuture<'static, Result<PageResponse<T>, Error<B::Error>>> {
+        let client = self.client.clone();
+        let req = PageRequest::new(url)
+            .with_headers(self.headers.clone())
+            .with_extra_accept(self.accept.clone())
+            .with_timeout(self.timeout)
+            .with_parser(self.parser.clone());
+        async move { client.request(req).await }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{ClientConfig, RequestParts, tokio::AsyncFnBackend},
+        pagination::{Reversed, WithPageRange},
+        testing::PaginationFixtures,
+    };
+    use futures_util::TryStreamExt;
+    use rstest::rstest;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    struct ListItems;
+
+    impl PaginationRequest for ListItems {
+        type Item = Item;
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["items"])
+        }
+    }
+
+    async fn collect_ids<B, R>(stream: ParallelPaginationStream<B, R>) -> Vec<u64>
+    where
+        B: AsyncBackend + Send + Sync + 'static,
+        B::Error: Send + std::fmt::Debug,
+        R: PaginationRequest<Item = Item>,
+    {
+        stream
+            .map_ok(|item| item.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case(0, 25)]
+    #[case(1, 25)]
+    #[case(3, 25)]
+    #[case(3, 3)]
+    #[case(10, 0)]
+    #[tokio::test]
+    async fn collect_all(#[case] concurrency: usize, #[case] n: u64) {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), n)
+                .with_page_size(5);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let ids = collect_ids(client.paginate_parallel(ListItems, concurrency)).await;
+        assert_eq!(ids, (1..=n).collect::<Vec<_>>());
+    }
+
+    #[rstest]
+    #[case(WithPageRange::new(ListItems, 2..=3), (6..=15).collect())]
+    #[case(WithPageRange::new(ListItems, ..2), (1..=5).collect())]
+    #[case(WithPageRange::new(ListItems, 4..), (16..=25).collect())]
+    #[tokio::test]
+    async fn page_range(#[case] req: WithPageRange<ListItems>, #[case] expected: Vec<u64>) {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(5);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let ids = collect_ids(client.paginate_parallel(req, 3)).await;
+        assert_eq!(ids, expected);
+    }
+
+    #[rstest]
+    #[case(1, 25)]
+    #[case(3, 25)]
+    #[case(3, 4)]
+    #[case(3, 0)]
+    #[tokio::test]
+    async fn reverse(#[case] concurrency: usize, #[case] n: u64) {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), n)
+                .with_page_size(5);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let ids =
+            collect_ids(client.paginate_parallel(Reversed::new(ListItems), concurrency)).await;
+        assert_eq!(ids, (1..=n).rev().collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn without_last_page() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(5);
+        let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(
+            move |parts: RequestParts, _| {
+                let mut resp = fixtures.response_for(&parts.url);
+                if let Some(link) = resp.headers().get(http::header::LINK) {
+                    let link = link
+                        .to_str()
+                        .unwrap()
+                        .split(", ")
+                        .filter(|l| !l.ends_with("rel=\"last\""))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    resp.headers_mut()
+                        .insert(http::header::LINK, link.parse().unwrap());
+                }
+                resp
+            },
+        ));
+        let ids = collect_ids(client.paginate_parallel(ListItems, 3)).await;
+        assert_eq!(ids, (1..=25).collect::<Vec<_>>());
+    }
+}
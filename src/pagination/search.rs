@@ -0,0 +1,860 @@
+use super::{PageCursor, PageRequest, PageResponse, PaginationRequest, PaginationState};
+use crate::{
+    Endpoint, HttpUrl,
+    client::{Backend, Client},
+    errors::Error,
+    util::{civil_from_days, days_from_civil},
+};
+use http::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+
+#[cfg(feature = "tokio")]
+use crate::client::tokio::{AsyncBackend, AsyncClient};
+#[cfg(feature = "tokio")]
+use futures_util::{FutureExt, Stream, future::BoxFuture};
+#[cfg(feature = "tokio")]
+use pin_project_lite::pin_project;
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::task::{Context, Poll, ready};
+
+/// The maximum number of results that GitHub's search endpoints will return
+/// for a single query, regardless of the reported `total_count`
+pub const SEARCH_RESULT_LIMIT: u64 = 1000;
+
+/// A [`PaginationRequest`] for one of GitHub's search endpoints
+///
+/// GitHub only returns the first [`SEARCH_RESULT_LIMIT`] results of any
+/// search, even if the `total_count` reported in the response is higher.  In
+/// order to retrieve complete result sets, a `SearchRequest` can be given a
+/// [`SearchRange`] with [`SearchRequest::with_range()`], which adds a range
+/// qualifier (e.g., `created:2024-01-01..2024-12-31`) to the query.  When the
+/// request is then paginated with [`Client::search()`] or
+/// [`AsyncClient::search()`], any range whose results exceed the limit is
+/// repeatedly split in half and each half searched separately.
+///
+/// Paginating a `SearchRequest` with the ordinary `paginate()` methods
+/// searches the whole range at once and is thus subject to the limit.
+pub struct SearchRequest<T> {
+    endpoint: Endpoint,
+    query: String,
+    params: Vec<(String, String)>,
+    range: Option<SearchRange>,
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    incomplete_retries: u32,
+    _items: PhantomData<fn() -> T>,
+}
+
+impl<T> SearchRequest<T> {
+    /// Create a request to search `endpoint` (e.g., `search/issues`) with the
+    /// given query string, which is sent as the `q` parameter
+    pub fn new<S: Into<String>>(endpoint: Endpoint, query: S) -> SearchRequest<T> {
+        SearchRequest {
+            endpoint,
+            query: query.into(),
+            params: Vec::new(),
+            range: None,
+            headers: HeaderMap::new(),
+            timeout: None,
+            incomplete_retries: 0,
+            _items: PhantomData,
+        }
+    }
+
+    /// Create a request to search issues and pull requests
+    pub fn issues<S: Into<String>>(query: S) -> SearchRequest<T> {
+        SearchRequest::new(Endpoint::from_iter(["search", "issues"]), query)
+    }
+
+    /// Create a request to search repositories
+    pub fn repositories<S: Into<String>>(query: S) -> SearchRequest<T> {
+        SearchRequest::new(Endpoint::from_iter(["search", "repositories"]), query)
+    }
+
+    /// Create a request to search code
+    pub fn code<S: Into<String>>(query: S) -> SearchRequest<T> {
+        SearchRequest::new(Endpoint::from_iter(["search", "code"]), query)
+    }
+
+    /// Create a request to search commits
+    pub fn commits<S: Into<String>>(query: S) -> SearchRequest<T> {
+        SearchRequest::new(Endpoint::from_iter(["search", "commits"]), query)
+    }
+
+    /// Create a request to search users
+    pub fn users<S: Into<String>>(query: S) -> SearchRequest<T> {
+        SearchRequest::new(Endpoint::from_iter(["search", "users"]), query)
+    }
+
+    /// Add a query parameter (e.g., `sort` or `order`) to the request
+    pub fn with_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Restrict the search to the given range, which is split as necessary
+    /// when paginating with [`Client::search()`] or [`AsyncClient::search()`]
+    pub fn with_range(mut self, range: SearchRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// When paginating with [`Client::search()`] or [`AsyncClient::search()`],
+    /// re-request any page whose response has `incomplete_results` set to
+    /// `true` up to `retries` times before yielding its items.
+    ///
+    /// GitHub sets `incomplete_results` when a search times out before
+    /// finding all matches, so such a page may be missing items that a
+    /// repeated request would return.  If a page is still incomplete after
+    /// all retries, its items are yielded anyway, and the `incomplete_results()`
+    /// method of the iterator or stream will return `true`.  By default, pages
+    /// are not retried.
+    pub fn with_incomplete_retries(mut self, retries: u32) -> Self {
+        self.incomplete_retries = retries;
+        self
+    }
+
+    /// Returns the query string, without any range qualifier
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn range(&self) -> Option<&SearchRange> {
+        self.range.as_ref()
+    }
+
+    /// Returns the full search query sent as the `q` parameter, including the
+    /// range qualifier, if any
+    pub fn full_query(&self) -> String {
+        match &self.range {
+            Some(range) if self.query.trim().is_empty() => range.to_string(),
+            Some(range) => format!("{} {range}", self.query.trim_end()),
+            None => self.query.clone(),
+        }
+    }
+}
+
+// Implemented manually so as not to require `T: Clone`
+impl<T> Clone for SearchRequest<T> {
+    fn clone(&self) -> SearchRequest<T> {
+        SearchRequest {
+            endpoint: self.endpoint.clone(),
+            query: self.query.clone(),
+            params: self.params.clone(),
+            range: self.range.clone(),
+            headers: self.headers.clone(),
+            timeout: self.timeout,
+            incomplete_retries: self.incomplete_retries,
+            _items: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for SearchRequest<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchRequest")
+            .field("endpoint", &self.endpoint)
+            .field("query", &self.query)
+            .field("params", &self.params)
+            .field("range", &self.range)
+            .field("headers", &self.headers)
+            .field("timeout", &self.timeout)
+            .field("incomplete_retries", &self.incomplete_retries)
+            .finish()
+    }
+}
+
+impl<T: DeserializeOwned + Send> PaginationRequest for SearchRequest<T> {
+    type Item = T;
+
+    fn endpoint(&self) -> Endpoint {
+        self.endpoint.clone()
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        let mut params = vec![(String::from("q"), self.full_query())];
+        params.extend(self.params.iter().cloned());
+        params
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.headers.clone()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+/// A range of values of a search qualifier, used to split searches whose
+/// results exceed [`SEARCH_RESULT_LIMIT`]
+///
+/// A `SearchRange` is displayed as a search qualifier of the form
+/// `{qualifier}:{start}..{end}`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SearchRange {
+    /// An inclusive range of dates for a qualifier like `created`, `updated`,
+    /// or `pushed`.  Ranges are split at day granularity.
+    Dates {
+        qualifier: String,
+        start: SearchDate,
+        end: SearchDate,
+    },
+
+    /// An inclusive range of integers for a qualifier like `size`, `stars`,
+    /// or `comments`
+    Numbers {
+        qualifier: String,
+        start: u64,
+        end: u64,
+    },
+}
+
+impl SearchRange {
+    /// Create a range of dates for the given qualifier
+    pub fn dates<S: Into<String>>(qualifier: S, start: SearchDate, end: SearchDate) -> SearchRange {
+        SearchRange::Dates {
+            qualifier: qualifier.into(),
+            start,
+            end,
+        }
+    }
+
+    /// Create a range of integers for the given qualifier
+    pub fn numbers<S: Into<String>>(qualifier: S, start: u64, end: u64) -> SearchRange {
+        SearchRange::Numbers {
+            qualifier: qualifier.into(),
+            start,
+            end,
+        }
+    }
+
+    /// Split the range into two halves.  Returns `None` if the range contains
+    /// only a single value (or is empty).
+    pub fn split(&self) -> Option<(SearchRange, SearchRange)> {
+        match self {
+            SearchRange::Dates {
+                qualifier,
+                start,
+                end,
+            } => {
+                let (a, b) = split_range(start.0, end.0)?;
+                Some((
+                    SearchRange::dates(qualifier.clone(), *start, SearchDate(a)),
+                    SearchRange::dates(qualifier.clone(), SearchDate(b), *end),
+                ))
+            }
+            SearchRange::Numbers {
+                qualifier,
+                start,
+                end,
+            } => {
+                let (a, b) = split_range(*start, *end)?;
+                Some((
+                    SearchRange::numbers(qualifier.clone(), *start, a),
+                    SearchRange::numbers(qualifier.clone(), b, *end),
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SearchRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchRange::Dates {
+                qualifier,
+                start,
+                end,
+            } => write!(f, "{qualifier}:{start}..{end}"),
+            SearchRange::Numbers {
+                qualifier,
+                start,
+                end,
+            } => write!(f, "{qualifier}:{start}..{end}"),
+        }
+    }
+}
+
+/// [Private] Split the inclusive range `start..=end` into the end of its first
+/// half and the start of its second half
+fn split_range<N>(start: N, end: N) -> Option<(N, N)>
+where
+    N: Copy
+        + Ord
+        + std::ops::Add<Output = N>
+        + std::ops::Sub<Output = N>
+        + std::ops::Div<Output = N>,
+    N: From<u8>,
+{
+    if start >= end {
+        return None;
+    }
+    let mid = start + (end - start) / N::from(2);
+    Some((mid, mid + N::from(1)))
+}
+
+/// A calendar date in the proleptic Gregorian calendar, as used in date
+/// ranges for search qualifiers
+///
+/// `SearchDate`s are parsed from and displayed as strings of the form
+/// `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SearchDate(
+    // Days since 1970-01-01
+    i64,
+);
+
+impl SearchDate {
+    /// Construct a `SearchDate` from a year, month (1–12), and day.  Returns
+    /// `None` if the date is invalid or the year is not in the range
+    /// 0 through 9999.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<SearchDate> {
+        if year > 9999 || !(1..=12).contains(&month) || day < 1 {
+            return None;
+        }
+        let date = SearchDate(days_from_civil(year.into(), month.into(), day.into()));
+        (date.ymd() == (year.into(), month.into(), day.into())).then_some(date)
+    }
+
+    pub fn year(&self) -> u16 {
+        u16::try_from(self.ymd().0).unwrap_or_default()
+    }
+
+    pub fn month(&self) -> u8 {
+        u8::try_from(self.ymd().1).unwrap_or_default()
+    }
+
+    pub fn day(&self) -> u8 {
+        u8::try_from(self.ymd().2).unwrap_or_default()
+    }
+
+    /// [Private] Returns the date's year, month, and day
+    fn ymd(&self) -> (i64, i64, i64) {
+        civil_from_days(self.0)
+    }
+}
+
+impl fmt::Display for SearchDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (y, m, d) = self.ymd();
+        write!(f, "{y:04}-{m:02}-{d:02}")
+    }
+}
+
+impl FromStr for SearchDate {
+    type Err = ParseSearchDateError;
+
+    fn from_str(s: &str) -> Result<SearchDate, ParseSearchDateError> {
+        let mut parts = s.split('-');
+        let (Some(y), Some(m), Some(d), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ParseSearchDateError);
+        };
+        if y.len() != 4 || m.len() != 2 || d.len() != 2 {
+            return Err(ParseSearchDateError);
+        }
+        let parse = |s: &str| {
+            if s.bytes().all(|b| b.is_ascii_digit()) {
+                s.parse::<u16>().ok()
+            } else {
+                None
+            }
+        };
+        let (Some(y), Some(m), Some(d)) = (parse(y), parse(m), parse(d)) else {
+            return Err(ParseSearchDateError);
+        };
+        let (Ok(m), Ok(d)) = (u8::try_from(m), u8::try_from(d)) else {
+            return Err(ParseSearchDateError);
+        };
+        SearchDate::new(y, m, d).ok_or(ParseSearchDateError)
+    }
+}
+
+/// Error returned when parsing an invalid [`SearchDate`]
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+#[error("invalid date; expected YYYY-MM-DD")]
+pub struct ParseSearchDateError;
+
+/// [Private] The request-independent state of a search, shared by
+/// `SearchIter` and `SearchStream`
+#[derive(Debug)]
+struct SearchState<T> {
+    req: SearchRequest<T>,
+    pending: Vec<SearchRequest<T>>,
+    cursor: Option<PageCursor<(), SearchRequest<T>>>,
+    items: std::vec::IntoIter<T>,
+    truncated: bool,
+    incomplete_results: bool,
+    // Number of times the current page has been re-requested due to
+    // `incomplete_results`
+    retries: u32,
+}
+
+impl<T: DeserializeOwned + Send> SearchState<T> {
+    fn new(req: SearchRequest<T>) -> SearchState<T> {
+        SearchState {
+            pending: vec![req.clone()],
+            req,
+            cursor: None,
+            items: Vec::new().into_iter(),
+            truncated: false,
+            incomplete_results: false,
+            retries: 0,
+        }
+    }
+
+    /// Returns the request for the next page to fetch, or `None` if the
+    /// search is complete
+    fn next_request<E>(&mut self, base_url: &HttpUrl) -> Option<Result<PageRequest<T>, Error<E>>> {
+        loop {
+            if let Some(cursor) = self.cursor.as_mut() {
+                match cursor.next_request(base_url) {
+                    Some(r) => return Some(r),
+                    None => self.cursor = None,
+                }
+            }
+            let req = self.pending.pop()?;
+            self.cursor = Some(PageCursor::new((), req));
+        }
+    }
+
+    fn handle_page(&mut self, mut page: PageResponse<T>) {
+        let Some(cursor) = self.cursor.as_mut() else {
+            return;
+        };
+        if page.info.incomplete_results == Some(true)
+            && self.retries < self.req.incomplete_retries
+            && cursor.retry()
+        {
+            self.retries += 1;
+            return;
+        }
+        self.retries = 0;
+        let first = cursor.state() == PaginationState::NotStarted;
+        cursor.handle_page(&mut page);
+        if first
+            && page
+                .info
+                .total_count
+                .is_some_and(|n| n > SEARCH_RESULT_LIMIT)
+        {
+            match cursor.request().range().and_then(SearchRange::split) {
+                Some((lower, upper)) => {
+                    let subrequest = cursor.request().clone();
+                    self.pending.push(subrequest.clone().with_range(upper));
+                    self.pending.push(subrequest.with_range(lower));
+                    self.cursor = None;
+                    return;
+                }
+                None => self.truncated = true,
+            }
+        }
+        if page.info.incomplete_results == Some(true) {
+            self.incomplete_results = true;
+        }
+        self.items = page.items.into_iter();
+    }
+
+    fn handle_error(&mut self) {
+        self.cursor = None;
+        self.retries = 0;
+        self.pending.clear();
+    }
+}
+
+/// An iterator over the results of a [`SearchRequest`], returned by
+/// [`Client::search()`]
+///
+/// If the request has a [`SearchRange`], then, whenever a search over a range
+/// reports more than [`SEARCH_RESULT_LIMIT`] results, the range is split in
+/// half and each half is searched in turn, so that results are yielded in
+/// order of the range qualifier's value as long as the search's sort order is
+/// the same.  The first page of each split range is requested and discarded
+/// in the process.
+#[derive(Debug)]
+pub struct SearchIter<'a, B, T> {
+    client: &'a Client<B>,
+    state: SearchState<T>,
+}
+
+impl<'a, B, T: DeserializeOwned + Send> SearchIter<'a, B, T> {
+    pub fn new(client: &'a Client<B>, req: SearchRequest<T>) -> Self {
+        SearchIter {
+            client,
+            state: SearchState::new(req),
+        }
+    }
+
+    /// Returns the search request
+    pub fn request(&self) -> &SearchRequest<T> {
+        &self.state.req
+    }
+
+    /// Returns true if any results were left out because a search reported
+    /// more than [`SEARCH_RESULT_LIMIT`] results and its range (if any) could
+    /// not be split further
+    pub fn truncated(&self) -> bool {
+        self.state.truncated
+    }
+
+    /// Returns true if any page received so far had `incomplete_results` set
+    /// to `true`, indicating that the search timed out on GitHub's end and
+    /// that some matches may be missing
+    pub fn incomplete_results(&self) -> bool {
+        self.state.incomplete_results
+    }
+}
+
+impl<B: Backend, T: DeserializeOwned + Send> Iterator for SearchIter<'_, B, T> {
+    type Item = Result<T, Error<B::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.state.items.next() {
+                return Some(Ok(item));
+            }
+            let req = match self.state.next_request(&self.client.base_url())? {
+                Ok(req) => req,
+                Err(e) => {
+                    self.state.handle_error();
+                    return Some(Err(e));
+                }
+            };
+            match self.client.request(req) {
+                Ok(page) => self.state.handle_page(page),
+                Err(e) => {
+                    self.state.handle_error();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<B: Backend, T: DeserializeOwned + Send> std::iter::FusedIterator for SearchIter<'_, B, T> {}
+
+#[cfg(feature = "tokio")]
+pin_project! {
+    /// A stream of the results of a [`SearchRequest`], returned by
+    /// [`AsyncClient::search()`]
+    ///
+    /// Ranges are split in the same way as by [`SearchIter`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SearchStream<B: AsyncBackend, T> {
+        client: AsyncClient<B>,
+        state: SearchState<T>,
+        inflight: Option<BoxFuture<'static, Result<PageResponse<T>, Error<B::Error>>>>,
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<B: AsyncBackend, T: DeserializeOwned + Send> SearchStream<B, T> {
+    pub fn new(client: AsyncClient<B>, req: SearchRequest<T>) -> Self {
+        SearchStream {
+            client,
+            state: SearchState::new(req),
+            inflight: None,
+        }
+    }
+
+    /// Returns the search request
+    pub fn request(&self) -> &SearchRequest<T> {
+        &self.state.req
+    }
+
+    /// Returns true if any results were left out because a search reported
+    /// more than [`SEARCH_RESULT_LIMIT`] results and its range (if any) could
+    /// not be split further
+    pub fn truncated(&self) -> bool {
+        self.state.truncated
+    }
+
+    /// Returns true if any page received so far had `incomplete_results` set
+    /// to `true`, indicating that the search timed out on GitHub's end and
+    /// that some matches may be missing
+    pub fn incomplete_results(&self) -> bool {
+        self.state.incomplete_results
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B, T> Stream for SearchStream<B, T>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    type Item = Result<T, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        loop {
+            if let Some(item) = this.state.items.next() {
+                return Some(Ok(item)).into();
+            }
+            if let Some(fut) = this.inflight.as_mut() {
+                let r = ready!(fut.as_mut().poll(cx));
+                *this.inflight = None;
+                match r {
+                    Ok(page) => this.state.handle_page(page),
+                    Err(e) => {
+                        this.state.handle_error();
+                        return Some(Err(e)).into();
+                    }
+                }
+                continue;
+            }
+            match this.state.next_request(&this.client.base_url()) {
+                Some(Ok(req)) => {
+                    let client = this.client.clone();
+                    *this.inflight = Some(async move { client.request(req).await }.boxed());
+                }
+                Some(Err(e)) => {
+                    this.state.handle_error();
+                    return Some(Err(e)).into();
+                }
+                None => return None.into(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend, RequestParts};
+    use rstest::rstest;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Repo {
+        id: u64,
+    }
+
+    /// A fake search endpoint over repositories with IDs `1..=n` whose `size`
+    /// equals their ID.  Matches beyond the result limit are omitted, and only
+    /// the first page of each search is ever returned.
+    fn search_responder(
+        n: u64,
+        queries: Arc<Mutex<Vec<String>>>,
+    ) -> impl Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>> + Send + Sync + 'static {
+        move |parts: RequestParts, _| {
+            let q = parts
+                .url
+                .as_url()
+                .query_pairs()
+                .find_map(|(k, v)| (k == "q").then(|| v.into_owned()))
+                .unwrap();
+            let (lo, hi) = match q.split_once("size:") {
+                Some((_, range)) => {
+                    let (lo, hi) = range.split_once("..").unwrap();
+                    (lo.parse::<u64>().unwrap(), hi.parse::<u64>().unwrap())
+                }
+                None => (1, n),
+            };
+            queries.lock().unwrap().push(q);
+            let matches = (lo..=hi.min(n)).collect::<Vec<_>>();
+            let items = matches
+                .iter()
+                .take(usize::try_from(SEARCH_RESULT_LIMIT).unwrap())
+                .map(|&id| json!({"id": id}))
+                .collect::<Vec<_>>();
+            let body = json!({
+                "total_count": matches.len(),
+                "incomplete_results": hi == 7,
+                "items": items,
+            });
+            http::Response::builder()
+                .status(200)
+                .header("content-type", "application/json")
+                .body(body.to_string().into_bytes())
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn split_ranges() {
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let client = ClientConfig::new()
+            .with_backend(FnBackend::new(search_responder(2500, Arc::clone(&queries))));
+        let req = SearchRequest::<Repo>::repositories("language:rust")
+            .with_range(SearchRange::numbers("size", 1, 2500));
+        let mut iter = client.search(req);
+        let ids = iter.by_ref().map(|r| r.unwrap().id).collect::<Vec<_>>();
+        assert_eq!(ids, (1..=2500).collect::<Vec<_>>());
+        assert!(!iter.truncated());
+        assert!(!iter.incomplete_results());
+        assert_eq!(
+            *queries.lock().unwrap(),
+            [
+                "language:rust size:1..2500",
+                "language:rust size:1..1250",
+                "language:rust size:1..625",
+                "language:rust size:626..1250",
+                "language:rust size:1251..2500",
+                "language:rust size:1251..1875",
+                "language:rust size:1876..2500",
+            ]
+        );
+    }
+
+    #[test]
+    fn unsplittable() {
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let client = ClientConfig::new()
+            .with_backend(FnBackend::new(search_responder(1500, Arc::clone(&queries))));
+        let mut iter = client.search(SearchRequest::<Repo>::repositories("language:rust"));
+        assert_eq!(iter.by_ref().count(), 1000);
+        assert!(iter.truncated());
+        assert_eq!(*queries.lock().unwrap(), ["language:rust"]);
+    }
+
+    #[test]
+    fn incomplete_results() {
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let client =
+            ClientConfig::new().with_backend(FnBackend::new(search_responder(10, queries)));
+        let req =
+            SearchRequest::<Repo>::repositories("").with_range(SearchRange::numbers("size", 5, 7));
+        let mut iter = client.search(req);
+        let ids = iter.by_ref().map(|r| r.unwrap().id).collect::<Vec<_>>();
+        assert_eq!(ids, [5, 6, 7]);
+        assert!(iter.incomplete_results());
+        assert!(!iter.truncated());
+    }
+
+    #[rstest]
+    #[case(0, &[1], true, 1)]
+    #[case(1, &[1], true, 2)]
+    #[case(2, &[1, 2, 3], false, 3)]
+    #[case(5, &[1, 2, 3], false, 3)]
+    fn retry_incomplete(
+        #[case] retries: u32,
+        #[case] expected: &[u64],
+        #[case] incomplete: bool,
+        #[case] requests: usize,
+    ) {
+        let count = Arc::new(Mutex::new(0));
+        let count2 = Arc::clone(&count);
+        let client = ClientConfig::new().with_backend(FnBackend::new(move |_, _| {
+            let mut count = count2.lock().unwrap();
+            *count += 1;
+            let body = if *count <= 2 {
+                json!({"total_count": 1, "incomplete_results": true, "items": [{"id": 1}]})
+            } else {
+                json!({
+                    "total_count": 3,
+                    "incomplete_results": false,
+                    "items": [{"id": 1}, {"id": 2}, {"id": 3}],
+                })
+            };
+            http::Response::builder()
+                .status(200)
+                .header("content-type", "application/json")
+                .body(body.to_string().into_bytes())
+                .unwrap()
+        }));
+        let req = SearchRequest::<Repo>::code("ghreq").with_incomplete_retries(retries);
+        let mut iter = client.search(req);
+        let ids = iter.by_ref().map(|r| r.unwrap().id).collect::<Vec<_>>();
+        assert_eq!(ids, expected);
+        assert_eq!(iter.incomplete_results(), incomplete);
+        assert_eq!(*count.lock().unwrap(), requests);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn split_ranges_async() {
+        use crate::client::tokio::AsyncFnBackend;
+        use futures_util::TryStreamExt;
+
+        let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(search_responder(
+            2500,
+            Arc::new(Mutex::new(Vec::new())),
+        )));
+        let req = SearchRequest::<Repo>::repositories("language:rust")
+            .with_range(SearchRange::numbers("size", 1, 2500));
+        let ids = client
+            .search(req)
+            .map_ok(|repo| repo.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids, (1..=2500).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn full_query() {
+        let req = SearchRequest::<Repo>::issues("is:pr  ");
+        assert_eq!(req.full_query(), "is:pr  ");
+        let start = "2024-01-01".parse::<SearchDate>().unwrap();
+        let end = "2024-12-31".parse::<SearchDate>().unwrap();
+        let req = req.with_range(SearchRange::dates("created", start, end));
+        assert_eq!(req.full_query(), "is:pr created:2024-01-01..2024-12-31");
+        let req =
+            SearchRequest::<Repo>::issues("").with_range(SearchRange::numbers("comments", 0, 9));
+        assert_eq!(req.full_query(), "comments:0..9");
+    }
+
+    #[test]
+    fn split_dates() {
+        let range = SearchRange::dates(
+            "created",
+            SearchDate::new(2024, 2, 20).unwrap(),
+            SearchDate::new(2024, 3, 10).unwrap(),
+        );
+        let (lower, upper) = range.split().unwrap();
+        assert_eq!(lower.to_string(), "created:2024-02-20..2024-02-29");
+        assert_eq!(upper.to_string(), "created:2024-03-01..2024-03-10");
+        let day = SearchDate::new(2024, 3, 1).unwrap();
+        assert_eq!(SearchRange::dates("created", day, day).split(), None);
+        assert_eq!(SearchRange::numbers("size", 5, 5).split(), None);
+    }
+
+    #[rstest]
+    #[case("1970-01-01", Some((1970, 1, 1)))]
+    #[case("2000-02-29", Some((2000, 2, 29)))]
+    #[case("1900-02-29", None)]
+    #[case("2023-04-31", None)]
+    #[case("2023-13-01", None)]
+    #[case("2023-00-10", None)]
+    #[case("0001-01-01", Some((1, 1, 1)))]
+    #[case("9999-12-31", Some((9999, 12, 31)))]
+    #[case("2023-1-01", None)]
+    #[case("2023-01-01-", None)]
+    #[case("+023-01-01", None)]
+    #[case("", None)]
+    fn parse_date(#[case] s: &str, #[case] ymd: Option<(u16, u8, u8)>) {
+        match ymd {
+            Some((y, m, d)) => {
+                let date = s.parse::<SearchDate>().unwrap();
+                assert_eq!((date.year(), date.month(), date.day()), (y, m, d));
+                assert_eq!(date.to_string(), s);
+                assert_eq!(SearchDate::new(y, m, d), Some(date));
+            }
+            None => assert_eq!(s.parse::<SearchDate>(), Err(ParseSearchDateError)),
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use super::{
+    AdaptivePaging, PageParser, PageResponse, PaginationIter, PaginationRequest, PaginationStyle,
+};
+use crate::{
+    AcceptList, Endpoint, Timestamp,
+    client::{Backend, Client},
+    errors::Error,
+};
+use http::header::HeaderMap;
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use {
+    super::PaginationStream,
+    crate::client::tokio::{AsyncBackend, AsyncClient},
+    futures_util::{Stream, stream::FusedStream},
+    pin_project_lite::pin_project,
+    std::pin::Pin,
+    std::task::{Context, Poll, ready},
+};
+
+/// A [`PaginationRequest`] wrapper that anchors a listing to a stable sort key
+/// in order to avoid "page drift" during long paginations.
+///
+/// When a listing is paginated in GitHub's default order (usually newest
+/// first), any item created while pagination is in progress shifts every
+/// later item down by one position, so that an item at the end of one page
+/// reappears at the start of the next page.  A `SnapshotRequest` avoids this
+/// by:
+///
+/// - requesting the items in ascending order of the given sort key by adding
+///   `sort={sort}&direction=asc` to the inner request's parameters, so that
+///   newly-created items are appended to the end of the listing rather than
+///   inserted before the items not yet fetched; and
+///
+/// - stopping pagination at the first item whose key (as extracted by the
+///   `key` function) is greater than an upper bound timestamp, by default the
+///   time at which the `SnapshotRequest` was created.
+///
+/// When paginated with [`Client::paginate_snapshot()`] or
+/// [`AsyncClient::paginate_snapshot()`], every item that existed when the
+/// upper bound was captured and that still exists when its page is fetched is
+/// yielded exactly once, and no item created afterwards is yielded.  Items
+/// deleted during pagination can still cause later items to be skipped, as
+/// no page-number-based scheme can prevent that.  Paginating a
+/// `SnapshotRequest` with the ordinary `paginate()` methods only applies the
+/// sort order, not the upper bound.
+///
+/// Keys and the upper bound are compared as strings, which orders timestamps
+/// correctly as long as they all use the same format with a fixed time zone,
+/// as GitHub's `YYYY-MM-DDTHH:MM:SSZ` timestamps do.  The endpoint must
+/// support the `sort` and `direction` parameters with the given sort key.
+#[derive(Clone, Debug)]
+pub struct SnapshotRequest<R, F> {
+    request: R,
+    sort: String,
+    upper_bound: String,
+    key: F,
+}
+
+impl<R, F> SnapshotRequest<R, F>
+where
+    R: PaginationRequest,
+    F: Fn(&R::Item) -> &str,
+{
+    /// Anchor `request` to the sort key `sort` (e.g., `"created"`), where
+    /// `key` returns the value of that sort key for a given item.  The upper
+    /// bound is set to the current system time.
+    pub fn new<S: Into<String>>(request: R, sort: S, key: F) -> Self {
+        SnapshotRequest {
+            request,
+            sort: sort.into(),
+            upper_bound: Timestamp::now().to_string(),
+            key,
+        }
+    }
+
+    /// Returns true if the given item falls within the snapshot, i.e., if its
+    /// key is not greater than the upper bound
+    pub fn contains(&self, item: &R::Item) -> bool {
+        (self.key)(item) <= self.upper_bound.as_str()
+    }
+}
+
+impl<R, F> SnapshotRequest<R, F> {
+    /// Use the given timestamp as the upper bound instead of the time at which
+    /// the `SnapshotRequest` was created
+    pub fn with_upper_bound<S: Into<String>>(mut self, upper_bound: S) -> Self {
+        self.upper_bound = upper_bound.into();
+        self
+    }
+
+    /// Returns the upper bound on item keys
+    pub fn upper_bound(&self) -> &str {
+        &self.upper_bound
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.request
+    }
+
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: PaginationRequest, F> PaginationRequest for SnapshotRequest<R, F> {
+    type Item = R::Item;
+
+    fn endpoint(&self) -> Endpoint {
+        self.request.endpoint()
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        let mut params = self.request.params();
+        params.retain(|(k, _)| k != "sort" && k != "direction");
+        params.push((String::from("sort"), self.sort.clone()));
+        params.push((String::from("direction"), String::from("asc")));
+        params
+    }
+
+    fn query(&self) -> impl Serialize {
+        self.request.query()
+    }
+
+    fn per_page(&self) -> Option<u8> {
+        self.request.per_page()
+    }
+
+    fn style(&self) -> PaginationStyle {
+        self.request.style()
+    }
+
+    fn start_page(&self) -> Option<u64> {
+        self.request.start_page()
+    }
+
+    fn end_page(&self) -> Option<u64> {
+        self.request.end_page()
+    }
+
+    fn adaptive(&self) -> Option<AdaptivePaging> {
+        self.request.adaptive()
+    }
+
+    fn should_continue(&self, page: &PageResponse<Self::Item>) -> bool {
+        self.request.should_continue(page)
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.request.headers()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+
+    fn extra_accept(&self) -> AcceptList {
+        self.request.extra_accept()
+    }
+
+    fn page_parser(&self) -> PageParser<Self::Item> {
+        self.request.page_parser()
+    }
+}
+
+impl<B: Backend> Client<B> {
+    /// Paginate over a [`SnapshotRequest`], stopping at the first item beyond
+    /// the request's upper bound
+    pub fn paginate_snapshot<R, F>(&self, req: SnapshotRequest<R, F>) -> SnapshotIter<'_, B, R, F>
+    where
+        R: PaginationRequest,
+    {
+        SnapshotIter {
+            inner: PaginationIter::new(self, req),
+            done: false,
+        }
+    }
+}
+
+/// An iterator over the items of a [`SnapshotRequest`], returned by
+/// [`Client::paginate_snapshot()`]
+pub struct SnapshotIter<'a, B, R: PaginationRequest, F> {
+    inner: PaginationIter<'a, B, SnapshotRequest<R, F>>,
+    done: bool,
+}
+
+impl<'a, B, R: PaginationRequest, F> Clone for SnapshotIter<'a, B, R, F>
+where
+    PaginationIter<'a, B, SnapshotRequest<R, F>>: Clone,
+{
+    fn clone(&self) -> Self {
+        SnapshotIter {
+            inner: self.inner.clone(),
+            done: self.done,
+        }
+    }
+}
+
+impl<'a, B, R: PaginationRequest, F> fmt::Debug for SnapshotIter<'a, B, R, F>
+where
+    PaginationIter<'a, B, SnapshotRequest<R, F>>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapshotIter")
+            .field("inner", &self.inner)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<B, R, F> Iterator for SnapshotIter<'_, B, R, F>
+where
+    B: Backend,
+    R: PaginationRequest<Item: DeserializeOwned + Send>,
+    F: Fn(&R::Item) -> &str,
+{
+    type Item = Result<R::Item, Error<B::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let r = self.inner.next();
+        match r {
+            Some(Ok(ref item)) if !self.inner.request().contains(item) => {
+                self.done = true;
+                None
+            }
+            Some(Ok(_)) => r,
+            Some(Err(_)) | None => {
+                self.done = true;
+                r
+            }
+        }
+    }
+}
+
+impl<B, R, F> std::iter::FusedIterator for SnapshotIter<'_, B, R, F>
+where
+    B: Backend,
+    R: PaginationRequest<Item: DeserializeOwned + Send>,
+    F: Fn(&R::Item) -> &str,
+{
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<B: AsyncBackend + Send + Sync> AsyncClient<B> {
+    /// Paginate over a [`SnapshotRequest`], stopping at the first item beyond
+    /// the request's upper bound
+    pub fn paginate_snapshot<R, F>(&self, req: SnapshotRequest<R, F>) -> SnapshotStream<B, R, F>
+    where
+        R: PaginationRequest,
+    {
+        SnapshotStream {
+            inner: PaginationStream::new(self.clone(), req),
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+pin_project! {
+    /// A stream of the items of a [`SnapshotRequest`], returned by
+    /// [`AsyncClient::paginate_snapshot()`]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct SnapshotStream<B: AsyncBackend, R: PaginationRequest, F> {
+        #[pin]
+        inner: PaginationStream<B, SnapshotRequest<R, F>>,
+        done: bool,
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B, R, F> Stream for SnapshotStream<B, R, F>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+    F: Fn(&R::Item) -> &str,
+{
+    type Item = Result<R::Item, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.done {
+            return None.into();
+        }
+        let r = ready!(this.inner.as_mut().poll_next(cx));
+        match r {
+            Some(Ok(ref item)) if !this.inner.request().contains(item) => {
+                *this.done = true;
+                None.into()
+            }
+            Some(Ok(_)) => r.into(),
+            Some(Err(_)) | None => {
+                *this.done = true;
+                r.into()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B, R, F> FusedStream for SnapshotStream<B, R, F>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+    F: Fn(&R::Item) -> &str,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, FnBackend};
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Issue {
+        number: u64,
+        created_at: String,
+    }
+
+    struct ListIssues;
+
+    impl PaginationRequest for ListIssues {
+        type Item = Issue;
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["issues"])
+        }
+
+        fn params(&self) -> Vec<(String, String)> {
+            vec![(String::from("sort"), String::from("updated"))]
+        }
+    }
+
+    #[test]
+    fn stops_at_upper_bound() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            let (body, link) = if parts.url.as_str().contains("page=2") {
+                (
+                    r#"[
+                        {"number": 3, "created_at": "2025-01-03T00:00:00Z"},
+                        {"number": 4, "created_at": "2025-01-04T00:00:00Z"}
+                    ]"#,
+                    Some(r#"<https://api.github.com/issues?page=3>; rel="next""#),
+                )
+            } else if parts.url.as_str().contains("page=3") {
+                panic!("Third page should not be requested");
+            } else {
+                assert_eq!(
+                    parts.url.as_str(),
+                    "https://api.github.com/issues?sort=created&direction=asc&per_page=100"
+                );
+                (
+                    r#"[
+                        {"number": 1, "created_at": "2025-01-01T00:00:00Z"},
+                        {"number": 2, "created_at": "2025-01-02T00:00:00Z"}
+                    ]"#,
+                    Some(r#"<https://api.github.com/issues?page=2>; rel="next""#),
+                )
+            };
+            let mut resp = http::Response::builder();
+            if let Some(link) = link {
+                resp = resp.header(http::header::LINK, link);
+            }
+            resp.body(body.as_bytes().to_vec()).unwrap()
+        }));
+        let req = SnapshotRequest::new(ListIssues, "created", |issue: &Issue| {
+            issue.created_at.as_str()
+        })
+        .with_upper_bound("2025-01-03T00:00:00Z");
+        let numbers = client
+            .paginate_snapshot(req)
+            .map(|r| r.map(|issue| issue.number))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(numbers, [1, 2, 3]);
+    }
+}
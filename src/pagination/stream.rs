@@ -1,38 +1,232 @@
-use super::{PageRequest, PageResponse, PaginationInfo, PaginationRequest, PaginationState};
+use super::{
+    PageCursor, PageProgress, PageRequest, PageResponse, PaginationInfo, PaginationRequest,
+    PaginationState, estimate_remaining, is_reverse,
+};
 use crate::{
-    Endpoint,
     client::tokio::{AsyncBackend, AsyncClient},
     errors::Error,
 };
-use futures_util::{FutureExt, Stream, future::BoxFuture, stream::FusedStream};
+use futures_util::{
+    Stream, StreamExt,
+    stream::{BoxStream, FusedStream},
+};
 use pin_project_lite::pin_project;
 use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::fmt;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+/// [Private] A page request waiting to be sent by a `RequestDriver`, along
+/// with the time to wait before sending it
+type PendingRequest<T> = (PageRequest<T>, Option<Duration>);
+
+/// [Private] A long-lived stream that sends the page requests handed to it
+/// by a `PageStream` one at a time and yields their results
+///
+/// The driver is allocated once per `PageStream`.  As the futures for the
+/// individual requests all have the same type, each one is stored in the
+/// driver's allocation in place of the last rather than being boxed afresh
+/// for every page.
+struct RequestDriver<T, E> {
+    pending: Arc<Mutex<Option<PendingRequest<T>>>>,
+    responses: BoxStream<'static, Result<PageResponse<T>, Error<E>>>,
+}
+
+impl<T: DeserializeOwned + Send + 'static, E> RequestDriver<T, E> {
+    fn new<B>(client: AsyncClient<B>) -> Self
+    where
+        B: AsyncBackend<Error = E> + Send + Sync + 'static,
+    {
+        let pending = Arc::new(Mutex::new(None));
+        let responses = futures_util::stream::unfold(
+            (client, Arc::clone(&pending)),
+            |(client, pending)| async move {
+                let (req, delay) = take_pending(&pending)?;
+                if let Some(delay) = delay
+                    && let Some(sleeper) = client.sleeper()
+                {
+                    sleeper.sleep(delay).await;
+                }
+                let r = client.request(req).await;
+                Some((r, (client, pending)))
+            },
+        )
+        .boxed();
+        RequestDriver { pending, responses }
+    }
+}
+
+impl<T, E> RequestDriver<T, E> {
+    /// Hand a request to the driver to send on the next call to
+    /// `poll_response()`
+    fn send(&self, req: PageRequest<T>, delay: Option<Duration>) {
+        *self.pending.lock().unwrap_or_else(PoisonError::into_inner) = Some((req, delay));
+    }
+
+    /// Poll for the result of the request most recently passed to `send()`
+    fn poll_response(&mut self, cx: &mut Context<'_>) -> Poll<Result<PageResponse<T>, Error<E>>> {
+        match ready!(self.responses.poll_next_unpin(cx)) {
+            Some(r) => Poll::Ready(r),
+            // The stream only ends if it's polled without a request pending,
+            // which `PageStream` never does
+            None => unreachable!("RequestDriver polled without a pending request"),
+        }
+    }
+}
+
+impl<T, E> fmt::Debug for RequestDriver<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestDriver").finish_non_exhaustive()
+    }
+}
+
+/// [Private] Take the pending request, if any, out of a `RequestDriver`'s
+/// slot
+fn take_pending<T>(pending: &Mutex<Option<PendingRequest<T>>>) -> Option<PendingRequest<T>> {
+    pending
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+}
+
+pin_project! {
+    /// A stream of the pages of a [`PaginationRequest`], returned by
+    /// [`AsyncClient::paginate_pages()`]
+    ///
+    /// Each page is yielded as a [`PageResponse`] containing the page's items,
+    /// its [`PaginationInfo`], and the URL of the next page, if any.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct PageStream<B: AsyncBackend, R: PaginationRequest> {
+        pub(super) cursor: PageCursor<AsyncClient<B>, R>,
+        driver: Option<RequestDriver<R::Item, B::Error>>,
+        inflight: bool,
+    }
+}
+
+impl<B: AsyncBackend, R: PaginationRequest> PageStream<B, R> {
+    pub fn new(client: AsyncClient<B>, req: R) -> Self {
+        PageStream {
+            cursor: PageCursor::new(client, req),
+            driver: None,
+            inflight: false,
+        }
+    }
+
+    pub fn state(&self) -> PaginationState {
+        self.cursor.state()
+    }
+
+    /// Call `f` after each page is fetched with the progress of the
+    /// pagination so far; see [`PageCursor::on_page()`]
+    pub fn on_page<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&PageProgress) + Send + Sync + 'static,
+    {
+        self.cursor = self.cursor.on_page(f);
+        self
+    }
+
+    /// [Private] Returns whether any further pages will be requested
+    fn has_next_page(&self) -> bool {
+        self.inflight || self.cursor.has_next_page()
+    }
+}
+
+impl<B, R> Stream for PageStream<B, R>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+{
+    type Item = Result<PageResponse<R::Item>, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        loop {
+            if *this.inflight
+                && let Some(driver) = this.driver.as_mut()
+            {
+                let r = ready!(driver.poll_response(cx));
+                *this.inflight = false;
+                match r {
+                    Ok(mut page_resp) => {
+                        if this.cursor.handle_page(&mut page_resp) {
+                            return Some(Ok(page_resp)).into();
+                        }
+                    }
+                    Err(e) => {
+                        this.cursor.handle_error();
+                        return Some(Err(e)).into();
+                    }
+                }
+            }
+            let client = this.cursor.client.clone();
+            match this.cursor.next_request(&client.base_url()) {
+                Some(Ok(req)) => {
+                    let delay = this.cursor.take_delay();
+                    this.driver
+                        .get_or_insert_with(|| RequestDriver::new(client))
+                        .send(req, delay);
+                    *this.inflight = true;
+                }
+                Some(Err(e)) => return Some(Err(e)).into(),
+                None => return None.into(),
+            }
+        }
+    }
+}
+
+impl<B, R> FusedStream for PageStream<B, R>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+{
+    fn is_terminated(&self) -> bool {
+        self.state() == PaginationState::Ended
+    }
+}
 
 pin_project! {
     #[must_use = "streams do nothing unless polled"]
     pub struct PaginationStream<B: AsyncBackend, R: PaginationRequest> {
-        client: AsyncClient<B>,
-        req: R,
-        inner: InnerState<R::Item, B::Error>,
+        #[pin]
+        pages: PageStream<B, R>,
+        items: std::vec::IntoIter<R::Item>,
         info: Option<PaginationInfo>,
-        state: PaginationState,
+        page_len: usize,
     }
 }
 
 impl<B: AsyncBackend, R: PaginationRequest> PaginationStream<B, R> {
     pub fn new(client: AsyncClient<B>, req: R) -> Self {
-        let next_url = Some(req.endpoint());
         PaginationStream {
-            client,
-            req,
-            inner: InnerState::Yielding {
-                items: Vec::new().into_iter(),
-                next_url,
-            },
+            pages: PageStream::new(client, req),
+            items: Vec::new().into_iter(),
             info: None,
-            state: PaginationState::NotStarted,
+            page_len: 0,
+        }
+    }
+
+    /// Return a stream that fetches up to `pages` pages ahead of the page
+    /// whose items are currently being yielded.
+    ///
+    /// By default, the next page is only requested once all of the items of
+    /// the current page have been consumed.  With prefetching, the request
+    /// for the next page is started as soon as the current page has been
+    /// received and is driven forwards whenever the stream is polled, so that
+    /// the latency of the request is hidden from consumers that process items
+    /// quickly.  As the URL of each page is only known once the previous page
+    /// has been received, the prefetched pages are still requested one at a
+    /// time.
+    pub fn prefetch(self, pages: usize) -> PrefetchStream<B, R> {
+        PrefetchStream {
+            pages: self.pages,
+            items: self.items,
+            info: self.info,
+            prefetch: pages,
+            buffered: VecDeque::new(),
         }
     }
 
@@ -41,86 +235,372 @@ impl<B: AsyncBackend, R: PaginationRequest> PaginationStream<B, R> {
     }
 
     pub fn state(&self) -> PaginationState {
-        self.state
+        self.pages.state()
+    }
+
+    /// Call `f` after each page is fetched with the progress of the
+    /// pagination so far; see [`PageCursor::on_page()`].  Note that, when
+    /// prefetching, pages are reported as they are fetched rather than as
+    /// their items are yielded.
+    pub fn on_page<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&PageProgress) + Send + Sync + 'static,
+    {
+        self.pages = self.pages.on_page(f);
+        self
+    }
+
+    /// [Private] Returns the request being paginated
+    pub(super) fn request(&self) -> &R {
+        self.pages.cursor.request()
     }
 }
 
 impl<B, R> Stream for PaginationStream<B, R>
 where
-    B: AsyncBackend + Clone + Send + Sync + 'static,
+    B: AsyncBackend + Send + Sync + 'static,
     R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
 {
     type Item = Result<R::Item, Error<B::Error>>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
+        let mut this = self.project();
         loop {
-            match this.inner {
-                InnerState::Requesting(fut) => match ready!(fut.as_mut().poll(cx)) {
-                    Ok(page_resp) => {
-                        *this.state = PaginationState::Paging;
-                        *this.inner = InnerState::Yielding {
-                            items: page_resp.items.into_iter(),
-                            next_url: page_resp.next_url.map(Into::into),
-                        };
-                        *this.info = Some(page_resp.info);
-                    }
-                    Err(e) => {
-                        *this.state = PaginationState::Ended;
-                        *this.inner = InnerState::Done;
-                        *this.info = None;
-                        return Some(Err(e)).into();
-                    }
-                },
-                InnerState::Yielding { items, next_url } => {
-                    if let Some(value) = items.next() {
-                        return Some(Ok(value)).into();
-                    } else if let Some(url) = next_url.take() {
-                        let mut req = PageRequest::new(url.clone())
-                            .with_headers(this.req.headers())
-                            .with_timeout(this.req.timeout());
-                        if *this.state == PaginationState::NotStarted {
-                            req = req.with_params(this.req.params());
-                        }
-                        let client = this.client.clone();
-                        *this.inner = InnerState::Requesting(
-                            async move { client.clone().request(req).await }.boxed(),
-                        );
-                    } else {
-                        *this.state = PaginationState::Ended;
-                        *this.inner = InnerState::Done;
-                        *this.info = None;
-                    }
+            if let Some(item) = this.items.next() {
+                return Some(Ok(item)).into();
+            }
+            match ready!(this.pages.as_mut().poll_next(cx)) {
+                Some(Ok(page_resp)) => {
+                    *this.page_len = page_resp.items.len();
+                    *this.items = page_resp.items.into_iter();
+                    *this.info = Some(page_resp.info);
+                }
+                Some(Err(e)) => {
+                    *this.info = None;
+                    return Some(Err(e)).into();
+                }
+                None => {
+                    *this.info = None;
+                    return None.into();
                 }
-                InnerState::Done => return None.into(),
             }
         }
     }
+
+    /// Estimates the number of remaining items in the same way as
+    /// [`PaginationIter`][super::PaginationIter]'s `size_hint()`
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        estimate_remaining(
+            self.items.len(),
+            self.page_len,
+            self.info
+                .as_ref()
+                .filter(|_| !is_reverse(self.pages.cursor.request())),
+            self.pages.has_next_page(),
+            self.pages.cursor.pages_left(),
+        )
+    }
 }
 
 impl<B, R> FusedStream for PaginationStream<B, R>
 where
-    B: AsyncBackend + Clone + Send + Sync + 'static,
+    B: AsyncBackend + Send + Sync + 'static,
     R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
 {
     fn is_terminated(&self) -> bool {
-        self.state == PaginationState::Ended
+        self.pages.is_terminated()
+    }
+}
+
+pin_project! {
+    /// A [`PaginationStream`] that fetches pages ahead of the items being
+    /// consumed, returned by [`PaginationStream::prefetch()`]
+    #[must_use = "streams do nothing unless polled"]
+    pub struct PrefetchStream<B: AsyncBackend, R: PaginationRequest> {
+        #[pin]
+        pages: PageStream<B, R>,
+        items: std::vec::IntoIter<R::Item>,
+        info: Option<PaginationInfo>,
+        prefetch: usize,
+        buffered: VecDeque<Result<PageResponse<R::Item>, Error<B::Error>>>,
+    }
+}
+
+impl<B: AsyncBackend, R: PaginationRequest> PrefetchStream<B, R> {
+    /// Returns pagination information for the page whose items are currently
+    /// being yielded
+    pub fn info(&self) -> Option<PaginationInfo> {
+        self.info
+    }
+
+    pub fn state(&self) -> PaginationState {
+        match self.pages.state() {
+            PaginationState::Ended if !self.buffered.is_empty() || self.items.len() > 0 => {
+                PaginationState::Paging
+            }
+            state => state,
+        }
+    }
+}
+
+impl<B, R> Stream for PrefetchStream<B, R>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+{
+    type Item = Result<R::Item, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let mut pending = false;
+            while this.buffered.len() < *this.prefetch && !this.pages.is_terminated() {
+                match this.pages.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(r)) => this.buffered.push_back(r),
+                    Poll::Ready(None) => break,
+                    Poll::Pending => {
+                        pending = true;
+                        break;
+                    }
+                }
+            }
+            if let Some(item) = this.items.next() {
+                return Some(Ok(item)).into();
+            }
+            let next = match this.buffered.pop_front() {
+                Some(r) => Some(r),
+                None if pending => return Poll::Pending,
+                None => ready!(this.pages.as_mut().poll_next(cx)),
+            };
+            match next {
+                Some(Ok(page_resp)) => {
+                    *this.items = page_resp.items.into_iter();
+                    *this.info = Some(page_resp.info);
+                }
+                Some(Err(e)) => {
+                    *this.info = None;
+                    return Some(Err(e)).into();
+                }
+                None => {
+                    *this.info = None;
+                    return None.into();
+                }
+            }
+        }
     }
 }
 
-enum InnerState<T, BE> {
-    Requesting(BoxFuture<'static, Result<PageResponse<T>, Error<BE>>>),
-    Yielding {
-        items: std::vec::IntoIter<T>,
-        next_url: Option<Endpoint>,
-    },
-    Done,
+impl<B, R> FusedStream for PrefetchStream<B, R>
+where
+    B: AsyncBackend + Send + Sync + 'static,
+    R: PaginationRequest<Item: DeserializeOwned + Send + 'static>,
+{
+    fn is_terminated(&self) -> bool {
+        self.state() == PaginationState::Ended
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use futures_util::StreamExt;
+    use crate::{
+        Endpoint,
+        client::{ClientConfig, RequestParts, tokio::AsyncFnBackend},
+        pagination::Reversed,
+        testing::PaginationFixtures,
+    };
+    use futures_util::{StreamExt, TryStreamExt};
+    use rstest::rstest;
+    use serde::Deserialize;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    struct ListItems;
+
+    impl PaginationRequest for ListItems {
+        type Item = Item;
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["items"])
+        }
+    }
+
+    #[tokio::test]
+    async fn reverse() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let ids = client
+            .paginate(Reversed::new(ListItems))
+            .map_ok(|item| item.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids, (1..=25).rev().collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn on_page_progress() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let items = Arc::new(AtomicUsize::new(0));
+        let items2 = Arc::clone(&items);
+        let ids = client
+            .paginate(ListItems)
+            .on_page(move |p| {
+                items2.store(usize::try_from(p.items).unwrap(), Ordering::SeqCst);
+            })
+            .map_ok(|item| item.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 25);
+        assert_eq!(items.load(Ordering::SeqCst), 25);
+    }
+
+    #[tokio::test]
+    async fn size_hint() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let mut stream = client.paginate(ListItems);
+        assert_eq!(stream.size_hint(), (0, None));
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(stream.size_hint(), (20, Some(29)));
+        let mut stream = stream.skip(20);
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(stream.size_hint(), (3, Some(3)));
+    }
+
+    #[tokio::test]
+    async fn collect_all() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let items = client
+            .paginate(ListItems)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            items.into_iter().map(|item| item.id).collect::<Vec<_>>(),
+            (1..=25).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn unclonable_backend() {
+        /// A backend that does not implement `Clone`
+        struct Unclonable<B>(B);
+
+        impl<B: AsyncBackend> AsyncBackend for Unclonable<B> {
+            type Request = B::Request;
+            type Response = B::Response;
+            type Error = B::Error;
+
+            fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
+                self.0.prepare_request(r)
+            }
+
+            fn send<R: tokio::io::AsyncRead + Send + 'static>(
+                &self,
+                r: Self::Request,
+                body: R,
+            ) -> impl Future<Output = Result<Self::Response, Self::Error>> + Send + 'static
+            {
+                self.0.send(r, body)
+            }
+        }
+
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let mut client =
+            ClientConfig::new().with_async_backend(Unclonable(fixtures.async_backend()));
+        let stream = client.paginate(ListItems);
+        assert!(client.get_backend_mut().is_none());
+        let ids = stream
+            .map_ok(|item| item.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids, (1..=25).collect::<Vec<_>>());
+        assert!(client.get_backend_mut().is_some());
+    }
+
+    #[tokio::test]
+    async fn paginate_all() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let items = client.paginate_all(ListItems).await.unwrap();
+        assert_eq!(
+            items.into_iter().map(|item| item.id).collect::<Vec<_>>(),
+            (1..=25).collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(1, 2)]
+    #[case(2, 3)]
+    #[case(5, 3)]
+    #[tokio::test]
+    async fn prefetch(#[case] pages: usize, #[case] requests_after_first: usize) {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let client = ClientConfig::new().with_async_backend(AsyncFnBackend::new(
+            move |parts: RequestParts, _| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                fixtures.response_for(&parts.url)
+            },
+        ));
+        let mut stream = client.paginate(ListItems).prefetch(pages);
+        assert_eq!(stream.next().await.unwrap().unwrap(), Item { id: 1 });
+        assert_eq!(requests.load(Ordering::SeqCst), requests_after_first);
+        assert_eq!(stream.info().map(|i| i.current_page), Some(None));
+        let mut ids = vec![1];
+        while let Some(item) = stream.next().await {
+            ids.push(item.unwrap().id);
+        }
+        assert_eq!(ids, (1..=25).collect::<Vec<_>>());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(stream.state(), PaginationState::Ended);
+    }
+
+    #[tokio::test]
+    async fn paginate_pages() {
+        let fixtures =
+            PaginationFixtures::numbered("https://api.github.com/items".parse().unwrap(), 25)
+                .with_page_size(10);
+        let client = ClientConfig::new().with_async_backend(fixtures.async_backend());
+        let pages = client
+            .paginate_pages(ListItems)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            pages.iter().map(|p| p.items.len()).collect::<Vec<_>>(),
+            [10, 10, 5]
+        );
+        assert!(pages[2].next_url.is_none());
+    }
 
     #[test]
     fn stream_next_is_send() {
@@ -130,7 +610,7 @@ mod tests {
         #[allow(dead_code)]
         fn check<B, R>(stream: PaginationStream<B, R>)
         where
-            B: AsyncBackend + Clone + Send + Sync + 'static,
+            B: AsyncBackend + Send + Sync + 'static,
             R: PaginationRequest<Item: DeserializeOwned + Send + 'static> + Send,
         {
             tokio::pin!(stream);
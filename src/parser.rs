@@ -1,13 +1,18 @@
 use crate::{
     HeaderMapExt,
     consts::READ_BLOCK_SIZE,
-    errors::{CommonError, ParseResponseError},
+    errors::{CommonError, ParseResponseError, ResponseTooLargeError},
     response::{Response, ResponseParts},
+    util::json_from_slice,
 };
 use bstr::ByteVec;
+use bytes::{Bytes, BytesMut};
 use serde::de::DeserializeOwned;
+use std::fmt;
 use std::io::Write;
 use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 #[cfg(feature = "tokio")]
 use tokio::io::AsyncReadExt;
 
@@ -66,12 +71,64 @@ impl ResponseParser for Vec<u8> {
     }
 }
 
+impl ResponseParser for BytesMut {
+    type Output = BytesMut;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        if let Some(size) = parts
+            .headers()
+            .content_length()
+            .and_then(|sz| usize::try_from(sz).ok())
+        {
+            self.reserve(size);
+        }
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.extend_from_slice(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        Ok(self)
+    }
+}
+
+/// A parser that collects the response body into a [`Bytes`] buffer, which
+/// can be cheaply cloned and sliced in order to share the body without
+/// copying it
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BytesResponse(BytesMut);
+
+impl BytesResponse {
+    pub fn new() -> BytesResponse {
+        BytesResponse(BytesMut::new())
+    }
+}
+
+impl ResponseParser for BytesResponse {
+    type Output = Bytes;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.0.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.0.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.0.freeze())
+    }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub struct Utf8Text(Vec<u8>);
+pub struct Utf8Text(BytesMut);
 
 impl Utf8Text {
     pub fn new() -> Self {
-        Utf8Text(Vec::new())
+        Utf8Text(BytesMut::new())
     }
 }
 
@@ -88,16 +145,16 @@ impl ResponseParser for Utf8Text {
     }
 
     fn end(self) -> Result<Self::Output, Self::Error> {
-        String::from_utf8(self.0).map_err(|e| e.utf8_error().into())
+        String::from_utf8(Vec::from(self.0)).map_err(|e| e.utf8_error().into())
     }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub struct LossyUtf8Text(Vec<u8>);
+pub struct LossyUtf8Text(BytesMut);
 
 impl LossyUtf8Text {
     pub fn new() -> Self {
-        LossyUtf8Text(Vec::new())
+        LossyUtf8Text(BytesMut::new())
     }
 }
 
@@ -114,20 +171,20 @@ impl ResponseParser for LossyUtf8Text {
     }
 
     fn end(self) -> Result<Self::Output, Self::Error> {
-        Ok(self.0.into_string_lossy())
+        Ok(Vec::from(self.0).into_string_lossy())
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct JsonResponse<T> {
-    buf: Vec<u8>,
+    buf: BytesMut,
     _output: PhantomData<T>,
 }
 
 impl<T> JsonResponse<T> {
     pub fn new() -> JsonResponse<T> {
         JsonResponse {
-            buf: Vec::new(),
+            buf: BytesMut::new(),
             _output: PhantomData,
         }
     }
@@ -151,8 +208,627 @@ impl<T: DeserializeOwned> ResponseParser for JsonResponse<T> {
         self.buf.handle_bytes(buf);
     }
 
+    fn end(mut self) -> Result<Self::Output, Self::Error> {
+        parse_json(&mut self.buf)
+    }
+}
+
+/// A variant of [`JsonResponse`] for responses that may or may not have a
+/// body, such as those to some `PUT` and `DELETE` requests
+///
+/// The output is `None` if the response has status 204 No Content or if the
+/// body is empty (ignoring whitespace and any byte order mark); otherwise,
+/// the body is deserialized as a `T`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaybeJsonResponse<T> {
+    inner: JsonResponse<T>,
+    no_content: bool,
+}
+
+impl<T> MaybeJsonResponse<T> {
+    pub fn new() -> MaybeJsonResponse<T> {
+        MaybeJsonResponse {
+            inner: JsonResponse::new(),
+            no_content: false,
+        }
+    }
+}
+
+impl<T> Default for MaybeJsonResponse<T> {
+    fn default() -> MaybeJsonResponse<T> {
+        MaybeJsonResponse::new()
+    }
+}
+
+impl<T: DeserializeOwned> ResponseParser for MaybeJsonResponse<T> {
+    type Output = Option<T>;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.no_content = parts.status() == http::StatusCode::NO_CONTENT;
+        if !self.no_content {
+            self.inner.handle_parts(parts);
+        }
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        if !self.no_content {
+            self.inner.handle_bytes(buf);
+        }
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        if self.no_content || strip_bom(&self.inner.buf).trim_ascii().is_empty() {
+            Ok(None)
+        } else {
+            self.inner.end().map(Some)
+        }
+    }
+}
+
+/// A variant of [`JsonResponse`] that checks that the response has a JSON
+/// `Content-Type` before parsing the body
+///
+/// If the response's `Content-Type` is not `application/json` or
+/// `application/*+json` (see [`HeaderMapExt::content_type_is_json()`]), the
+/// body is not buffered, and parsing fails with [`CommonError::NotJson`].
+/// This gives a clear "expected JSON, got text/html" error instead of a
+/// confusing deserialization error when a proxy or authentication portal
+/// returns an HTML page.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrictJsonResponse<T> {
+    inner: JsonResponse<T>,
+    is_json: bool,
+    content_type: Option<String>,
+}
+
+impl<T> StrictJsonResponse<T> {
+    pub fn new() -> StrictJsonResponse<T> {
+        StrictJsonResponse {
+            inner: JsonResponse::new(),
+            is_json: false,
+            content_type: None,
+        }
+    }
+}
+
+impl<T> Default for StrictJsonResponse<T> {
+    fn default() -> StrictJsonResponse<T> {
+        StrictJsonResponse::new()
+    }
+}
+
+impl<T: DeserializeOwned> ResponseParser for StrictJsonResponse<T> {
+    type Output = T;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.is_json = parts.headers().content_type_is_json();
+        if self.is_json {
+            self.inner.handle_parts(parts);
+        } else {
+            self.content_type = parts
+                .headers()
+                .get(http::header::CONTENT_TYPE)
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned());
+        }
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        if self.is_json {
+            self.inner.handle_bytes(buf);
+        }
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        if self.is_json {
+            self.inner.end()
+        } else {
+            Err(CommonError::NotJson {
+                content_type: self.content_type,
+            })
+        }
+    }
+}
+
+/// A parser for newline-delimited JSON response bodies, in which each
+/// nonblank line is a separate JSON value
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NdJson<T> {
+    buf: BytesMut,
+    _output: PhantomData<T>,
+}
+
+impl<T> NdJson<T> {
+    pub fn new() -> NdJson<T> {
+        NdJson {
+            buf: BytesMut::new(),
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for NdJson<T> {
+    fn default() -> NdJson<T> {
+        NdJson::new()
+    }
+}
+
+impl<T: DeserializeOwned> ResponseParser for NdJson<T> {
+    type Output = Vec<T>;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.buf.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.buf.handle_bytes(buf);
+    }
+
+    fn end(mut self) -> Result<Self::Output, Self::Error> {
+        strip_bom_mut(&mut self.buf)
+            .split_mut(|&b| b == b'\n')
+            .filter(|line| !line.trim_ascii().is_empty())
+            .map(|line| json_from_slice(line))
+            .collect()
+    }
+}
+
+/// A parser that gives a callback zero-copy access to the JSON response body
+///
+/// [`JsonResponse`] deserializes the body into an owned value, which means
+/// allocating every string and collection that the output type contains.  For
+/// consumers that only need to inspect a few fields of a very large response
+/// before discarding it, `BorrowedJson` instead buffers the body, checks that
+/// it is well-formed JSON, and passes the callback a
+/// [`&RawValue`][serde_json::value::RawValue] borrowing from the buffer.  The
+/// callback can then deserialize the value (or parts of it) into types that
+/// borrow from the buffer, such as `&str` or `&RawValue` fields, and return
+/// whatever owned data it extracts.  The borrowed data cannot outlive the
+/// callback, as the buffer is dropped once the callback returns.
+///
+/// As with [`JsonResponse`], a leading byte order mark and surrounding
+/// whitespace are ignored, and an empty body results in
+/// [`CommonError::EmptyBody`].
+///
+/// # Example
+///
+/// ```
+/// use ghreq::parser::{BorrowedJson, ResponseParser};
+/// use serde::Deserialize;
+/// use serde_json::value::RawValue;
+///
+/// #[derive(Deserialize)]
+/// struct Slim<'a> {
+///     full_name: &'a str,
+/// }
+///
+/// let mut parser = BorrowedJson::new(|raw: &RawValue| {
+///     let repo = serde_json::from_str::<Slim<'_>>(raw.get())?;
+///     Ok::<_, serde_json::Error>(repo.full_name.len())
+/// });
+/// parser.handle_bytes(br#"{"full_name": "octocat/hello-world", "id": 1}"#);
+/// assert_eq!(parser.end().unwrap(), 19);
+/// ```
+pub struct BorrowedJson<F> {
+    buf: BytesMut,
+    f: F,
+}
+
+impl<F> BorrowedJson<F> {
+    pub fn new<U, E>(f: F) -> BorrowedJson<F>
+    where
+        F: for<'buf> FnOnce(&'buf serde_json::value::RawValue) -> Result<U, E>,
+        E: Into<CommonError>,
+    {
+        BorrowedJson {
+            buf: BytesMut::new(),
+            f,
+        }
+    }
+}
+
+impl<F> fmt::Debug for BorrowedJson<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BorrowedJson")
+            .field("buf", &self.buf)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, U, E> ResponseParser for BorrowedJson<F>
+where
+    F: for<'buf> FnOnce(&'buf serde_json::value::RawValue) -> Result<U, E>,
+    E: Into<CommonError>,
+{
+    type Output = U;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.buf.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.buf.handle_bytes(buf);
+    }
+
     fn end(self) -> Result<Self::Output, Self::Error> {
-        serde_json::from_slice(&self.buf).map_err(Into::into)
+        let buf = strip_bom(&self.buf).trim_ascii();
+        if buf.is_empty() {
+            return Err(CommonError::EmptyBody);
+        }
+        let raw = serde_json::from_slice::<&serde_json::value::RawValue>(buf)?;
+        (self.f)(raw).map_err(Into::into)
+    }
+}
+
+/// [Private] Remove a leading UTF-8 byte order mark, as sent by some proxies
+/// and GitHub Enterprise Server instances, from a response body
+fn strip_bom(buf: &[u8]) -> &[u8] {
+    buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(buf)
+}
+
+/// [Private] Like [`strip_bom()`], but for a buffer that is about to be
+/// parsed in place
+fn strip_bom_mut(buf: &mut [u8]) -> &mut [u8] {
+    let start = buf.len() - strip_bom(buf).len();
+    &mut buf[start..]
+}
+
+/// [Private] Deserialize a JSON response body, ignoring any byte order mark
+/// and surrounding whitespace.
+///
+/// An empty body is treated as `null` if `T` accepts it, and otherwise results
+/// in [`CommonError::EmptyBody`].  A `null` body that `T` does not accept
+/// results in [`CommonError::NullBody`].
+fn parse_json<T: DeserializeOwned>(buf: &mut [u8]) -> Result<T, CommonError> {
+    let buf = strip_bom_mut(buf);
+    match buf.trim_ascii() {
+        b"" => serde_json::from_slice(b"null").map_err(|_| CommonError::EmptyBody),
+        b"null" => serde_json::from_slice(b"null").map_err(|_| CommonError::NullBody),
+        _ => json_from_slice(buf),
+    }
+}
+
+/// An incremental decoder for a JSON array that yields each element of the
+/// array as soon as all of its bytes have been fed in, rather than waiting for
+/// the entire array
+///
+/// This is used by [`Client::request_json_array()`][crate::client::Client::request_json_array]
+/// to process very large responses without buffering them in memory, but it
+/// can also be driven manually: pass each chunk of the body to
+/// [`StreamingJsonArray::feed()`], call [`StreamingJsonArray::next_item()`]
+/// until it returns `None`, and call [`StreamingJsonArray::finish()`] once the
+/// body has been read in full.  As with [`JsonResponse`], a leading byte order
+/// mark is ignored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamingJsonArray<T> {
+    buf: BytesMut,
+    /// The offset in `buf` of the next byte to scan
+    pos: usize,
+    /// The offset in `buf` at which the element currently being scanned
+    /// starts
+    item_start: usize,
+    state: ArrayState,
+    failed: bool,
+    _output: PhantomData<T>,
+}
+
+/// [Private] The position of a [`StreamingJsonArray`] within the array
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ArrayState {
+    Start,
+    BeforeItem {
+        first: bool,
+    },
+    InItem {
+        depth: usize,
+        in_string: bool,
+        escape: bool,
+    },
+    AfterItem,
+    Done,
+}
+
+impl<T> StreamingJsonArray<T> {
+    pub fn new() -> StreamingJsonArray<T> {
+        StreamingJsonArray {
+            buf: BytesMut::new(),
+            pos: 0,
+            item_start: 0,
+            state: ArrayState::Start,
+            failed: false,
+            _output: PhantomData,
+        }
+    }
+
+    /// Append a chunk of the response body to the data to decode
+    pub fn feed(&mut self, data: &[u8]) {
+        // Discard everything before the element currently being scanned (or
+        // before the scan position, if between elements) before growing the
+        // buffer
+        let keep = if matches!(self.state, ArrayState::InItem { .. }) {
+            self.item_start
+        } else {
+            self.pos
+        };
+        if keep > 0 {
+            bytes::Buf::advance(&mut self.buf, keep);
+            self.pos -= keep;
+            self.item_start = self.item_start.saturating_sub(keep);
+        }
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Returns `true` if the closing bracket of the array has been decoded
+    pub fn is_done(&self) -> bool {
+        self.state == ArrayState::Done
+    }
+
+    /// Check that the data fed in so far consists of a complete array.  Call
+    /// this once the end of the response body has been reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the array was not closed or if an error was
+    /// previously returned by [`StreamingJsonArray::next_item()`].
+    pub fn finish(&self) -> Result<(), CommonError> {
+        if self.failed {
+            Err(json_syntax_error("JSON array contains an error"))
+        } else if self.state == ArrayState::Start && strip_bom(&self.buf).trim_ascii().is_empty() {
+            Err(CommonError::EmptyBody)
+        } else if self.is_done() {
+            Ok(())
+        } else {
+            Err(json_syntax_error("EOF while parsing JSON array"))
+        }
+    }
+
+    /// [Private] Advance through the buffer until the next complete element
+    /// is found, returning its byte range
+    fn scan(&mut self) -> Result<Option<std::ops::Range<usize>>, CommonError> {
+        while let Some(&b) = self.buf.get(self.pos) {
+            match self.state {
+                ArrayState::Start => {
+                    if self.pos == 0 && b == 0xEF {
+                        if self.buf.len() < 3 {
+                            // Wait for the rest of a possible byte order mark
+                            return Ok(None);
+                        } else if self.buf.starts_with(b"\xEF\xBB\xBF") {
+                            self.pos = 3;
+                            continue;
+                        }
+                    }
+                    if b == b'[' {
+                        self.state = ArrayState::BeforeItem { first: true };
+                    } else if !b.is_ascii_whitespace() {
+                        return Err(json_syntax_error("expected JSON array"));
+                    }
+                }
+                ArrayState::BeforeItem { first } => {
+                    if b == b']' && first {
+                        self.state = ArrayState::Done;
+                    } else if !b.is_ascii_whitespace() {
+                        self.item_start = self.pos;
+                        self.state = ArrayState::InItem {
+                            depth: 0,
+                            in_string: false,
+                            escape: false,
+                        };
+                        continue;
+                    }
+                }
+                ArrayState::InItem {
+                    mut depth,
+                    mut in_string,
+                    mut escape,
+                } => {
+                    if in_string {
+                        if escape {
+                            escape = false;
+                        } else if b == b'\\' {
+                            escape = true;
+                        } else if b == b'"' {
+                            in_string = false;
+                        }
+                    } else {
+                        match b {
+                            b'"' => in_string = true,
+                            b'{' | b'[' => depth += 1,
+                            b'}' | b']' if depth > 0 => depth -= 1,
+                            b',' | b'}' | b']' if depth == 0 => {
+                                // End of a scalar element; the terminator is
+                                // handled in the `AfterItem` state
+                                self.state = ArrayState::AfterItem;
+                                return Ok(Some(self.item_start..self.pos));
+                            }
+                            b if b.is_ascii_whitespace() && depth == 0 => {
+                                self.state = ArrayState::AfterItem;
+                                return Ok(Some(self.item_start..self.pos));
+                            }
+                            _ => (),
+                        }
+                    }
+                    self.pos += 1;
+                    if depth == 0 && !in_string && matches!(b, b'"' | b'}' | b']') {
+                        self.state = ArrayState::AfterItem;
+                        return Ok(Some(self.item_start..self.pos));
+                    }
+                    self.state = ArrayState::InItem {
+                        depth,
+                        in_string,
+                        escape,
+                    };
+                    continue;
+                }
+                ArrayState::AfterItem => match b {
+                    b',' => self.state = ArrayState::BeforeItem { first: false },
+                    b']' => self.state = ArrayState::Done,
+                    b if b.is_ascii_whitespace() => (),
+                    _ => return Err(json_syntax_error("expected ',' or ']' in JSON array")),
+                },
+                ArrayState::Done => {
+                    if !b.is_ascii_whitespace() {
+                        return Err(json_syntax_error("trailing characters after JSON array"));
+                    }
+                }
+            }
+            self.pos += 1;
+        }
+        Ok(None)
+    }
+}
+
+impl<T: DeserializeOwned> StreamingJsonArray<T> {
+    /// Decode the next element of the array.  Returns `None` if more data
+    /// needs to be fed in first or if the end of the array has been reached.
+    ///
+    /// Once an error has been returned, all further calls return `None`.
+    pub fn next_item(&mut self) -> Option<Result<T, CommonError>> {
+        if self.failed {
+            return None;
+        }
+        let r = match self.scan() {
+            Ok(Some(range)) => serde_json::from_slice(&self.buf[range]).map_err(Into::into),
+            Ok(None) => return None,
+            Err(e) => Err(e),
+        };
+        if r.is_err() {
+            self.failed = true;
+        }
+        Some(r)
+    }
+}
+
+impl<T> Default for StreamingJsonArray<T> {
+    fn default() -> StreamingJsonArray<T> {
+        StreamingJsonArray::new()
+    }
+}
+
+/// [Private] Construct a [`CommonError::Json`] describing malformed input
+fn json_syntax_error(msg: &str) -> CommonError {
+    CommonError::Json(<serde_json::Error as serde::de::Error>::custom(msg))
+}
+
+/// An iterator over the elements of a JSON array read from a response body,
+/// returned by [`Client::request_json_array()`][crate::client::Client::request_json_array]
+///
+/// Each element is yielded as soon as it has been read in full.  If reading
+/// or decoding fails, the error is yielded, and the iterator then ends.
+#[derive(Debug)]
+pub struct JsonArrayIter<R, T> {
+    reader: R,
+    decoder: StreamingJsonArray<T>,
+    done: bool,
+}
+
+impl<R, T> JsonArrayIter<R, T> {
+    pub fn new(reader: R) -> JsonArrayIter<R, T> {
+        JsonArrayIter {
+            reader,
+            decoder: StreamingJsonArray::new(),
+            done: false,
+        }
+    }
+}
+
+impl<R: std::io::Read, T: DeserializeOwned> Iterator for JsonArrayIter<R, T> {
+    type Item = Result<T, ParseResponseError<CommonError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = vec![0u8; READ_BLOCK_SIZE];
+        while !self.done {
+            if let Some(r) = self.decoder.next_item() {
+                self.done = r.is_err();
+                return Some(r.map_err(ParseResponseError::Parse));
+            }
+            match self.reader.read(&mut buf) {
+                Ok(0) => {
+                    self.done = true;
+                    return self
+                        .decoder
+                        .finish()
+                        .err()
+                        .map(|e| Err(ParseResponseError::Parse(e)));
+                }
+                Ok(n) => self.decoder.feed(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(ParseResponseError::Read(e)));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<R: std::io::Read, T: DeserializeOwned> std::iter::FusedIterator for JsonArrayIter<R, T> {}
+
+#[cfg(feature = "tokio")]
+pin_project_lite::pin_project! {
+    /// A stream of the elements of a JSON array read from an asynchronous
+    /// response body, returned by
+    /// [`AsyncClient::request_json_array()`][crate::client::tokio::AsyncClient::request_json_array]
+    ///
+    /// Each element is yielded as soon as it has been read in full.  If
+    /// reading or decoding fails, the error is yielded, and the stream then
+    /// ends.
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    #[derive(Debug)]
+    pub struct JsonArrayStream<R, T> {
+        #[pin]
+        reader: R,
+        decoder: StreamingJsonArray<T>,
+        buf: Box<[u8]>,
+        done: bool,
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R, T> JsonArrayStream<R, T> {
+    pub fn new(reader: R) -> JsonArrayStream<R, T> {
+        JsonArrayStream {
+            reader,
+            decoder: StreamingJsonArray::new(),
+            buf: vec![0u8; READ_BLOCK_SIZE].into_boxed_slice(),
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead, T: DeserializeOwned> futures_util::Stream for JsonArrayStream<R, T> {
+    type Item = Result<T, ParseResponseError<CommonError>>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        while !*this.done {
+            if let Some(r) = this.decoder.next_item() {
+                *this.done = r.is_err();
+                return Some(r.map_err(ParseResponseError::Parse)).into();
+            }
+            let mut buf = tokio::io::ReadBuf::new(this.buf);
+            match std::task::ready!(this.reader.as_mut().poll_read(cx, &mut buf)) {
+                Ok(()) if buf.filled().is_empty() => {
+                    *this.done = true;
+                    let r = this.decoder.finish();
+                    return r.err().map(|e| Err(ParseResponseError::Parse(e))).into();
+                }
+                Ok(()) => this.decoder.feed(buf.filled()),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => {
+                    *this.done = true;
+                    return Some(Err(ParseResponseError::Read(e))).into();
+                }
+            }
+        }
+        None.into()
     }
 }
 
@@ -188,51 +864,716 @@ impl<T: ResponseParser> ResponseParser for WithParts<T> {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct ToWriter<W> {
-    writer: W,
-    err: Option<std::io::Error>,
+/// A parser that passes the response body to an inner parser only as long as
+/// the body does not exceed a given number of bytes
+///
+/// If the response's `Content-Length` header or the number of bytes actually
+/// received exceeds the limit, no further data is passed to the inner parser,
+/// and [`end()`][ResponseParser::end] fails with a
+/// [`ResponseTooLargeError`] wrapped in a [`std::io::Error`].  This protects
+/// memory when an endpoint misbehaves or a wrong URL returns an enormous
+/// payload.  The rest of the body is still read and discarded; to stop
+/// reading as soon as the limit is exceeded, use
+/// [`ClientConfig::with_max_response_size()`][crate::client::ClientConfig::with_max_response_size].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Limited<P> {
+    inner: P,
+    limit: u64,
+    received: u64,
+    exceeded: bool,
 }
 
-impl<W> ToWriter<W> {
-    pub fn new(writer: W) -> ToWriter<W> {
-        ToWriter { writer, err: None }
+impl<P> Limited<P> {
+    /// Wrap `inner` so that it receives at most `limit` bytes
+    pub fn new(inner: P, limit: u64) -> Limited<P> {
+        Limited {
+            inner,
+            limit,
+            received: 0,
+            exceeded: false,
+        }
     }
 }
 
-impl<W: Write> ResponseParser for ToWriter<W> {
-    type Output = ();
-    type Error = CommonError;
+impl<P: ResponseParser> ResponseParser for Limited<P> {
+    type Output = P::Output;
+    type Error = P::Error;
 
-    fn handle_parts(&mut self, _parts: &ResponseParts) {}
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        if parts
+            .headers()
+            .content_length()
+            .is_some_and(|sz| sz > self.limit)
+        {
+            self.exceeded = true;
+        }
+        self.inner.handle_parts(parts);
+    }
 
     fn handle_bytes(&mut self, buf: &[u8]) {
-        if self.err.is_none()
-            && let Err(e) = self.writer.write_all(buf)
-        {
-            self.err = Some(e);
+        if self.exceeded {
+            return;
+        }
+        self.received = self.received.saturating_add(buf.len() as u64);
+        if self.received > self.limit {
+            self.exceeded = true;
+        } else {
+            self.inner.handle_bytes(buf);
         }
     }
 
     fn end(self) -> Result<Self::Output, Self::Error> {
-        if let Some(e) = self.err {
-            Err(e.into())
+        if self.exceeded {
+            Err(ResponseTooLargeError::new(self.limit)
+                .into_io_error()
+                .into())
         } else {
-            Ok(())
+            self.inner.end()
         }
     }
 }
 
-pub trait ResponseParserExt: ResponseParser {
-    fn parse_response<R: std::io::Read>(
-        mut self,
-        resp: Response<R>,
-    ) -> Result<Self::Output, ParseResponseError<Self::Error>> {
-        let (parts, mut body) = resp.into_parts();
-        self.handle_parts(&parts);
-        let mut buf = vec![0u8; READ_BLOCK_SIZE];
-        loop {
-            match body.read(&mut buf) {
+/// A parser that passes the response body to an inner parser while reporting
+/// the download's progress to a callback, e.g., for displaying a progress bar
+///
+/// The callback is called with the number of body bytes received so far and
+/// the total size of the body from the `Content-Length` header, if known:
+/// once with zero bytes when the response's headers are received, and then
+/// after each chunk of the body.  As this is a parser, it works the same way
+/// with both synchronous and asynchronous clients.
+///
+/// # Example
+///
+/// ```
+/// use ghreq::parser::{Progress, ResponseParser};
+///
+/// let mut reports = Vec::new();
+/// let mut parser = Progress::new(Vec::new(), |received, total| {
+///     reports.push((received, total));
+/// });
+/// parser.handle_bytes(b"Hello, ");
+/// parser.handle_bytes(b"world!");
+/// assert_eq!(parser.end().unwrap(), b"Hello, world!");
+/// assert_eq!(reports, [(7, None), (13, None)]);
+/// ```
+pub struct Progress<P, F> {
+    inner: P,
+    callback: F,
+    received: u64,
+    total: Option<u64>,
+}
+
+impl<P, F> Progress<P, F>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    pub fn new(inner: P, callback: F) -> Progress<P, F> {
+        Progress {
+            inner,
+            callback,
+            received: 0,
+            total: None,
+        }
+    }
+}
+
+impl<P: fmt::Debug, F> fmt::Debug for Progress<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("inner", &self.inner)
+            .field("received", &self.received)
+            .field("total", &self.total)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, F> ResponseParser for Progress<P, F>
+where
+    P: ResponseParser,
+    F: FnMut(u64, Option<u64>),
+{
+    type Output = P::Output;
+    type Error = P::Error;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.total = parts.headers().content_length();
+        self.inner.handle_parts(parts);
+        (self.callback)(self.received, self.total);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.inner.handle_bytes(buf);
+        self.received = self.received.saturating_add(buf.len() as u64);
+        (self.callback)(self.received, self.total);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        self.inner.end()
+    }
+}
+
+/// A parser that computes a cryptographic digest of the response body while
+/// passing the body on to an inner parser, returning the inner parser's
+/// output along with the [`Digest`]
+///
+/// This is useful for verifying downloads, such as release assets, against a
+/// published checksum.  The digest is computed with SHA-256 by default; other
+/// algorithms can be used by passing any hasher implementing
+/// [`sha2::Digest`] (e.g., `sha1::Sha1` from the `sha1` crate) to
+/// [`WithDigest::with_hasher()`].
+///
+/// # Example
+///
+/// ```
+/// use ghreq::parser::{Digest, ResponseParser, WithDigest};
+///
+/// let mut parser = WithDigest::new(Vec::new());
+/// parser.handle_bytes(b"hello world");
+/// let (body, digest): (Vec<u8>, Digest) = parser.end().unwrap();
+/// assert_eq!(body, b"hello world");
+/// assert_eq!(
+///     digest.to_string(),
+///     "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+/// );
+/// ```
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Clone)]
+pub struct WithDigest<P, H = sha2::Sha256> {
+    inner: P,
+    hasher: H,
+}
+
+#[cfg(feature = "digest")]
+impl<P> WithDigest<P> {
+    /// Wrap `inner` so that a SHA-256 digest of the body is computed
+    pub fn new(inner: P) -> WithDigest<P> {
+        WithDigest::with_hasher(inner, sha2::Digest::new())
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<P, H: sha2::Digest> WithDigest<P, H> {
+    /// Wrap `inner` so that the body is fed to `hasher`
+    pub fn with_hasher(inner: P, hasher: H) -> WithDigest<P, H> {
+        WithDigest { inner, hasher }
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<P: fmt::Debug, H> fmt::Debug for WithDigest<P, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithDigest")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<P: ResponseParser, H: sha2::Digest> ResponseParser for WithDigest<P, H> {
+    type Output = (P::Output, Digest);
+    type Error = P::Error;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.inner.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.hasher.update(buf);
+        self.inner.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        let digest = Digest(self.hasher.finalize().to_vec());
+        Ok((self.inner.end()?, digest))
+    }
+}
+
+/// A digest of a response or request body, as computed by [`WithDigest`] or
+/// [`ChecksumBody`][crate::request::ChecksumBody]
+///
+/// A `Digest` displays as a string of lowercase hexadecimal digits.
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Digest(Vec<u8>);
+
+#[cfg(feature = "digest")]
+impl Digest {
+    pub(crate) fn new(bytes: Vec<u8>) -> Digest {
+        Digest(bytes)
+    }
+
+    /// Returns the raw bytes of the digest
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Test whether the digest equals the digest given as a string of
+    /// hexadecimal digits, ignoring case.  An algorithm prefix like that in
+    /// GitHub's release asset digests (e.g., `"sha256:"`) is ignored.
+    pub fn matches_hex(&self, s: &str) -> bool {
+        let s = s.split_once(':').map_or(s, |(_, hex)| hex);
+        s.len() == self.0.len() * 2
+            && s.bytes().all(|c| c.is_ascii_hexdigit())
+            && s.as_bytes().chunks(2).zip(&self.0).all(|(pair, &b)| {
+                std::str::from_utf8(pair)
+                    .ok()
+                    .and_then(|p| u8::from_str_radix(p, 16).ok())
+                    == Some(b)
+            })
+    }
+}
+
+#[cfg(feature = "digest")]
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "digest")]
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ToWriter<W> {
+    writer: W,
+    err: Option<std::io::Error>,
+}
+
+impl<W> ToWriter<W> {
+    pub fn new(writer: W) -> ToWriter<W> {
+        ToWriter { writer, err: None }
+    }
+}
+
+impl<W: Write> ResponseParser for ToWriter<W> {
+    type Output = ();
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, _parts: &ResponseParts) {}
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        if self.err.is_none()
+            && let Err(e) = self.writer.write_all(buf)
+        {
+            self.err = Some(e);
+        }
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        if let Some(e) = self.err {
+            Err(e.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// An asynchronous counterpart of [`ToWriter`] that writes the response body
+/// to a [`tokio::io::AsyncWrite`] without blocking the runtime
+///
+/// Because [`ResponseParser`] methods are synchronous, this is not a
+/// `ResponseParser`; instead, pass a response to
+/// [`ToAsyncWriter::parse_async_response()`], or use
+/// [`AsyncClient::request_to_writer()`][crate::client::tokio::AsyncClient::request_to_writer].
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Debug, Default)]
+pub struct ToAsyncWriter<W> {
+    writer: W,
+}
+
+#[cfg(feature = "tokio")]
+impl<W> ToAsyncWriter<W> {
+    pub fn new(writer: W) -> ToAsyncWriter<W> {
+        ToAsyncWriter { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin + Send> ToAsyncWriter<W> {
+    /// Write the body of `resp` to the writer and then flush it
+    ///
+    /// # Errors
+    ///
+    /// Errors in reading the body are returned as
+    /// [`ParseResponseError::Read`], and errors in writing to the writer are
+    /// returned as [`ParseResponseError::Parse`].
+    pub async fn parse_async_response<R: tokio::io::AsyncRead + Send + 'static>(
+        mut self,
+        resp: Response<R>,
+    ) -> Result<(), ParseResponseError<CommonError>> {
+        use tokio::io::AsyncWriteExt;
+        let write_err = |e: std::io::Error| ParseResponseError::Parse(CommonError::from(e));
+        let body = resp.into_body();
+        tokio::pin!(body);
+        let mut buf = BytesMut::with_capacity(READ_BLOCK_SIZE);
+        loop {
+            buf.clear();
+            match body.read_buf(&mut buf).await {
+                Ok(0) => break,
+                Ok(_) => self.writer.write_all(&buf).await.map_err(write_err)?,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(ParseResponseError::Read(e)),
+            }
+        }
+        self.writer.flush().await.map_err(write_err)
+    }
+}
+
+/// A parser that writes the response body to a file atomically
+///
+/// The body is streamed to a temporary file in the same directory as the
+/// target path, which is renamed to the target path only once the body has
+/// been received and written in full, replacing any existing file.  If
+/// anything goes wrong — including the parser being dropped before the body
+/// is complete — the temporary file is removed, and so a `ToFile` never
+/// leaves a truncated file at the target path.
+///
+/// See [`AsyncToFile`] for an asynchronous equivalent that performs file I/O
+/// via [`tokio::fs`].
+#[derive(Debug)]
+pub struct ToFile {
+    path: PathBuf,
+    temp: Option<(TempPath, std::fs::File)>,
+    err: Option<std::io::Error>,
+}
+
+impl ToFile {
+    pub fn new<P: Into<PathBuf>>(path: P) -> ToFile {
+        ToFile {
+            path: path.into(),
+            temp: None,
+            err: None,
+        }
+    }
+
+    /// Returns the path to which the body will be written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ResponseParser for ToFile {
+    type Output = ();
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, _parts: &ResponseParts) {
+        match TempPath::create(&self.path) {
+            Ok(temp) => self.temp = Some(temp),
+            Err(e) => self.err = Some(e),
+        }
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        if self.err.is_none()
+            && let Some((_, fp)) = self.temp.as_mut()
+            && let Err(e) = fp.write_all(buf)
+        {
+            self.err = Some(e);
+        }
+    }
+
+    fn end(mut self) -> Result<Self::Output, Self::Error> {
+        if let Some(e) = self.err.take() {
+            return Err(e.into());
+        }
+        let Some((temp, mut fp)) = self.temp.take() else {
+            return Err(std::io::Error::other("handle_parts() was not called").into());
+        };
+        fp.flush()?;
+        fp.sync_all()?;
+        drop(fp);
+        temp.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+/// An asynchronous counterpart of [`ToFile`] that writes the response body to
+/// a file atomically using [`tokio::fs`]
+///
+/// Because [`ResponseParser`] methods are synchronous, this is not a
+/// `ResponseParser`; instead, pass a response to
+/// [`AsyncToFile::parse_async_response()`], or use
+/// [`AsyncClient::request_to_file()`][crate::client::tokio::AsyncClient::request_to_file].
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AsyncToFile {
+    path: PathBuf,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncToFile {
+    pub fn new<P: Into<PathBuf>>(path: P) -> AsyncToFile {
+        AsyncToFile { path: path.into() }
+    }
+
+    /// Returns the path to which the body will be written
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the body of `resp` to a temporary file and then rename it to
+    /// the target path.  On failure, or if the returned future is dropped
+    /// before completion, the temporary file is removed.
+    ///
+    /// # Errors
+    ///
+    /// Errors in reading the body are returned as
+    /// [`ParseResponseError::Read`], and errors in creating, writing, or
+    /// renaming the file are returned as [`ParseResponseError::Parse`].
+    pub async fn parse_async_response<R: tokio::io::AsyncRead + Send + 'static>(
+        self,
+        resp: Response<R>,
+    ) -> Result<(), ParseResponseError<CommonError>> {
+        let file_err = |e: std::io::Error| ParseResponseError::Parse(CommonError::from(e));
+        let (temp, fp) = TempPath::create_async(&self.path).await.map_err(file_err)?;
+        let mut fp = tokio::io::BufWriter::new(fp);
+        ToAsyncWriter::new(&mut fp)
+            .parse_async_response(resp)
+            .await?;
+        let fp = fp.into_inner();
+        fp.sync_all().await.map_err(file_err)?;
+        drop(fp);
+        temp.persist_async(&self.path).await.map_err(file_err)
+    }
+}
+
+/// [Private] The path of a temporary file that is removed when dropped unless
+/// it has been persisted
+#[derive(Debug)]
+struct TempPath(Option<PathBuf>);
+
+impl TempPath {
+    /// [Private] The maximum number of names to try when creating a temporary
+    /// file
+    const MAX_ATTEMPTS: usize = 100;
+
+    /// [Private] Returns a candidate path for a temporary file alongside
+    /// `target`
+    fn candidate(target: &Path) -> std::io::Result<PathBuf> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let Some(name) = target.file_name() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "target path has no file name",
+            ));
+        };
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        Ok(target.with_file_name(temp_name))
+    }
+
+    /// [Private] Create a new temporary file alongside `target`
+    fn create(target: &Path) -> std::io::Result<(TempPath, std::fs::File)> {
+        let mut attempts = 0;
+        loop {
+            let path = TempPath::candidate(target)?;
+            match std::fs::File::create_new(&path) {
+                Ok(fp) => return Ok((TempPath(Some(path)), fp)),
+                Err(e)
+                    if e.kind() == std::io::ErrorKind::AlreadyExists
+                        && attempts < TempPath::MAX_ATTEMPTS =>
+                {
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// [Private] Asynchronously create a new temporary file alongside
+    /// `target`
+    #[cfg(feature = "tokio")]
+    async fn create_async(target: &Path) -> std::io::Result<(TempPath, tokio::fs::File)> {
+        let mut attempts = 0;
+        loop {
+            let path = TempPath::candidate(target)?;
+            match tokio::fs::File::create_new(&path).await {
+                Ok(fp) => return Ok((TempPath(Some(path)), fp)),
+                Err(e)
+                    if e.kind() == std::io::ErrorKind::AlreadyExists
+                        && attempts < TempPath::MAX_ATTEMPTS =>
+                {
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// [Private] Rename the temporary file to `target`
+    fn persist(mut self, target: &Path) -> std::io::Result<()> {
+        if let Some(path) = self.0.as_ref() {
+            std::fs::rename(path, target)?;
+            self.0 = None;
+        }
+        Ok(())
+    }
+
+    /// [Private] Asynchronously rename the temporary file to `target`
+    #[cfg(feature = "tokio")]
+    async fn persist_async(mut self, target: &Path) -> std::io::Result<()> {
+        if let Some(path) = self.0.as_ref() {
+            tokio::fs::rename(path, target).await?;
+            self.0 = None;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            // Cleanup is best-effort; there is nowhere to report a failure
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// A line of a GitHub Actions job log, as passed to the callback of a
+/// [`LogLines`] parser
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LogLine<'a> {
+    /// The RFC 3339 timestamp that GitHub prefixes to each line of a job log,
+    /// if present
+    pub timestamp: Option<&'a str>,
+
+    /// The text of the line, without the timestamp or line terminator
+    pub text: &'a str,
+}
+
+/// A parser for GitHub Actions job logs that passes each line of the response
+/// body to a callback as soon as it is received, so that even very large logs
+/// can be processed without buffering them in memory
+///
+/// Lines may be terminated by either LF or CRLF, and a leading byte order mark
+/// is ignored.  Invalid UTF-8 is replaced with U+FFFD REPLACEMENT CHARACTER.
+/// The parser's output is the number of lines processed.
+pub struct LogLines<F> {
+    buf: BytesMut,
+    callback: F,
+    lines: u64,
+}
+
+impl<F: FnMut(LogLine<'_>)> LogLines<F> {
+    pub fn new(callback: F) -> LogLines<F> {
+        LogLines {
+            buf: BytesMut::new(),
+            callback,
+            lines: 0,
+        }
+    }
+
+    /// [Private] Pass a complete line (without its LF) to the callback
+    fn emit(&mut self, line: &[u8]) {
+        let line = if self.lines == 0 {
+            strip_bom(line)
+        } else {
+            line
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let line = String::from_utf8_lossy(line);
+        let (timestamp, text) = match line.split_once(' ') {
+            Some((ts, text)) if is_log_timestamp(ts) => (Some(ts), text),
+            _ => (None, &*line),
+        };
+        (self.callback)(LogLine { timestamp, text });
+        self.lines += 1;
+    }
+}
+
+impl<F> fmt::Debug for LogLines<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogLines")
+            .field("buf", &self.buf)
+            .field("lines", &self.lines)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: FnMut(LogLine<'_>)> ResponseParser for LogLines<F> {
+    type Output = u64;
+    type Error = CommonError;
+
+    fn handle_parts(&mut self, _parts: &ResponseParts) {}
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        let mut rest = buf;
+        while let Some(i) = rest.iter().position(|&b| b == b'\n') {
+            if self.buf.is_empty() {
+                self.emit(&rest[..i]);
+            } else {
+                let mut line = std::mem::take(&mut self.buf);
+                line.extend_from_slice(&rest[..i]);
+                self.emit(&line);
+            }
+            rest = &rest[(i + 1)..];
+        }
+        self.buf.extend_from_slice(rest);
+    }
+
+    fn end(mut self) -> Result<Self::Output, Self::Error> {
+        if !self.buf.is_empty() {
+            let line = std::mem::take(&mut self.buf);
+            self.emit(&line);
+        }
+        Ok(self.lines)
+    }
+}
+
+/// [Private] Test whether a string is a job log timestamp of the form
+/// `2024-01-02T03:04:05.1234567Z`
+fn is_log_timestamp(s: &str) -> bool {
+    let Some((date, time)) = s.split_once('T') else {
+        return false;
+    };
+    let Some(time) = time.strip_suffix('Z') else {
+        return false;
+    };
+    let (hms, frac) = time.split_once('.').unwrap_or((time, "0"));
+    let digits = |s: &str, sep: char, lens: &[usize]| {
+        let parts = s.split(sep).collect::<Vec<_>>();
+        parts.len() == lens.len()
+            && parts
+                .iter()
+                .zip(lens)
+                .all(|(p, &n)| p.len() == n && p.bytes().all(|b| b.is_ascii_digit()))
+    };
+    digits(date, '-', &[4, 2, 2])
+        && digits(hms, ':', &[2, 2, 2])
+        && !frac.is_empty()
+        && frac.bytes().all(|b| b.is_ascii_digit())
+}
+
+pub trait ResponseParserExt: ResponseParser {
+    fn parse_response<R: std::io::Read>(
+        mut self,
+        resp: Response<R>,
+    ) -> Result<Self::Output, ParseResponseError<Self::Error>> {
+        let (parts, mut body) = resp.into_parts();
+        self.handle_parts(&parts);
+        let mut buf = vec![0u8; READ_BLOCK_SIZE];
+        loop {
+            match body.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => self.handle_bytes(&buf[..n]),
                 Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
@@ -250,26 +1591,855 @@ pub trait ResponseParserExt: ResponseParser {
     ) -> Result<Self::Output, ParseResponseError<Self::Error>> {
         let (parts, body) = resp.into_parts();
         self.handle_parts(&parts);
-        let mut buf = vec![0u8; READ_BLOCK_SIZE];
+        // Reading into the spare capacity of a `BytesMut` spares us from
+        // zero-initializing the buffer first
+        let mut buf = BytesMut::with_capacity(READ_BLOCK_SIZE);
         tokio::pin!(body);
         loop {
-            match body.read(&mut buf).await {
+            buf.clear();
+            match body.read_buf(&mut buf).await {
                 Ok(0) => break,
-                Ok(n) => self.handle_bytes(&buf[..n]),
+                Ok(_) => self.handle_bytes(&buf),
                 Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
                 Err(e) => return Err(ParseResponseError::Read(e)),
             }
         }
         self.end().map_err(ParseResponseError::Parse)
     }
+
+    /// Convert the parser's output with the given function once parsing
+    /// completes successfully
+    fn map<F, U>(self, f: F) -> Map<Self, F>
+    where
+        F: FnOnce(Self::Output) -> U,
+    {
+        Map { inner: self, f }
+    }
+
+    /// Convert the parser's output with the given fallible function once
+    /// parsing completes successfully.  An error returned by the function is
+    /// converted into the parser's error type.
+    fn try_map<F, U, E>(self, f: F) -> TryMap<Self, F>
+    where
+        F: FnOnce(Self::Output) -> Result<U, E>,
+        E: Into<Self::Error>,
+    {
+        TryMap { inner: self, f }
+    }
+
+    /// Convert the parser's output with the given fallible function once
+    /// parsing completes successfully, passing the function the response's
+    /// status & headers as well.  This allows combining the body with
+    /// information from the headers, e.g., a `Location` header.  An error
+    /// returned by the function is converted into the parser's error type.
+    fn and_then<F, U, E>(self, f: F) -> AndThen<Self, F>
+    where
+        F: FnOnce(Self::Output, &ResponseParts) -> Result<U, E>,
+        E: Into<Self::Error>,
+    {
+        AndThen {
+            inner: self,
+            parts: None,
+            f,
+        }
+    }
+
+    /// Convert the parser's errors with the given function
+    fn map_err<F, E>(self, f: F) -> MapErr<Self, F>
+    where
+        F: FnOnce(Self::Error) -> E,
+        E: From<std::io::Error>,
+    {
+        MapErr { inner: self, f }
+    }
+
+    /// Feed the response to both this parser and `other`, returning both
+    /// outputs.  An error from `other` is converted into this parser's error
+    /// type.
+    ///
+    /// `self` is finished before `other`, so a parser that saves the raw body
+    /// (e.g., [`ToFile`]) can be combined with one that deserializes it in
+    /// order to keep a copy of the body even when deserialization fails:
+    ///
+    /// ```
+    /// use ghreq::parser::{JsonResponse, ResponseParserExt, ToFile};
+    ///
+    /// let parser = ToFile::new("response.json").tee(JsonResponse::<serde_json::Value>::new());
+    /// ```
+    fn tee<Q>(self, other: Q) -> Tee<Self, Q>
+    where
+        Q: ResponseParser<Error: Into<Self::Error>>,
+    {
+        Tee {
+            first: self,
+            second: other,
+        }
+    }
 }
 
 impl<R: ResponseParser> ResponseParserExt for R {}
 
-#[cfg(all(test, feature = "tokio"))]
+/// A parser that converts the output of another parser with a function; see
+/// [`ResponseParserExt::map()`]
+#[derive(Clone)]
+pub struct Map<P, F> {
+    inner: P,
+    f: F,
+}
+
+impl<P: fmt::Debug, F> fmt::Debug for Map<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, F, U> ResponseParser for Map<P, F>
+where
+    P: ResponseParser,
+    F: FnOnce(P::Output) -> U,
+{
+    type Output = U;
+    type Error = P::Error;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.inner.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.inner.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        self.inner.end().map(self.f)
+    }
+}
+
+/// A parser that converts the output of another parser with a fallible
+/// function; see [`ResponseParserExt::try_map()`]
+#[derive(Clone)]
+pub struct TryMap<P, F> {
+    inner: P,
+    f: F,
+}
+
+impl<P: fmt::Debug, F> fmt::Debug for TryMap<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryMap")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, F, U, E> ResponseParser for TryMap<P, F>
+where
+    P: ResponseParser,
+    F: FnOnce(P::Output) -> Result<U, E>,
+    E: Into<P::Error>,
+{
+    type Output = U;
+    type Error = P::Error;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.inner.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.inner.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        (self.f)(self.inner.end()?).map_err(Into::into)
+    }
+}
+
+/// A parser that converts the output of another parser with a fallible
+/// function that also receives the response's status & headers; see
+/// [`ResponseParserExt::and_then()`]
+#[derive(Clone)]
+pub struct AndThen<P, F> {
+    inner: P,
+    parts: Option<ResponseParts>,
+    f: F,
+}
+
+impl<P: fmt::Debug, F> fmt::Debug for AndThen<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AndThen")
+            .field("inner", &self.inner)
+            .field("parts", &self.parts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, F, U, E> ResponseParser for AndThen<P, F>
+where
+    P: ResponseParser,
+    F: FnOnce(P::Output, &ResponseParts) -> Result<U, E>,
+    E: Into<P::Error>,
+{
+    type Output = U;
+    type Error = P::Error;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.inner.handle_parts(parts);
+        self.parts = Some(parts.clone());
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.inner.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        let parts = self.parts.expect("handle_parts() should have been called");
+        let output = self.inner.end()?;
+        (self.f)(output, &parts).map_err(Into::into)
+    }
+}
+
+/// A parser that converts the errors of another parser with a function; see
+/// [`ResponseParserExt::map_err()`]
+#[derive(Clone)]
+pub struct MapErr<P, F> {
+    inner: P,
+    f: F,
+}
+
+impl<P: fmt::Debug, F> fmt::Debug for MapErr<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapErr")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, F, E> ResponseParser for MapErr<P, F>
+where
+    P: ResponseParser,
+    F: FnOnce(P::Error) -> E,
+    E: From<std::io::Error>,
+{
+    type Output = P::Output;
+    type Error = E;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.inner.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.inner.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        self.inner.end().map_err(self.f)
+    }
+}
+
+/// A parser that feeds a response to two parsers and returns both of their
+/// outputs; see [`ResponseParserExt::tee()`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Tee<P, Q> {
+    first: P,
+    second: Q,
+}
+
+impl<P, Q> ResponseParser for Tee<P, Q>
+where
+    P: ResponseParser,
+    Q: ResponseParser<Error: Into<P::Error>>,
+{
+    type Output = (P::Output, Q::Output);
+    type Error = P::Error;
+
+    fn handle_parts(&mut self, parts: &ResponseParts) {
+        self.first.handle_parts(parts);
+        self.second.handle_parts(parts);
+    }
+
+    fn handle_bytes(&mut self, buf: &[u8]) {
+        self.first.handle_bytes(buf);
+        self.second.handle_bytes(buf);
+    }
+
+    fn end(self) -> Result<Self::Output, Self::Error> {
+        let first = self.first.end();
+        let second = self.second.end().map_err(Into::into);
+        Ok((first?, second?))
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    /// Returns true if `e` is a JSON deserialization error from whichever
+    /// JSON backend is enabled
+    fn is_json_error(e: &CommonError) -> bool {
+        match e {
+            CommonError::Json(_) => true,
+            #[cfg(feature = "simd-json")]
+            CommonError::SimdJson(_) => true,
+            _ => false,
+        }
+    }
+
+    fn parse<P: ResponseParser>(mut parser: P, body: &[u8]) -> Result<P::Output, P::Error> {
+        parser.handle_bytes(body);
+        parser.end()
+    }
+
+    #[rstest]
+    #[case(b"{\"id\": 1}")]
+    #[case(b"\xEF\xBB\xBF{\"id\": 1}")]
+    #[case(b"\xEF\xBB\xBF  {\"id\": 1}\r\n\r\n")]
+    fn json_response(#[case] body: &[u8]) {
+        let item = parse(JsonResponse::<Item>::new(), body).unwrap();
+        assert_eq!(item, Item { id: 1 });
+    }
+
+    #[rstest]
+    #[case(b"")]
+    #[case(b"\xEF\xBB\xBF\n")]
+    fn json_response_empty(#[case] body: &[u8]) {
+        assert!(matches!(
+            parse(JsonResponse::<Item>::new(), body),
+            Err(CommonError::EmptyBody)
+        ));
+        assert_eq!(
+            parse(JsonResponse::<Option<Item>>::new(), body).unwrap(),
+            None
+        );
+    }
+
+    #[cfg(all(feature = "simd-json", not(feature = "json-arbitrary-precision")))]
+    #[test]
+    fn json_response_simd_json() {
+        let mut parser = JsonResponse::<Vec<Item>>::new();
+        parser.handle_bytes(b"[{\"id\": 1}, ");
+        parser.handle_bytes(b"{\"id\": 2}]");
+        assert_eq!(parser.end().unwrap(), [Item { id: 1 }, Item { id: 2 }]);
+        let r = parse(JsonResponse::<Item>::new(), b"{\"id\": \"one\"}");
+        assert!(matches!(r, Err(CommonError::SimdJson(ref e)) if e.is_data()));
+    }
+
+    #[test]
+    fn json_response_null() {
+        assert!(matches!(
+            parse(JsonResponse::<Item>::new(), b"null\n"),
+            Err(CommonError::NullBody)
+        ));
+        assert_eq!(
+            parse(JsonResponse::<Option<Item>>::new(), b"null\n").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn map_combinators() {
+        #[derive(Debug, Deserialize)]
+        struct Repo {
+            full_name: String,
+        }
+
+        let body = br#"{"full_name": "octocat/hello-world", "id": 1}"#;
+        let parser = JsonResponse::<Repo>::new().map(|r| r.full_name);
+        assert_eq!(parse(parser, body).unwrap(), "octocat/hello-world");
+
+        let parser = JsonResponse::<serde_json::Value>::new().try_map(|v| {
+            v["id"]
+                .as_u64()
+                .ok_or_else(|| std::io::Error::other("missing id"))
+        });
+        assert_eq!(parse(parser, body).unwrap(), 1);
+        let parser = JsonResponse::<serde_json::Value>::new().try_map(|v| {
+            v["number"]
+                .as_u64()
+                .ok_or_else(|| std::io::Error::other("missing number"))
+        });
+        assert!(parse(parser, body).is_err());
+
+        let parser = JsonResponse::<Repo>::new().map_err(|e| std::io::Error::other(e.to_string()));
+        let e = parse(parser, b"[]").unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::Other);
+    }
+
+    #[test]
+    fn and_then() {
+        use crate::{HttpUrl, Method};
+        let url = "https://api.github.com/repos/octocat/hello-world/issues"
+            .parse::<HttpUrl>()
+            .unwrap();
+        let parts = ResponseParts::new(url, Method::Post, http::StatusCode::CREATED).with_header(
+            http::header::LOCATION,
+            http::HeaderValue::from_static(
+                "https://api.github.com/repos/octocat/hello-world/issues/1",
+            ),
+        );
+        let mut parser = JsonResponse::<Item>::new().and_then(|item, parts: &ResponseParts| {
+            let location = parts
+                .headers()
+                .get(http::header::LOCATION)
+                .ok_or_else(|| std::io::Error::other("no Location header"))?;
+            Ok::<_, std::io::Error>((item, location.clone()))
+        });
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"{\"id\": 1}");
+        let (item, location) = parser.end().unwrap();
+        assert_eq!(item, Item { id: 1 });
+        assert_eq!(
+            location,
+            "https://api.github.com/repos/octocat/hello-world/issues/1"
+        );
+    }
+
+    /// Feed `body` to a [`StreamingJsonArray`] one byte at a time, collecting
+    /// the elements as they become available
+    fn stream_array<T: DeserializeOwned>(body: &[u8]) -> Result<Vec<T>, CommonError> {
+        let mut decoder = StreamingJsonArray::<T>::new();
+        let mut items = Vec::new();
+        for b in body {
+            decoder.feed(std::slice::from_ref(b));
+            while let Some(r) = decoder.next_item() {
+                items.push(r?);
+            }
+        }
+        decoder.finish()?;
+        Ok(items)
+    }
+
+    #[test]
+    fn streaming_json_array() {
+        let body = br#"[{"id": 1, "tags": [{"x": "]"}, []]}, {"id":2} ,{"id": 3, "s": "a\"],{"}]"#;
+        assert_eq!(
+            stream_array::<Item>(body).unwrap(),
+            [Item { id: 1 }, Item { id: 2 }, Item { id: 3 }]
+        );
+        assert_eq!(
+            stream_array::<serde_json::Value>(b"\xEF\xBB\xBF [1, true,null , \"a,]\", -25]\n")
+                .unwrap(),
+            [
+                serde_json::json!(1),
+                serde_json::json!(true),
+                serde_json::Value::Null,
+                serde_json::json!("a,]"),
+                serde_json::json!(-25),
+            ]
+        );
+        assert!(stream_array::<Item>(b" [ ] ").unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[case(b"{\"id\": 1}")]
+    #[case(b"[{\"id\": 1}")]
+    #[case(b"[{\"id\": 1},]")]
+    #[case(b"[{\"id\": 1} {\"id\": 2}]")]
+    #[case(b"[{\"id\": 1}] x")]
+    #[case(b"[{\"id\": \"1\"}]")]
+    fn streaming_json_array_invalid(#[case] body: &[u8]) {
+        assert!(matches!(
+            stream_array::<Item>(body),
+            Err(CommonError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn streaming_json_array_empty() {
+        assert!(matches!(
+            stream_array::<Item>(b"\xEF\xBB\xBF\n"),
+            Err(CommonError::EmptyBody)
+        ));
+    }
+
+    #[test]
+    fn json_array_iter() {
+        let body = &b"[{\"id\": 1}, {\"id\": 2}]"[..];
+        let mut iter = JsonArrayIter::<_, Item>::new(body);
+        assert_eq!(iter.next().unwrap().unwrap(), Item { id: 1 });
+        assert_eq!(iter.next().unwrap().unwrap(), Item { id: 2 });
+        assert!(iter.next().is_none());
+        let mut iter = JsonArrayIter::<_, Item>::new(&b"[{\"id\": 1}, {\"id"[..]);
+        assert_eq!(iter.next().unwrap().unwrap(), Item { id: 1 });
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseResponseError::Parse(CommonError::Json(_))))
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn borrowed_json() {
+        use serde_json::value::RawValue;
+
+        #[derive(Deserialize)]
+        struct Tree<'a> {
+            sha: &'a str,
+            #[serde(borrow)]
+            tree: &'a RawValue,
+        }
+
+        let body = b"\xEF\xBB\xBF{\"sha\": \"abc123\", \"tree\": [{\"path\": \"a\"}, {\"path\": \"b\"}]}\n";
+        let parser = BorrowedJson::new(|raw: &RawValue| {
+            let tree = serde_json::from_str::<Tree<'_>>(raw.get())?;
+            Ok::<_, serde_json::Error>((tree.sha.to_owned(), tree.tree.get().len()))
+        });
+        assert_eq!(parse(parser, body).unwrap(), (String::from("abc123"), 30));
+
+        let parser = BorrowedJson::new(|_: &RawValue| Ok::<_, CommonError>(()));
+        assert!(matches!(parse(parser, b" \n"), Err(CommonError::EmptyBody)));
+        let parser = BorrowedJson::new(|_: &RawValue| Ok::<_, CommonError>(()));
+        assert!(matches!(
+            parse(parser, b"{\"sha\": "),
+            Err(CommonError::Json(_))
+        ));
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn with_digest() {
+        let (value, digest) = parse(WithDigest::new(JsonResponse::<u32>::new()), b"42\n").unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(
+            digest.to_string(),
+            "084c799cd551dd1d8d5c5f9a5d593b2e931f5e36122ee5c793c1d08a19839cc0"
+        );
+        assert!(digest.matches_hex(
+            "sha256:084C799CD551DD1D8D5C5F9A5D593B2E931F5E36122EE5C793C1D08A19839CC0"
+        ));
+        assert!(!digest.matches_hex("084c799c"));
+        assert!(
+            !digest.matches_hex("+84c799cd551dd1d8d5c5f9a5d593b2e931f5e36122ee5c793c1d08a19839cc0")
+        );
+        assert_eq!(digest.as_bytes().len(), 32);
+
+        let (body, digest) = parse(
+            WithDigest::with_hasher(Vec::new(), sha2::Sha512::default()),
+            b"",
+        )
+        .unwrap();
+        assert!(body.is_empty());
+        assert!(digest.to_string().starts_with("cf83e1357eefb8bd"));
+    }
+
+    #[test]
+    fn tee() {
+        let mut raw = Vec::new();
+        let parser = ToWriter::new(&mut raw).tee(JsonResponse::<Item>::new());
+        assert_eq!(parse(parser, b"{\"id\": 1}").unwrap(), ((), Item { id: 1 }));
+        assert_eq!(raw, b"{\"id\": 1}");
+
+        let mut raw = Vec::new();
+        let parser = ToWriter::new(&mut raw).tee(JsonResponse::<Item>::new());
+        assert!(parse(parser, b"{\"id\": \"one\"}").is_err_and(|e| is_json_error(&e)));
+        assert_eq!(raw, b"{\"id\": \"one\"}");
+    }
 
+    #[test]
+    fn limited() {
+        let url = "https://api.github.com/zen"
+            .parse::<crate::HttpUrl>()
+            .unwrap();
+        let parts = ResponseParts::new(url, crate::Method::Get, http::StatusCode::OK);
+        let mut parser = Limited::new(Vec::new(), 8);
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"[1, 2, ");
+        parser.handle_bytes(b"3]");
+        let e = parser.end().unwrap_err();
+        let CommonError::Io(e) = e else {
+            panic!("Expected I/O error, got {e:?}");
+        };
+        assert_eq!(e.kind(), std::io::ErrorKind::FileTooLarge);
+        assert_eq!(
+            e.get_ref().unwrap().to_string(),
+            "response body exceeded size limit of 8 bytes"
+        );
+
+        let mut parser = Limited::new(Vec::new(), 8);
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"[1, 2]");
+        assert_eq!(parser.end().unwrap(), b"[1, 2]");
+
+        let parts = parts.with_header(
+            http::header::CONTENT_LENGTH,
+            http::header::HeaderValue::from_static("1000000"),
+        );
+        let mut parser = Limited::new(Vec::new(), 8);
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"[");
+        assert!(parser.end().is_err());
+    }
+
+    #[rstest]
+    #[case(http::StatusCode::OK, b"{\"id\": 1}", Some(Item { id: 1 }))]
+    #[case(http::StatusCode::OK, b"", None)]
+    #[case(http::StatusCode::OK, b"\xEF\xBB\xBF \r\n", None)]
+    #[case(http::StatusCode::CREATED, b"{\"id\": 1}\n", Some(Item { id: 1 }))]
+    #[case(http::StatusCode::NO_CONTENT, b"", None)]
+    #[case(http::StatusCode::NO_CONTENT, b"{\"id\": 1}", None)]
+    fn maybe_json_response(
+        #[case] status: http::StatusCode,
+        #[case] body: &[u8],
+        #[case] expected: Option<Item>,
+    ) {
+        let url = "https://api.github.com/zen"
+            .parse::<crate::HttpUrl>()
+            .unwrap();
+        let parts = ResponseParts::new(url, crate::Method::Delete, status);
+        let mut parser = MaybeJsonResponse::<Item>::new();
+        parser.handle_parts(&parts);
+        parser.handle_bytes(body);
+        assert_eq!(parser.end().unwrap(), expected);
+    }
+
+    #[test]
+    fn bytes_response() {
+        let url = "https://api.github.com/zen"
+            .parse::<crate::HttpUrl>()
+            .unwrap();
+        let parts = ResponseParts::new(url, crate::Method::Get, http::StatusCode::OK).with_header(
+            http::header::CONTENT_LENGTH,
+            http::header::HeaderValue::from_static("13"),
+        );
+        let mut parser = BytesResponse::new();
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"Hello, ");
+        parser.handle_bytes(b"world!");
+        let body = parser.end().unwrap();
+        assert_eq!(body, &b"Hello, world!"[..]);
+        assert_eq!(body.slice(7..12), &b"world"[..]);
+    }
+
+    #[test]
+    fn progress() {
+        use crate::{HttpUrl, Method, response::Response};
+        let url = "https://api.github.com/zen".parse::<HttpUrl>().unwrap();
+        let parts = ResponseParts::new(url, Method::Get, http::StatusCode::OK).with_header(
+            http::header::CONTENT_LENGTH,
+            http::header::HeaderValue::from_static("13"),
+        );
+        let mut reports = Vec::new();
+        let parser = Progress::new(Vec::new(), |received, total| {
+            reports.push((received, total));
+        });
+        let body = parser
+            .parse_response(Response::from_parts(parts, &b"Hello, world!"[..]))
+            .unwrap();
+        assert_eq!(body, b"Hello, world!");
+        assert_eq!(reports, [(0, Some(13)), (13, Some(13))]);
+    }
+
+    #[test]
+    fn maybe_json_response_invalid() {
+        let r = parse(MaybeJsonResponse::<Item>::new(), b"{\"id\": ");
+        assert!(r.is_err_and(|e| is_json_error(&e)));
+    }
+
+    #[rstest]
+    #[case(Some("application/json; charset=utf-8"), Ok(Item { id: 1 }))]
+    #[case(Some("application/vnd.github+json"), Ok(Item { id: 1 }))]
+    #[case(Some("text/html"), Err("expected JSON response, got text/html"))]
+    #[case(None, Err("expected JSON response, got no Content-Type"))]
+    fn strict_json_response(
+        #[case] content_type: Option<&'static str>,
+        #[case] expected: Result<Item, &str>,
+    ) {
+        let url = "https://api.github.com/zen"
+            .parse::<crate::HttpUrl>()
+            .unwrap();
+        let mut parts = ResponseParts::new(url, crate::Method::Get, http::StatusCode::OK);
+        if let Some(ct) = content_type {
+            parts = parts.with_header(
+                http::header::CONTENT_TYPE,
+                http::header::HeaderValue::from_static(ct),
+            );
+        }
+        let mut parser = StrictJsonResponse::<Item>::new();
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"{\"id\": 1}");
+        let r = parser.end().map_err(|e| e.to_string());
+        assert_eq!(r, expected.map_err(String::from));
+    }
+
+    /// [Private] Returns a scratch directory unique to the given test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ghreq-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// [Private] Returns the names of the entries in a directory, sorted
+    fn dir_entries(dir: &Path) -> Vec<String> {
+        let mut names = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn to_file() {
+        use crate::{HttpUrl, Method};
+        let dir = scratch_dir("to-file");
+        let path = dir.join("out.bin");
+        std::fs::write(&path, b"old contents").unwrap();
+        let url = "https://api.github.com/zen".parse::<HttpUrl>().unwrap();
+        let parts = ResponseParts::new(url, Method::Get, http::StatusCode::OK);
+
+        let mut parser = ToFile::new(&path);
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"new ");
+        assert_eq!(dir_entries(&dir).len(), 2);
+        // The target is untouched until the body is complete
+        assert_eq!(std::fs::read(&path).unwrap(), b"old contents");
+        parser.handle_bytes(b"contents");
+        parser.end().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"new contents");
+        assert_eq!(dir_entries(&dir), ["out.bin"]);
+
+        // A parser dropped mid-body removes its temporary file
+        let mut parser = ToFile::new(&path);
+        parser.handle_parts(&parts);
+        parser.handle_bytes(b"partial");
+        drop(parser);
+        assert_eq!(std::fs::read(&path).unwrap(), b"new contents");
+        assert_eq!(dir_entries(&dir), ["out.bin"]);
+
+        // A target without a file name is an error
+        let mut parser = ToFile::new("/");
+        parser.handle_parts(&parts);
+        assert!(parser.end().is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn to_async_writer() {
+        use crate::{HttpUrl, Method};
+        let url = "https://api.github.com/zen".parse::<HttpUrl>().unwrap();
+        let parts = ResponseParts::new(url, Method::Get, http::StatusCode::OK);
+        let mut out = Vec::new();
+        let resp = Response::from_parts(parts, &b"Keep it logically awesome."[..]);
+        ToAsyncWriter::new(&mut out)
+            .parse_async_response(resp)
+            .await
+            .unwrap();
+        assert_eq!(out, b"Keep it logically awesome.");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_to_file() {
+        use crate::{HttpUrl, Method};
+
+        /// A reader that always fails
+        struct Failing;
+
+        impl tokio::io::AsyncRead for Failing {
+            fn poll_read(
+                self: std::pin::Pin<&mut Self>,
+                _cx: &mut std::task::Context<'_>,
+                _buf: &mut tokio::io::ReadBuf<'_>,
+            ) -> std::task::Poll<std::io::Result<()>> {
+                std::task::Poll::Ready(Err(std::io::Error::other("connection reset")))
+            }
+        }
+
+        let dir = scratch_dir("async-to-file");
+        let path = dir.join("out.bin");
+        let url = "https://api.github.com/zen".parse::<HttpUrl>().unwrap();
+        let parts = ResponseParts::new(url, Method::Get, http::StatusCode::OK);
+
+        let resp = Response::from_parts(parts.clone(), &b"contents"[..]);
+        AsyncToFile::new(&path)
+            .parse_async_response(resp)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"contents");
+
+        // A read error leaves the existing file in place and removes the
+        // temporary file
+        let body = (&b"partial"[..]).chain(Failing);
+        let resp = Response::from_parts(parts, body);
+        let r = AsyncToFile::new(&path).parse_async_response(resp).await;
+        assert!(matches!(r, Err(ParseResponseError::Read(_))));
+        assert_eq!(std::fs::read(&path).unwrap(), b"contents");
+        assert_eq!(dir_entries(&dir), ["out.bin"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn ndjson() {
+        let body = b"\xEF\xBB\xBF{\"id\": 1}\r\n\n  {\"id\": 2}  \n{\"id\": 3}";
+        let items = parse(NdJson::<Item>::new(), body).unwrap();
+        assert_eq!(items, [Item { id: 1 }, Item { id: 2 }, Item { id: 3 }]);
+        assert!(parse(NdJson::<Item>::new(), b"\n\n").unwrap().is_empty());
+        let r = parse(NdJson::<Item>::new(), b"{\"id\": 1}\n{\"id\": \"two\"}\n");
+        assert!(r.is_err_and(|e| is_json_error(&e)));
+    }
+
+    #[cfg(feature = "json-arbitrary-precision")]
+    #[test]
+    fn json_arbitrary_precision() {
+        let src = br#"{"id": 123456789012345678901234567890, "score": 0.1000000000000000055511151231257827}"#;
+        let value = parse(JsonResponse::<serde_json::Value>::new(), src).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{"id":123456789012345678901234567890,"score":0.1000000000000000055511151231257827}"#
+        );
+    }
+
+    #[test]
+    fn log_lines() {
+        let mut lines = Vec::new();
+        let mut parser = LogLines::new(|line: LogLine<'_>| {
+            lines.push((line.timestamp.map(String::from), line.text.to_owned()));
+        });
+        for chunk in [
+            &b"\xEF\xBB\xBF2024-01-02T03:04:05.1234567Z ##[group]Run actions"[..],
+            b"/checkout@v4\r\n2024-01-02T03:04:05.2Z with:\r\n\r",
+            b"\nplain line\n2024-01-02T03:04:06.0000000Z ",
+            b"last",
+        ] {
+            parser.handle_bytes(chunk);
+        }
+        assert_eq!(parser.end().unwrap(), 5);
+        let ts = |s: &str| Some(String::from(s));
+        assert_eq!(
+            lines,
+            [
+                (
+                    ts("2024-01-02T03:04:05.1234567Z"),
+                    String::from("##[group]Run actions/checkout@v4")
+                ),
+                (ts("2024-01-02T03:04:05.2Z"), String::from("with:")),
+                (None, String::new()),
+                (None, String::from("plain line")),
+                (ts("2024-01-02T03:04:06.0000000Z"), String::from("last")),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("2024-01-02T03:04:05.1234567Z", true)]
+    #[case("2024-01-02T03:04:05Z", true)]
+    #[case("2024-01-02T03:04:05.Z", false)]
+    #[case("2024-01-02T03:04:05.123", false)]
+    #[case("2024-1-02T03:04:05Z", false)]
+    #[case("2024-01-02 03:04:05Z", false)]
+    #[case("##[group]Run", false)]
+    fn test_is_log_timestamp(#[case] s: &str, #[case] b: bool) {
+        assert_eq!(is_log_timestamp(s), b);
+    }
+
+    #[cfg(feature = "tokio")]
     #[test]
     fn parse_async_response_is_send() {
         #[allow(dead_code)]
@@ -1,11 +1,38 @@
-use crate::{Endpoint, HeaderMapExt, Method, errors::CommonError, parser::ResponseParser};
-use http::header::HeaderMap;
+#[cfg(feature = "digest")]
+use crate::parser::Digest;
+use crate::{
+    AcceptList, Endpoint, EntityTag, HeaderMapExt, HttpUrl, Method,
+    errors::CommonError,
+    parser::{Ignore, ResponseParser},
+};
+#[cfg(feature = "digest")]
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::Serialize;
+use std::borrow::Cow;
+use std::fmt;
 use std::fs::File;
 use std::io::Cursor;
+use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// Derive macro generating an implementation of the [`Request`] trait
+///
+/// See the macro's documentation for details on its attributes.
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use ghreq_derive::Request;
+
+/// The `Content-Digest` header, defined by RFC 9530
+#[cfg(feature = "digest")]
+const CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// The `Content-MD5` header, defined by RFC 1864
+#[cfg(feature = "digest")]
+const CONTENT_MD5: HeaderName = HeaderName::from_static("content-md5");
+
 pub trait Request {
     type Output;
     type Error: From<CommonError>;
@@ -15,6 +42,12 @@ pub trait Request {
 
     fn endpoint(&self) -> Endpoint;
 
+    /// Returns which of the client's base URLs a [`Endpoint::Path`] endpoint
+    /// is resolved against.  The default is [`BaseUrl::Api`].
+    fn base_url(&self) -> BaseUrl {
+        BaseUrl::Api
+    }
+
     fn method(&self) -> Method;
 
     fn headers(&self) -> HeaderMap {
@@ -25,10 +58,33 @@ pub trait Request {
         Vec::new()
     }
 
+    /// Returns a value to encode as additional query parameters with
+    /// [`serde_urlencoded`], which are appended to the URL after those
+    /// returned by [`Request::params()`].  This allows filters like
+    /// `state=open&sort=created&per_page=100` to be expressed as a struct
+    /// deriving [`Serialize`].
+    ///
+    /// The value must serialize to a map, a struct, a sequence of pairs, or
+    /// `()`; if it cannot be encoded, the client returns an
+    /// [`ErrorPayload::PrepareRequest`][crate::errors::ErrorPayload::PrepareRequest]
+    /// error containing [`CommonError::Query`].  The default implementation
+    /// returns `()`, adding no parameters.
+    fn query(&self) -> impl Serialize {}
+
     fn timeout(&self) -> Option<Duration> {
         None
     }
 
+    /// Returns media types that the request needs to have included in its
+    /// `Accept` header, such as preview media types that enable extra fields
+    /// in the response.  The client merges these into the `Accept` header
+    /// that would otherwise be sent (i.e., the client's configured value or
+    /// the one returned by [`Request::headers()`]) rather than replacing it.
+    /// The default implementation returns an empty list.
+    fn extra_accept(&self) -> AcceptList {
+        AcceptList::new()
+    }
+
     fn body(&self) -> Self::Body;
 
     fn parser(&self)
@@ -44,6 +100,10 @@ impl<T: Request + ?Sized> Request for &T {
         (*self).endpoint()
     }
 
+    fn base_url(&self) -> BaseUrl {
+        (*self).base_url()
+    }
+
     fn method(&self) -> Method {
         (*self).method()
     }
@@ -56,10 +116,18 @@ impl<T: Request + ?Sized> Request for &T {
         (*self).params()
     }
 
+    fn query(&self) -> impl Serialize {
+        (*self).query()
+    }
+
     fn timeout(&self) -> Option<Duration> {
         (*self).timeout()
     }
 
+    fn extra_accept(&self) -> AcceptList {
+        (*self).extra_accept()
+    }
+
     fn body(&self) -> Self::Body {
         (*self).body()
     }
@@ -80,6 +148,10 @@ impl<T: Request + ?Sized> Request for &mut T {
         (**self).endpoint()
     }
 
+    fn base_url(&self) -> BaseUrl {
+        (**self).base_url()
+    }
+
     fn method(&self) -> Method {
         (**self).method()
     }
@@ -92,10 +164,18 @@ impl<T: Request + ?Sized> Request for &mut T {
         (**self).params()
     }
 
+    fn query(&self) -> impl Serialize {
+        (**self).query()
+    }
+
     fn timeout(&self) -> Option<Duration> {
         (**self).timeout()
     }
 
+    fn extra_accept(&self) -> AcceptList {
+        (**self).extra_accept()
+    }
+
     fn body(&self) -> Self::Body {
         (**self).body()
     }
@@ -116,6 +196,10 @@ impl<T: Request + ?Sized> Request for std::sync::Arc<T> {
         (**self).endpoint()
     }
 
+    fn base_url(&self) -> BaseUrl {
+        (**self).base_url()
+    }
+
     fn method(&self) -> Method {
         (**self).method()
     }
@@ -128,10 +212,18 @@ impl<T: Request + ?Sized> Request for std::sync::Arc<T> {
         (**self).params()
     }
 
+    fn query(&self) -> impl Serialize {
+        (**self).query()
+    }
+
     fn timeout(&self) -> Option<Duration> {
         (**self).timeout()
     }
 
+    fn extra_accept(&self) -> AcceptList {
+        (**self).extra_accept()
+    }
+
     fn body(&self) -> Self::Body {
         (**self).body()
     }
@@ -152,6 +244,10 @@ impl<T: Request + ?Sized> Request for Box<T> {
         (**self).endpoint()
     }
 
+    fn base_url(&self) -> BaseUrl {
+        (**self).base_url()
+    }
+
     fn method(&self) -> Method {
         (**self).method()
     }
@@ -164,10 +260,18 @@ impl<T: Request + ?Sized> Request for Box<T> {
         (**self).params()
     }
 
+    fn query(&self) -> impl Serialize {
+        (**self).query()
+    }
+
     fn timeout(&self) -> Option<Duration> {
         (**self).timeout()
     }
 
+    fn extra_accept(&self) -> AcceptList {
+        (**self).extra_accept()
+    }
+
     fn body(&self) -> Self::Body {
         (**self).body()
     }
@@ -179,6 +283,343 @@ impl<T: Request + ?Sized> Request for Box<T> {
     }
 }
 
+/// The base URLs that a [`Request`]'s endpoint can be resolved against
+///
+/// Each variant corresponds to a URL configured on
+/// [`ClientConfig`][crate::client::ClientConfig].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum BaseUrl {
+    /// The base REST API URL; see
+    /// [`ClientConfig::base_url()`][crate::client::ClientConfig::base_url]
+    #[default]
+    Api,
+
+    /// The base URL for uploading release assets; see
+    /// [`ClientConfig::uploads_url()`][crate::client::ClientConfig::uploads_url]
+    Uploads,
+
+    /// The URL of the GraphQL API endpoint; see
+    /// [`ClientConfig::graphql_url()`][crate::client::ClientConfig::graphql_url]
+    Graphql,
+}
+
+/// A wrapper around a [`Request`] that adds an `If-Match` header to it
+///
+/// When used with a mutating request, this causes the server to only perform
+/// the mutation if the resource's current entity tag matches one of the given
+/// entity tags; otherwise, the server responds with 412 Precondition Failed,
+/// which can be detected by checking whether the request's error has an
+/// [`ErrorClass`][crate::errors::ErrorClass] of
+/// [`PreconditionFailed`][crate::errors::ErrorClass::PreconditionFailed].
+/// This allows for safe read-modify-write cycles: fetch a resource, note its
+/// `ETag`, and then update it only if no one else has modified it in the
+/// meantime.
+///
+/// If no entity tags are given, the header is set to `*`, which requires the
+/// resource to merely exist.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IfMatch<R> {
+    request: R,
+    etags: Vec<EntityTag>,
+}
+
+impl<R> IfMatch<R> {
+    /// Wrap `request` so that it is only performed if the resource's current
+    /// entity tag matches `etag`
+    pub fn new(request: R, etag: EntityTag) -> IfMatch<R> {
+        IfMatch {
+            request,
+            etags: vec![etag],
+        }
+    }
+
+    /// Wrap `request` so that it is only performed if the resource exists
+    pub fn any(request: R) -> IfMatch<R> {
+        IfMatch {
+            request,
+            etags: Vec::new(),
+        }
+    }
+
+    /// Add another entity tag that the resource's current entity tag may
+    /// match
+    pub fn with_etag(mut self, etag: EntityTag) -> Self {
+        self.etags.push(etag);
+        self
+    }
+
+    pub fn etags(&self) -> &[EntityTag] {
+        &self.etags
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.request
+    }
+
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: Request> Request for IfMatch<R> {
+    type Output = R::Output;
+    type Error = R::Error;
+    type Body = R::Body;
+
+    fn endpoint(&self) -> Endpoint {
+        self.request.endpoint()
+    }
+
+    fn base_url(&self) -> BaseUrl {
+        self.request.base_url()
+    }
+
+    fn method(&self) -> Method {
+        self.request.method()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = self.request.headers();
+        headers.insert(
+            http::header::IF_MATCH,
+            EntityTag::list_header_value(&self.etags),
+        );
+        headers
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.request.params()
+    }
+
+    fn query(&self) -> impl Serialize {
+        self.request.query()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+
+    fn extra_accept(&self) -> AcceptList {
+        self.request.extra_accept()
+    }
+
+    fn body(&self) -> Self::Body {
+        self.request.body()
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        self.request.parser()
+    }
+}
+
+/// A wrapper around a [`Request`] that resolves its endpoint against a given
+/// base URL instead of the client's base URL
+///
+/// This allows a single long-lived client to make requests to multiple GitHub
+/// instances.  If the inner request's endpoint is an [`Endpoint::Path`], it is
+/// appended to the given URL; if it is an [`Endpoint::Url`], it is checked
+/// against the given URL as described there and then used as-is.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithBaseUrl<R> {
+    request: R,
+    base_url: HttpUrl,
+}
+
+impl<R> WithBaseUrl<R> {
+    pub fn new(request: R, base_url: HttpUrl) -> WithBaseUrl<R> {
+        WithBaseUrl { request, base_url }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.request
+    }
+
+    pub fn into_inner(self) -> R {
+        self.request
+    }
+}
+
+impl<R: Request> Request for WithBaseUrl<R> {
+    type Output = R::Output;
+    type Error = R::Error;
+    type Body = R::Body;
+
+    fn endpoint(&self) -> Endpoint {
+        let endpoint = self.request.endpoint();
+        // Leave invalid endpoints for the client to reject
+        if endpoint.validate_against(&self.base_url).is_ok() {
+            Endpoint::Absolute(self.base_url.join_endpoint(endpoint))
+        } else {
+            endpoint
+        }
+    }
+
+    fn base_url(&self) -> BaseUrl {
+        self.request.base_url()
+    }
+
+    fn method(&self) -> Method {
+        self.request.method()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.request.headers()
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.request.params()
+    }
+
+    fn query(&self) -> impl Serialize {
+        self.request.query()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.request.timeout()
+    }
+
+    fn extra_accept(&self) -> AcceptList {
+        self.request.extra_accept()
+    }
+
+    fn body(&self) -> Self::Body {
+        self.request.body()
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        self.request.parser()
+    }
+}
+
+/// A [`Request`] assembled at runtime rather than defined as a dedicated
+/// type, for one-off calls to endpoints that `ghreq` has no request type for
+///
+/// The response body is parsed with a default-constructed `P`; use
+/// [`AdHocRequest::with_parser()`] to change the parser type.  If a body is
+/// set with [`AdHocRequest::with_json_body()`], it is sent as JSON;
+/// otherwise, the request has an empty body.
+///
+/// The [`Client`][crate::client::Client] and
+/// [`AsyncClient`][crate::client::tokio::AsyncClient] types provide
+/// `get()`, `post()`, etc. methods that build & send `AdHocRequest`s.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdHocRequest<P = Ignore> {
+    method: Method,
+    endpoint: Endpoint,
+    headers: HeaderMap,
+    params: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    body: Option<serde_json::Value>,
+    _parser: PhantomData<fn() -> P>,
+}
+
+impl AdHocRequest {
+    pub fn new(method: Method, endpoint: Endpoint) -> AdHocRequest {
+        AdHocRequest {
+            method,
+            endpoint,
+            headers: HeaderMap::new(),
+            params: Vec::new(),
+            timeout: None,
+            body: None,
+            _parser: PhantomData,
+        }
+    }
+}
+
+impl<P> AdHocRequest<P> {
+    /// Add a query parameter to the request
+    pub fn with_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Add a header to the request.  Any previous values for the header are
+    /// replaced.
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Set a JSON value to send as the request body
+    pub fn with_json_body(mut self, body: serde_json::Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Set the request's timeout, overriding the client's default
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Change the type of parser used to parse the response body
+    pub fn with_parser<Q>(self) -> AdHocRequest<Q> {
+        AdHocRequest {
+            method: self.method,
+            endpoint: self.endpoint,
+            headers: self.headers,
+            params: self.params,
+            timeout: self.timeout,
+            body: self.body,
+            _parser: PhantomData,
+        }
+    }
+}
+
+impl<P> Request for AdHocRequest<P>
+where
+    P: ResponseParser<Error: Into<CommonError>> + Default + Send,
+{
+    type Output = P::Output;
+    type Error = CommonError;
+    type Body = Vec<u8>;
+
+    fn endpoint(&self) -> Endpoint {
+        self.endpoint.clone()
+    }
+
+    fn method(&self) -> Method {
+        self.method.clone()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if self.body.is_some() && !headers.contains_key(http::header::CONTENT_TYPE) {
+            headers.insert(
+                http::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+        }
+        headers
+    }
+
+    fn params(&self) -> Vec<(String, String)> {
+        self.params.clone()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn body(&self) -> Self::Body {
+        self.body
+            .as_ref()
+            .map(|v| v.to_string().into_bytes())
+            .unwrap_or_default()
+    }
+
+    fn parser(
+        &self,
+    ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+        P::default()
+    }
+}
+
 pub trait RequestBody {
     type Error;
 
@@ -186,6 +627,17 @@ pub trait RequestBody {
         HeaderMap::new()
     }
 
+    /// Returns the complete body if it is held in memory, so that a copy of it
+    /// can be attached to errors for diagnostic purposes; see
+    /// [`ClientConfig::with_request_body_capture()`][crate::client::ClientConfig::with_request_body_capture].
+    ///
+    /// This is only called when request body capture is enabled.  The default
+    /// implementation returns `None`, which is appropriate for bodies that are
+    /// streamed from elsewhere.
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        None
+    }
+
     fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error>;
 }
 
@@ -197,7 +649,18 @@ pub trait AsyncRequestBody {
         HeaderMap::new()
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>;
+    /// Returns the complete body if it is held in memory; see
+    /// [`RequestBody::in_memory()`]
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        None
+    }
+
+    /// Convert the body into an asynchronous reader.  This is asynchronous so
+    /// that preparing the body, such as by opening a file, does not block the
+    /// runtime.
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send;
 }
 
 impl RequestBody for () {
@@ -224,8 +687,11 @@ impl AsyncRequestBody for () {
         headers
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
-        Ok(tokio::io::empty())
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(tokio::io::empty()))
     }
 }
 
@@ -240,6 +706,10 @@ impl RequestBody for Vec<u8> {
         headers
     }
 
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self))
+    }
+
     fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
         Ok(Cursor::new(self))
     }
@@ -257,8 +727,15 @@ impl AsyncRequestBody for Vec<u8> {
         headers
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
-        Ok(Cursor::new(self))
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self))
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(Cursor::new(self)))
     }
 }
 
@@ -273,6 +750,10 @@ impl RequestBody for String {
         headers
     }
 
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self.as_bytes()))
+    }
+
     fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
         Ok(Cursor::new(self.into_bytes()))
     }
@@ -290,112 +771,345 @@ impl AsyncRequestBody for String {
         headers
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
-        Ok(Cursor::new(self.into_bytes()))
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self.as_bytes()))
     }
-}
-
-#[derive(Clone, Copy, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
-pub struct JsonBody<T>(T);
 
-impl<T> JsonBody<T> {
-    pub fn new(value: T) -> JsonBody<T> {
-        JsonBody(value)
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(Cursor::new(self.into_bytes())))
     }
 }
 
-impl<T: Serialize> RequestBody for JsonBody<T> {
+impl RequestBody for Bytes {
     type Error = CommonError;
 
     fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            http::header::CONTENT_TYPE,
-            "application/json"
-                .parse()
-                .expect(r#""application/json" should be a valid HeaderValue"#),
-        );
+        if let Ok(sz) = self.len().try_into() {
+            headers.set_content_length(sz);
+        }
         headers
     }
 
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self))
+    }
+
     fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
-        Ok(Cursor::new(serde_json::to_vec(&self.0)?))
+        Ok(Cursor::new(self))
     }
 }
 
 #[cfg(feature = "tokio")]
-impl<T: Serialize> AsyncRequestBody for JsonBody<T> {
+impl AsyncRequestBody for Bytes {
     type Error = CommonError;
 
     fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            http::header::CONTENT_TYPE,
-            "application/json"
-                .parse()
-                .expect(r#""application/json" should be a valid HeaderValue"#),
-        );
+        if let Ok(sz) = self.len().try_into() {
+            headers.set_content_length(sz);
+        }
         headers
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
-        Ok(Cursor::new(serde_json::to_vec(&self.0)?))
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self))
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(Cursor::new(self)))
     }
 }
 
-impl RequestBody for PathBuf {
+impl RequestBody for Cow<'static, [u8]> {
     type Error = CommonError;
 
     fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
-        if let Ok(md) = std::fs::metadata(self) {
-            headers.set_content_length(md.len());
+        if let Ok(sz) = self.len().try_into() {
+            headers.set_content_length(sz);
         }
         headers
     }
 
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self))
+    }
+
     fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
-        File::open(self).map_err(Into::into)
+        Ok(Cursor::new(self))
     }
 }
 
 #[cfg(feature = "tokio")]
-impl AsyncRequestBody for PathBuf {
+impl AsyncRequestBody for Cow<'static, [u8]> {
     type Error = CommonError;
 
     fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
-        if let Ok(md) = std::fs::metadata(self) {
-            headers.set_content_length(md.len());
+        if let Ok(sz) = self.len().try_into() {
+            headers.set_content_length(sz);
         }
         headers
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
-        // ASYNC: tokio::fs::File::open(self.0).await.map_err(Into::into)
-        let fp = File::open(self)?;
-        Ok(tokio::fs::File::from_std(fp))
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self))
     }
-}
-
-impl RequestBody for File {
-    type Error = CommonError;
 
-    fn headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        if let Ok(md) = self.metadata() {
-            headers.set_content_length(md.len());
-        }
-        headers
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(Cursor::new(self)))
     }
+}
 
-    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
-        Ok(self)
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct JsonBody<T>(T);
+
+impl<T> JsonBody<T> {
+    pub fn new(value: T) -> JsonBody<T> {
+        JsonBody(value)
     }
 }
 
-#[cfg(feature = "tokio")]
-impl AsyncRequestBody for File {
+impl<T: Serialize> RequestBody for JsonBody<T> {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json"
+                .parse()
+                .expect(r#""application/json" should be a valid HeaderValue"#),
+        );
+        headers
+    }
+
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        serde_json::to_vec(&self.0).ok().map(Cow::Owned)
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        Ok(Cursor::new(serde_json::to_vec(&self.0)?))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: Serialize> AsyncRequestBody for JsonBody<T> {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json"
+                .parse()
+                .expect(r#""application/json" should be a valid HeaderValue"#),
+        );
+        headers
+    }
+
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        serde_json::to_vec(&self.0).ok().map(Cow::Owned)
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(
+            serde_json::to_vec(&self.0)
+                .map(Cursor::new)
+                .map_err(Into::into),
+        )
+    }
+}
+
+/// A request body that serializes the items of an iterator as a JSON array,
+/// for endpoints that accept large arrays in a single request
+///
+/// Unlike [`JsonBody`], the array is never held in memory in its entirety:
+/// each item is serialized only when the HTTP backend reads the part of the
+/// body that contains it, so memory use stays constant regardless of the
+/// number of items.  As a consequence, no `Content-Length` header is sent,
+/// the body is not captured in errors, and an item that fails to serialize
+/// causes the read to fail partway through the request.
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct JsonArrayBody<I>(I);
+
+impl<I> JsonArrayBody<I> {
+    pub fn new(items: I) -> JsonArrayBody<I> {
+        JsonArrayBody(items)
+    }
+}
+
+impl<I> RequestBody for JsonArrayBody<I>
+where
+    I: IntoIterator<Item: Serialize, IntoIter: 'static>,
+{
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json"
+                .parse()
+                .expect(r#""application/json" should be a valid HeaderValue"#),
+        );
+        headers
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        Ok(JsonArrayReader::new(self.0.into_iter()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<I> AsyncRequestBody for JsonArrayBody<I>
+where
+    I: IntoIterator<Item: Serialize, IntoIter: Send + 'static>,
+{
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json"
+                .parse()
+                .expect(r#""application/json" should be a valid HeaderValue"#),
+        );
+        headers
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(JsonArrayReader::new(self.0.into_iter())))
+    }
+}
+
+/// [Private] A reader that serializes the items of an iterator as a JSON
+/// array, one item at a time
+#[derive(Debug)]
+struct JsonArrayReader<I> {
+    items: I,
+    /// The serialized text not yet returned to the caller is `buf[pos..]`
+    buf: Vec<u8>,
+    pos: usize,
+    /// Whether at least one item has been serialized
+    started: bool,
+    /// Whether the closing bracket has been written to `buf`
+    done: bool,
+}
+
+impl<I: Iterator<Item: Serialize>> JsonArrayReader<I> {
+    fn new(items: I) -> Self {
+        JsonArrayReader {
+            items,
+            buf: vec![b'['],
+            pos: 0,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Copy as much pending text as possible into `out`, serializing the next
+    /// item first if no text is pending
+    fn read_into(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.buf.len() && !self.done {
+            self.buf.clear();
+            self.pos = 0;
+            if let Some(item) = self.items.next() {
+                if self.started {
+                    self.buf.push(b',');
+                }
+                self.started = true;
+                serde_json::to_writer(&mut self.buf, &item).map_err(std::io::Error::other)?;
+            } else {
+                self.buf.push(b']');
+                self.done = true;
+            }
+        }
+        let pending = &self.buf[self.pos..];
+        let n = pending.len().min(out.len());
+        out[..n].copy_from_slice(&pending[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl<I: Iterator<Item: Serialize>> std::io::Read for JsonArrayReader<I> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.read_into(buf)
+    }
+}
+
+// The reader is never pinned structurally, so it is `Unpin` regardless of
+// whether the iterator is.
+impl<I> Unpin for JsonArrayReader<I> {}
+
+#[cfg(feature = "tokio")]
+impl<I: Iterator<Item: Serialize>> tokio::io::AsyncRead for JsonArrayReader<I> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let n = this.read_into(buf.initialize_unfilled())?;
+        buf.advance(n);
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+impl RequestBody for PathBuf {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(md) = std::fs::metadata(self) {
+            headers.set_content_length(md.len());
+        }
+        headers
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        File::open(self).map_err(Into::into)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncRequestBody for PathBuf {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(md) = std::fs::metadata(self) {
+            headers.set_content_length(md.len());
+        }
+        headers
+    }
+
+    async fn into_async_read(
+        self,
+    ) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
+        Ok(tokio::fs::File::open(self).await?)
+    }
+}
+
+impl RequestBody for File {
     type Error = CommonError;
 
     fn headers(&self) -> HeaderMap {
@@ -406,7 +1120,1484 @@ impl AsyncRequestBody for File {
         headers
     }
 
-    fn into_async_read(self) -> Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error> {
-        Ok(tokio::fs::File::from_std(self))
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncRequestBody for File {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(md) = self.metadata() {
+            headers.set_content_length(md.len());
+        }
+        headers
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(tokio::fs::File::from_std(self)))
+    }
+}
+
+/// A request body that streams from an arbitrary [`std::io::Read`], such as
+/// a pipe, a socket, or an archive builder, without first buffering it in
+/// memory or in a temporary file
+///
+/// If the length of the body is known in advance, set it with
+/// [`ReaderBody::with_length()`] so that it is sent in a `Content-Length`
+/// header; some endpoints, such as release asset uploads, require one.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReaderBody<R> {
+    reader: R,
+    length: Option<u64>,
+}
+
+impl<R> ReaderBody<R> {
+    pub fn new(reader: R) -> ReaderBody<R> {
+        ReaderBody {
+            reader,
+            length: None,
+        }
+    }
+
+    /// Set the length of the body in bytes
+    pub fn with_length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
+
+impl<R: std::io::Read + 'static> RequestBody for ReaderBody<R> {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(length) = self.length {
+            headers.set_content_length(length);
+        }
+        headers
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        Ok(self.reader)
+    }
+}
+
+/// A request body that streams from an arbitrary
+/// [`tokio::io::AsyncRead`]; this is the asynchronous counterpart of
+/// [`ReaderBody`]
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AsyncReaderBody<R> {
+    reader: R,
+    length: Option<u64>,
+}
+
+#[cfg(feature = "tokio")]
+impl<R> AsyncReaderBody<R> {
+    pub fn new(reader: R) -> AsyncReaderBody<R> {
+        AsyncReaderBody {
+            reader,
+            length: None,
+        }
+    }
+
+    /// Set the length of the body in bytes
+    pub fn with_length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Send + 'static> AsyncRequestBody for AsyncReaderBody<R> {
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(length) = self.length {
+            headers.set_content_length(length);
+        }
+        headers
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(self.reader))
+    }
+}
+
+/// A request body for asynchronous clients that is produced as a
+/// [`Stream`][futures_util::Stream] of chunks, such as the output of an
+/// archive encoder
+///
+/// As with [`ReaderBody`], the length of the body can be set with
+/// [`StreamBody::with_length()`] if it is known in advance.
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StreamBody<S> {
+    stream: S,
+    length: Option<u64>,
+}
+
+#[cfg(feature = "tokio")]
+impl<S> StreamBody<S> {
+    pub fn new(stream: S) -> StreamBody<S> {
+        StreamBody {
+            stream,
+            length: None,
+        }
+    }
+
+    /// Set the length of the body in bytes
+    pub fn with_length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S> AsyncRequestBody for StreamBody<S>
+where
+    S: futures_util::Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static,
+{
+    type Error = CommonError;
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(length) = self.length {
+            headers.set_content_length(length);
+        }
+        headers
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        std::future::ready(Ok(StreamReader {
+            stream: self.stream,
+            chunk: Bytes::new(),
+        }))
+    }
+}
+
+#[cfg(feature = "tokio")]
+pin_project_lite::pin_project! {
+    /// [Private] An `AsyncRead` adapter over a stream of byte chunks
+    struct StreamReader<S> {
+        #[pin]
+        stream: S,
+        // The remainder of the most recent chunk not yet returned to the
+        // caller
+        chunk: Bytes,
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<S> tokio::io::AsyncRead for StreamReader<S>
+where
+    S: futures_util::Stream<Item = Result<Bytes, std::io::Error>>,
+{
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        out: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let mut this = self.project();
+        while this.chunk.is_empty() {
+            match std::task::ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(chunk) => *this.chunk = chunk?,
+                None => return std::task::Poll::Ready(Ok(())),
+            }
+        }
+        let n = this.chunk.len().min(out.remaining());
+        out.put_slice(&this.chunk.split_to(n));
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// A hash algorithm for computing a [`ChecksumBody`]'s digest, along with
+/// the header in which the digest is sent
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChecksumAlgorithm {
+    /// MD5, sent base64-encoded in a `Content-MD5` header
+    Md5,
+
+    /// SHA-256, sent in a `Content-Digest` header as defined by RFC 9530
+    Sha256,
+}
+
+#[cfg(feature = "digest")]
+impl ChecksumAlgorithm {
+    /// Compute the digest of the data read from `reader`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if reading from `reader` fails.
+    pub fn digest_reader<R: std::io::Read>(self, mut reader: R) -> std::io::Result<Digest> {
+        let mut hasher = Hasher::new(self);
+        let mut buf = vec![0u8; crate::consts::READ_BLOCK_SIZE];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return Ok(hasher.finish()),
+                Ok(n) => hasher.update(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Asynchronously compute the digest of the data read from `reader`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if reading from `reader` fails.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn digest_async_reader<R>(self, reader: R) -> std::io::Result<Digest>
+    where
+        R: tokio::io::AsyncRead,
+    {
+        use tokio::io::AsyncReadExt;
+        tokio::pin!(reader);
+        let mut hasher = Hasher::new(self);
+        let mut buf = vec![0u8; crate::consts::READ_BLOCK_SIZE];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) => return Ok(hasher.finish()),
+                Ok(n) => hasher.update(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// [Private] Returns the name and value of the header that conveys
+    /// `digest`
+    fn header(self, digest: &[u8]) -> (HeaderName, Option<HeaderValue>) {
+        match self {
+            ChecksumAlgorithm::Md5 => (
+                CONTENT_MD5,
+                HeaderValue::from_str(&STANDARD.encode(digest)).ok(),
+            ),
+            ChecksumAlgorithm::Sha256 => (
+                CONTENT_DIGEST,
+                HeaderValue::from_str(&format!("sha-256=:{}:", STANDARD.encode(digest))).ok(),
+            ),
+        }
+    }
+}
+
+/// [Private] An incremental hasher for a [`ChecksumAlgorithm`]
+#[cfg(feature = "digest")]
+#[derive(Clone, Debug)]
+enum Hasher {
+    Md5(md5::Md5),
+    Sha256(sha2::Sha256),
+}
+
+#[cfg(feature = "digest")]
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Hasher {
+        use sha2::Digest as _;
+        match algorithm {
+            ChecksumAlgorithm::Md5 => Hasher::Md5(md5::Md5::new()),
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        use sha2::Digest as _;
+        match self {
+            Hasher::Md5(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+        }
+    }
+
+    fn finish(self) -> Digest {
+        use sha2::Digest as _;
+        match self {
+            Hasher::Md5(h) => Digest::new(h.finalize().to_vec()),
+            Hasher::Sha256(h) => Digest::new(h.finalize().to_vec()),
+        }
+    }
+}
+
+/// A request body wrapper that computes a digest of the inner body while it
+/// is being sent, for use with storage services that verify the integrity of
+/// uploads
+///
+/// The body is hashed as the backend reads it, so it is never held in memory
+/// and is read only once; in particular, wrapping a `ChecksumBody` in a
+/// [`WithProgress`] reports progress and computes the digest in the same
+/// pass.  Once the body has been read in full, the digest can be retrieved
+/// through the [`ChecksumHandle`] returned by [`ChecksumBody::handle()`].
+///
+/// As headers are sent before the body, the `Content-MD5` or `Content-Digest`
+/// header is only sent if the digest is known in advance and supplied with
+/// [`ChecksumBody::with_digest()`].  The digest computed while streaming is
+/// then checked against it, and reading the body fails if they differ.  For
+/// files, [`ChecksumBody::for_file()`] computes the digest by reading the file
+/// before it is uploaded, so that the file is read twice but never buffered
+/// in memory.
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Clone, Debug)]
+pub struct ChecksumBody<B> {
+    body: B,
+    algorithm: ChecksumAlgorithm,
+    expected: Option<Digest>,
+    handle: ChecksumHandle,
+}
+
+#[cfg(feature = "digest")]
+impl<B> ChecksumBody<B> {
+    /// Wrap `body` so that its digest is computed with `algorithm` while it
+    /// is sent
+    pub fn new(body: B, algorithm: ChecksumAlgorithm) -> ChecksumBody<B> {
+        ChecksumBody {
+            body,
+            algorithm,
+            expected: None,
+            handle: ChecksumHandle::default(),
+        }
+    }
+
+    /// Set the precomputed digest of the body, to be sent in a header and
+    /// verified while the body is sent
+    pub fn with_digest(mut self, digest: Digest) -> Self {
+        self.expected = Some(digest);
+        self
+    }
+
+    /// Returns a handle through which the digest computed while sending the
+    /// body can be retrieved
+    pub fn handle(&self) -> ChecksumHandle {
+        self.handle.clone()
+    }
+
+    /// [Private] Add the digest header, if the digest is known, to the inner
+    /// body's headers
+    fn with_header(&self, mut headers: HeaderMap) -> HeaderMap {
+        if let Some(digest) = &self.expected
+            && let (name, Some(value)) = self.algorithm.header(digest.as_bytes())
+        {
+            headers.insert(name, value);
+        }
+        headers
+    }
+}
+
+#[cfg(feature = "digest")]
+impl ChecksumBody<PathBuf> {
+    /// Compute the digest of the file at `path` with `algorithm` and return a
+    /// body that uploads the file with the digest in a header
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if opening or reading the file fails.
+    pub fn for_file<P: Into<PathBuf>>(
+        path: P,
+        algorithm: ChecksumAlgorithm,
+    ) -> std::io::Result<ChecksumBody<PathBuf>> {
+        let path = path.into();
+        let digest = algorithm.digest_reader(File::open(&path)?)?;
+        Ok(ChecksumBody::new(path, algorithm).with_digest(digest))
+    }
+
+    /// Asynchronously compute the digest of the file at `path` with
+    /// `algorithm` and return a body that uploads the file with the digest in
+    /// a header
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if opening or reading the file fails.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn for_file_async<P: Into<PathBuf>>(
+        path: P,
+        algorithm: ChecksumAlgorithm,
+    ) -> std::io::Result<ChecksumBody<PathBuf>> {
+        let path = path.into();
+        let fp = tokio::fs::File::open(&path).await?;
+        let digest = algorithm.digest_async_reader(fp).await?;
+        Ok(ChecksumBody::new(path, algorithm).with_digest(digest))
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<B: RequestBody<Error: From<std::io::Error>>> RequestBody for ChecksumBody<B> {
+    type Error = B::Error;
+
+    fn headers(&self) -> HeaderMap {
+        self.with_header(self.body.headers())
+    }
+
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        self.body.in_memory()
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        let inner = self.body.into_read()?;
+        Ok(ChecksumReader {
+            inner,
+            hasher: Some(Hasher::new(self.algorithm)),
+            expected: self.expected,
+            handle: self.handle,
+        })
+    }
+}
+
+#[cfg(all(feature = "digest", feature = "tokio"))]
+impl<B: AsyncRequestBody<Error: From<std::io::Error>>> AsyncRequestBody for ChecksumBody<B> {
+    type Error = B::Error;
+
+    fn headers(&self) -> HeaderMap {
+        self.with_header(self.body.headers())
+    }
+
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        self.body.in_memory()
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        let inner = self.body.into_async_read();
+        let hasher = Some(Hasher::new(self.algorithm));
+        let (expected, handle) = (self.expected, self.handle);
+        async move {
+            Ok(ChecksumReader {
+                inner: inner.await?,
+                hasher,
+                expected,
+                handle,
+            })
+        }
+    }
+}
+
+/// A handle for retrieving the digest computed by a [`ChecksumBody`] once the
+/// body has been sent
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Clone, Debug, Default)]
+pub struct ChecksumHandle(std::sync::Arc<std::sync::Mutex<Option<Digest>>>);
+
+#[cfg(feature = "digest")]
+impl ChecksumHandle {
+    /// Returns the digest of the body, or `None` if the body has not yet been
+    /// read in full.  If the body was read more than once, as when following
+    /// a redirect, this is the digest from the most recent read.
+    pub fn digest(&self) -> Option<Digest> {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(feature = "digest")]
+pin_project_lite::pin_project! {
+    /// [Private] A reader that hashes the data read through it and, at
+    /// end-of-file, records the digest and checks it against the expected
+    /// digest, if any
+    struct ChecksumReader<R> {
+        #[pin]
+        inner: R,
+        // `None` once end-of-file has been reached
+        hasher: Option<Hasher>,
+        expected: Option<Digest>,
+        handle: ChecksumHandle,
+    }
+}
+
+/// [Private] Feed `data` to `hasher`, or, if `data` is empty (indicating
+/// end-of-file), finish the digest, record it in `handle`, and check it
+/// against `expected`
+#[cfg(feature = "digest")]
+fn checksum_chunk(
+    hasher: &mut Option<Hasher>,
+    expected: Option<&Digest>,
+    handle: &ChecksumHandle,
+    data: &[u8],
+) -> std::io::Result<()> {
+    if !data.is_empty() {
+        if let Some(h) = hasher.as_mut() {
+            h.update(data);
+        }
+    } else if let Some(h) = hasher.take() {
+        let digest = h.finish();
+        let matches = expected.is_none_or(|e| *e == digest);
+        *handle
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(digest);
+        if !matches {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "request body does not match its precomputed digest",
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "digest")]
+impl<R: std::io::Read> std::io::Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 || !buf.is_empty() {
+            checksum_chunk(
+                &mut self.hasher,
+                self.expected.as_ref(),
+                &self.handle,
+                &buf[..n],
+            )?;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(all(feature = "digest", feature = "tokio"))]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for ChecksumReader<R> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        let room = buf.remaining();
+        std::task::ready!(this.inner.poll_read(cx, buf))?;
+        let data = buf.filled().get(before..).unwrap_or_default();
+        if !data.is_empty() || room > 0 {
+            checksum_chunk(this.hasher, this.expected.as_ref(), this.handle, data)?;
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// A request body wrapper that reports the upload's progress to a callback as
+/// the body is read by the backend, e.g., for displaying a progress bar while
+/// uploading a release asset
+///
+/// The callback is called with the number of body bytes sent so far and the
+/// total size of the body from its `Content-Length` header, if known, after
+/// each chunk of the body is read; this mirrors
+/// [`Progress`][crate::parser::Progress] for downloads.  Note that the body
+/// may be read again from the start if the client follows a redirect that
+/// preserves the body.
+pub struct WithProgress<B, F> {
+    body: B,
+    callback: F,
+}
+
+impl<B, F> WithProgress<B, F>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    pub fn new(body: B, callback: F) -> WithProgress<B, F> {
+        WithProgress { body, callback }
+    }
+}
+
+impl<B: fmt::Debug, F> fmt::Debug for WithProgress<B, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithProgress")
+            .field("body", &self.body)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B, F> RequestBody for WithProgress<B, F>
+where
+    B: RequestBody,
+    F: FnMut(u64, Option<u64>) + 'static,
+{
+    type Error = B::Error;
+
+    fn headers(&self) -> HeaderMap {
+        self.body.headers()
+    }
+
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        self.body.in_memory()
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        let total = self.body.headers().content_length();
+        let inner = self.body.into_read()?;
+        Ok(ProgressReader::new(inner, self.callback, total))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B, F> AsyncRequestBody for WithProgress<B, F>
+where
+    B: AsyncRequestBody,
+    F: FnMut(u64, Option<u64>) + Send + 'static,
+{
+    type Error = B::Error;
+
+    fn headers(&self) -> HeaderMap {
+        self.body.headers()
+    }
+
+    fn in_memory(&self) -> Option<Cow<'_, [u8]>> {
+        self.body.in_memory()
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        let total = self.body.headers().content_length();
+        let inner = self.body.into_async_read();
+        let callback = self.callback;
+        async move { Ok(ProgressReader::new(inner.await?, callback, total)) }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// [Private] A reader that reports the number of bytes read through it to
+    /// a callback
+    struct ProgressReader<R, F> {
+        #[pin]
+        inner: R,
+        callback: F,
+        sent: u64,
+        total: Option<u64>,
+    }
+}
+
+impl<R, F: FnMut(u64, Option<u64>)> ProgressReader<R, F> {
+    fn new(inner: R, callback: F, total: Option<u64>) -> Self {
+        ProgressReader {
+            inner,
+            callback,
+            sent: 0,
+            total,
+        }
+    }
+}
+
+impl<R: std::io::Read, F: FnMut(u64, Option<u64>)> std::io::Read for ProgressReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            self.sent = self.sent.saturating_add(n as u64);
+            (self.callback)(self.sent, self.total);
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R, F> tokio::io::AsyncRead for ProgressReader<R, F>
+where
+    R: tokio::io::AsyncRead,
+    F: FnMut(u64, Option<u64>),
+{
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        let this = self.project();
+        let before = buf.filled().len();
+        std::task::ready!(this.inner.poll_read(cx, buf))?;
+        let n = buf.filled().len() - before;
+        if n > 0 {
+            *this.sent = this.sent.saturating_add(n as u64);
+            (this.callback)(*this.sent, *this.total);
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// A request body wrapper that gzip-compresses the inner body as it is sent
+/// and sets the `Content-Encoding: gzip` header, for sending large payloads
+/// to servers that accept compressed request bodies
+///
+/// The body is compressed on the fly, so it is never held in memory in its
+/// entirety.  As the size of the compressed body is not known in advance, any
+/// `Content-Length` header of the inner body is removed, and the body is not
+/// captured in errors.
+#[cfg(feature = "gzip")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Gzipped<B>(B);
+
+#[cfg(feature = "gzip")]
+impl<B> Gzipped<B> {
+    pub fn new(body: B) -> Gzipped<B> {
+        Gzipped(body)
+    }
+
+    /// [Private] Convert the headers of the inner body into those of the
+    /// compressed body
+    fn convert_headers(mut headers: HeaderMap) -> HeaderMap {
+        headers.remove(http::header::CONTENT_LENGTH);
+        headers.insert(
+            http::header::CONTENT_ENCODING,
+            HeaderValue::from_static("gzip"),
+        );
+        headers
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<B: RequestBody> RequestBody for Gzipped<B> {
+    type Error = B::Error;
+
+    fn headers(&self) -> HeaderMap {
+        Gzipped::<B>::convert_headers(self.0.headers())
+    }
+
+    fn into_read(self) -> Result<impl std::io::Read + 'static, Self::Error> {
+        let inner = self.0.into_read()?;
+        Ok(flate2::read::GzEncoder::new(
+            inner,
+            flate2::Compression::default(),
+        ))
+    }
+}
+
+#[cfg(all(feature = "gzip", feature = "tokio"))]
+impl<B: AsyncRequestBody> AsyncRequestBody for Gzipped<B> {
+    type Error = B::Error;
+
+    fn headers(&self) -> HeaderMap {
+        Gzipped::<B>::convert_headers(self.0.headers())
+    }
+
+    fn into_async_read(
+        self,
+    ) -> impl Future<Output = Result<impl tokio::io::AsyncRead + Send + 'static, Self::Error>> + Send
+    {
+        let inner = self.0.into_async_read();
+        async move { Ok(AsyncGzipReader::new(inner.await?)) }
+    }
+}
+
+#[cfg(all(feature = "gzip", feature = "tokio"))]
+pin_project_lite::pin_project! {
+    /// [Private] An `AsyncRead` adapter that gzip-compresses the data read
+    /// from an inner reader
+    struct AsyncGzipReader<R> {
+        #[pin]
+        inner: R,
+        // The compressed data not yet returned to the caller is
+        // `encoder.get_ref()[pos..]`
+        encoder: flate2::write::GzEncoder<Vec<u8>>,
+        pos: usize,
+        buf: Vec<u8>,
+        eof: bool,
+        finished: bool,
+    }
+}
+
+#[cfg(all(feature = "gzip", feature = "tokio"))]
+impl<R> AsyncGzipReader<R> {
+    fn new(inner: R) -> Self {
+        AsyncGzipReader {
+            inner,
+            encoder: flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()),
+            pos: 0,
+            buf: vec![0u8; crate::consts::READ_BLOCK_SIZE],
+            eof: false,
+            finished: false,
+        }
+    }
+}
+
+#[cfg(all(feature = "gzip", feature = "tokio"))]
+impl<R: tokio::io::AsyncRead> tokio::io::AsyncRead for AsyncGzipReader<R> {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        out: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        use std::io::Write;
+        let mut this = self.project();
+        loop {
+            let pending = this.encoder.get_ref().get(*this.pos..).unwrap_or_default();
+            if !pending.is_empty() {
+                let n = pending.len().min(out.remaining());
+                out.put_slice(&pending[..n]);
+                *this.pos += n;
+                return std::task::Poll::Ready(Ok(()));
+            }
+            this.encoder.get_mut().clear();
+            *this.pos = 0;
+            if *this.finished {
+                return std::task::Poll::Ready(Ok(()));
+            } else if *this.eof {
+                this.encoder.try_finish()?;
+                *this.finished = true;
+            } else {
+                let mut chunk = tokio::io::ReadBuf::new(this.buf);
+                std::task::ready!(this.inner.as_mut().poll_read(cx, &mut chunk))?;
+                if chunk.filled().is_empty() {
+                    *this.eof = true;
+                } else {
+                    this.encoder.write_all(chunk.filled())?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::{ClientConfig, FnBackend},
+        errors::ErrorClass,
+        parser::Ignore,
+    };
+    use http::status::StatusCode;
+
+    struct DeleteThing;
+
+    impl Request for DeleteThing {
+        type Output = ();
+        type Error = CommonError;
+        type Body = ();
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["thing"])
+        }
+
+        fn method(&self) -> Method {
+            Method::Delete
+        }
+
+        fn body(&self) -> Self::Body {}
+
+        fn parser(
+            &self,
+        ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+            Ignore
+        }
+    }
+
+    #[test]
+    fn uploads_base_url() {
+        struct UploadAsset;
+
+        impl Request for UploadAsset {
+            type Output = ();
+            type Error = CommonError;
+            type Body = ();
+
+            fn endpoint(&self) -> Endpoint {
+                Endpoint::from_iter(["repos", "octocat", "hello-world", "releases", "1", "assets"])
+            }
+
+            fn base_url(&self) -> BaseUrl {
+                BaseUrl::Uploads
+            }
+
+            fn method(&self) -> Method {
+                Method::Post
+            }
+
+            fn body(&self) -> Self::Body {}
+
+            fn parser(
+                &self,
+            ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send
+            {
+                Ignore
+            }
+        }
+
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://uploads.github.com/repos/octocat/hello-world/releases/1/assets"
+            );
+            http::Response::builder()
+                .status(StatusCode::CREATED)
+                .body(Vec::new())
+                .unwrap()
+        }));
+        client.request(IfMatch::any(UploadAsset)).unwrap();
+    }
+
+    #[test]
+    fn with_base_url() {
+        let urls = std::sync::Mutex::new(Vec::new());
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            urls.lock().unwrap().push(parts.url.to_string());
+            http::Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Vec::new())
+                .unwrap()
+        }));
+        client.set_base_url("https://ghe1.example.com/api/v3".parse().unwrap());
+        client.request(DeleteThing).unwrap();
+        let req = WithBaseUrl::new(
+            DeleteThing,
+            "https://ghe2.example.com/api/v3".parse().unwrap(),
+        );
+        client.request(req).unwrap();
+        client.request(DeleteThing).unwrap();
+        assert_eq!(
+            *urls.lock().unwrap(),
+            [
+                "https://ghe1.example.com/api/v3/thing",
+                "https://ghe2.example.com/api/v3/thing",
+                "https://ghe1.example.com/api/v3/thing",
+            ]
+        );
+    }
+
+    struct ListIssues<Q> {
+        filter: Q,
+    }
+
+    impl<Q: Serialize> Request for ListIssues<Q> {
+        type Output = ();
+        type Error = CommonError;
+        type Body = ();
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["issues"])
+        }
+
+        fn method(&self) -> Method {
+            Method::Get
+        }
+
+        fn params(&self) -> Vec<(String, String)> {
+            vec![(String::from("per_page"), String::from("100"))]
+        }
+
+        fn query(&self) -> impl Serialize {
+            &self.filter
+        }
+
+        fn body(&self) -> Self::Body {}
+
+        fn parser(
+            &self,
+        ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+            Ignore
+        }
+    }
+
+    #[test]
+    fn serialized_query() {
+        #[derive(Serialize)]
+        struct IssueFilter {
+            state: &'static str,
+            sort: &'static str,
+            labels: Option<&'static str>,
+        }
+
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/issues?per_page=100&state=open&sort=created"
+            );
+            http::Response::new(Vec::new())
+        }));
+        client
+            .request(ListIssues {
+                filter: IssueFilter {
+                    state: "open",
+                    sort: "created",
+                    labels: None,
+                },
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn endpoint_query_merged_with_params() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(
+                parts.url.as_str(),
+                "https://api.github.com/repos/octocat/hello-world/issues?state=all&per_page=100"
+            );
+            http::Response::new(Vec::new())
+        }));
+        let endpoint = "repos/octocat/hello-world/issues?state=all"
+            .parse::<Endpoint>()
+            .unwrap();
+        client
+            .request(
+                AdHocRequest::<Ignore>::new(Method::Get, endpoint).with_param("per_page", "100"),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn unencodable_query() {
+        let client =
+            ClientConfig::new().with_backend(FnBackend::new(|_, _| -> http::Response<Vec<u8>> {
+                panic!("request should not be sent")
+            }));
+        let e = client.request(ListIssues { filter: "open" }).unwrap_err();
+        assert!(matches!(
+            e.into_payload(),
+            crate::errors::ErrorPayload::PrepareRequest(CommonError::Query(_))
+        ));
+    }
+
+    #[test]
+    fn json_array_body() {
+        use std::io::Read;
+        let body = JsonArrayBody::new((1..=3).map(|i| serde_json::json!({"id": i})));
+        assert_eq!(
+            RequestBody::headers(&body).get(http::header::CONTENT_TYPE),
+            Some(&HeaderValue::from_static("application/json"))
+        );
+        assert_eq!(RequestBody::in_memory(&body), None);
+        let mut reader = RequestBody::into_read(body).unwrap();
+        let mut buf = [0u8; 4];
+        let mut out = Vec::new();
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(out, br#"[{"id":1},{"id":2},{"id":3}]"#);
+
+        let mut empty = String::new();
+        RequestBody::into_read(JsonArrayBody::new(Vec::<u32>::new()))
+            .unwrap()
+            .read_to_string(&mut empty)
+            .unwrap();
+        assert_eq!(empty, "[]");
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn checksum_body() {
+        use std::io::Read;
+        let body = ChecksumBody::new(
+            JsonBody::new(serde_json::json!({"a": 1})),
+            ChecksumAlgorithm::Md5,
+        );
+        let handle = body.handle();
+        let headers = RequestBody::headers(&body);
+        assert_eq!(headers[http::header::CONTENT_TYPE], "application/json");
+        assert!(!headers.contains_key(CONTENT_MD5));
+        let mut data = String::new();
+        body.into_read().unwrap().read_to_string(&mut data).unwrap();
+        assert_eq!(data, r#"{"a":1}"#);
+        let digest = handle.digest().unwrap();
+        assert_eq!(digest.to_string(), "bb6cb5c68df4652941caf652a366f2d8");
+
+        let body = ChecksumBody::new(String::from(r#"{"a":1}"#), ChecksumAlgorithm::Md5)
+            .with_digest(digest.clone());
+        assert_eq!(
+            RequestBody::headers(&body)[CONTENT_MD5],
+            "u2y1xo30ZSlByvZSo2by2A=="
+        );
+        let mut data = Vec::new();
+        body.into_read().unwrap().read_to_end(&mut data).unwrap();
+
+        // A body that does not match the precomputed digest fails to be read
+        let body = ChecksumBody::new(String::from(r#"{"a":2}"#), ChecksumAlgorithm::Md5)
+            .with_digest(digest);
+        let e = body
+            .into_read()
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn checksum_body_with_progress() {
+        use std::io::Read;
+        use std::sync::{Arc, Mutex};
+        let path = std::env::temp_dir().join(format!("ghreq-{}-checksum-body", std::process::id()));
+        std::fs::write(&path, "Hello, world!").unwrap();
+        let body = ChecksumBody::for_file(&path, ChecksumAlgorithm::Sha256).unwrap();
+        let handle = body.handle();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let r2 = Arc::clone(&reports);
+        let body = WithProgress::new(body, move |sent, total| {
+            r2.lock().unwrap().push((sent, total));
+        });
+        let headers = RequestBody::headers(&body);
+        assert_eq!(headers.content_length(), Some(13));
+        assert_eq!(
+            headers[CONTENT_DIGEST],
+            "sha-256=:MV9b23bQeMQ7isAGTkoBZGErH853yGk0W/yUx1iU7dM=:"
+        );
+        let mut data = String::new();
+        body.into_read().unwrap().read_to_string(&mut data).unwrap();
+        assert_eq!(data, "Hello, world!");
+        assert_eq!(*reports.lock().unwrap(), [(13, Some(13))]);
+        assert!(
+            handle
+                .digest()
+                .unwrap()
+                .matches_hex("315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3")
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(all(feature = "digest", feature = "tokio"))]
+    #[tokio::test]
+    async fn checksum_body_async() {
+        use tokio::io::AsyncReadExt;
+        let path =
+            std::env::temp_dir().join(format!("ghreq-{}-checksum-body-async", std::process::id()));
+        std::fs::write(&path, "Hello, world!").unwrap();
+        let body = ChecksumBody::for_file_async(&path, ChecksumAlgorithm::Md5)
+            .await
+            .unwrap();
+        let handle = body.handle();
+        assert_eq!(
+            AsyncRequestBody::headers(&body)[CONTENT_MD5],
+            "bNNVbesNpUvKBgtMOUeYOQ=="
+        );
+        let reader = body.into_async_read().await.unwrap();
+        tokio::pin!(reader);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"Hello, world!");
+        assert!(handle.digest().is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn bytes_body() {
+        use std::io::Read;
+        let body = Bytes::from_static(b"Hello, world!");
+        let headers = RequestBody::headers(&body);
+        assert_eq!(headers.content_length(), Some(13));
+        assert_eq!(
+            RequestBody::in_memory(&body).unwrap(),
+            &b"Hello, world!"[..]
+        );
+        let mut data = String::new();
+        body.into_read().unwrap().read_to_string(&mut data).unwrap();
+        assert_eq!(data, "Hello, world!");
+
+        let body: Cow<'static, [u8]> = Cow::Borrowed(b"Hello");
+        assert_eq!(RequestBody::headers(&body).content_length(), Some(5));
+        let mut data = Vec::new();
+        body.into_read().unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"Hello");
+    }
+
+    #[test]
+    fn reader_body() {
+        use std::io::Read;
+        let body = ReaderBody::new(&b"Hello, world!"[..]).with_length(13);
+        assert_eq!(RequestBody::headers(&body).content_length(), Some(13));
+        assert!(RequestBody::in_memory(&body).is_none());
+        let mut data = String::new();
+        body.into_read().unwrap().read_to_string(&mut data).unwrap();
+        assert_eq!(data, "Hello, world!");
+
+        let body = ReaderBody::new(std::io::empty());
+        assert_eq!(RequestBody::headers(&body).content_length(), None);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_reader_body() {
+        use tokio::io::AsyncReadExt;
+        let body = AsyncReaderBody::new(&b"Hello"[..]).with_length(5);
+        assert_eq!(AsyncRequestBody::headers(&body).content_length(), Some(5));
+        let mut data = Vec::new();
+        let mut r = Box::pin(body.into_async_read().await.unwrap());
+        r.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"Hello");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn path_body_async() {
+        use tokio::io::AsyncReadExt;
+        let path = std::env::temp_dir().join(format!("ghreq-{}-path-body", std::process::id()));
+        std::fs::write(&path, "Hello, world!").unwrap();
+        let body = path.clone();
+        assert_eq!(AsyncRequestBody::headers(&body).content_length(), Some(13));
+        let reader = body.into_async_read().await.unwrap();
+        tokio::pin!(reader);
+        let mut data = String::new();
+        reader.read_to_string(&mut data).await.unwrap();
+        assert_eq!(data, "Hello, world!");
+        std::fs::remove_file(&path).unwrap();
+
+        let e = path.into_async_read().await.err().unwrap();
+        assert!(matches!(e, CommonError::Io(_)));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn stream_body() {
+        use tokio::io::AsyncReadExt;
+        let chunks = ["Hello", "", ", ", "world!"].map(|s| Ok(Bytes::from_static(s.as_bytes())));
+        let body = StreamBody::new(futures_util::stream::iter(chunks));
+        assert_eq!(AsyncRequestBody::headers(&body).content_length(), None);
+        let mut data = String::new();
+        let mut r = Box::pin(body.into_async_read().await.unwrap());
+        r.read_to_string(&mut data).await.unwrap();
+        assert_eq!(data, "Hello, world!");
+
+        let chunks = [
+            Ok(Bytes::from_static(b"abc")),
+            Err(std::io::Error::other("encoder failed")),
+        ];
+        let body = StreamBody::new(futures_util::stream::iter(chunks)).with_length(6);
+        assert_eq!(AsyncRequestBody::headers(&body).content_length(), Some(6));
+        let mut r = Box::pin(body.into_async_read().await.unwrap());
+        let e = r.read_to_end(&mut Vec::new()).await.unwrap_err();
+        assert_eq!(e.to_string(), "encoder failed");
+    }
+
+    #[test]
+    fn with_progress() {
+        use std::sync::{Arc, Mutex};
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let r2 = Arc::clone(&reports);
+        let body = WithProgress::new(vec![b'x'; 10000], move |sent, total| {
+            r2.lock().unwrap().push((sent, total));
+        });
+        assert_eq!(RequestBody::headers(&body).content_length(), Some(10000));
+        let mut reader = body.into_read().unwrap();
+        let mut buf = [0u8; 4096];
+        while std::io::Read::read(&mut reader, &mut buf).unwrap() > 0 {}
+        assert_eq!(
+            *reports.lock().unwrap(),
+            [
+                (4096, Some(10000)),
+                (8192, Some(10000)),
+                (10000, Some(10000))
+            ]
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzipped() {
+        use std::io::Read;
+        let body = Gzipped::new(JsonBody::new(serde_json::json!({"a": 1})));
+        let headers = RequestBody::headers(&body);
+        assert_eq!(headers[http::header::CONTENT_TYPE], "application/json");
+        assert_eq!(headers[http::header::CONTENT_ENCODING], "gzip");
+        assert_eq!(headers.content_length(), None);
+        let mut data = String::new();
+        flate2::read::GzDecoder::new(body.into_read().unwrap())
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!(data, r#"{"a":1}"#);
+    }
+
+    #[cfg(all(feature = "gzip", feature = "tokio"))]
+    #[tokio::test]
+    async fn gzipped_async() {
+        use std::io::Read;
+        use tokio::io::AsyncReadExt;
+        let text = "Hello, world!\n".repeat(10000);
+        let reader = Gzipped::new(text.clone()).into_async_read().await.unwrap();
+        tokio::pin!(reader);
+        let mut compressed = Vec::new();
+        reader.read_to_end(&mut compressed).await.unwrap();
+        assert!(compressed.len() < text.len() / 10);
+        let mut data = String::new();
+        flate2::read::GzDecoder::new(&compressed[..])
+            .read_to_string(&mut data)
+            .unwrap();
+        assert_eq!(data, text);
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use crate::{
+            client::{ClientConfig, FnBackend},
+            request::Request,
+        };
+        use serde::{Deserialize, Serialize};
+        use serde_json::json;
+
+        #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+        struct Label {
+            name: String,
+        }
+
+        #[derive(Request)]
+        #[ghreq(
+            method = "GET",
+            endpoint = "repos/{owner}/{repo}/labels/{name}",
+            output = Label
+        )]
+        struct GetLabel {
+            owner: String,
+            repo: String,
+            name: String,
+        }
+
+        #[derive(Clone, Serialize)]
+        struct NewLabel {
+            name: String,
+            color: String,
+        }
+
+        #[derive(Request)]
+        #[ghreq(
+            method = "post",
+            endpoint = "/repos/{owner}/{repo}/labels/",
+            output = Label,
+            body = label
+        )]
+        struct CreateLabel {
+            owner: String,
+            repo: u64,
+            label: NewLabel,
+        }
+
+        #[test]
+        fn derived_request() {
+            let req = GetLabel {
+                owner: "octocat".into(),
+                repo: "hello-world".into(),
+                name: "bug fix".into(),
+            };
+            assert_eq!(
+                req.endpoint(),
+                crate::Endpoint::from_iter([
+                    "repos",
+                    "octocat",
+                    "hello-world",
+                    "labels",
+                    "bug fix"
+                ])
+            );
+            assert_eq!(req.method(), crate::Method::Get);
+        }
+
+        #[derive(Request)]
+        #[ghreq(method = "PROPFIND", endpoint = "dav", output = serde_json::Value)]
+        struct Propfind;
+
+        #[test]
+        fn derived_request_other_method() {
+            assert_eq!(
+                Propfind.method(),
+                crate::Method::Other(http::Method::from_bytes(b"PROPFIND").unwrap())
+            );
+        }
+
+        #[test]
+        fn derived_request_with_body() {
+            let client = ClientConfig::new().with_backend(FnBackend::new(|parts, body| {
+                assert_eq!(
+                    parts.url.as_str(),
+                    "https://api.github.com/repos/octocat/42/labels"
+                );
+                assert_eq!(parts.method, crate::Method::Post);
+                assert_eq!(
+                    serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+                    json!({"name": "bug", "color": "f00"})
+                );
+                http::Response::builder()
+                    .status(http::status::StatusCode::CREATED)
+                    .body(br#"{"name": "bug"}"#.to_vec())
+                    .unwrap()
+            }));
+            let label = client
+                .request(CreateLabel {
+                    owner: "octocat".into(),
+                    repo: 42,
+                    label: NewLabel {
+                        name: "bug".into(),
+                        color: "f00".into(),
+                    },
+                })
+                .unwrap();
+            assert_eq!(label.name, "bug");
+        }
+
+        #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+        struct Topics {
+            topics: Vec<String>,
+        }
+
+        #[derive(Request)]
+        #[ghreq(
+            method = "GET",
+            endpoint = "repos/{owner}/{repo}",
+            output = Topics,
+            accept = "application/vnd.github.mercy-preview+json"
+        )]
+        struct GetTopics {
+            owner: String,
+            repo: String,
+        }
+
+        #[test]
+        fn derived_request_extra_accept() {
+            let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+                assert_eq!(
+                    parts.headers[http::header::ACCEPT],
+                    "application/vnd.github+json, application/vnd.github.mercy-preview+json"
+                );
+                http::Response::new(br#"{"topics": ["rust"]}"#.to_vec())
+            }));
+            let topics = client
+                .request(GetTopics {
+                    owner: "octocat".into(),
+                    repo: "hello-world".into(),
+                })
+                .unwrap();
+            assert_eq!(topics.topics, ["rust"]);
+        }
+    }
+
+    #[test]
+    fn if_match_precondition_failed() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(parts.headers[http::header::IF_MATCH], r#""abc", "def""#);
+            http::Response::builder()
+                .status(StatusCode::PRECONDITION_FAILED)
+                .body(Vec::new())
+                .unwrap()
+        }));
+        let req = IfMatch::new(DeleteThing, EntityTag::strong("abc").unwrap())
+            .with_etag(EntityTag::strong("def").unwrap());
+        let e = client.request(req).unwrap_err();
+        assert_eq!(e.class(), Some(ErrorClass::PreconditionFailed));
+    }
+
+    #[test]
+    fn if_match_any() {
+        let client = ClientConfig::new().with_backend(FnBackend::new(|parts, _| {
+            assert_eq!(parts.headers[http::header::IF_MATCH], "*");
+            http::Response::builder()
+                .status(StatusCode::NO_CONTENT)
+                .body(Vec::new())
+                .unwrap()
+        }));
+        client.request(IfMatch::any(DeleteThing)).unwrap();
     }
 }
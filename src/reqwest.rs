@@ -14,18 +14,18 @@ use tokio_util::io::{ReaderStream, StreamReader};
 pub type ReqwestClient = AsyncClient<reqwest::Client>;
 
 impl AsyncBackend for reqwest::Client {
-    type Request = reqwest::RequestBuilder;
+    type Request = reqwest::Request;
     type Response = reqwest::Response;
     type Error = reqwest::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
         let mut req = self
             .request(r.method.into(), r.url.as_str())
             .headers(r.headers);
         if let Some(d) = r.timeout {
             req = req.timeout(d);
         }
-        req
+        req.build()
     }
 
     fn send<R: tokio::io::AsyncRead + Send + 'static>(
@@ -33,8 +33,9 @@ impl AsyncBackend for reqwest::Client {
         r: Self::Request,
         body: R,
     ) -> impl Future<Output = Result<Self::Response, Self::Error>> + Send + 'static {
-        r.body(reqwest::Body::wrap_stream(ReaderStream::new(body)))
-            .send()
+        let mut r = r;
+        *r.body_mut() = Some(reqwest::Body::wrap_stream(ReaderStream::new(body)));
+        self.execute(r)
     }
 }
 
@@ -67,3 +68,9 @@ pub type ReqwestError<E = CommonError> = Error<reqwest::Error, E>;
 /// The `E` parameter is the `Error` type of the input
 /// [`Request`][crate::request::Request] provided to a method.
 pub type ReqwestErrorPayload<E = CommonError> = ErrorPayload<reqwest::Error, E>;
+
+/// Result type returned by [`ReqwestClient`] methods.
+///
+/// The `E` parameter is the `Error` type of the input
+/// [`Request`][crate::request::Request] provided to a method.
+pub type ReqwestResult<T, E = CommonError> = Result<T, ReqwestError<E>>;
@@ -1,4 +1,5 @@
-use crate::{HttpUrl, Method};
+use crate::{HttpUrl, Method, Timestamp};
+use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ResponseParts {
@@ -7,9 +8,92 @@ pub struct ResponseParts {
     pub(crate) method: Method,
     pub(crate) status: http::status::StatusCode,
     pub(crate) headers: http::header::HeaderMap,
+    pub(crate) host_changed: bool,
+    pub(crate) tls: Option<TlsInfo>,
 }
 
 impl ResponseParts {
+    /// Construct the parts of a response with the given status to a request
+    /// with the given URL and method.
+    ///
+    /// The response has no headers, its initial URL is the same as its URL,
+    /// and [`host_changed()`][ResponseParts::host_changed] is `false`; use the
+    /// `with_*()` methods to change these.
+    ///
+    /// This is mainly useful for testing [`ResponseParser`] implementations
+    /// without a client.
+    ///
+    /// [`ResponseParser`]: crate::parser::ResponseParser
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ghreq::{
+    ///     Method,
+    ///     parser::{JsonResponse, ResponseParserExt},
+    ///     response::{Response, ResponseParts},
+    /// };
+    /// use http::StatusCode;
+    ///
+    /// let parts = ResponseParts::new(
+    ///     "https://api.github.com/octocat".parse().unwrap(),
+    ///     Method::Get,
+    ///     StatusCode::OK,
+    /// );
+    /// let response = Response::from_parts(parts, &br#"{"id": 1}"#[..]);
+    /// let value = JsonResponse::<serde_json::Value>::new()
+    ///     .parse_response(response)
+    ///     .unwrap();
+    /// assert_eq!(value["id"], 1);
+    /// ```
+    pub fn new(url: HttpUrl, method: Method, status: http::status::StatusCode) -> ResponseParts {
+        ResponseParts {
+            initial_url: url.clone(),
+            url,
+            method,
+            status,
+            headers: http::header::HeaderMap::new(),
+            host_changed: false,
+            tls: None,
+        }
+    }
+
+    /// Set the URL of the request that initially produced the response, before
+    /// any redirects were followed
+    pub fn with_initial_url(mut self, url: HttpUrl) -> Self {
+        self.initial_url = url;
+        self
+    }
+
+    /// Set the response's headers
+    pub fn with_headers(mut self, headers: http::header::HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Add a header to the response, replacing any previous values for the
+    /// same name
+    pub fn with_header(
+        mut self,
+        name: http::header::HeaderName,
+        value: http::header::HeaderValue,
+    ) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Set the value returned by [`ResponseParts::host_changed()`]
+    pub fn with_host_changed(mut self, host_changed: bool) -> Self {
+        self.host_changed = host_changed;
+        self
+    }
+
+    /// Set the value returned by [`ResponseParts::tls_info()`]
+    pub fn with_tls_info(mut self, tls: TlsInfo) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
     pub fn initial_url(&self) -> &HttpUrl {
         &self.initial_url
     }
@@ -19,7 +103,7 @@ impl ResponseParts {
     }
 
     pub fn method(&self) -> Method {
-        self.method
+        self.method.clone()
     }
 
     pub fn status(&self) -> http::status::StatusCode {
@@ -29,6 +113,102 @@ impl ResponseParts {
     pub fn headers(&self) -> &http::header::HeaderMap {
         &self.headers
     }
+
+    /// Returns `true` if the response came from a different origin (scheme,
+    /// host, or port) than the one the client sent the request to, i.e., if
+    /// the backend transparently followed a redirect to another origin or the
+    /// request was otherwise diverted, e.g., by a proxy or a misconfigured
+    /// load balancer.
+    ///
+    /// Redirects followed by the client itself (see
+    /// [`RedirectPolicy`][crate::client::RedirectPolicy]) do not count, as the
+    /// client sends a new request for each one.
+    pub fn host_changed(&self) -> bool {
+        self.host_changed
+    }
+
+    /// Returns details of the TLS connection over which the response was
+    /// received, if the backend reports them; see [`TlsInfo`]
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.tls.as_ref()
+    }
+}
+
+/// Details of the TLS connection over which a response was received
+///
+/// Backends report these via
+/// [`BackendResponse::tls_info()`][crate::client::BackendResponse::tls_info]
+/// (or the asynchronous equivalent), and the client makes them available as
+/// [`ResponseParts::tls_info()`], so that policies such as rejecting
+/// connections below TLS 1.2 can be enforced the same way for any backend.
+/// Any detail that a backend cannot determine is `None`.
+///
+/// [`FnBackend`][crate::client::FnBackend] reports the `TlsInfo` stored in the
+/// extensions of the `http::Response` returned by its closure, if any.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TlsInfo {
+    version: Option<TlsVersion>,
+    peer_cert_not_before: Option<Timestamp>,
+    peer_cert_not_after: Option<Timestamp>,
+}
+
+impl TlsInfo {
+    /// Create a `TlsInfo` with all details unknown
+    pub fn new() -> TlsInfo {
+        TlsInfo::default()
+    }
+
+    /// Set the negotiated protocol version
+    pub fn with_version(mut self, version: TlsVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Set the validity window of the peer's certificate
+    pub fn with_peer_cert_validity(mut self, not_before: Timestamp, not_after: Timestamp) -> Self {
+        self.peer_cert_not_before = Some(not_before);
+        self.peer_cert_not_after = Some(not_after);
+        self
+    }
+
+    /// Returns the negotiated protocol version
+    pub fn version(&self) -> Option<TlsVersion> {
+        self.version
+    }
+
+    /// Returns the start of the validity window of the peer's certificate
+    pub fn peer_cert_not_before(&self) -> Option<Timestamp> {
+        self.peer_cert_not_before
+    }
+
+    /// Returns the end of the validity window of the peer's certificate
+    pub fn peer_cert_not_after(&self) -> Option<Timestamp> {
+        self.peer_cert_not_after
+    }
+}
+
+/// A version of the TLS protocol
+///
+/// Versions are ordered chronologically, so a minimum version can be enforced
+/// with a comparison.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TlsVersion {
+    Tls1_0,
+    Tls1_1,
+    Tls1_2,
+    Tls1_3,
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TlsVersion::Tls1_0 => "TLSv1.0",
+            TlsVersion::Tls1_1 => "TLSv1.1",
+            TlsVersion::Tls1_2 => "TLSv1.2",
+            TlsVersion::Tls1_3 => "TLSv1.3",
+        };
+        f.write_str(s)
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -58,6 +238,14 @@ impl<T> Response<T> {
         self.parts.headers()
     }
 
+    pub fn host_changed(&self) -> bool {
+        self.parts.host_changed()
+    }
+
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.parts.tls_info()
+    }
+
     pub fn body_ref(&self) -> &T {
         &self.body
     }
@@ -78,3 +266,38 @@ impl<T> Response<T> {
         Response { parts, body }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::{
+        StatusCode,
+        header::{CONTENT_TYPE, HeaderValue},
+    };
+
+    #[test]
+    fn response_parts_builder() {
+        let url = "https://api.github.com/repos/octocat/hello-world"
+            .parse::<HttpUrl>()
+            .unwrap();
+        let parts = ResponseParts::new(url.clone(), Method::Get, StatusCode::NOT_FOUND);
+        assert_eq!(parts.initial_url(), &url);
+        assert_eq!(parts.url(), &url);
+        assert_eq!(parts.method(), Method::Get);
+        assert_eq!(parts.status(), StatusCode::NOT_FOUND);
+        assert!(parts.headers().is_empty());
+        assert!(!parts.host_changed());
+
+        let initial = "https://api.github.com/repos/octocat/old-name"
+            .parse::<HttpUrl>()
+            .unwrap();
+        let parts = parts
+            .with_initial_url(initial.clone())
+            .with_header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .with_host_changed(true);
+        assert_eq!(parts.initial_url(), &initial);
+        assert_eq!(parts.url(), &url);
+        assert_eq!(parts.headers()[CONTENT_TYPE], "application/json");
+        assert!(parts.host_changed());
+    }
+}
@@ -0,0 +1,971 @@
+use crate::{
+    HttpUrl, Method,
+    client::{Backend, BackendResponse, RequestParts},
+};
+use http::header::{HeaderMap, HeaderValue};
+use serde_json::{Map, Value, json};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tokio")]
+use crate::client::tokio::{AsyncBackend, AsyncBackendResponse};
+#[cfg(feature = "tokio")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Size of the request & response bodies used by the streaming checks
+const STREAM_BODY_SIZE: usize = 1 << 20;
+
+/// Maximum number of bytes produced by each read of a streamed request body
+const STREAM_READ_SIZE: usize = 1000;
+
+/// Size of each chunk of a chunked response body sent by [`FakeServer`]
+const RESPONSE_CHUNK_SIZE: usize = 16384;
+
+/// Timeout used by the timeout check
+const TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How long the server waits before responding in the timeout check
+const DELAY: Duration = Duration::from_secs(3);
+
+/// Value of the `X-Fake-Server` header included in every [`FakeServer`]
+/// response
+const SERVER_HEADER: &str = "ghreq";
+
+/// Statuses requested by the error status check
+const ERROR_STATUSES: [u16; 4] = [404, 422, 500, 503];
+
+/// A fake HTTP server for testing [`Backend`] and
+/// [`AsyncBackend`][crate::client::tokio::AsyncBackend] implementations
+///
+/// A `FakeServer` listens for HTTP/1.1 connections on a random port on the
+/// loopback interface, serving each connection on a separate thread, and it
+/// stops listening when dropped.  Each connection is closed after a single
+/// response.  Request bodies may be sent either with a `Content-Length` header
+/// or with chunked transfer encoding.
+///
+/// The server responds to the following paths:
+///
+/// - `/echo` — Responds with a JSON object describing the request, containing
+///   the fields `method`, `path`, `query` (the raw query string, or `null`),
+///   `headers` (an object mapping lowercased header names to their values,
+///   with repeated headers joined by `", "`), `body_len`, and `body_checksum`
+///   (the 64-bit FNV-1a hash of the request body)
+///
+/// - `/status/{code}` — Responds with the given status code and a JSON body of
+///   the form `{"message": "Status {code}"}`
+///
+/// - `/bytes/{n}` — Responds with `n` bytes in which the byte at offset `i` is
+///   `i % 251`, sent with chunked transfer encoding
+///
+/// - `/delay/{ms}` — Waits for the given number of milliseconds and then
+///   responds with an empty JSON object
+///
+/// - `/redirect` — Responds with a `302 Found` redirect to `/echo`
+///
+/// Any other path results in a 404 response.  Every response includes an
+/// `X-Fake-Server: ghreq` header.
+///
+/// The server is used by [`check_backend()`] and
+/// [`check_async_backend()`][crate::testing::check_async_backend], but it can
+/// also be used directly to write further tests for a backend.
+#[derive(Debug)]
+pub struct FakeServer {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl FakeServer {
+    /// Start a new server listening on a random local port
+    pub fn start() -> io::Result<FakeServer> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&shutdown);
+        let handle = thread::spawn(move || {
+            for conn in listener.incoming() {
+                if flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = conn {
+                    thread::spawn(move || {
+                        // Errors here only mean that the client went away
+                        let _ = serve(stream, addr);
+                    });
+                }
+            }
+        });
+        Ok(FakeServer {
+            addr,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Return the address on which the server is listening
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Return the URL of the root of the server
+    pub fn base_url(&self) -> HttpUrl {
+        server_url(self.addr, "/")
+    }
+
+    /// Return the URL of the given path on the server
+    pub fn url(&self, path: &str) -> HttpUrl {
+        server_url(self.addr, path)
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake up the listener thread so that it sees the shutdown flag
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn server_url(addr: SocketAddr, path: &str) -> HttpUrl {
+    let Ok(url) = format!("http://{addr}/{}", path.trim_start_matches('/')).parse::<HttpUrl>()
+    else {
+        unreachable!("server URL should be a valid HTTP URL");
+    };
+    url
+}
+
+/// A request received by [`FakeServer`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct FakeRequest {
+    method: String,
+    path: String,
+    query: Option<String>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl FakeRequest {
+    fn read<R: BufRead>(mut reader: R) -> io::Result<FakeRequest> {
+        let line = read_line(&mut reader)?;
+        let mut words = line.split_ascii_whitespace();
+        let (Some(method), Some(target), Some(_)) = (words.next(), words.next(), words.next())
+        else {
+            return Err(invalid_data("malformed request line"));
+        };
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_owned(), Some(query.to_owned())),
+            None => (target.to_owned(), None),
+        };
+        let mut headers = Vec::new();
+        loop {
+            let line = read_line(&mut reader)?;
+            if line.is_empty() {
+                break;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(invalid_data("malformed header line"));
+            };
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+        let mut req = FakeRequest {
+            method: method.to_owned(),
+            path,
+            query,
+            headers,
+            body: Vec::new(),
+        };
+        if req
+            .header("transfer-encoding")
+            .is_some_and(|te| te.to_ascii_lowercase().contains("chunked"))
+        {
+            req.body = read_chunked(&mut reader)?;
+        } else if let Some(len) = req.header("content-length") {
+            let len = len
+                .parse::<usize>()
+                .map_err(|_| invalid_data("invalid Content-Length"))?;
+            req.body = vec![0; len];
+            reader.read_exact(&mut req.body)?;
+        }
+        Ok(req)
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn echo(&self) -> Value {
+        let mut headers = Map::new();
+        for (name, value) in &self.headers {
+            match headers.get_mut(name) {
+                Some(Value::String(s)) => {
+                    s.push_str(", ");
+                    s.push_str(value);
+                }
+                _ => {
+                    headers.insert(name.clone(), Value::String(value.clone()));
+                }
+            }
+        }
+        json!({
+            "method": self.method,
+            "path": self.path,
+            "query": self.query,
+            "headers": headers,
+            "body_len": self.body.len(),
+            "body_checksum": checksum(&self.body),
+        })
+    }
+}
+
+/// A response sent by [`FakeServer`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct FakeResponse {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: FakeBody,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum FakeBody {
+    /// A body sent with a `Content-Length` header
+    Full(Vec<u8>),
+
+    /// A body of the given number of pattern bytes sent with chunked transfer
+    /// encoding
+    Chunked(usize),
+}
+
+impl FakeResponse {
+    fn json(status: u16, value: &Value) -> FakeResponse {
+        FakeResponse {
+            status,
+            headers: vec![("Content-Type", String::from("application/json"))],
+            body: FakeBody::Full(value.to_string().into_bytes()),
+        }
+    }
+
+    fn write<W: Write>(&self, mut writer: W, head: bool) -> io::Result<()> {
+        let reason = http::StatusCode::from_u16(self.status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("Unknown");
+        let mut out = Vec::new();
+        write!(out, "HTTP/1.1 {} {reason}\r\n", self.status)?;
+        for (name, value) in &self.headers {
+            write!(out, "{name}: {value}\r\n")?;
+        }
+        write!(
+            out,
+            "X-Fake-Server: {SERVER_HEADER}\r\nConnection: close\r\n"
+        )?;
+        match &self.body {
+            FakeBody::Full(body) => {
+                write!(out, "Content-Length: {}\r\n\r\n", body.len())?;
+                writer.write_all(&out)?;
+                if !head {
+                    writer.write_all(body)?;
+                }
+            }
+            FakeBody::Chunked(size) => {
+                out.extend_from_slice(b"Transfer-Encoding: chunked\r\n\r\n");
+                writer.write_all(&out)?;
+                if !head {
+                    let body = pattern(*size);
+                    for chunk in body.chunks(RESPONSE_CHUNK_SIZE) {
+                        write!(writer, "{:x}\r\n", chunk.len())?;
+                        writer.write_all(chunk)?;
+                        writer.write_all(b"\r\n")?;
+                    }
+                    writer.write_all(b"0\r\n\r\n")?;
+                }
+            }
+        }
+        writer.flush()
+    }
+}
+
+fn serve(stream: TcpStream, addr: SocketAddr) -> io::Result<()> {
+    let req = FakeRequest::read(BufReader::new(&stream))?;
+    let resp = route(&req, addr);
+    resp.write(&stream, req.method.eq_ignore_ascii_case("HEAD"))?;
+    stream.shutdown(std::net::Shutdown::Write)
+}
+
+fn route(req: &FakeRequest, addr: SocketAddr) -> FakeResponse {
+    let mut segments = req.path.trim_start_matches('/').split('/');
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some("echo"), None, None) => FakeResponse::json(200, &req.echo()),
+        (Some("status"), Some(code), None) => match code.parse::<u16>() {
+            Ok(code) if (200..=599).contains(&code) => {
+                FakeResponse::json(code, &json!({"message": format!("Status {code}")}))
+            }
+            _ => FakeResponse::json(400, &json!({"message": "Invalid status"})),
+        },
+        (Some("bytes"), Some(n), None) => match n.parse::<usize>() {
+            Ok(n) => FakeResponse {
+                status: 200,
+                headers: vec![("Content-Type", String::from("application/octet-stream"))],
+                body: FakeBody::Chunked(n),
+            },
+            Err(_) => FakeResponse::json(400, &json!({"message": "Invalid size"})),
+        },
+        (Some("delay"), Some(ms), None) => match ms.parse::<u64>() {
+            Ok(ms) => {
+                thread::sleep(Duration::from_millis(ms));
+                FakeResponse::json(200, &json!({}))
+            }
+            Err(_) => FakeResponse::json(400, &json!({"message": "Invalid delay"})),
+        },
+        (Some("redirect"), None, None) => FakeResponse {
+            status: 302,
+            headers: vec![("Location", server_url(addr, "/echo").to_string())],
+            body: FakeBody::Full(Vec::new()),
+        },
+        _ => FakeResponse::json(404, &json!({"message": "Not Found"})),
+    }
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+fn read_chunked<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size =
+            usize::from_str_radix(size, 16).map_err(|_| invalid_data("invalid chunk size"))?;
+        if size == 0 {
+            // Skip any trailers
+            while !read_line(reader)?.is_empty() {}
+            return Ok(body);
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        if !read_line(reader)?.is_empty() {
+            return Err(invalid_data("missing CRLF after chunk"));
+        }
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Return the byte at offset `i` in a body produced by [`pattern()`]
+fn pattern_byte(i: usize) -> u8 {
+    // `i % 251` always fits in a `u8`
+    #[allow(clippy::cast_possible_truncation)]
+    let b = (i % 251) as u8;
+    b
+}
+
+fn pattern(size: usize) -> Vec<u8> {
+    (0..size).map(pattern_byte).collect()
+}
+
+/// Compute the 64-bit FNV-1a hash of `data`
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A request body that produces [`pattern()`] bytes in small pieces in order
+/// to exercise streaming of request bodies
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PatternReader {
+    offset: usize,
+    size: usize,
+}
+
+impl PatternReader {
+    fn new(size: usize) -> PatternReader {
+        PatternReader { offset: 0, size }
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(STREAM_READ_SIZE).min(self.size - self.offset);
+        for (i, b) in buf[..n].iter_mut().enumerate() {
+            *b = pattern_byte(self.offset + i);
+        }
+        self.offset += n;
+        n
+    }
+}
+
+impl Read for PatternReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.fill(buf))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for PatternReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let n = self.fill(buf.initialize_unfilled());
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A request made by one of the conformance checks
+#[derive(Clone, Debug)]
+struct CheckRequest {
+    check: &'static str,
+    parts: RequestParts,
+}
+
+impl CheckRequest {
+    fn new(check: &'static str, server: &FakeServer, method: Method, path: &str) -> CheckRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ghreq-check", HeaderValue::from_static(check));
+        CheckRequest {
+            check,
+            parts: RequestParts {
+                url: server.url(path),
+                method,
+                headers,
+                timeout: None,
+            },
+        }
+    }
+
+    fn fail(&self, msg: impl fmt::Display) -> ! {
+        panic!(
+            "backend conformance check {:?} failed for {} {}: {msg}",
+            self.check, self.parts.method, self.parts.url
+        )
+    }
+
+    fn expect<T, E: fmt::Debug>(&self, r: Result<T, E>, what: &str) -> T {
+        r.unwrap_or_else(|e| self.fail(format_args!("{what}: {e:?}")))
+    }
+
+    fn ensure(&self, cond: bool, msg: impl fmt::Display) {
+        if !cond {
+            self.fail(msg);
+        }
+    }
+
+    fn parse_json(&self, body: &[u8]) -> Value {
+        self.expect(serde_json::from_slice(body), "response body is not JSON")
+    }
+
+    /// Check the status, URL, and headers of a response to a request for
+    /// `/echo` and its decoded body
+    fn check_echo(
+        &self,
+        status: http::StatusCode,
+        url: &HttpUrl,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) {
+        self.ensure(
+            status == 200,
+            format_args!("expected status 200, got {status}"),
+        );
+        self.ensure(
+            url == &self.parts.url,
+            format_args!("expected response URL {}, got {url}", self.parts.url),
+        );
+        self.ensure(
+            headers
+                .get("x-fake-server")
+                .is_some_and(|v| v == SERVER_HEADER),
+            "response is missing the X-Fake-Server header",
+        );
+        let echo = self.parse_json(body);
+        self.ensure(
+            echo["method"] == self.parts.method.as_str(),
+            format_args!("server received method {}", echo["method"]),
+        );
+        for (name, value) in &self.parts.headers {
+            let sent = value.to_str().unwrap_or_default();
+            self.ensure(
+                echo["headers"][name.as_str()] == sent,
+                format_args!(
+                    "server received {name} header {} instead of {sent:?}",
+                    echo["headers"][name.as_str()]
+                ),
+            );
+        }
+    }
+}
+
+/// Exercise a [`Backend`] implementation against a [`FakeServer`] in order to
+/// verify that it behaves the way that `ghreq` expects
+///
+/// The following checks are performed:
+///
+/// - Request headers (including `User-Agent` and `Accept`) are sent as given,
+///   and response headers are reported.
+///
+/// - All [`Method`] variants (including [`Method::Other`]) are sent as given,
+///   and the response body of a HEAD request is empty.
+///
+/// - A large request body read in small pieces is sent in full.
+///
+/// - A large response body sent with chunked transfer encoding is read in
+///   full.
+///
+/// - 4xx and 5xx responses are returned as successful responses with the
+///   correct status and body rather than as errors.
+///
+/// - Redirects are either followed (in which case the response URL must be
+///   the URL of the final response) or returned as 3xx responses with a
+///   `Location` header.
+///
+/// - [`RequestParts::timeout`] is honored.
+///
+/// # Panics
+///
+/// Panics if the server cannot be started or if any check fails.  The panic
+/// message identifies the failing check.
+///
+/// # Example
+///
+/// ```no_run
+/// # struct MyBackend;
+/// # impl ghreq::client::Backend for MyBackend {
+/// #     type Request = ();
+/// #     type Response = ghreq::client::FnResponse;
+/// #     type Error = std::io::Error;
+/// #     fn prepare_request(&self, _: ghreq::client::RequestParts) -> std::io::Result<()> {
+/// #         Ok(())
+/// #     }
+/// #     fn send<R: std::io::Read>(&self, _: (), _: R) -> std::io::Result<Self::Response> {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// #[test]
+/// fn conformance() {
+///     ghreq::testing::check_backend(&MyBackend);
+/// }
+/// ```
+pub fn check_backend<B>(backend: &B)
+where
+    B: Backend,
+    B::Error: fmt::Debug,
+{
+    let server = FakeServer::start().expect("failed to start fake server");
+    let send = |req: &CheckRequest, body: &mut dyn Read| {
+        let prepped = backend.prepare_request(req.parts.clone())?;
+        backend.send(prepped, body)
+    };
+    let read_body = |req: &CheckRequest, resp: B::Response| {
+        let mut buf = Vec::new();
+        req.expect(
+            resp.body_reader().read_to_end(&mut buf),
+            "failed to read response body",
+        );
+        buf
+    };
+    let fetch = |req: &CheckRequest, body: &mut dyn Read| {
+        let resp = req.expect(send(req, body), "request failed");
+        let status = resp.status();
+        let url = resp.url();
+        let headers = resp.headers();
+        (status, url, headers, read_body(req, resp))
+    };
+
+    // Headers
+    let mut req = CheckRequest::new("headers", &server, Method::Get, "/echo");
+    req.parts.headers.insert(
+        http::header::USER_AGENT,
+        HeaderValue::from_static("ghreq-conformance"),
+    );
+    req.parts.headers.insert(
+        http::header::ACCEPT,
+        HeaderValue::from_static("application/json"),
+    );
+    let (status, url, headers, body) = fetch(&req, &mut io::empty());
+    req.check_echo(status, &url, &headers, &body);
+    req.ensure(
+        headers
+            .get(http::header::CONTENT_TYPE)
+            .is_some_and(|v| v == "application/json"),
+        "response is missing the Content-Type header",
+    );
+
+    // Methods
+    for method in check_methods() {
+        let req = CheckRequest::new("methods", &server, method, "/echo");
+        let (status, url, headers, body) = fetch(&req, &mut io::empty());
+        req.check_echo(status, &url, &headers, &body);
+    }
+    let req = CheckRequest::new("methods", &server, Method::Head, "/echo");
+    let (status, _, _, body) = fetch(&req, &mut io::empty());
+    req.ensure(
+        status == 200,
+        format_args!("expected status 200, got {status}"),
+    );
+    req.ensure(body.is_empty(), "HEAD response has a body");
+
+    // Request body
+    let req = CheckRequest::new("request body", &server, Method::Post, "/echo");
+    let (status, url, headers, body) = fetch(&req, &mut PatternReader::new(STREAM_BODY_SIZE));
+    req.check_echo(status, &url, &headers, &body);
+    req.check_request_body(&body);
+
+    // Response body
+    let req = CheckRequest::new(
+        "response body",
+        &server,
+        Method::Get,
+        &format!("/bytes/{STREAM_BODY_SIZE}"),
+    );
+    let (status, _, _, body) = fetch(&req, &mut io::empty());
+    req.ensure(
+        status == 200,
+        format_args!("expected status 200, got {status}"),
+    );
+    req.check_response_body(&body);
+
+    // Error statuses
+    for code in ERROR_STATUSES {
+        let req = CheckRequest::new(
+            "error status",
+            &server,
+            Method::Get,
+            &format!("/status/{code}"),
+        );
+        let (status, _, _, body) = fetch(&req, &mut io::empty());
+        req.check_error_status(code, status, &body);
+    }
+
+    // Redirects
+    let req = CheckRequest::new("redirect", &server, Method::Get, "/redirect");
+    let (status, url, headers, _) = fetch(&req, &mut io::empty());
+    req.check_redirect(&server, status, &url, &headers);
+
+    // Timeout
+    let mut req = CheckRequest::new(
+        "timeout",
+        &server,
+        Method::Get,
+        &format!("/delay/{}", DELAY.as_millis()),
+    );
+    req.parts.timeout = Some(TIMEOUT);
+    let start = Instant::now();
+    let timed_out = match send(&req, &mut io::empty()) {
+        Ok(resp) => resp.body_reader().read_to_end(&mut Vec::new()).is_err(),
+        Err(_) => true,
+    };
+    req.check_timeout(timed_out, start.elapsed());
+}
+
+/// Asynchronous counterpart of [`check_backend()`] for [`AsyncBackend`]
+/// implementations
+///
+/// The same checks are performed as by [`check_backend()`].
+///
+/// # Panics
+///
+/// Panics if the server cannot be started or if any check fails.  The panic
+/// message identifies the failing check.
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub async fn check_async_backend<B>(backend: &B)
+where
+    B: AsyncBackend + Sync,
+    B::Error: fmt::Debug,
+{
+    use tokio::io::AsyncReadExt;
+
+    async fn fetch<B>(
+        backend: &B,
+        req: &CheckRequest,
+        body: PatternReader,
+    ) -> (http::StatusCode, HttpUrl, HeaderMap, Vec<u8>)
+    where
+        B: AsyncBackend + Sync,
+        B::Error: fmt::Debug,
+    {
+        let prepped = req.expect(
+            backend.prepare_request(req.parts.clone()),
+            "failed to prepare request",
+        );
+        let resp = req.expect(backend.send(prepped, body).await, "request failed");
+        let status = resp.status();
+        let url = resp.url();
+        let headers = resp.headers();
+        let mut buf = Vec::new();
+        req.expect(
+            Box::pin(resp.body_reader()).read_to_end(&mut buf).await,
+            "failed to read response body",
+        );
+        (status, url, headers, buf)
+    }
+
+    let server = FakeServer::start().expect("failed to start fake server");
+    let empty = || PatternReader::new(0);
+
+    // Headers
+    let mut req = CheckRequest::new("headers", &server, Method::Get, "/echo");
+    req.parts.headers.insert(
+        http::header::USER_AGENT,
+        HeaderValue::from_static("ghreq-conformance"),
+    );
+    req.parts.headers.insert(
+        http::header::ACCEPT,
+        HeaderValue::from_static("application/json"),
+    );
+    let (status, url, headers, body) = fetch(backend, &req, empty()).await;
+    req.check_echo(status, &url, &headers, &body);
+    req.ensure(
+        headers
+            .get(http::header::CONTENT_TYPE)
+            .is_some_and(|v| v == "application/json"),
+        "response is missing the Content-Type header",
+    );
+
+    // Methods
+    for method in check_methods() {
+        let req = CheckRequest::new("methods", &server, method, "/echo");
+        let (status, url, headers, body) = fetch(backend, &req, empty()).await;
+        req.check_echo(status, &url, &headers, &body);
+    }
+    let req = CheckRequest::new("methods", &server, Method::Head, "/echo");
+    let (status, _, _, body) = fetch(backend, &req, empty()).await;
+    req.ensure(
+        status == 200,
+        format_args!("expected status 200, got {status}"),
+    );
+    req.ensure(body.is_empty(), "HEAD response has a body");
+
+    // Request body
+    let req = CheckRequest::new("request body", &server, Method::Post, "/echo");
+    let (status, url, headers, body) =
+        fetch(backend, &req, PatternReader::new(STREAM_BODY_SIZE)).await;
+    req.check_echo(status, &url, &headers, &body);
+    req.check_request_body(&body);
+
+    // Response body
+    let req = CheckRequest::new(
+        "response body",
+        &server,
+        Method::Get,
+        &format!("/bytes/{STREAM_BODY_SIZE}"),
+    );
+    let (status, _, _, body) = fetch(backend, &req, empty()).await;
+    req.ensure(
+        status == 200,
+        format_args!("expected status 200, got {status}"),
+    );
+    req.check_response_body(&body);
+
+    // Error statuses
+    for code in ERROR_STATUSES {
+        let req = CheckRequest::new(
+            "error status",
+            &server,
+            Method::Get,
+            &format!("/status/{code}"),
+        );
+        let (status, _, _, body) = fetch(backend, &req, empty()).await;
+        req.check_error_status(code, status, &body);
+    }
+
+    // Redirects
+    let req = CheckRequest::new("redirect", &server, Method::Get, "/redirect");
+    let (status, url, headers, _) = fetch(backend, &req, empty()).await;
+    req.check_redirect(&server, status, &url, &headers);
+
+    // Timeout
+    let mut req = CheckRequest::new(
+        "timeout",
+        &server,
+        Method::Get,
+        &format!("/delay/{}", DELAY.as_millis()),
+    );
+    req.parts.timeout = Some(TIMEOUT);
+    let start = Instant::now();
+    let prepped = req.expect(
+        backend.prepare_request(req.parts.clone()),
+        "failed to prepare request",
+    );
+    let timed_out = match backend.send(prepped, empty()).await.ok() {
+        Some(resp) => Box::pin(resp.body_reader())
+            .read_to_end(&mut Vec::new())
+            .await
+            .is_err(),
+        None => true,
+    };
+    req.check_timeout(timed_out, start.elapsed());
+}
+
+/// Return the methods other than HEAD exercised by the method check
+fn check_methods() -> Vec<Method> {
+    vec![
+        Method::Get,
+        Method::Post,
+        Method::Put,
+        Method::Patch,
+        Method::Delete,
+        Method::Options,
+        Method::Other(http::Method::from_bytes(b"PROPFIND").expect("method name should be valid")),
+    ]
+}
+
+impl CheckRequest {
+    fn check_request_body(&self, echo_body: &[u8]) {
+        let echo = self.parse_json(echo_body);
+        self.ensure(
+            echo["body_len"] == STREAM_BODY_SIZE,
+            format_args!(
+                "server received {} bytes of body instead of {STREAM_BODY_SIZE}",
+                echo["body_len"]
+            ),
+        );
+        self.ensure(
+            echo["body_checksum"] == checksum(&pattern(STREAM_BODY_SIZE)),
+            "server received corrupted request body",
+        );
+    }
+
+    fn check_response_body(&self, body: &[u8]) {
+        self.ensure(
+            body.len() == STREAM_BODY_SIZE,
+            format_args!(
+                "read {} bytes of response body instead of {STREAM_BODY_SIZE}",
+                body.len()
+            ),
+        );
+        self.ensure(
+            body == pattern(STREAM_BODY_SIZE),
+            "response body was corrupted",
+        );
+    }
+
+    fn check_error_status(&self, code: u16, status: http::StatusCode, body: &[u8]) {
+        self.ensure(
+            status == code,
+            format_args!("expected status {code}, got {status}"),
+        );
+        let message = format!("Status {code}");
+        self.ensure(
+            self.parse_json(body)["message"] == message,
+            "error response body was not returned",
+        );
+    }
+
+    fn check_redirect(
+        &self,
+        server: &FakeServer,
+        status: http::StatusCode,
+        url: &HttpUrl,
+        headers: &HeaderMap,
+    ) {
+        if status.is_redirection() {
+            self.ensure(
+                headers.contains_key(http::header::LOCATION),
+                "redirect response is missing the Location header",
+            );
+        } else {
+            self.ensure(
+                status == 200,
+                format_args!("expected status 200 or a redirect, got {status}"),
+            );
+            let target = server.url("/echo");
+            self.ensure(
+                url == &target,
+                format_args!("expected final response URL {target}, got {url}"),
+            );
+        }
+    }
+
+    fn check_timeout(&self, timed_out: bool, elapsed: Duration) {
+        self.ensure(timed_out, "request succeeded despite exceeding the timeout");
+        self.ensure(
+            elapsed < DELAY,
+            format_args!("request took {elapsed:?} to time out instead of about {TIMEOUT:?}"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_request(server: &FakeServer, request: &[u8]) -> String {
+        let mut conn = TcpStream::connect(server.addr()).unwrap();
+        conn.write_all(request).unwrap();
+        let mut response = String::new();
+        conn.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn echo_chunked_body() {
+        let server = FakeServer::start().unwrap();
+        let response = raw_request(
+            &server,
+            b"POST /echo?x=1 HTTP/1.1\r\nHost: localhost\r\nX-Foo: bar\r\nX-Foo: baz\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2;ext=1\r\nde\r\n0\r\n\r\n",
+        );
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("\r\nX-Fake-Server: ghreq\r\n"));
+        let echo = serde_json::from_str::<Value>(body).unwrap();
+        assert_eq!(echo["method"], "POST");
+        assert_eq!(echo["path"], "/echo");
+        assert_eq!(echo["query"], "x=1");
+        assert_eq!(echo["headers"]["x-foo"], "bar, baz");
+        assert_eq!(echo["body_len"], 5);
+        assert_eq!(echo["body_checksum"], checksum(b"abcde"));
+    }
+
+    #[test]
+    fn unknown_path() {
+        let server = FakeServer::start().unwrap();
+        let response = raw_request(&server, b"GET /nowhere HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn pattern_reader() {
+        let mut buf = Vec::new();
+        PatternReader::new(2000).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, pattern(2000));
+        assert_eq!(buf[251], 0);
+        assert_eq!(buf[252], 1);
+    }
+
+    #[cfg(feature = "ureq")]
+    #[test]
+    fn ureq_conformance() {
+        check_backend(&ureq::Agent::new_with_defaults());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn reqwest_conformance() {
+        check_async_backend(&reqwest::Client::new()).await;
+    }
+}
@@ -0,0 +1,572 @@
+use crate::response::Response;
+use http::{
+    header::{HeaderName, HeaderValue},
+    status::StatusCode,
+};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+/// A comparison of recorded and live responses, for contract tests that check
+/// whether GitHub's responses still match previously-recorded fixtures
+///
+/// A `ResponseDiffer` compares the status codes, headers, and JSON bodies of
+/// two responses and reports every difference found, skipping any headers and
+/// body fields that have been configured as volatile.  Use
+/// [`ResponseDiffer::github_defaults()`] to start with a set of fields and
+/// headers that change between otherwise-identical GitHub responses.
+///
+/// # Example
+///
+/// ```
+/// use ghreq::{
+///     Method,
+///     response::{Response, ResponseParts},
+///     testing::{Difference, ResponseDiffer},
+/// };
+/// use http::StatusCode;
+/// use serde_json::json;
+///
+/// let parts = ResponseParts::new(
+///     "https://api.github.com/repos/octocat/hello-world".parse().unwrap(),
+///     Method::Get,
+///     StatusCode::OK,
+/// );
+/// let recorded = Response::from_parts(
+///     parts.clone(),
+///     json!({"name": "hello-world", "updated_at": "2011-01-26T19:14:43Z"}),
+/// );
+/// let live = Response::from_parts(
+///     parts,
+///     json!({"name": "Hello-World", "updated_at": "2024-05-01T08:00:00Z"}),
+/// );
+/// let diff = ResponseDiffer::new()
+///     .with_ignored_field("updated_at")
+///     .compare(&recorded, &live);
+/// assert_eq!(
+///     diff.differences(),
+///     [Difference::Changed {
+///         pointer: "/name".into(),
+///         recorded: json!("hello-world"),
+///         live: json!("Hello-World"),
+///     }]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResponseDiffer {
+    ignored_fields: BTreeSet<String>,
+    ignored_pointers: BTreeSet<String>,
+    ignored_headers: HashSet<HeaderName>,
+    compare_headers: bool,
+    structural: bool,
+}
+
+impl ResponseDiffer {
+    /// Create a `ResponseDiffer` that compares status codes, all headers, and
+    /// the complete bodies of responses
+    pub fn new() -> ResponseDiffer {
+        ResponseDiffer {
+            compare_headers: true,
+            ..ResponseDiffer::default()
+        }
+    }
+
+    /// Create a `ResponseDiffer` that ignores the headers and body fields
+    /// whose values routinely change between GitHub responses: the `Date`,
+    /// `ETag`, `Last-Modified`, `X-GitHub-Request-Id`, and `X-RateLimit-*`
+    /// headers and the `created_at`, `updated_at`, and `pushed_at` fields
+    pub fn github_defaults() -> ResponseDiffer {
+        let mut differ = ResponseDiffer::new();
+        for name in ["created_at", "updated_at", "pushed_at"] {
+            differ = differ.with_ignored_field(name);
+        }
+        for name in [
+            http::header::DATE,
+            http::header::ETAG,
+            http::header::LAST_MODIFIED,
+            HeaderName::from_static("x-github-request-id"),
+            HeaderName::from_static("x-ratelimit-limit"),
+            HeaderName::from_static("x-ratelimit-remaining"),
+            HeaderName::from_static("x-ratelimit-reset"),
+            HeaderName::from_static("x-ratelimit-resource"),
+            HeaderName::from_static("x-ratelimit-used"),
+        ] {
+            differ = differ.with_ignored_header(name);
+        }
+        differ
+    }
+
+    /// Ignore all object fields with the given name, at any depth of the body
+    pub fn with_ignored_field<S: Into<String>>(mut self, name: S) -> Self {
+        self.ignored_fields.insert(name.into());
+        self
+    }
+
+    /// Ignore the value at the given [JSON Pointer][rfc6901] (e.g.,
+    /// `"/owner/id"`) in the body, along with everything beneath it
+    ///
+    /// [rfc6901]: https://www.rfc-editor.org/rfc/rfc6901
+    pub fn with_ignored_pointer<S: Into<String>>(mut self, pointer: S) -> Self {
+        self.ignored_pointers.insert(pointer.into());
+        self
+    }
+
+    /// Ignore the header with the given name
+    pub fn with_ignored_header(mut self, name: HeaderName) -> Self {
+        self.ignored_headers.insert(name);
+        self
+    }
+
+    /// Set whether to compare headers at all.  The default is `true`.
+    pub fn with_compare_headers(mut self, compare_headers: bool) -> Self {
+        self.compare_headers = compare_headers;
+        self
+    }
+
+    /// Set whether to only compare the structure of the bodies.  The default
+    /// is `false`.
+    ///
+    /// When `true`, scalar values are considered equal if they are of the same
+    /// JSON type, and arrays are only compared up to the length of the shorter
+    /// one, so that only missing or added fields and changes in type are
+    /// reported.
+    pub fn with_structural(mut self, structural: bool) -> Self {
+        self.structural = structural;
+        self
+    }
+
+    /// Compare a recorded response with a live response
+    pub fn compare(&self, recorded: &Response<Value>, live: &Response<Value>) -> ResponseDiff {
+        let mut differences = Vec::new();
+        if recorded.status() != live.status() {
+            differences.push(Difference::Status {
+                recorded: recorded.status(),
+                live: live.status(),
+            });
+        }
+        if self.compare_headers {
+            let mut names = recorded
+                .headers()
+                .keys()
+                .chain(live.headers().keys())
+                .filter(|&name| !self.ignored_headers.contains(name))
+                .collect::<Vec<_>>();
+            names.sort_unstable_by_key(|name| name.as_str());
+            names.dedup();
+            for name in names {
+                let rec_values = recorded
+                    .headers()
+                    .get_all(name)
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let live_values = live
+                    .headers()
+                    .get_all(name)
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if rec_values != live_values {
+                    differences.push(Difference::Header {
+                        name: name.clone(),
+                        recorded: rec_values,
+                        live: live_values,
+                    });
+                }
+            }
+        }
+        self.compare_values(
+            &mut String::new(),
+            recorded.body_ref(),
+            live.body_ref(),
+            &mut differences,
+        );
+        ResponseDiff { differences }
+    }
+
+    /// [Private] Compare the recorded and live values at `pointer` and append
+    /// any differences to `differences`
+    fn compare_values(
+        &self,
+        pointer: &mut String,
+        recorded: &Value,
+        live: &Value,
+        differences: &mut Vec<Difference>,
+    ) {
+        if self.ignored_pointers.contains(pointer.as_str()) {
+            return;
+        }
+        match (recorded, live) {
+            (Value::Object(rec_map), Value::Object(live_map)) => {
+                let keys = rec_map
+                    .keys()
+                    .chain(live_map.keys())
+                    .filter(|&k| !self.ignored_fields.contains(k))
+                    .collect::<BTreeSet<_>>();
+                for key in keys {
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    self.compare_entries(pointer, rec_map.get(key), live_map.get(key), differences);
+                    pointer.truncate(len);
+                }
+            }
+            (Value::Array(rec_items), Value::Array(live_items)) => {
+                let len = if self.structural {
+                    rec_items.len().min(live_items.len())
+                } else {
+                    rec_items.len().max(live_items.len())
+                };
+                for i in 0..len {
+                    let plen = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&i.to_string());
+                    self.compare_entries(pointer, rec_items.get(i), live_items.get(i), differences);
+                    pointer.truncate(plen);
+                }
+            }
+            (rec, lv) => {
+                let equal = if self.structural {
+                    json_type(rec) == json_type(lv)
+                } else {
+                    rec == lv
+                };
+                if !equal {
+                    differences.push(Difference::Changed {
+                        pointer: pointer.clone(),
+                        recorded: rec.clone(),
+                        live: lv.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// [Private] Compare an object field or array element that may be absent
+    /// from either the recorded or the live value
+    fn compare_entries(
+        &self,
+        pointer: &mut String,
+        recorded: Option<&Value>,
+        live: Option<&Value>,
+        differences: &mut Vec<Difference>,
+    ) {
+        if self.ignored_pointers.contains(pointer.as_str()) {
+            return;
+        }
+        match (recorded, live) {
+            (Some(rec), Some(lv)) => self.compare_values(pointer, rec, lv, differences),
+            (Some(rec), None) => differences.push(Difference::Removed {
+                pointer: pointer.clone(),
+                recorded: rec.clone(),
+            }),
+            (None, Some(lv)) => differences.push(Difference::Added {
+                pointer: pointer.clone(),
+                live: lv.clone(),
+            }),
+            (None, None) => (),
+        }
+    }
+}
+
+/// [Private] Returns the name of the JSON type of a value
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// The differences between a recorded and a live response, returned by
+/// [`ResponseDiffer::compare()`]
+///
+/// The `Display` implementation lists the differences one per line, making
+/// it suitable for use in assertion messages.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResponseDiff {
+    differences: Vec<Difference>,
+}
+
+impl ResponseDiff {
+    /// Returns `true` if no differences were found
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Returns the differences found, in the order status, headers (sorted by
+    /// name), body
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// Consume the `ResponseDiff` and return the differences found
+    pub fn into_differences(self) -> Vec<Difference> {
+        self.differences
+    }
+}
+
+impl fmt::Display for ResponseDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.differences.is_empty() {
+            return write!(f, "responses match");
+        }
+        for (i, d) in self.differences.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{d}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single difference between a recorded and a live response
+///
+/// Locations within response bodies are given as [JSON Pointers][rfc6901],
+/// with the empty string denoting the entire body.
+///
+/// [rfc6901]: https://www.rfc-editor.org/rfc/rfc6901
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Difference {
+    /// The responses have different status codes
+    Status {
+        recorded: StatusCode,
+        live: StatusCode,
+    },
+
+    /// The responses have different values for a header.  A header missing
+    /// from one of the responses has an empty list of values.
+    Header {
+        name: HeaderName,
+        recorded: Vec<HeaderValue>,
+        live: Vec<HeaderValue>,
+    },
+
+    /// A body field or array element in the recorded response is absent from
+    /// the live response
+    Removed { pointer: String, recorded: Value },
+
+    /// A body field or array element in the live response is absent from the
+    /// recorded response
+    Added { pointer: String, live: Value },
+
+    /// A body value differs between the responses
+    Changed {
+        pointer: String,
+        recorded: Value,
+        live: Value,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Status { recorded, live } => {
+                write!(f, "status: recorded {recorded}, live {live}")
+            }
+            Difference::Header {
+                name,
+                recorded,
+                live,
+            } => write!(f, "header {name}: recorded {recorded:?}, live {live:?}"),
+            Difference::Removed { pointer, recorded } => {
+                write!(f, "body {pointer:?}: removed (recorded {recorded})")
+            }
+            Difference::Added { pointer, live } => {
+                write!(f, "body {pointer:?}: added (live {live})")
+            }
+            Difference::Changed {
+                pointer,
+                recorded,
+                live,
+            } => write!(f, "body {pointer:?}: recorded {recorded}, live {live}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Method, response::ResponseParts};
+    use serde_json::json;
+
+    fn response(status: StatusCode, headers: &[(&str, &str)], body: Value) -> Response<Value> {
+        let mut parts = ResponseParts::new(
+            "https://api.github.com/repos/octocat/hello-world"
+                .parse()
+                .unwrap(),
+            Method::Get,
+            status,
+        );
+        for &(name, value) in headers {
+            parts = parts.with_header(
+                HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        Response::from_parts(parts, body)
+    }
+
+    #[test]
+    fn identical() {
+        let r = response(
+            StatusCode::OK,
+            &[("content-type", "application/json")],
+            json!({"id": 1, "topics": ["a", "b"]}),
+        );
+        let diff = ResponseDiffer::new().compare(&r, &r);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "responses match");
+    }
+
+    #[test]
+    fn all_differences() {
+        let recorded = response(
+            StatusCode::OK,
+            &[("etag", "\"abc\""), ("x-custom", "1")],
+            json!({
+                "id": 1,
+                "owner": {"login": "octocat", "id": 583231},
+                "topics": ["a", "b"],
+                "a/b": true,
+            }),
+        );
+        let live = response(
+            StatusCode::MOVED_PERMANENTLY,
+            &[("etag", "\"def\"")],
+            json!({
+                "id": 1,
+                "owner": {"login": "octocat", "id": "583231"},
+                "topics": ["a"],
+                "license": null,
+            }),
+        );
+        let diff = ResponseDiffer::new().compare(&recorded, &live);
+        assert_eq!(
+            diff.differences(),
+            [
+                Difference::Status {
+                    recorded: StatusCode::OK,
+                    live: StatusCode::MOVED_PERMANENTLY,
+                },
+                Difference::Header {
+                    name: http::header::ETAG,
+                    recorded: vec![HeaderValue::from_static("\"abc\"")],
+                    live: vec![HeaderValue::from_static("\"def\"")],
+                },
+                Difference::Header {
+                    name: HeaderName::from_static("x-custom"),
+                    recorded: vec![HeaderValue::from_static("1")],
+                    live: Vec::new(),
+                },
+                Difference::Removed {
+                    pointer: "/a~1b".into(),
+                    recorded: json!(true),
+                },
+                Difference::Added {
+                    pointer: "/license".into(),
+                    live: json!(null),
+                },
+                Difference::Changed {
+                    pointer: "/owner/id".into(),
+                    recorded: json!(583231),
+                    live: json!("583231"),
+                },
+                Difference::Removed {
+                    pointer: "/topics/1".into(),
+                    recorded: json!("b"),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "status: recorded 200 OK, live 301 Moved Permanently\n",
+                "header etag: recorded [\"\\\"abc\\\"\"], live [\"\\\"def\\\"\"]\n",
+                "header x-custom: recorded [\"1\"], live []\n",
+                "body \"/a~1b\": removed (recorded true)\n",
+                "body \"/license\": added (live null)\n",
+                "body \"/owner/id\": recorded 583231, live \"583231\"\n",
+                "body \"/topics/1\": removed (recorded \"b\")",
+            )
+        );
+    }
+
+    #[test]
+    fn ignored() {
+        let recorded = response(
+            StatusCode::OK,
+            &[("etag", "\"abc\""), ("x-ratelimit-remaining", "4999")],
+            json!({
+                "id": 1,
+                "updated_at": "2011-01-26T19:14:43Z",
+                "owner": {"id": 1, "updated_at": "2011-01-26T19:14:43Z"},
+                "stargazers_count": 80,
+            }),
+        );
+        let live = response(
+            StatusCode::OK,
+            &[("etag", "\"def\""), ("x-ratelimit-remaining", "4998")],
+            json!({
+                "id": 1,
+                "updated_at": "2024-05-01T08:00:00Z",
+                "owner": {"id": 1},
+                "stargazers_count": 2000,
+            }),
+        );
+        let diff = ResponseDiffer::github_defaults()
+            .with_ignored_pointer("/stargazers_count")
+            .compare(&recorded, &live);
+        assert!(diff.is_empty(), "{diff}");
+        let diff = ResponseDiffer::new()
+            .with_compare_headers(false)
+            .with_ignored_field("updated_at")
+            .compare(&recorded, &live);
+        assert_eq!(
+            diff.differences(),
+            [Difference::Changed {
+                pointer: "/stargazers_count".into(),
+                recorded: json!(80),
+                live: json!(2000),
+            }]
+        );
+    }
+
+    #[test]
+    fn structural() {
+        let recorded = response(
+            StatusCode::OK,
+            &[],
+            json!([
+                {"id": 1, "name": "foo", "private": false},
+                {"id": 2, "name": "bar", "private": false},
+            ]),
+        );
+        let live = response(
+            StatusCode::OK,
+            &[],
+            json!([{"id": 3, "name": null, "private": true, "visibility": "private"}]),
+        );
+        let diff = ResponseDiffer::new()
+            .with_structural(true)
+            .compare(&recorded, &live);
+        assert_eq!(
+            diff.differences(),
+            [
+                Difference::Changed {
+                    pointer: "/0/name".into(),
+                    recorded: json!("foo"),
+                    live: json!(null),
+                },
+                Difference::Added {
+                    pointer: "/0/visibility".into(),
+                    live: json!("private"),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! Utilities for testing code that uses `ghreq`
+mod conformance;
+mod diff;
+mod pagination;
+mod sleep;
+pub use self::conformance::*;
+pub use self::diff::*;
+pub use self::pagination::*;
+pub use self::sleep::*;
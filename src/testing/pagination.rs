@@ -0,0 +1,277 @@
+use crate::{
+    HttpUrl,
+    client::{FnBackend, RequestParts},
+    util::get_page_number,
+};
+use serde_json::{Value, json};
+
+#[cfg(feature = "tokio")]
+use crate::client::tokio::AsyncFnBackend;
+#[cfg(feature = "tokio")]
+use std::sync::Arc;
+
+/// The default number of items per page in [`PaginationFixtures`]
+pub const DEFAULT_FIXTURE_PAGE_SIZE: usize = 30;
+
+/// A generator of fake paginated responses
+///
+/// A `PaginationFixtures` splits a list of JSON items into pages of a fixed
+/// size and serves them in the same way that GitHub does: the first page is
+/// served at the base URL, subsequent pages are served at the base URL with a
+/// `page` query parameter added, and each response has a `Link` header
+/// pointing to the first, previous, next, and last pages as appropriate.
+///
+/// The pages can be served through a [`FnBackend`] (or an
+/// [`AsyncFnBackend`][crate::client::tokio::AsyncFnBackend]) in order to test
+/// code that performs pagination.
+///
+/// # Example
+///
+/// ```
+/// use ghreq::{Endpoint, client::ClientConfig, testing::PaginationFixtures};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Item {
+///     id: u64,
+/// }
+///
+/// struct ListItems;
+///
+/// impl ghreq::pagination::PaginationRequest for ListItems {
+///     type Item = Item;
+///
+///     fn endpoint(&self) -> Endpoint {
+///         Endpoint::from_iter(["items"])
+///     }
+/// }
+///
+/// let fixtures = PaginationFixtures::numbered(
+///     "https://api.github.com/items".parse().unwrap(),
+///     25,
+/// )
+/// .with_page_size(10);
+/// let client = ClientConfig::new().with_backend(fixtures.backend());
+/// let ids = client
+///     .paginate(ListItems)
+///     .map(|r| r.map(|item| item.id))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(ids, (1..=25).collect::<Vec<_>>());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaginationFixtures {
+    base_url: HttpUrl,
+    items: Vec<Value>,
+    page_size: usize,
+    shape: PageShape,
+}
+
+impl PaginationFixtures {
+    /// Create a `PaginationFixtures` that serves the given items from
+    /// `base_url`
+    pub fn new(base_url: HttpUrl, items: Vec<Value>) -> PaginationFixtures {
+        PaginationFixtures {
+            base_url,
+            items,
+            page_size: DEFAULT_FIXTURE_PAGE_SIZE,
+            shape: PageShape::default(),
+        }
+    }
+
+    /// Create a `PaginationFixtures` that serves `n` items of the form `{"id":
+    /// i}`, where `i` runs from 1 through `n`
+    pub fn numbered(base_url: HttpUrl, n: u64) -> PaginationFixtures {
+        let items = (1..=n).map(|i| json!({"id": i})).collect();
+        PaginationFixtures::new(base_url, items)
+    }
+
+    /// Set the number of items per page.  The default is
+    /// [`DEFAULT_FIXTURE_PAGE_SIZE`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        assert!(page_size > 0, "page size must be positive");
+        self.page_size = page_size;
+        self
+    }
+
+    /// Set the shape of the page response bodies.  The default is
+    /// [`PageShape::Array`].
+    pub fn with_shape(mut self, shape: PageShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn items(&self) -> &[Value] {
+        &self.items
+    }
+
+    /// Returns the number of pages.  This is always at least 1, as an empty
+    /// list of items is served as a single empty page.
+    pub fn page_count(&self) -> u64 {
+        let count = self.items.len().div_ceil(self.page_size).max(1);
+        u64::try_from(count).unwrap_or(u64::MAX)
+    }
+
+    /// Returns the URL at which the given (1-based) page is served
+    pub fn page_url(&self, page: u64) -> HttpUrl {
+        let mut url = self.base_url.clone();
+        if page > 1 {
+            url.append_query_param("page", &page.to_string());
+        }
+        url
+    }
+
+    /// Returns the response for a request to `url`.
+    ///
+    /// The page number is taken from the `page` query parameter of `url`,
+    /// defaulting to 1.  Other query parameters and the path of the URL are
+    /// ignored.  As on GitHub, requests for pages past the last page receive
+    /// an empty page.
+    pub fn response_for(&self, url: &HttpUrl) -> http::Response<Vec<u8>> {
+        let page = get_page_number(url).unwrap_or(1).max(1);
+        let page_items = usize::try_from(page - 1)
+            .ok()
+            .and_then(|i| i.checked_mul(self.page_size))
+            .and_then(|start| self.items.get(start..))
+            .map(|rest| &rest[..rest.len().min(self.page_size)])
+            .unwrap_or_default();
+        let body = match &self.shape {
+            PageShape::Array => json!(page_items),
+            PageShape::Object { key } => json!({
+                "total_count": self.items.len(),
+                key: page_items,
+            }),
+        };
+        let last = self.page_count();
+        let mut links = Vec::new();
+        if page > 1 {
+            links.push(self.link(1, "first"));
+            links.push(self.link(page.min(last + 1) - 1, "prev"));
+        }
+        if page < last {
+            links.push(self.link(page + 1, "next"));
+            links.push(self.link(last, "last"));
+        }
+        let mut builder =
+            http::Response::builder().header(http::header::CONTENT_TYPE, "application/json");
+        if !links.is_empty() {
+            builder = builder.header(http::header::LINK, links.join(", "));
+        }
+        let Ok(resp) = builder.body(body.to_string().into_bytes()) else {
+            unreachable!("Fixture response should be valid");
+        };
+        resp
+    }
+
+    /// Return a [`FnBackend`] that responds to every request with the
+    /// response from [`PaginationFixtures::response_for()`]
+    pub fn backend(self) -> FnBackend<impl Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>>> {
+        FnBackend::new(move |parts: RequestParts, _| self.response_for(&parts.url))
+    }
+
+    /// Return an [`AsyncFnBackend`] that responds to every request with the
+    /// response from [`PaginationFixtures::response_for()`]
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn async_backend(
+        self,
+    ) -> AsyncFnBackend<
+        impl Fn(RequestParts, Vec<u8>) -> http::Response<Vec<u8>> + Send + Sync + 'static,
+    > {
+        let fixtures = Arc::new(self);
+        AsyncFnBackend::new(move |parts: RequestParts, _| fixtures.response_for(&parts.url))
+    }
+
+    fn link(&self, page: u64, rel: &str) -> String {
+        let mut url = self.base_url.clone();
+        url.append_query_param("page", &page.to_string());
+        format!("<{url}>; rel=\"{rel}\"")
+    }
+}
+
+/// The shape of the body of a page response served by [`PaginationFixtures`]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum PageShape {
+    /// The body is a JSON array of items
+    #[default]
+    Array,
+
+    /// The body is a JSON object containing a `total_count` field and a field
+    /// with the given name whose value is the array of items, as is done by
+    /// endpoints like the search API
+    Object { key: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn fixtures(n: u64, page_size: usize) -> PaginationFixtures {
+        PaginationFixtures::numbered("https://api.github.com/widgets".parse().unwrap(), n)
+            .with_page_size(page_size)
+    }
+
+    #[rstest]
+    #[case(0, 10, 1)]
+    #[case(1, 10, 1)]
+    #[case(10, 10, 1)]
+    #[case(11, 10, 2)]
+    #[case(25, 10, 3)]
+    fn page_count(#[case] n: u64, #[case] page_size: usize, #[case] pages: u64) {
+        assert_eq!(fixtures(n, page_size).page_count(), pages);
+    }
+
+    #[test]
+    fn middle_page() {
+        let fx = fixtures(25, 10);
+        let resp = fx.response_for(&fx.page_url(2));
+        let body = serde_json::from_slice::<Value>(resp.body()).unwrap();
+        assert_eq!(
+            body,
+            json!((11..=20).map(|i| json!({"id": i})).collect::<Vec<_>>())
+        );
+        assert_eq!(
+            resp.headers()[http::header::LINK],
+            concat!(
+                r#"<https://api.github.com/widgets?page=1>; rel="first", "#,
+                r#"<https://api.github.com/widgets?page=1>; rel="prev", "#,
+                r#"<https://api.github.com/widgets?page=3>; rel="next", "#,
+                r#"<https://api.github.com/widgets?page=3>; rel="last""#,
+            )
+        );
+    }
+
+    #[test]
+    fn last_page_object() {
+        let fx = fixtures(25, 10).with_shape(PageShape::Object {
+            key: "widgets".into(),
+        });
+        let resp = fx.response_for(&fx.page_url(3));
+        let body = serde_json::from_slice::<Value>(resp.body()).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "total_count": 25,
+                "widgets": (21..=25).map(|i| json!({"id": i})).collect::<Vec<_>>(),
+            })
+        );
+        assert!(
+            !resp.headers()[http::header::LINK]
+                .to_str()
+                .unwrap()
+                .contains("next")
+        );
+    }
+
+    #[test]
+    fn past_last_page() {
+        let fx = fixtures(5, 10);
+        let resp = fx.response_for(&fx.page_url(4));
+        assert_eq!(resp.body(), b"[]");
+    }
+}
@@ -0,0 +1,48 @@
+use crate::client::{SleepFuture, Sleeper};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+/// A [`Sleeper`] that completes immediately and records each requested delay
+///
+/// Configure a client with a `FakeSleeper` via
+/// [`ClientConfig::with_sleeper()`][crate::client::ClientConfig::with_sleeper]
+/// in order to test code that waits between requests without actually
+/// waiting.  Clones of a `FakeSleeper` share the same record of delays.
+#[derive(Clone, Debug, Default)]
+pub struct FakeSleeper {
+    sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl FakeSleeper {
+    /// Create a new `FakeSleeper` with no recorded delays
+    pub fn new() -> FakeSleeper {
+        FakeSleeper::default()
+    }
+
+    /// Returns the delays requested so far, in order
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the sum of the delays requested so far
+    pub fn total(&self) -> Duration {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .sum()
+    }
+}
+
+impl Sleeper for FakeSleeper {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(duration);
+        Box::pin(std::future::ready(()))
+    }
+}
@@ -9,19 +9,50 @@ use ureq::{ResponseExt, SendBody};
 /// A synchronous client backed by [`ureq`]
 pub type UreqClient = Client<ureq::Agent>;
 
+/// A request prepared by the [`ureq`] backend
+#[derive(Debug)]
+pub struct UreqRequest(UreqRequestInner);
+
+#[derive(Debug)]
+enum UreqRequestInner {
+    /// A request with one of the methods for which `ureq` has a request
+    /// builder
+    Builder(ureq::RequestBuilder<ureq::typestate::WithBody>),
+
+    /// A request with any other method, to be sent with
+    /// [`ureq::Agent::run()`]
+    Http(http::Request<()>),
+}
+
 impl Backend for ureq::Agent {
-    type Request = ureq::RequestBuilder<ureq::typestate::WithBody>;
+    type Request = UreqRequest;
     type Response = http::Response<ureq::Body>;
     type Error = ureq::Error;
 
-    fn prepare_request(&self, r: RequestParts) -> Self::Request {
+    fn prepare_request(&self, r: RequestParts) -> Result<Self::Request, Self::Error> {
+        let uri = http::Uri::try_from(r.url).map_err(|e| ureq::Error::Http(e.into()))?;
         let mut req = match r.method {
-            Method::Get => self.get(r.url).force_send_body(),
-            Method::Head => self.head(r.url).force_send_body(),
-            Method::Post => self.post(r.url),
-            Method::Put => self.put(r.url),
-            Method::Patch => self.patch(r.url),
-            Method::Delete => self.delete(r.url).force_send_body(),
+            Method::Get => self.get(uri).force_send_body(),
+            Method::Head => self.head(uri).force_send_body(),
+            Method::Post => self.post(uri),
+            Method::Put => self.put(uri),
+            Method::Patch => self.patch(uri),
+            Method::Delete => self.delete(uri).force_send_body(),
+            Method::Options => self.options(uri).force_send_body(),
+            Method::Other(method) => {
+                let mut req = http::Request::new(());
+                *req.method_mut() = method;
+                *req.uri_mut() = uri;
+                *req.headers_mut() = r.headers;
+                let mut config = self
+                    .configure_request(req)
+                    .allow_non_standard_methods(true)
+                    .http_status_as_error(false);
+                if let Some(d) = r.timeout {
+                    config = config.timeout_global(Some(d));
+                }
+                return Ok(UreqRequest(UreqRequestInner::Http(config.build())));
+            }
         };
         for (k, v) in &r.headers {
             req = req.header(k, v);
@@ -29,7 +60,9 @@ impl Backend for ureq::Agent {
         if let Some(d) = r.timeout {
             req = req.config().timeout_global(Some(d)).build();
         }
-        req.config().http_status_as_error(false).build()
+        Ok(UreqRequest(UreqRequestInner::Builder(
+            req.config().http_status_as_error(false).build(),
+        )))
     }
 
     fn send<R: std::io::Read>(
@@ -37,7 +70,10 @@ impl Backend for ureq::Agent {
         r: Self::Request,
         mut body: R,
     ) -> Result<Self::Response, Self::Error> {
-        r.send(SendBody::from_reader(&mut body))
+        match r.0 {
+            UreqRequestInner::Builder(req) => req.send(SendBody::from_reader(&mut body)),
+            UreqRequestInner::Http(req) => self.run(req.map(|()| SendBody::from_reader(&mut body))),
+        }
     }
 }
 
@@ -73,3 +109,61 @@ pub type UreqError<E = CommonError> = Error<ureq::Error, E>;
 /// The `E` parameter is the `Error` type of the input
 /// [`Request`][crate::request::Request] provided to a method.
 pub type UreqErrorPayload<E = CommonError> = ErrorPayload<ureq::Error, E>;
+
+/// Result type returned by [`UreqClient`] methods.
+///
+/// The `E` parameter is the `Error` type of the input
+/// [`Request`][crate::request::Request] provided to a method.
+pub type UreqResult<T, E = CommonError> = Result<T, UreqError<E>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Endpoint,
+        client::ClientConfig,
+        parser::{ResponseParser, Utf8Text},
+        request::Request,
+    };
+    use rstest::rstest;
+
+    /// A request for a URL too long for `http::Uri`, which rejects URLs
+    /// longer than 64 KiB, unlike `url`
+    struct LongUrl(Method);
+
+    impl Request for LongUrl {
+        type Output = String;
+        type Error = CommonError;
+        type Body = ();
+
+        fn endpoint(&self) -> Endpoint {
+            Endpoint::from_iter(["x".repeat(70_000)])
+        }
+
+        fn method(&self) -> Method {
+            self.0.clone()
+        }
+
+        fn body(&self) -> Self::Body {}
+
+        fn parser(
+            &self,
+        ) -> impl ResponseParser<Output = Self::Output, Error: Into<Self::Error>> + Send {
+            Utf8Text::new()
+        }
+    }
+
+    #[rstest]
+    #[case(Method::Get)]
+    #[case(Method::Head)]
+    #[case(Method::Post)]
+    #[case(Method::Delete)]
+    #[case(Method::Other(http::Method::from_bytes(b"PURGE").unwrap()))]
+    fn invalid_uri(#[case] method: Method) {
+        let client = ClientConfig::new().with_backend(ureq::Agent::new_with_defaults());
+        let e = client.request(LongUrl(method)).unwrap_err();
+        let ErrorPayload::Send(ureq::Error::Http(_)) = e.payload_ref() else {
+            panic!("Expected Send error, got {e:?}");
+        };
+    }
+}
@@ -1,4 +1,36 @@
-use crate::HttpUrl;
+use crate::{HttpUrl, errors::CommonError};
+use http::header::{HeaderMap, HeaderName};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+
+/// Encode a value returned by a request's `query()` method as a list of query
+/// parameter name-value pairs
+pub(crate) fn encode_query<Q: Serialize>(query: &Q) -> Result<Vec<(String, String)>, CommonError> {
+    let qs = serde_urlencoded::to_string(query).map_err(|e| CommonError::Query(Box::new(e)))?;
+    Ok(url::form_urlencoded::parse(qs.as_bytes())
+        .into_owned()
+        .collect())
+}
+
+/// Deserialize a value from a buffer of JSON owned by the caller.
+///
+/// If the `simd-json` feature is enabled, the input is parsed in place with
+/// [`simd_json`], which overwrites the buffer in the process, and failures
+/// are reported as [`CommonError::SimdJson`] so that their classification and
+/// position are kept.  As simd-json cannot preserve numbers beyond 64-bit
+/// precision, `serde_json` is still used when the `json-arbitrary-precision`
+/// feature is also enabled.
+#[allow(clippy::needless_pass_by_ref_mut)]
+pub(crate) fn json_from_slice<T: DeserializeOwned>(buf: &mut [u8]) -> Result<T, CommonError> {
+    #[cfg(all(feature = "simd-json", not(feature = "json-arbitrary-precision")))]
+    {
+        simd_json::serde::from_slice(buf).map_err(CommonError::SimdJson)
+    }
+    #[cfg(any(not(feature = "simd-json"), feature = "json-arbitrary-precision"))]
+    {
+        serde_json::from_slice(buf).map_err(Into::into)
+    }
+}
 
 /// Extract the value of the `page` query parameter from the given URL.
 /// Returns `None` if there is no `page` parameter or if the value could not be
@@ -17,11 +49,145 @@ pub(crate) fn get_page_number(url: &HttpUrl) -> Option<u64> {
         .and_then(|v| v.parse::<u64>().ok())
 }
 
+/// Return a copy of the given URL with its `page` query parameter(s) replaced
+/// by a single `page` parameter with the given value.  If the URL has no
+/// `page` parameter, one is appended.
+pub(crate) fn set_page_number(url: &HttpUrl, page: u64) -> HttpUrl {
+    set_query_param(url, "page", &page.to_string())
+}
+
+/// Return a copy of the given URL with its `key` query parameter(s) replaced
+/// by a single `key` parameter with the given value.  If the URL has no `key`
+/// parameter, one is appended.
+pub(crate) fn set_query_param(url: &HttpUrl, key: &str, value: &str) -> HttpUrl {
+    let mut new = url.as_url().clone();
+    let mut pairs = Vec::new();
+    let mut replaced = false;
+    for (k, v) in url.as_url().query_pairs() {
+        if k == key {
+            if !replaced {
+                pairs.push((k, value.into()));
+                replaced = true;
+            }
+        } else {
+            pairs.push((k, v));
+        }
+    }
+    if !replaced {
+        pairs.push((key.into(), value.into()));
+    }
+    new.query_pairs_mut().clear().extend_pairs(pairs);
+    let Ok(new) = HttpUrl::try_from(new) else {
+        unreachable!("changing the query of an HTTP URL should produce an HTTP URL");
+    };
+    new
+}
+
+/// Convert a civil date to a number of days since the Unix epoch;
+/// see <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Convert a number of days since the Unix epoch to a civil date;
+/// see <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// [Private] Headers whose values are always redacted in `Debug` output,
+/// even if they are not marked as sensitive
+const CREDENTIAL_HEADERS: [HeaderName; 4] = [
+    http::header::AUTHORIZATION,
+    http::header::COOKIE,
+    http::header::PROXY_AUTHORIZATION,
+    http::header::SET_COOKIE,
+];
+
+/// A wrapper around a `HeaderMap` whose `Debug` implementation masks the
+/// values of credential-bearing and sensitive headers
+pub(crate) struct RedactedHeaders<'a>(pub(crate) &'a HeaderMap);
+
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.0 {
+            if value.is_sensitive() || CREDENTIAL_HEADERS.contains(name) {
+                map.entry(name, &format_args!("<redacted>"));
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstest::rstest;
 
+    #[cfg(feature = "simd-json")]
+    #[rstest]
+    #[case(br#"{"name": "octocat", "id": 1}"#)]
+    #[case(br#"{"name": "\u00e9\"t\u00e9\"", "id": 18446744073709551615}"#)]
+    #[case(b"[1, 2.5, null, true, \"x\"]")]
+    #[case(b"  \"padded\"\n")]
+    fn json_from_slice_matches_serde_json(#[case] input: &[u8]) {
+        let simd = json_from_slice::<serde_json::Value>(&mut input.to_vec()).unwrap();
+        assert_eq!(
+            simd,
+            serde_json::from_slice::<serde_json::Value>(input).unwrap()
+        );
+    }
+
+    #[cfg(all(feature = "simd-json", not(feature = "json-arbitrary-precision")))]
+    #[test]
+    fn json_from_slice_invalid() {
+        let r = json_from_slice::<serde_json::Value>(&mut b"{\"name\": 1 2}".to_vec());
+        let Err(CommonError::SimdJson(e)) = r else {
+            panic!("Expected SimdJson error, got {r:?}");
+        };
+        assert!(e.is_syntax());
+        assert_eq!(e.index(), 11);
+        let r = json_from_slice::<Vec<u64>>(&mut br#"{"name": "octocat"}"#.to_vec());
+        let Err(CommonError::SimdJson(e)) = r else {
+            panic!("Expected SimdJson error, got {r:?}");
+        };
+        assert!(e.is_data());
+    }
+
+    #[cfg(any(not(feature = "simd-json"), feature = "json-arbitrary-precision"))]
+    #[test]
+    fn json_from_slice_invalid() {
+        let r = json_from_slice::<serde_json::Value>(&mut b"{\"name\": 1 2}".to_vec());
+        let Err(CommonError::Json(e)) = r else {
+            panic!("Expected Json error, got {r:?}");
+        };
+        assert!(e.is_syntax());
+        assert_eq!((e.line(), e.column()), (1, 12));
+        let r = json_from_slice::<Vec<u64>>(&mut br#"{"name": "octocat"}"#.to_vec());
+        let Err(CommonError::Json(e)) = r else {
+            panic!("Expected Json error, got {r:?}");
+        };
+        assert!(e.is_data());
+    }
+
     #[rstest]
     #[case("https://api.github.com/users/jwodder/repos", None)]
     #[case("https://api.github.com/users/jwodder/repos?per_page=100", None)]
@@ -40,4 +206,56 @@ mod tests {
     fn test_get_page_number(#[case] url: HttpUrl, #[case] num: Option<u64>) {
         assert_eq!(get_page_number(&url), num);
     }
+
+    #[cfg(feature = "tokio")]
+    #[rstest]
+    #[case(
+        "https://api.github.com/users/jwodder/repos?per_page=100&page=2",
+        "https://api.github.com/users/jwodder/repos?per_page=100&page=5"
+    )]
+    #[case(
+        "https://api.github.com/users/jwodder/repos?page=2&per_page=100&page=3",
+        "https://api.github.com/users/jwodder/repos?page=5&per_page=100"
+    )]
+    #[case(
+        "https://api.github.com/users/jwodder/repos",
+        "https://api.github.com/users/jwodder/repos?page=5"
+    )]
+    fn test_set_page_number(#[case] url: HttpUrl, #[case] expected: &str) {
+        assert_eq!(set_page_number(&url, 5).as_str(), expected);
+    }
+
+    #[rstest]
+    #[case(1970, 1, 1, 0)]
+    #[case(1969, 12, 31, -1)]
+    #[case(2000, 2, 29, 11_016)]
+    #[case(2024, 3, 1, 19_783)]
+    #[case(0, 1, 1, -719_528)]
+    #[case(9999, 12, 31, 2_932_896)]
+    fn civil_days(#[case] year: i64, #[case] month: i64, #[case] day: i64, #[case] days: i64) {
+        assert_eq!(days_from_civil(year, month, day), days);
+        assert_eq!(civil_from_days(days), (year, month, day));
+    }
+
+    #[test]
+    fn redacted_headers() {
+        use http::header::HeaderValue;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer hunter2"),
+        );
+        headers.insert(
+            http::header::ACCEPT,
+            HeaderValue::from_static("application/json"),
+        );
+        let mut secret = HeaderValue::from_static("s3kr1t");
+        secret.set_sensitive(true);
+        headers.insert("x-api-key", secret);
+        let s = format!("{:?}", RedactedHeaders(&headers));
+        assert_eq!(
+            s,
+            r#"{"authorization": <redacted>, "accept": "application/json", "x-api-key": <redacted>}"#
+        );
+    }
 }